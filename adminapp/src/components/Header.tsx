@@ -17,6 +17,15 @@ function Header() {
     setUserMenuOpen(false);
   };
 
+  const handleLogoutEverywhere = async () => {
+    setUserMenuOpen(false);
+    try {
+      await auth.logoutEverywhere();
+    } catch (error) {
+      console.error('Failed to sign out everywhere:', error);
+    }
+  };
+
   const handleRestaurantSelect = (selectedRestaurant: typeof restaurant.currentRestaurant) => {
     restaurant.setCurrentRestaurant(selectedRestaurant);
     setRestaurantMenuOpen(false);
@@ -173,6 +182,12 @@ function Header() {
                   >
                     Sign out
                   </button>
+                  <button
+                    onClick={handleLogoutEverywhere}
+                    class="block w-full text-left px-3 py-2 text-sm leading-6 text-gray-900 hover:bg-gray-50"
+                  >
+                    Sign out everywhere
+                  </button>
                 </div>
               </Show>
             </div>
@@ -6,6 +6,7 @@ const mockAuth = {
   user: null as any,
   isAuthenticated: false,
   logout: vi.fn(),
+  logoutEverywhere: vi.fn(),
   loading: false,
   error: null,
   login: vi.fn(),
@@ -8,6 +8,15 @@ interface RestaurantFormProps {
   onCancel: () => void;
 }
 
+// IANA names, e.g. "Asia/Kolkata"; business days and order numbers follow this zone
+const browserTimezone = Intl.DateTimeFormat().resolvedOptions().timeZone || 'UTC';
+const supportedTimezones: string[] =
+  (Intl as unknown as { supportedValuesOf?: (key: string) => string[] })
+    .supportedValuesOf?.('timeZone') ?? [];
+const timezoneOptions: string[] = Array.from(
+  new Set(['UTC', browserTimezone, ...supportedTimezones])
+);
+
 function RestaurantForm(props: RestaurantFormProps) {
   const restaurant = useRestaurant();
   
@@ -16,6 +25,8 @@ function RestaurantForm(props: RestaurantFormProps) {
   const [address, setAddress] = createSignal('');
   const [establishmentYear, setEstablishmentYear] = createSignal<number | undefined>(undefined);
   const [googleMapsLink, setGoogleMapsLink] = createSignal('');
+  const [timezone, setTimezone] = createSignal(browserTimezone);
+  const [defaultLanguage, setDefaultLanguage] = createSignal('en');
   
   // Form state
   const [isSubmitting, setIsSubmitting] = createSignal(false);
@@ -30,12 +41,16 @@ function RestaurantForm(props: RestaurantFormProps) {
       setAddress(props.restaurant.address || '');
       setEstablishmentYear(props.restaurant.establishment_year);
       setGoogleMapsLink(props.restaurant.google_maps_link || '');
+      setTimezone(props.restaurant.timezone);
+      setDefaultLanguage(props.restaurant.default_language);
     } else {
       // Reset form for create mode
       setName('');
       setAddress('');
       setEstablishmentYear(undefined);
       setGoogleMapsLink('');
+      setTimezone(browserTimezone);
+      setDefaultLanguage('en');
     }
     setValidationErrors({});
   });
@@ -86,6 +101,8 @@ function RestaurantForm(props: RestaurantFormProps) {
         address: address().trim() || undefined,
         establishment_year: establishmentYear(),
         google_maps_link: googleMapsLink().trim() || undefined,
+        timezone: timezone(),
+        default_language: defaultLanguage().trim() || undefined,
       };
 
       let result: Restaurant;
@@ -215,6 +232,46 @@ function RestaurantForm(props: RestaurantFormProps) {
           </p>
         </div>
 
+        {/* Timezone */}
+        <div>
+          <label for="timezone" class="block text-sm font-medium text-gray-700">
+            Timezone
+          </label>
+          <select
+            id="timezone"
+            name="timezone"
+            class="mt-1 block w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500 sm:text-sm"
+            value={timezone()}
+            onChange={(e) => setTimezone(e.currentTarget.value)}
+          >
+            {timezoneOptions.map((zone) => (
+              <option value={zone}>{zone}</option>
+            ))}
+          </select>
+          <p class="mt-1 text-sm text-gray-500">
+            Used for "today" in the dashboard and for daily order numbers
+          </p>
+        </div>
+
+        {/* Default language */}
+        <div>
+          <label for="default_language" class="block text-sm font-medium text-gray-700">
+            Menu Language
+          </label>
+          <input
+            type="text"
+            id="default_language"
+            name="default_language"
+            class="mt-1 block w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500 sm:text-sm"
+            placeholder="en"
+            value={defaultLanguage()}
+            onInput={(e) => setDefaultLanguage(e.currentTarget.value)}
+          />
+          <p class="mt-1 text-sm text-gray-500">
+            Language code of the menu as you enter it, e.g. en or hi. Guests asking for another language see translations where you add them
+          </p>
+        </div>
+
         {/* Form Actions */}
         <div class="flex justify-end space-x-3">
           <button
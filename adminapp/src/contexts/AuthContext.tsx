@@ -2,12 +2,14 @@ import {
   createContext,
   createSignal,
   createEffect,
+  onCleanup,
   useContext,
   ParentComponent,
 } from 'solid-js';
 import { AuthService, TokenStorage } from '../services/auth';
 import type {
   AuthState,
+  AuthResponse,
   User,
   LoginRequest,
   RegisterRequest,
@@ -17,9 +19,13 @@ interface AuthContextType extends AuthState {
   login: (credentials: LoginRequest) => Promise<void>;
   register: (userData: RegisterRequest) => Promise<void>;
   logout: () => void;
+  logoutEverywhere: () => Promise<void>;
   clearError: () => void;
 }
 
+// Access tokens are renewed this long before they expire
+const REFRESH_MARGIN_MS = 60 * 1000;
+
 const AuthContext = createContext<AuthContextType | undefined>(undefined);
 
 export const AuthProvider: ParentComponent = (props) => {
@@ -27,6 +33,10 @@ export const AuthProvider: ParentComponent = (props) => {
   const [token, setToken] = createSignal<string | null>(null);
   const [isLoading, setIsLoading] = createSignal(true);
   const [error, setError] = createSignal<string | null>(null);
+  let refreshTimer: ReturnType<typeof setTimeout> | undefined;
+  let refreshInFlight: Promise<boolean> | undefined;
+
+  onCleanup(() => clearTimeout(refreshTimer));
 
   const isAuthenticated = () => {
     const authState = !!user() && !!token();
@@ -53,8 +63,13 @@ export const AuthProvider: ParentComponent = (props) => {
         if (storedToken && storedUser) {
           // Check if token is expired before making API call
           if (TokenStorage.isTokenExpired(storedToken)) {
-            console.log('Stored token is expired, clearing storage');
-            TokenStorage.clear();
+            if (TokenStorage.getRefreshToken()) {
+              console.log('Stored token is expired, refreshing session');
+              await refreshSession();
+            } else {
+              console.log('Stored token is expired, clearing storage');
+              TokenStorage.clear();
+            }
             setIsLoading(false);
             return;
           }
@@ -67,8 +82,8 @@ export const AuthProvider: ParentComponent = (props) => {
             console.log('Token validation successful, restoring session');
             setToken(storedToken);
             setUser(storedUser);
-            // Set up token expiration check
-            setupTokenExpirationCheck(storedToken);
+            // Renew the token before it expires
+            scheduleRefresh(storedToken);
           } else {
             console.log('Token validation failed, clearing storage');
             TokenStorage.clear();
@@ -87,40 +102,71 @@ export const AuthProvider: ParentComponent = (props) => {
     initializeAuth();
   });
 
-  // Set up token expiration monitoring
-  const setupTokenExpirationCheck = (token: string) => {
+  const saveSession = (response: AuthResponse) => {
+    setToken(response.token);
+    setUser(response.user);
+
+    TokenStorage.saveToken(response.token);
+    TokenStorage.saveRefreshToken(response.refresh_token);
+    TokenStorage.saveUser(response.user);
+
+    scheduleRefresh(response.token);
+  };
+
+  const endSession = () => {
+    clearTimeout(refreshTimer);
+    setToken(null);
+    setUser(null);
+    TokenStorage.clear();
+  };
+
+  // Refresh tokens rotate on every use, so concurrent callers share one request
+  const refreshSession = (): Promise<boolean> => {
+    if (refreshInFlight) {
+      return refreshInFlight;
+    }
+
+    const refreshToken = TokenStorage.getRefreshToken();
+    if (!refreshToken) {
+      endSession();
+      return Promise.resolve(false);
+    }
+
+    refreshInFlight = AuthService.refresh(refreshToken)
+      .then((response) => {
+        saveSession(response);
+        return true;
+      })
+      .catch((err) => {
+        console.error('Session refresh failed:', err);
+        endSession();
+        setError('Your session has expired. Please log in again.');
+        return false;
+      })
+      .finally(() => {
+        refreshInFlight = undefined;
+      });
+
+    return refreshInFlight;
+  };
+
+  // Renew the access token shortly before it expires
+  const scheduleRefresh = (token: string) => {
+    clearTimeout(refreshTimer);
+
     try {
       const payload = JSON.parse(atob(token.split('.')[1]));
-      const expirationTime = payload.exp * 1000;
-      const currentTime = Date.now();
-      const timeUntilExpiration = expirationTime - currentTime;
-
-      console.log('Setting up token expiration check:', {
-        expirationTime,
-        currentTime,
-        timeUntilExpiration,
-        timeUntilExpirationHours: Math.floor(
-          timeUntilExpiration / (1000 * 60 * 60)
-        ),
-      });
+      const timeUntilExpiration = payload.exp * 1000 - Date.now();
+      const refreshIn = Math.max(timeUntilExpiration - REFRESH_MARGIN_MS, 0);
 
-      // If token expires in less than 5 minutes, log out immediately
-      if (timeUntilExpiration <= 5 * 60 * 1000) {
-        console.log('Token expires soon, logging out immediately');
-        logout();
-        return;
-      }
+      console.log('Scheduling token refresh:', { timeUntilExpiration, refreshIn });
 
-      // Set timeout to logout 5 minutes before expiration
-      const logoutTime = Math.max(timeUntilExpiration - 5 * 60 * 1000, 0);
-      setTimeout(() => {
-        console.log('Token expiration timeout reached, logging out');
-        logout();
-        setError('Your session has expired. Please log in again.');
-      }, logoutTime);
+      refreshTimer = setTimeout(() => {
+        refreshSession();
+      }, refreshIn);
     } catch (error) {
-      console.error('Error parsing token for expiration check:', error);
-      logout();
+      console.error('Error parsing token for refresh scheduling:', error);
+      refreshSession();
     }
   };
 
@@ -132,19 +178,12 @@ export const AuthProvider: ParentComponent = (props) => {
       const response = await AuthService.login(credentials);
       console.log('Login response:', response);
 
-      setToken(response.token);
-      setUser(response.user);
-
-      TokenStorage.saveToken(response.token);
-      TokenStorage.saveUser(response.user);
+      saveSession(response);
 
       console.log('Auth state after login:', {
         user: response.user,
         token: response.token,
       });
-
-      // Set up token expiration monitoring
-      setupTokenExpirationCheck(response.token);
     } catch (err) {
       console.error('Login error:', err);
       const errorMessage = err instanceof Error ? err.message : 'Login failed';
@@ -162,14 +201,7 @@ export const AuthProvider: ParentComponent = (props) => {
     try {
       const response = await AuthService.register(userData);
 
-      setToken(response.token);
-      setUser(response.user);
-
-      TokenStorage.saveToken(response.token);
-      TokenStorage.saveUser(response.user);
-
-      // Set up token expiration monitoring
-      setupTokenExpirationCheck(response.token);
+      saveSession(response);
     } catch (err) {
       const errorMessage =
         err instanceof Error ? err.message : 'Registration failed';
@@ -181,10 +213,23 @@ export const AuthProvider: ParentComponent = (props) => {
   };
 
   const logout = () => {
-    setToken(null);
-    setUser(null);
+    const currentToken = token();
+    if (currentToken) {
+      // Revoke the session server-side; local state is cleared regardless
+      void AuthService.logout(currentToken);
+    }
+    endSession();
+    setError(null);
+  };
+
+  // Ends every session of the user, including this one
+  const logoutEverywhere = async () => {
+    const currentToken = token();
+    if (currentToken) {
+      await AuthService.logoutEverywhere(currentToken);
+    }
+    endSession();
     setError(null);
-    TokenStorage.clear();
   };
 
   const clearError = () => {
@@ -210,6 +255,7 @@ export const AuthProvider: ParentComponent = (props) => {
     login,
     register,
     logout,
+    logoutEverywhere,
     clearError,
   };
 
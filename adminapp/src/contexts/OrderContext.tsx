@@ -47,8 +47,8 @@ export const OrderProvider: ParentComponent = (props) => {
     setCurrentRestaurantId(restaurantId);
 
     try {
-      const fetchedOrders =
-        await OrderService.getRestaurantOrders(restaurantId);
+      const page = await OrderService.getRestaurantOrders(restaurantId);
+      const fetchedOrders = page.items;
       setOrders(fetchedOrders);
 
       // Calculate stats
@@ -191,9 +191,8 @@ export const RestaurantProvider: ParentComponent = (props) => {
     setError(null);
 
     try {
-      const restaurantManagers =
-        await RestaurantService.getRestaurantManagers(restaurantId);
-      setManagers(restaurantManagers);
+      const page = await RestaurantService.getRestaurantManagers(restaurantId);
+      setManagers(page.items);
     } catch (err) {
       handleError(err);
     } finally {
@@ -133,9 +133,8 @@ export const TableProvider: ParentComponent = (props) => {
     setError(null);
 
     try {
-      const restaurantTables =
-        await TableService.getRestaurantTables(restaurantId);
-      setTables(Array.isArray(restaurantTables) ? restaurantTables : []);
+      const page = await TableService.getRestaurantTables(restaurantId);
+      setTables(Array.isArray(page.items) ? page.items : []);
     } catch (err) {
       handleError(err);
     } finally {
@@ -9,14 +9,20 @@ vi.mock('../../services/auth', () => ({
   AuthService: {
     login: vi.fn(),
     register: vi.fn(),
+    refresh: vi.fn(),
+    logout: vi.fn(),
+    logoutEverywhere: vi.fn(),
     validateToken: vi.fn(),
   },
   TokenStorage: {
     getToken: vi.fn(),
+    getRefreshToken: vi.fn(),
     getUser: vi.fn(),
     saveToken: vi.fn(),
+    saveRefreshToken: vi.fn(),
     saveUser: vi.fn(),
     removeToken: vi.fn(),
+    removeRefreshToken: vi.fn(),
     removeUser: vi.fn(),
     clear: vi.fn(),
     isTokenExpired: vi.fn(),
@@ -151,6 +157,39 @@ describe('AuthContext', () => {
     expect(AuthService.validateToken).not.toHaveBeenCalled();
   });
 
+  it('should refresh an expired token when a refresh token is stored', async () => {
+    const mockUser = { id: 1, email: 'test@example.com' };
+    const mockResponse = {
+      token: createValidJWT({ email: 'test@example.com' }),
+      refresh_token: 'rotated-refresh-token',
+      expires_in: 900,
+      user: mockUser,
+    };
+
+    (TokenStorage.getToken as any).mockReturnValue(
+      createExpiredJWT({ email: 'test@example.com' })
+    );
+    (TokenStorage.getRefreshToken as any).mockReturnValue('refresh-token');
+    (TokenStorage.getUser as any).mockReturnValue(mockUser);
+    (TokenStorage.isTokenExpired as any).mockReturnValue(true);
+    (AuthService.refresh as any).mockResolvedValue(mockResponse);
+
+    render(() => (
+      <AuthProvider>
+        <TestComponent />
+      </AuthProvider>
+    ));
+
+    await waitFor(() => {
+      expect(screen.getByTestId('auth-status')).toHaveTextContent('authenticated');
+    });
+
+    expect(AuthService.refresh).toHaveBeenCalledWith('refresh-token');
+    expect(TokenStorage.saveToken).toHaveBeenCalledWith(mockResponse.token);
+    expect(TokenStorage.saveRefreshToken).toHaveBeenCalledWith('rotated-refresh-token');
+    expect(TokenStorage.clear).not.toHaveBeenCalled();
+  });
+
   it('should handle successful login', async () => {
     const mockResponse = {
       token: createValidJWT({ email: 'test@example.com' }),
@@ -252,6 +291,7 @@ describe('AuthContext', () => {
     });
 
     expect(TokenStorage.clear).toHaveBeenCalled();
+    expect(AuthService.logout).toHaveBeenCalledWith(mockToken);
   });
 
   it('should throw error when useAuth is used outside provider', () => {
@@ -454,7 +454,7 @@ function OrderDashboard() {
                           <div class="flex items-center justify-between mb-2">
                             <div class="flex items-center space-x-3">
                               <h4 class="text-sm font-medium text-gray-900">
-                                Order #{order.id.slice(-8)}
+                                Order #{order.order_number ?? order.id.slice(-8)}
                               </h4>
                               <span
                                 class={`inline-flex items-center px-2.5 py-0.5 rounded-full text-xs font-medium ${getStatusColor(order.status)}`}
@@ -96,6 +96,53 @@ describe('AuthService', () => {
     });
   });
 
+  describe('refresh', () => {
+    it('should exchange the refresh token for new tokens', async () => {
+      const mockResponse = {
+        token: 'new-jwt-token',
+        refresh_token: 'new-refresh-token',
+        expires_in: 900,
+        user: { id: 1, email: 'test@example.com' },
+      };
+
+      (fetch as any).mockResolvedValueOnce({
+        ok: true,
+        json: async () => mockResponse,
+      });
+
+      const result = await AuthService.refresh('old-refresh-token');
+
+      expect(fetch).toHaveBeenCalledWith(
+        expect.stringContaining('/auth/refresh'),
+        expect.objectContaining({
+          method: 'POST',
+          body: JSON.stringify({ refresh_token: 'old-refresh-token' }),
+        })
+      );
+      expect(result).toEqual(mockResponse);
+    });
+
+    it('should throw when the refresh token is rejected', async () => {
+      (fetch as any).mockResolvedValueOnce({
+        ok: false,
+        status: 401,
+        json: async () => ({ error: 'Invalid or expired refresh token' }),
+      });
+
+      await expect(AuthService.refresh('revoked-token')).rejects.toThrow(
+        'Invalid or expired refresh token'
+      );
+    });
+  });
+
+  describe('logout', () => {
+    it('should not throw on network error', async () => {
+      (fetch as any).mockRejectedValueOnce(new Error('Network error'));
+
+      await expect(AuthService.logout('token')).resolves.toBeUndefined();
+    });
+  });
+
   describe('validateToken', () => {
     it('should return true for valid token', async () => {
       (fetch as any).mockResolvedValueOnce({
@@ -195,10 +242,11 @@ describe('TokenStorage', () => {
   });
 
   describe('clear', () => {
-    it('should remove both token and user data', () => {
+    it('should remove tokens and user data', () => {
       TokenStorage.clear();
       
       expect(localStorageMock.removeItem).toHaveBeenCalledWith('letsorder_token');
+      expect(localStorageMock.removeItem).toHaveBeenCalledWith('letsorder_refresh_token');
       expect(localStorageMock.removeItem).toHaveBeenCalledWith('letsorder_user');
     });
   });
@@ -4,6 +4,7 @@ import type {
   LoginRequest,
   RegisterRequest,
 } from '../types/auth';
+import type { SessionInfo } from '../types/api';
 
 const API_BASE = config.apiUrl;
 
@@ -54,6 +55,89 @@ export class AuthService {
     return response.json();
   }
 
+  // Swaps the refresh token for a new access token and refresh token.
+  // The old refresh token stops working.
+  static async refresh(refreshToken: string): Promise<AuthResponse> {
+    const response = await fetch(`${API_BASE}/auth/refresh`, {
+      method: 'POST',
+      headers: this.getAuthHeaders(),
+      body: JSON.stringify({ refresh_token: refreshToken }),
+    });
+
+    if (!response.ok) {
+      const error = await response
+        .json()
+        .catch(() => ({ error: 'Session refresh failed' }));
+      throw new Error(
+        error.error || `Session refresh failed: ${response.status}`
+      );
+    }
+
+    return response.json();
+  }
+
+  // Best effort: the local session is cleared whether or not this succeeds
+  static async logout(token: string): Promise<void> {
+    try {
+      await fetch(`${API_BASE}/api/auth/logout`, {
+        method: 'POST',
+        headers: this.getAuthHeaders(token),
+      });
+    } catch (error) {
+      console.error('Logout request failed:', error);
+    }
+  }
+
+  static async logoutEverywhere(token: string): Promise<void> {
+    const response = await fetch(`${API_BASE}/api/auth/logout-all`, {
+      method: 'POST',
+      headers: this.getAuthHeaders(token),
+    });
+
+    if (!response.ok) {
+      const error = await response
+        .json()
+        .catch(() => ({ error: 'Failed to log out everywhere' }));
+      throw new Error(
+        error.error || `Failed to log out everywhere: ${response.status}`
+      );
+    }
+  }
+
+  static async getSessions(token: string): Promise<SessionInfo[]> {
+    const response = await fetch(`${API_BASE}/api/auth/sessions`, {
+      method: 'GET',
+      headers: this.getAuthHeaders(token),
+    });
+
+    if (!response.ok) {
+      const error = await response
+        .json()
+        .catch(() => ({ error: 'Failed to load sessions' }));
+      throw new Error(
+        error.error || `Failed to load sessions: ${response.status}`
+      );
+    }
+
+    return response.json();
+  }
+
+  static async revokeSession(token: string, sessionId: string): Promise<void> {
+    const response = await fetch(`${API_BASE}/api/auth/sessions/${sessionId}`, {
+      method: 'DELETE',
+      headers: this.getAuthHeaders(token),
+    });
+
+    if (!response.ok) {
+      const error = await response
+        .json()
+        .catch(() => ({ error: 'Failed to revoke session' }));
+      throw new Error(
+        error.error || `Failed to revoke session: ${response.status}`
+      );
+    }
+  }
+
   static async validateToken(token: string): Promise<boolean> {
     try {
       console.log('Validating token with backend:', `${API_BASE}/api/test`);
@@ -81,6 +165,7 @@ export class AuthService {
 export class TokenStorage {
   private static readonly TOKEN_KEY = 'letsorder_token';
   private static readonly USER_KEY = 'letsorder_user';
+  private static readonly REFRESH_TOKEN_KEY = 'letsorder_refresh_token';
 
   static saveToken(token: string): void {
     localStorage.setItem(this.TOKEN_KEY, token);
@@ -94,6 +179,18 @@ export class TokenStorage {
     localStorage.removeItem(this.TOKEN_KEY);
   }
 
+  static saveRefreshToken(refreshToken: string): void {
+    localStorage.setItem(this.REFRESH_TOKEN_KEY, refreshToken);
+  }
+
+  static getRefreshToken(): string | null {
+    return localStorage.getItem(this.REFRESH_TOKEN_KEY);
+  }
+
+  static removeRefreshToken(): void {
+    localStorage.removeItem(this.REFRESH_TOKEN_KEY);
+  }
+
   static saveUser(user: any): void {
     localStorage.setItem(this.USER_KEY, JSON.stringify(user));
   }
@@ -109,6 +206,7 @@ export class TokenStorage {
 
   static clear(): void {
     this.removeToken();
+    this.removeRefreshToken();
     this.removeUser();
   }
 
@@ -12,6 +12,43 @@ import type {
   ToggleAvailabilityRequest,
   MenuSectionWithItems,
   RestaurantMenu,
+  MenuPreview,
+  SectionScheduleResponse,
+  UpdateSectionScheduleRequest,
+  MenuItemStock,
+  UpdateStockRequest,
+  RestockRequest,
+  MenuVersionSummary,
+  MenuVersionDetail,
+  PublishMenuRequest,
+  MenuDiff,
+  MenuImportResult,
+  MenuFileFormat,
+  MenuImportMode,
+  CloneMenuRequest,
+  RestaurantTranslations,
+  RestaurantTranslation,
+  SectionTranslation,
+  ItemTranslation,
+  UpsertRestaurantTranslationRequest,
+  UpsertSectionTranslationRequest,
+  UpsertItemTranslationRequest,
+  MenuItemVariant,
+  CreateMenuItemVariantRequest,
+  UpdateMenuItemVariantRequest,
+  MenuBundle,
+  CreateBundleRequest,
+  UpdateBundleRequest,
+  PricingRule,
+  CreatePricingRuleRequest,
+  UpdatePricingRuleRequest,
+  Coupon,
+  CreateCouponRequest,
+  UpdateCouponRequest,
+  KitchenStation,
+  CreateKitchenStationRequest,
+  UpdateKitchenStationRequest,
+  StationLine,
 } from '../types/menu';
 
 export class MenuService {
@@ -65,6 +102,23 @@ export class MenuService {
     return this.handleResponse<RestaurantMenu>(response);
   }
 
+  // Menu as guests see it at the given time (defaults to now)
+  static async previewMenu(
+    restaurantId: string,
+    at?: Date
+  ): Promise<MenuPreview> {
+    const query = at ? `?at=${encodeURIComponent(at.toISOString())}` : '';
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/preview${query}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<MenuPreview>(response);
+  }
+
   // Menu Sections
   static async createSection(
     restaurantId: string,
@@ -104,6 +158,36 @@ export class MenuService {
     return this.handleResponse<void>(response);
   }
 
+  static async getSectionSchedule(
+    sectionId: string
+  ): Promise<SectionScheduleResponse> {
+    const response = await fetch(
+      `${this.BASE_URL}/sections/${sectionId}/schedule`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<SectionScheduleResponse>(response);
+  }
+
+  static async updateSectionSchedule(
+    sectionId: string,
+    data: UpdateSectionScheduleRequest
+  ): Promise<SectionScheduleResponse> {
+    const response = await fetch(
+      `${this.BASE_URL}/sections/${sectionId}/schedule`,
+      {
+        method: 'PUT',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<SectionScheduleResponse>(response);
+  }
+
   static async reorderSections(data: ReorderSectionsRequest): Promise<void> {
     const response = await fetch(`${this.BASE_URL}/sections/reorder`, {
       method: 'POST',
@@ -169,6 +253,620 @@ export class MenuService {
     return this.handleResponse<MenuItem>(response);
   }
 
+  // Variants
+  static async getItemVariants(itemId: string): Promise<MenuItemVariant[]> {
+    const response = await fetch(`${this.BASE_URL}/items/${itemId}/variants`, {
+      method: 'GET',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<MenuItemVariant[]>(response);
+  }
+
+  static async createVariant(
+    itemId: string,
+    data: CreateMenuItemVariantRequest
+  ): Promise<MenuItemVariant> {
+    const response = await fetch(`${this.BASE_URL}/items/${itemId}/variants`, {
+      method: 'POST',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<MenuItemVariant>(response);
+  }
+
+  static async updateVariant(
+    variantId: string,
+    data: UpdateMenuItemVariantRequest
+  ): Promise<MenuItemVariant> {
+    const response = await fetch(`${this.BASE_URL}/variants/${variantId}`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<MenuItemVariant>(response);
+  }
+
+  static async deleteVariant(variantId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/variants/${variantId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  // Bundles
+  static async getBundles(restaurantId: string): Promise<MenuBundle[]> {
+    const response = await fetch(`${this.BASE_URL}/restaurants/${restaurantId}/bundles`, {
+      method: 'GET',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<MenuBundle[]>(response);
+  }
+
+  static async createBundle(
+    restaurantId: string,
+    data: CreateBundleRequest
+  ): Promise<MenuBundle> {
+    const response = await fetch(`${this.BASE_URL}/restaurants/${restaurantId}/bundles`, {
+      method: 'POST',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<MenuBundle>(response);
+  }
+
+  static async updateBundle(
+    bundleId: string,
+    data: UpdateBundleRequest
+  ): Promise<MenuBundle> {
+    const response = await fetch(`${this.BASE_URL}/bundles/${bundleId}`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<MenuBundle>(response);
+  }
+
+  static async deleteBundle(bundleId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/bundles/${bundleId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  // Pricing rules
+  static async getPricingRules(restaurantId: string): Promise<PricingRule[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/pricing-rules`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<PricingRule[]>(response);
+  }
+
+  static async createPricingRule(
+    restaurantId: string,
+    data: CreatePricingRuleRequest
+  ): Promise<PricingRule> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/pricing-rules`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<PricingRule>(response);
+  }
+
+  static async updatePricingRule(
+    ruleId: string,
+    data: UpdatePricingRuleRequest
+  ): Promise<PricingRule> {
+    const response = await fetch(`${this.BASE_URL}/pricing-rules/${ruleId}`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<PricingRule>(response);
+  }
+
+  static async deletePricingRule(ruleId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/pricing-rules/${ruleId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  // Coupons
+  static async getCoupons(restaurantId: string): Promise<Coupon[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/coupons`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<Coupon[]>(response);
+  }
+
+  static async createCoupon(
+    restaurantId: string,
+    data: CreateCouponRequest
+  ): Promise<Coupon> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/coupons`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<Coupon>(response);
+  }
+
+  static async updateCoupon(
+    couponId: string,
+    data: UpdateCouponRequest
+  ): Promise<Coupon> {
+    const response = await fetch(`${this.BASE_URL}/coupons/${couponId}`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<Coupon>(response);
+  }
+
+  static async deleteCoupon(couponId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/coupons/${couponId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  // Kitchen stations
+  static async getStations(restaurantId: string): Promise<KitchenStation[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/stations`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<KitchenStation[]>(response);
+  }
+
+  static async createStation(
+    restaurantId: string,
+    data: CreateKitchenStationRequest
+  ): Promise<KitchenStation> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/stations`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<KitchenStation>(response);
+  }
+
+  static async updateStation(
+    stationId: string,
+    data: UpdateKitchenStationRequest
+  ): Promise<KitchenStation> {
+    const response = await fetch(`${this.BASE_URL}/stations/${stationId}`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<KitchenStation>(response);
+  }
+
+  static async deleteStation(stationId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/stations/${stationId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  // Route a whole section, or a single item, to a station (null unroutes it)
+  static async assignSectionStation(
+    sectionId: string,
+    stationId: string | null
+  ): Promise<void> {
+    const response = await fetch(
+      `${this.BASE_URL}/sections/${sectionId}/station`,
+      {
+        method: 'PUT',
+        headers: this.getHeaders(),
+        body: JSON.stringify({ station_id: stationId }),
+      }
+    );
+
+    return this.handleResponse<void>(response);
+  }
+
+  static async assignItemStation(
+    itemId: string,
+    stationId: string | null
+  ): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/items/${itemId}/station`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify({ station_id: stationId }),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  static async getStationLines(
+    restaurantId: string,
+    stationId?: string,
+    status: 'pending' | 'bumped' | 'all' = 'pending'
+  ): Promise<StationLine[]> {
+    const params = new URLSearchParams({ status });
+    if (stationId) {
+      params.set('station_id', stationId);
+    }
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/kitchen/lines?${params}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<StationLine[]>(response);
+  }
+
+  static async bumpStationLine(lineId: string): Promise<StationLine> {
+    const response = await fetch(
+      `${this.BASE_URL}/kitchen/lines/${lineId}/bump`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<StationLine>(response);
+  }
+
+  static async recallStationLine(lineId: string): Promise<StationLine> {
+    const response = await fetch(
+      `${this.BASE_URL}/kitchen/lines/${lineId}/recall`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<StationLine>(response);
+  }
+
+  // Inventory
+  static async getStockLevels(restaurantId: string): Promise<MenuItemStock[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/stock`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<MenuItemStock[]>(response);
+  }
+
+  static async updateItemStock(
+    itemId: string,
+    data: UpdateStockRequest
+  ): Promise<MenuItemStock> {
+    const response = await fetch(`${this.BASE_URL}/items/${itemId}/stock`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<MenuItemStock>(response);
+  }
+
+  static async restockItem(
+    itemId: string,
+    data: RestockRequest
+  ): Promise<MenuItemStock> {
+    const response = await fetch(`${this.BASE_URL}/items/${itemId}/restock`, {
+      method: 'POST',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<MenuItemStock>(response);
+  }
+
+  // Import/export
+  static async exportMenu(
+    restaurantId: string,
+    format: MenuFileFormat = 'json'
+  ): Promise<Blob> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/export?format=${format}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    if (!response.ok) {
+      await this.handleResponse<void>(response);
+    }
+
+    return response.blob();
+  }
+
+  // Validation failures come back as a result with row-level errors rather
+  // than an exception, so the caller can show them next to the file
+  static async importMenu(
+    restaurantId: string,
+    content: string,
+    options: { format: MenuFileFormat; mode: MenuImportMode; dryRun: boolean }
+  ): Promise<MenuImportResult> {
+    const params = new URLSearchParams({
+      format: options.format,
+      mode: options.mode,
+      dry_run: String(options.dryRun),
+    });
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/import?${params}`,
+      {
+        method: 'POST',
+        headers: {
+          ...this.getHeaders(),
+          'Content-Type':
+            options.format === 'csv' ? 'text/csv' : 'application/json',
+        },
+        body: content,
+      }
+    );
+
+    if (response.status === 422) {
+      return response.json();
+    }
+
+    return this.handleResponse<MenuImportResult>(response);
+  }
+
+  // Copy another restaurant's menu into this one
+  static async cloneMenu(
+    restaurantId: string,
+    data: CloneMenuRequest
+  ): Promise<MenuImportResult> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/clone`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<MenuImportResult>(response);
+  }
+
+  // Translations
+  static async getTranslations(
+    restaurantId: string
+  ): Promise<RestaurantTranslations> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/translations`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<RestaurantTranslations>(response);
+  }
+
+  static async saveRestaurantTranslation(
+    restaurantId: string,
+    locale: string,
+    data: UpsertRestaurantTranslationRequest
+  ): Promise<RestaurantTranslation> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/translations/${locale}`,
+      {
+        method: 'PUT',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<RestaurantTranslation>(response);
+  }
+
+  static async deleteRestaurantTranslation(
+    restaurantId: string,
+    locale: string
+  ): Promise<void> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/translations/${locale}`,
+      {
+        method: 'DELETE',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<void>(response);
+  }
+
+  static async saveSectionTranslation(
+    sectionId: string,
+    locale: string,
+    data: UpsertSectionTranslationRequest
+  ): Promise<SectionTranslation> {
+    const response = await fetch(
+      `${this.BASE_URL}/sections/${sectionId}/translations/${locale}`,
+      {
+        method: 'PUT',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<SectionTranslation>(response);
+  }
+
+  static async deleteSectionTranslation(
+    sectionId: string,
+    locale: string
+  ): Promise<void> {
+    const response = await fetch(
+      `${this.BASE_URL}/sections/${sectionId}/translations/${locale}`,
+      {
+        method: 'DELETE',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<void>(response);
+  }
+
+  static async saveItemTranslation(
+    itemId: string,
+    locale: string,
+    data: UpsertItemTranslationRequest
+  ): Promise<ItemTranslation> {
+    const response = await fetch(
+      `${this.BASE_URL}/items/${itemId}/translations/${locale}`,
+      {
+        method: 'PUT',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<ItemTranslation>(response);
+  }
+
+  static async deleteItemTranslation(
+    itemId: string,
+    locale: string
+  ): Promise<void> {
+    const response = await fetch(
+      `${this.BASE_URL}/items/${itemId}/translations/${locale}`,
+      {
+        method: 'DELETE',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<void>(response);
+  }
+
+  // Draft/publish workflow
+  static async publishMenu(
+    restaurantId: string,
+    data: PublishMenuRequest
+  ): Promise<MenuVersionSummary> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/publish`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<MenuVersionSummary>(response);
+  }
+
+  static async listMenuVersions(
+    restaurantId: string
+  ): Promise<MenuVersionSummary[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/versions`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<MenuVersionSummary[]>(response);
+  }
+
+  static async getMenuVersion(
+    restaurantId: string,
+    versionId: string
+  ): Promise<MenuVersionDetail> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/versions/${versionId}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<MenuVersionDetail>(response);
+  }
+
+  static async diffMenu(
+    restaurantId: string,
+    from = 'live',
+    to = 'draft'
+  ): Promise<MenuDiff> {
+    const params = new URLSearchParams({ from, to });
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/diff?${params}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<MenuDiff>(response);
+  }
+
+  static async rollbackMenu(
+    restaurantId: string,
+    versionId: string
+  ): Promise<MenuVersionSummary> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/menu/versions/${versionId}/rollback`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<MenuVersionSummary>(response);
+  }
+
   static async reorderItems(data: ReorderItemsRequest): Promise<void> {
     const response = await fetch(`${this.BASE_URL}/items/reorder`, {
       method: 'POST',
@@ -1,12 +1,22 @@
 import { config } from '../config/env';
 import type {
   Order,
+  OrderListParams,
   OrderResponse,
   OrderStats,
   OrderStatus,
+  SalesReportBreakdown,
   UpdateOrderStatusRequest,
 } from '../types/order';
-import type { OrderItemResponse } from '../types/api';
+import type {
+  ClosingAuditEntry,
+  ClosingSummary,
+  DayClosing,
+  OrderItemResponse,
+  Page,
+  PrintJob,
+  SalesReport,
+} from '../types/api';
 
 const API_BASE = config.apiUrl;
 
@@ -32,6 +42,7 @@ export class OrderService {
   private static transformOrder(orderResponse: OrderResponse): Order {
     return {
       id: orderResponse.id,
+      order_number: orderResponse.order_number,
       table_id: orderResponse.table_id,
       table_name: orderResponse.table_name,
       restaurant_name: orderResponse.restaurant_name,
@@ -44,22 +55,36 @@ export class OrderService {
       })),
       total_amount: orderResponse.total_amount,
       status: orderResponse.status as OrderStatus,
+      payment_status: orderResponse.payment_status,
       customer_name: orderResponse.customer_name,
+      discount: orderResponse.discount,
       created_at: orderResponse.created_at,
     };
   }
 
-  static async getRestaurantOrders(restaurantId: string): Promise<Order[]> {
+  static async getRestaurantOrders(
+    restaurantId: string,
+    params: OrderListParams = {}
+  ): Promise<Page<Order>> {
+    const query = new URLSearchParams();
+    Object.entries(params).forEach(([key, value]) => {
+      if (value !== undefined && value !== '') {
+        query.set(key, String(value));
+      }
+    });
     const response = await fetch(
-      `${API_BASE}/api/restaurants/${restaurantId}/orders`,
+      `${API_BASE}/api/restaurants/${restaurantId}/orders?${query}`,
       {
         method: 'GET',
         headers: this.getAuthHeaders(),
       }
     );
 
-    const orderResponses = await this.handleResponse<OrderResponse[]>(response);
-    return orderResponses.map(this.transformOrder);
+    const page = await this.handleResponse<Page<OrderResponse>>(response);
+    return {
+      items: page.items.map(this.transformOrder),
+      next_cursor: page.next_cursor,
+    };
   }
 
   static async getTodayOrders(restaurantId: string): Promise<Order[]> {
@@ -105,8 +130,6 @@ export class OrderService {
     orderId: string,
     status: OrderStatus
   ): Promise<void> {
-    // Note: This endpoint doesn't exist yet in the backend
-    // For now, we'll throw an error indicating this feature is not implemented
     const response = await fetch(`${API_BASE}/api/orders/${orderId}/status`, {
       method: 'PUT',
       headers: this.getAuthHeaders(),
@@ -116,6 +139,214 @@ export class OrderService {
     await this.handleResponse<void>(response);
   }
 
+  // Dates are days in the restaurant's timezone, both ends included
+  static async getSalesReport(
+    restaurantId: string,
+    from: string,
+    to: string
+  ): Promise<SalesReport> {
+    const params = new URLSearchParams({ from, to });
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/reports/sales?${params}`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    return this.handleResponse<SalesReport>(response);
+  }
+
+  static async exportSalesReport(
+    restaurantId: string,
+    from: string,
+    to: string,
+    breakdown: SalesReportBreakdown = 'summary'
+  ): Promise<Blob> {
+    const params = new URLSearchParams({ from, to, format: 'csv', breakdown });
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/reports/sales?${params}`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    if (!response.ok) {
+      await this.handleResponse<void>(response);
+    }
+
+    return response.blob();
+  }
+
+  // One row per order line, streamed by the backend for accounting tools
+  static async exportOrders(
+    restaurantId: string,
+    from: string,
+    to: string,
+    format: 'csv' | 'jsonl' = 'csv'
+  ): Promise<Blob> {
+    const params = new URLSearchParams({ from, to, format });
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/orders/export?${params}`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    if (!response.ok) {
+      await this.handleResponse<void>(response);
+    }
+
+    return response.blob();
+  }
+
+  // End-of-day closing (Z report); dates default to the restaurant's today
+  static async previewDayClosing(
+    restaurantId: string,
+    date?: string
+  ): Promise<ClosingSummary> {
+    const query = date ? `?${new URLSearchParams({ date })}` : '';
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/closings/preview${query}`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    return this.handleResponse<ClosingSummary>(response);
+  }
+
+  static async closeDay(
+    restaurantId: string,
+    businessDate?: string
+  ): Promise<DayClosing> {
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/closings`,
+      {
+        method: 'POST',
+        headers: this.getAuthHeaders(),
+        body: JSON.stringify({ business_date: businessDate ?? null }),
+      }
+    );
+
+    return this.handleResponse<DayClosing>(response);
+  }
+
+  static async getDayClosings(restaurantId: string): Promise<DayClosing[]> {
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/closings`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    return this.handleResponse<DayClosing[]>(response);
+  }
+
+  static async reopenDay(
+    restaurantId: string,
+    businessDate: string,
+    reason: string
+  ): Promise<void> {
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/closings/${businessDate}/reopen`,
+      {
+        method: 'POST',
+        headers: this.getAuthHeaders(),
+        body: JSON.stringify({ reason }),
+      }
+    );
+
+    if (!response.ok) {
+      await this.handleResponse<void>(response);
+    }
+  }
+
+  static async getClosingAudit(
+    restaurantId: string,
+    businessDate: string
+  ): Promise<ClosingAuditEntry[]> {
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/closings/${businessDate}/audit`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    return this.handleResponse<ClosingAuditEntry[]>(response);
+  }
+
+  // ESC/POS bytes for a thermal printer; station_id narrows a kitchen ticket
+  static async downloadPrintDocument(
+    restaurantId: string,
+    orderId: string,
+    kind: 'ticket' | 'receipt',
+    width: 58 | 80 = 80,
+    stationId?: string
+  ): Promise<Blob> {
+    const params = new URLSearchParams({ width: String(width) });
+    if (stationId) params.set('station_id', stationId);
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/orders/${orderId}/${kind}?${params}`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    if (!response.ok) {
+      await this.handleResponse<void>(response);
+    }
+
+    return response.blob();
+  }
+
+  // Queues a document for the restaurant's local print agent
+  static async queuePrintJob(
+    restaurantId: string,
+    orderId: string,
+    kind: 'ticket' | 'receipt',
+    width: 58 | 80 = 80,
+    stationId?: string
+  ): Promise<PrintJob> {
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/print-jobs`,
+      {
+        method: 'POST',
+        headers: this.getAuthHeaders(),
+        body: JSON.stringify({
+          order_id: orderId,
+          kind,
+          width,
+          station_id: stationId ?? null,
+        }),
+      }
+    );
+
+    return this.handleResponse<PrintJob>(response);
+  }
+
+  static async getPrintJobs(
+    restaurantId: string,
+    status?: 'queued' | 'printing' | 'printed' | 'failed'
+  ): Promise<PrintJob[]> {
+    const query = status ? `?${new URLSearchParams({ status })}` : '';
+    const response = await fetch(
+      `${API_BASE}/api/restaurants/${restaurantId}/print-jobs${query}`,
+      {
+        method: 'GET',
+        headers: this.getAuthHeaders(),
+      }
+    );
+
+    return this.handleResponse<PrintJob[]>(response);
+  }
+
   static calculateOrderStats(orders: Order[]): OrderStats {
     const stats: OrderStats = {
       total_orders: orders.length,
@@ -10,6 +10,17 @@ import type {
   UpdateManagerPermissionsRequest,
   InviteResponse,
 } from '../types/restaurant';
+import type {
+  ApiKey,
+  CreateApiKeyRequest,
+  CreatedApiKey,
+  Page,
+  WebhookSubscription,
+  CreateWebhookRequest,
+  UpdateWebhookRequest,
+  WebhookDelivery,
+  WebhookDeliveryDetail,
+} from '../types/api';
 
 export class RestaurantService {
   private static readonly BASE_URL = `${config.apiUrl}/api`;
@@ -76,7 +87,7 @@ export class RestaurantService {
       this.getRestaurantManagers(id),
     ]);
 
-    return { restaurant, managers };
+    return { restaurant, managers: managers.items };
   }
 
   // Create restaurant
@@ -117,16 +128,27 @@ export class RestaurantService {
   }
 
   // Manager management
-  static async getRestaurantManagers(id: string): Promise<ManagerInfo[]> {
+  static async getRestaurantManagers(
+    id: string,
+    params: { cursor?: string; limit?: number; role?: string; q?: string } = {
+      limit: 200,
+    }
+  ): Promise<Page<ManagerInfo>> {
+    const query = new URLSearchParams();
+    Object.entries(params).forEach(([key, value]) => {
+      if (value !== undefined && value !== '') {
+        query.set(key, String(value));
+      }
+    });
     const response = await fetch(
-      `${this.BASE_URL}/restaurants/${id}/managers`,
+      `${this.BASE_URL}/restaurants/${id}/managers?${query}`,
       {
         method: 'GET',
         headers: this.getHeaders(),
       }
     );
 
-    return this.handleResponse<ManagerInfo[]>(response);
+    return this.handleResponse<Page<ManagerInfo>>(response);
   }
 
   // Invite manager
@@ -179,4 +201,139 @@ export class RestaurantService {
 
     return this.handleResponse<void>(response);
   }
+
+  // Outbound webhooks
+  static async getWebhooks(
+    restaurantId: string
+  ): Promise<WebhookSubscription[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/webhooks`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<WebhookSubscription[]>(response);
+  }
+
+  static async createWebhook(
+    restaurantId: string,
+    data: CreateWebhookRequest
+  ): Promise<WebhookSubscription> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/webhooks`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<WebhookSubscription>(response);
+  }
+
+  static async updateWebhook(
+    webhookId: string,
+    data: UpdateWebhookRequest
+  ): Promise<WebhookSubscription> {
+    const response = await fetch(`${this.BASE_URL}/webhooks/${webhookId}`, {
+      method: 'PUT',
+      headers: this.getHeaders(),
+      body: JSON.stringify(data),
+    });
+
+    return this.handleResponse<WebhookSubscription>(response);
+  }
+
+  static async deleteWebhook(webhookId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/webhooks/${webhookId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
+
+  static async getWebhookDeliveries(
+    webhookId: string,
+    status?: string
+  ): Promise<WebhookDelivery[]> {
+    const query = status ? `?status=${encodeURIComponent(status)}` : '';
+    const response = await fetch(
+      `${this.BASE_URL}/webhooks/${webhookId}/deliveries${query}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<WebhookDelivery[]>(response);
+  }
+
+  static async getWebhookDelivery(
+    deliveryId: string
+  ): Promise<WebhookDeliveryDetail> {
+    const response = await fetch(
+      `${this.BASE_URL}/webhook-deliveries/${deliveryId}`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<WebhookDeliveryDetail>(response);
+  }
+
+  // Queues the same event again under a new delivery id
+  static async redeliverWebhook(deliveryId: string): Promise<WebhookDelivery> {
+    const response = await fetch(
+      `${this.BASE_URL}/webhook-deliveries/${deliveryId}/redeliver`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<WebhookDelivery>(response);
+  }
+
+  // API keys for integrations
+  static async getApiKeys(restaurantId: string): Promise<ApiKey[]> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/api-keys`,
+      {
+        method: 'GET',
+        headers: this.getHeaders(),
+      }
+    );
+
+    return this.handleResponse<ApiKey[]>(response);
+  }
+
+  // The returned key is shown once and cannot be retrieved later
+  static async createApiKey(
+    restaurantId: string,
+    data: CreateApiKeyRequest
+  ): Promise<CreatedApiKey> {
+    const response = await fetch(
+      `${this.BASE_URL}/restaurants/${restaurantId}/api-keys`,
+      {
+        method: 'POST',
+        headers: this.getHeaders(),
+        body: JSON.stringify(data),
+      }
+    );
+
+    return this.handleResponse<CreatedApiKey>(response);
+  }
+
+  static async revokeApiKey(keyId: string): Promise<void> {
+    const response = await fetch(`${this.BASE_URL}/api-keys/${keyId}`, {
+      method: 'DELETE',
+      headers: this.getHeaders(),
+    });
+
+    return this.handleResponse<void>(response);
+  }
 }
@@ -9,6 +9,7 @@ import type {
   BulkQrCodeResponse,
   RefreshCodeResponse,
 } from '../types/table';
+import type { Page } from '../types/api';
 
 export class TableService {
   private static readonly BASE_URL = `${config.apiUrl}/api`;
@@ -49,16 +50,25 @@ export class TableService {
   // Table CRUD operations
 
   // Get tables for a restaurant
-  static async getRestaurantTables(restaurantId: string): Promise<Table[]> {
+  static async getRestaurantTables(
+    restaurantId: string,
+    params: { cursor?: string; limit?: number; q?: string } = { limit: 200 }
+  ): Promise<Page<Table>> {
+    const query = new URLSearchParams();
+    Object.entries(params).forEach(([key, value]) => {
+      if (value !== undefined && value !== '') {
+        query.set(key, String(value));
+      }
+    });
     const response = await fetch(
-      `${this.BASE_URL}/restaurants/${restaurantId}/tables`,
+      `${this.BASE_URL}/restaurants/${restaurantId}/tables?${query}`,
       {
         method: 'GET',
         headers: this.getHeaders(),
       }
     );
 
-    return this.handleResponse<Table[]>(response);
+    return this.handleResponse<Page<Table>>(response);
   }
 
   // Get single table details
@@ -20,19 +20,411 @@ export type UserResponse = { id: string, email: string, phone: string | null, cr
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type AuthResponse = { token: string, user: UserResponse, };
+export type AuthResponse = { token: string, refresh_token: string, expires_in: number, user: UserResponse, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type CreateOrderResponse = { order_id: string, total_amount: number, status: string, created_at: string, };
+export type CreateOrderResponse = { order_id: string, order_number: number, total_amount: number, status: string, payment_status: string, payment: PaymentIntentResponse | null, discount: OrderDiscount | null, created_at: string, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type OrderItemResponse = { menu_item_id: string, menu_item_name: string, quantity: number, price: number, special_requests: string | null, };
+export type PaymentIntentResponse = { provider: string, intent_id: string, client_secret: string, amount: number, currency: string, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type OrderResponse = { id: string, table_id: string, table_name: string, restaurant_name: string, items: Array<OrderItemResponse>, total_amount: number, status: string, customer_name: string | null, created_at: string, };
+export type ConfirmPaymentRequest = { payment_method: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ConfirmPaymentResponse = { order_id: string, status: string, payment_status: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdatePricingRuleRequest = { name: string | null, adjustment_type: string | null, adjustment_value: number | null, active: boolean | null, windows: Array<PricingRuleWindow> | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreatePricingRuleRequest = { name: string, scope: string, target_id: string | null, adjustment_type: string, adjustment_value: number, active: boolean | null, windows: Array<PricingRuleWindow>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PricingRule = { id: string, restaurant_id: string, name: string, scope: string, target_id: string | null, adjustment_type: string, adjustment_value: number, active: boolean, windows: Array<PricingRuleWindow>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PricingRuleWindow = { day_of_week: number, start_time: string, end_time: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrderDiscount = { coupon_code: string, amount: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateCouponRequest = { discount_type: string | null, discount_value: number | null, min_order_amount: number | null, valid_from: string | null, valid_until: string | null, max_uses: number | null, active: boolean | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateCouponRequest = { code: string, discount_type: string, discount_value: number, min_order_amount: number | null, valid_from: string | null, valid_until: string | null, max_uses: number | null, item_id: string | null, active: boolean | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Coupon = { id: string, restaurant_id: string, code: string, discount_type: string, discount_value: number, min_order_amount: number | null, valid_from: string | null, valid_until: string | null, max_uses: number | null, times_used: number, item_id: string | null, active: boolean, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HourlySales = { day_of_week: number, hour: number, order_count: bigint, revenue: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TableSales = { table_id: string, name: string, order_count: bigint, revenue: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SectionSales = { section_id: string | null, name: string, quantity: bigint, revenue: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ItemSales = { menu_item_id: string, name: string, section_name: string, quantity: bigint, revenue: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SalesReport = { restaurant_id: string, from: string, to: string, timezone: string, order_count: bigint, cancelled_count: bigint, cancellation_rate: number, gross_sales: number, discounts: number, refunds: number, net_sales: number, average_order_value: number, items: Array<ItemSales>, sections: Array<SectionSales>, tables: Array<TableSales>, hours: Array<HourlySales>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClosingAuditEntry = { id: string, closing_id: string, business_date: string, action: string, user_id: string, reason: string | null, created_at: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DayClosing = { id: string, restaurant_id: string, business_date: string, summary: ClosingSummary, closed_by: string, closed_at: string, reopened_by: string | null, reopened_at: string | null, reopen_reason: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClosingSummary = { restaurant_id: string, business_date: string, timezone: string, order_count: bigint, cancelled_count: bigint, gross_sales: number, discounts: number, refunds: number, taxes: number, tips: number, net_sales: number, payment_methods: Array<ClosingPaymentMethod>, in_progress_orders: Array<InProgressOrder>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InProgressOrder = { order_id: string, order_number: number | null, table_name: string, status: string, payment_status: string, total_amount: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClosingPaymentMethod = { method: string, order_count: bigint, amount: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ReopenDayRequest = { reason: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CloseDayRequest = { business_date: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrderExportLine = { order_id: string, order_number: number | null, created_at: string, business_date: string, table_name: string, menu_item_id: string, item_name: string, variant_name: string | null, quantity: number, unit_price: number, line_total: number, tax: number, status: string, payment_status: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Page<T> = { items: Array<T>, next_cursor: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type KitchenStation = { id: string, restaurant_id: string, name: string, display_order: number, section_ids: Array<string>, item_ids: Array<string>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateKitchenStationRequest = { name: string, display_order: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateKitchenStationRequest = { name: string | null, display_order: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AssignStationRequest = { station_id: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type StationLine = { id: string, order_id: string, order_number: number | null, order_status: string, table_name: string, station_id: string | null, menu_item_id: string, item_name: string, variant_name: string | null, notes: string | null, quantity: number, status: string, created_at: string, bumped_at: string | null, recall_count: number, elapsed_seconds: bigint, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreatePrintJobRequest = { order_id: string, kind: string, width: bigint | null, station_id: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PrintJob = { id: string, restaurant_id: string, order_id: string, order_number: number | null, kind: string, station_id: string | null, width: bigint, status: string, attempts: number, last_error: string | null, created_at: string, claimed_at: string | null, printed_at: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ClaimedPrintJob = { job: PrintJob, payload_base64: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AckPrintJobRequest = { success: boolean, error: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebhookSubscription = { id: string, restaurant_id: string, url: string, secret: string, events: Array<string>, description: string | null, active: boolean, created_at: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateWebhookRequest = { url: string, events: Array<string>, description: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateWebhookRequest = { url: string | null, events: Array<string> | null, description: string | null, active: boolean | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebhookDelivery = { id: string, subscription_id: string, event_id: string, event_type: string, status: string, attempts: number, next_attempt_at: string | null, last_status_code: number | null, last_error: string | null, delivered_at: string | null, redelivery_of: string | null, created_at: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebhookDeliveryAttempt = { status_code: number | null, error: string | null, response_body: string | null, duration_ms: bigint, attempted_at: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebhookDeliveryDetail = { delivery: WebhookDelivery, payload: string, attempts: Array<WebhookDeliveryAttempt>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ApiKey = { id: string, restaurant_id: string, name: string, key_prefix: string, scopes: Array<string>, created_at: string, last_used_at: string | null, revoked_at: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateApiKeyRequest = { name: string, scopes: Array<string>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreatedApiKey = { api_key: ApiKey, key: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RefreshTokenRequest = { refresh_token: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SessionInfo = { id: string, user_agent: string | null, ip_address: string | null, created_at: string, last_used_at: string, expires_at: string, current: boolean, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PublicBundleChoice = { item_id: string, name: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PublicBundleSlot = { id: string, name: string, choices: Array<PublicBundleChoice>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PublicBundle = { id: string, name: string, description: string | null, price: number, slots: Array<PublicBundleSlot>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateBundleRequest = { name: string | null, description: string | null, price: number | null, available: boolean | null, display_order: number | null, slots: Array<BundleSlotRequest> | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateBundleRequest = { name: string, description: string | null, price: number, available: boolean | null, display_order: number | null, slots: Array<BundleSlotRequest>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BundleSlotRequest = { name: string, item_ids: Array<string>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuBundleSlot = { id: string, name: string, display_order: number, item_ids: Array<string>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuBundle = { id: string, restaurant_id: string, name: string, description: string | null, price: number, available: boolean, display_order: number, slots: Array<MenuBundleSlot>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateMenuItemVariantRequest = { name: string | null, price: number | null, available: boolean | null, display_order: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateMenuItemVariantRequest = { name: string, price: number, available: boolean | null, display_order: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuItemVariant = { id: string, item_id: string, name: string, price: number, available: boolean, display_order: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SnapshotVariant = { id: string, name: string, price: number, display_order: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PublicMenuVariant = { id: string, name: string, price: number, original_price: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuSearchResponse = { query: string, language: string, results: Array<MenuSearchResult>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuSearchResult = { section_id: string, section_name: string, item: PublicMenuItem, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpsertItemTranslationRequest = { name: string | null, description: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpsertSectionTranslationRequest = { name: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpsertRestaurantTranslationRequest = { name: string | null, address: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RestaurantTranslations = { default_language: string, languages: Array<string>, restaurant: Array<RestaurantTranslation>, sections: Array<SectionTranslation>, items: Array<ItemTranslation>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ItemTranslation = { item_id: string, locale: string, name: string | null, description: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SectionTranslation = { section_id: string, locale: string, name: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RestaurantTranslation = { locale: string, name: string | null, address: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CloneMenuRequest = { source_restaurant_id: string, price_multiplier: number | null, skip_unavailable: boolean | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuImportResult = { mode: string, dry_run: boolean, sections_created: number, sections_updated: number, sections_removed: number, items_created: number, items_updated: number, items_removed: number, errors: Array<MenuImportError>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuImportError = { row: number, field: string | null, message: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportMenuItem = { name: string, description: string | null, price: number, available: boolean | null, display_order: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportMenuSection = { name: string, display_order: number | null, items: Array<ImportMenuItem>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportMenu = { sections: Array<ImportMenuSection>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuDiff = { added_sections: Array<MenuDiffEntry>, removed_sections: Array<MenuDiffEntry>, changed_sections: Array<MenuDiffEntry>, added_items: Array<MenuDiffEntry>, removed_items: Array<MenuDiffEntry>, changed_items: Array<MenuDiffEntry>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuDiffEntry = { id: string, name: string, changes: Array<FieldChange>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FieldChange = { field: string, before: string, after: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PublishMenuRequest = { note: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuVersionDetail = { version: MenuVersionSummary, menu: MenuSnapshot, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuVersionSummary = { id: string, version_number: number, note: string | null, published_by: string | null, published_at: string, is_live: boolean, section_count: number, item_count: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SnapshotItem = { id: string, name: string, description: string | null, price: number, available: boolean, display_order: number, variants: Array<SnapshotVariant>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SnapshotSection = { id: string, name: string, display_order: number, items: Array<SnapshotItem>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuSnapshot = { sections: Array<SnapshotSection>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RestockRequest = { quantity: number, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateStockRequest = { stock_quantity: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MenuItemStock = { item_id: string, section_id: string, name: string, stock_quantity: number | null, available: boolean, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateSectionScheduleRequest = { windows: Array<SectionScheduleWindow>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SectionScheduleResponse = { section_id: string, windows: Array<SectionScheduleWindow>, active_now: boolean, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SectionScheduleWindow = { day_of_week: number, start_time: string, end_time: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateClosureRequest = { closed_until: string | null, reason: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpsertSpecialDayRequest = { closed: boolean, windows: Array<DailyWindow>, note: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UpdateOpeningHoursRequest = { weekly: Array<WeeklyOpeningWindow>, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OpeningHoursResponse = { timezone: string, weekly: Array<WeeklyOpeningWindow>, special_days: Array<SpecialDay>, closed_until: string | null, closure_reason: string | null, availability: OrderingAvailability, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpecialDay = { date: string, closed: boolean, windows: Array<DailyWindow>, note: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DailyWindow = { open_time: string, close_time: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WeeklyOpeningWindow = { day_of_week: number, open_time: string, close_time: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrderingAvailability = { is_open: boolean, reason: string | null, closure_message: string | null, next_open_at: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RefundPaymentRequest = { amount: number | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RefundPaymentResponse = { order_id: string, refund_id: string, amount: number, refunded_total: number, payment_status: string, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrderItemResponse = { menu_item_id: string, menu_item_name: string, quantity: number, price: number, special_requests: string | null, variant_id: string | null, variant_name: string | null, bundle_id: string | null, bundle_name: string | null, pricing_rule_id: string | null, pricing_rule_name: string | null, };
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrderResponse = { id: string, order_number: number | null, table_id: string, table_name: string, restaurant_name: string, items: Array<OrderItemResponse>, total_amount: number, status: string, payment_status: string, customer_name: string | null, discount: OrderDiscount | null, created_at: string, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
@@ -44,7 +436,7 @@ export type RestaurantMenu = { restaurant_id: string, sections: Array<MenuSectio
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type PublicMenuItem = { id: string, name: string, description: string | null, price: number, };
+export type PublicMenuItem = { id: string, name: string, description: string | null, price: number, original_price: number, pricing_rule: string | null, variants: Array<PublicMenuVariant>, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
@@ -56,11 +448,11 @@ export type PublicRestaurantInfo = { name: string, address: string | null, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type PublicMenu = { restaurant: PublicRestaurantInfo, sections: Array<PublicMenuSection>, };
+export type PublicMenu = { restaurant: PublicRestaurantInfo, sections: Array<PublicMenuSection>, availability: OrderingAvailability, language: string, available_languages: Array<string>, bundles: Array<PublicBundle>, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type OrderItem = { menu_item_id: string, quantity: number, price: number, notes: string | null, };
+export type OrderItem = { menu_item_id: string, quantity: number, price: number, notes: string | null, variant_id: string | null, variant_name: string | null, bundle_id: string | null, bundle_name: string | null, pricing_rule_id: string | null, pricing_rule_name: string | null, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
@@ -72,7 +464,7 @@ export type Table = { id: string, restaurant_id: string, name: string, unique_co
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Restaurant = { id: string, name: string, address: string | null, establishment_year: number | null, google_maps_link: string | null, created_at: string, };
+export type Restaurant = { id: string, name: string, address: string | null, establishment_year: number | null, google_maps_link: string | null, timezone: string, default_language: string, created_at: string, };
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
@@ -80,5 +472,5 @@ export type MenuSection = { id: string, restaurant_id: string, name: string, dis
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type MenuItem = { id: string, section_id: string, name: string, description: string | null, price: number, available: boolean, display_order: number, created_at: string, };
+export type MenuItem = { id: string, section_id: string, name: string, description: string | null, price: number, available: boolean, display_order: number, stock_quantity: number | null, created_at: string, variants: Array<MenuItemVariant>, };
 
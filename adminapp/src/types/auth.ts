@@ -10,6 +10,8 @@ export type User = {
 
 export type AuthResponse = {
   token: string;
+  refresh_token: string;
+  expires_in: number;
   user: User;
 };
 
@@ -2,7 +2,49 @@ import {
   MenuSection as GeneratedMenuSection, 
   MenuItem as GeneratedMenuItem,
   MenuSectionWithItems as GeneratedMenuSectionWithItems,
-  RestaurantMenu as GeneratedRestaurantMenu
+  RestaurantMenu as GeneratedRestaurantMenu,
+  PublicMenu,
+  SectionScheduleWindow,
+  SectionScheduleResponse,
+  UpdateSectionScheduleRequest,
+  MenuItemStock,
+  UpdateStockRequest,
+  RestockRequest,
+  MenuSnapshot,
+  MenuVersionSummary,
+  MenuVersionDetail,
+  PublishMenuRequest,
+  MenuDiff,
+  ImportMenu,
+  MenuImportError,
+  MenuImportResult,
+  CloneMenuRequest,
+  RestaurantTranslations,
+  RestaurantTranslation,
+  SectionTranslation,
+  ItemTranslation,
+  UpsertRestaurantTranslationRequest,
+  UpsertSectionTranslationRequest,
+  UpsertItemTranslationRequest,
+  MenuItemVariant,
+  CreateMenuItemVariantRequest,
+  UpdateMenuItemVariantRequest,
+  MenuBundle,
+  MenuBundleSlot,
+  BundleSlotRequest,
+  CreateBundleRequest,
+  UpdateBundleRequest,
+  PricingRule,
+  PricingRuleWindow,
+  CreatePricingRuleRequest,
+  UpdatePricingRuleRequest,
+  Coupon,
+  CreateCouponRequest,
+  UpdateCouponRequest,
+  KitchenStation,
+  CreateKitchenStationRequest,
+  UpdateKitchenStationRequest,
+  StationLine
 } from './api';
 
 // Use generated types
@@ -58,6 +100,67 @@ export interface ToggleAvailabilityRequest {
 export type MenuSectionWithItems = GeneratedMenuSectionWithItems;
 export type RestaurantMenu = GeneratedRestaurantMenu;
 
+// Section schedules and guest menu preview
+export type { SectionScheduleWindow, SectionScheduleResponse, UpdateSectionScheduleRequest };
+export type MenuPreview = PublicMenu;
+
+// Inventory
+export type { MenuItemStock, UpdateStockRequest, RestockRequest };
+
+// Variants
+export type {
+  MenuItemVariant,
+  CreateMenuItemVariantRequest,
+  UpdateMenuItemVariantRequest,
+};
+
+// Bundles
+export type {
+  MenuBundle,
+  MenuBundleSlot,
+  BundleSlotRequest,
+  CreateBundleRequest,
+  UpdateBundleRequest,
+};
+
+// Pricing rules
+export type {
+  PricingRule,
+  PricingRuleWindow,
+  CreatePricingRuleRequest,
+  UpdatePricingRuleRequest,
+};
+
+// Coupons
+export type { Coupon, CreateCouponRequest, UpdateCouponRequest };
+
+// Kitchen stations and their display feed
+export type {
+  KitchenStation,
+  CreateKitchenStationRequest,
+  UpdateKitchenStationRequest,
+  StationLine,
+};
+
+// Draft/publish workflow
+export type { MenuSnapshot, MenuVersionSummary, MenuVersionDetail, PublishMenuRequest, MenuDiff };
+
+// Import/export
+export type { ImportMenu, MenuImportError, MenuImportResult, CloneMenuRequest };
+
+// Translations
+export type {
+  RestaurantTranslations,
+  RestaurantTranslation,
+  SectionTranslation,
+  ItemTranslation,
+  UpsertRestaurantTranslationRequest,
+  UpsertSectionTranslationRequest,
+  UpsertItemTranslationRequest,
+};
+export type MenuFileFormat = 'json' | 'csv';
+export type MenuImportMode = 'merge' | 'replace';
+
 // Frontend-specific types
 export interface MenuState {
   sections: MenuSectionWithItems[];
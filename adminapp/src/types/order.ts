@@ -29,6 +29,26 @@ export interface OrderFilters {
   search?: string;
 }
 
+// Query for the paginated order list; dates are restaurant business days
+export interface OrderListParams {
+  cursor?: string;
+  limit?: number;
+  sort?: 'created_at' | 'order_number' | 'total_amount';
+  order?: 'asc' | 'desc';
+  status?: OrderStatus;
+  table_id?: string;
+  from?: string;
+  to?: string;
+  customer?: string;
+}
+
+export type SalesReportBreakdown =
+  | 'summary'
+  | 'items'
+  | 'sections'
+  | 'tables'
+  | 'hours';
+
 export interface OrderStats {
   total_orders: number;
   total_revenue: number;
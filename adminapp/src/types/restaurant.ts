@@ -7,6 +7,8 @@ export type Restaurant = {
   address?: string;
   establishment_year?: number;
   google_maps_link?: string;
+  timezone: string;
+  default_language: string;
   created_at: string;
 };
 
@@ -15,6 +17,8 @@ export interface CreateRestaurantRequest {
   address?: string;
   establishment_year?: number;
   google_maps_link?: string;
+  timezone?: string;
+  default_language?: string;
 }
 
 export interface UpdateRestaurantRequest {
@@ -22,6 +26,8 @@ export interface UpdateRestaurantRequest {
   address?: string;
   establishment_year?: number;
   google_maps_link?: string;
+  timezone?: string;
+  default_language?: string;
 }
 
 export interface ManagerInfo {
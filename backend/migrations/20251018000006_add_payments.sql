@@ -0,0 +1,24 @@
+-- Add online prepayment support
+
+-- Track payment state alongside the kitchen status of an order
+ALTER TABLE orders ADD COLUMN payment_status TEXT NOT NULL DEFAULT 'not_required' CHECK (payment_status IN ('not_required', 'awaiting_payment', 'paid', 'partially_refunded', 'refunded'));
+
+-- Create payments table (one row per provider payment intent)
+CREATE TABLE payments (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    order_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    provider_intent_id TEXT NOT NULL UNIQUE,
+    amount REAL NOT NULL,
+    currency TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'requires_confirmation' CHECK (status IN ('requires_confirmation', 'succeeded', 'canceled')),
+    refunded_amount REAL NOT NULL DEFAULT 0,
+    failure_reason TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE
+);
+
+-- Create indexes
+CREATE INDEX idx_payments_order_id ON payments(order_id);
+CREATE INDEX idx_orders_payment_status ON orders(payment_status);
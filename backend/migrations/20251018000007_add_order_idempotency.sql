@@ -0,0 +1,8 @@
+-- Add idempotency keys so retried order submissions do not create duplicates
+
+ALTER TABLE orders ADD COLUMN idempotency_key TEXT;
+ALTER TABLE orders ADD COLUMN idempotency_fingerprint TEXT; -- SHA-256 of the request payload
+ALTER TABLE orders ADD COLUMN idempotency_response TEXT; -- JSON of the original CreateOrderResponse
+
+-- Keys are scoped to the table the order was placed from
+CREATE UNIQUE INDEX idx_orders_idempotency_key ON orders(table_id, idempotency_key) WHERE idempotency_key IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Add human-friendly order numbers that restart every business day
+
+ALTER TABLE orders ADD COLUMN order_number INTEGER;
+ALTER TABLE orders ADD COLUMN business_date TEXT; -- YYYY-MM-DD of the restaurant's local day
+
+-- Last number handed out per restaurant and business day
+CREATE TABLE order_number_counters (
+    restaurant_id TEXT NOT NULL,
+    business_date TEXT NOT NULL,
+    last_number INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (restaurant_id, business_date),
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_orders_business_date ON orders(business_date);
@@ -0,0 +1,2 @@
+-- Add an IANA timezone to restaurants so business days follow local time
+ALTER TABLE restaurants ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
@@ -0,0 +1,35 @@
+-- Add opening hours, special days and temporary closures to restaurants
+
+-- Weekly opening windows in the restaurant's timezone. A close_time that is not
+-- after open_time runs past midnight. No rows means the restaurant is always open.
+CREATE TABLE restaurant_opening_hours (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    day_of_week INTEGER NOT NULL CHECK (day_of_week BETWEEN 0 AND 6), -- 0 = Monday
+    open_time TEXT NOT NULL, -- HH:MM
+    close_time TEXT NOT NULL, -- HH:MM
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+-- Dates whose hours replace the weekly schedule (holidays, special events).
+-- A closed day has a single row with closed = TRUE and no times.
+CREATE TABLE restaurant_special_days (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    date TEXT NOT NULL, -- YYYY-MM-DD
+    closed BOOLEAN NOT NULL DEFAULT FALSE,
+    open_time TEXT,
+    close_time TEXT,
+    note TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+-- Temporary closure, e.g. "kitchen closed until 7pm"
+ALTER TABLE restaurants ADD COLUMN closed_until DATETIME;
+ALTER TABLE restaurants ADD COLUMN closure_reason TEXT;
+
+-- Create indexes
+CREATE INDEX idx_restaurant_opening_hours_restaurant_id ON restaurant_opening_hours(restaurant_id);
+CREATE INDEX idx_restaurant_special_days_restaurant_date ON restaurant_special_days(restaurant_id, date);
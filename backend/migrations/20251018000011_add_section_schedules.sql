@@ -0,0 +1,14 @@
+-- Time windows during which a menu section is offered (breakfast, happy hour).
+-- Times are in the restaurant's timezone and an end_time that is not after
+-- start_time runs past midnight. Sections without rows are always offered.
+CREATE TABLE menu_section_schedules (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    section_id TEXT NOT NULL,
+    day_of_week INTEGER NOT NULL CHECK (day_of_week BETWEEN 0 AND 6), -- 0 = Monday
+    start_time TEXT NOT NULL, -- HH:MM
+    end_time TEXT NOT NULL, -- HH:MM
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (section_id) REFERENCES menu_sections(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_menu_section_schedules_section_id ON menu_section_schedules(section_id);
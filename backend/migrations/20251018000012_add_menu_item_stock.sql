@@ -0,0 +1,3 @@
+-- Optional stock count per menu item. NULL means stock is not tracked; tracked
+-- items are decremented when ordered and marked unavailable when they reach 0.
+ALTER TABLE menu_items ADD COLUMN stock_quantity INTEGER CHECK (stock_quantity IS NULL OR stock_quantity >= 0);
@@ -0,0 +1,25 @@
+-- Published menu versions. The menu_sections and menu_items tables act as the
+-- draft; publishing copies them into an immutable JSON snapshot that guests see.
+CREATE TABLE menu_versions (
+    id TEXT PRIMARY KEY,
+    restaurant_id TEXT NOT NULL,
+    version_number INTEGER NOT NULL,
+    snapshot TEXT NOT NULL, -- JSON MenuSnapshot
+    note TEXT,
+    published_by TEXT,
+    published_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE,
+    UNIQUE (restaurant_id, version_number)
+);
+
+CREATE TRIGGER menu_versions_immutable
+BEFORE UPDATE ON menu_versions
+BEGIN
+    SELECT RAISE(ABORT, 'menu versions are immutable');
+END;
+
+-- Version currently served to guests. NULL means nothing has been published
+-- yet and guests see the draft tables directly.
+ALTER TABLE restaurants ADD COLUMN live_menu_version_id TEXT;
+
+CREATE INDEX idx_menu_versions_restaurant_id ON menu_versions(restaurant_id);
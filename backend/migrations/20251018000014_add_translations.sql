@@ -0,0 +1,32 @@
+-- Per-locale copies of guest-facing text. The base columns stay in the
+-- restaurant's default language; missing translated fields fall back to them.
+ALTER TABLE restaurants ADD COLUMN default_language TEXT NOT NULL DEFAULT 'en';
+
+CREATE TABLE restaurant_translations (
+    restaurant_id TEXT NOT NULL,
+    locale TEXT NOT NULL,
+    name TEXT,
+    address TEXT,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (restaurant_id, locale),
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+CREATE TABLE menu_section_translations (
+    section_id TEXT NOT NULL,
+    locale TEXT NOT NULL,
+    name TEXT,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (section_id, locale),
+    FOREIGN KEY (section_id) REFERENCES menu_sections(id) ON DELETE CASCADE
+);
+
+CREATE TABLE menu_item_translations (
+    item_id TEXT NOT NULL,
+    locale TEXT NOT NULL,
+    name TEXT,
+    description TEXT,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (item_id, locale),
+    FOREIGN KEY (item_id) REFERENCES menu_items(id) ON DELETE CASCADE
+);
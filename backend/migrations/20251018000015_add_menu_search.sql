@@ -0,0 +1,67 @@
+-- Full-text index over menu item text in every language. Triggers keep it in
+-- sync with menu_items and menu_item_translations, whichever code path writes.
+CREATE VIRTUAL TABLE menu_item_search USING fts5(
+    item_id UNINDEXED,
+    name,
+    description,
+    translated_names,
+    translated_descriptions,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO menu_item_search (item_id, name, description, translated_names, translated_descriptions)
+SELECT mi.id, mi.name, mi.description,
+       (SELECT group_concat(t.name, ' ') FROM menu_item_translations t WHERE t.item_id = mi.id),
+       (SELECT group_concat(t.description, ' ') FROM menu_item_translations t WHERE t.item_id = mi.id)
+FROM menu_items mi;
+
+-- Rows that are about to fail their foreign key check are skipped so the
+-- failed statement never holds a lock on the index
+CREATE TRIGGER menu_items_search_insert
+AFTER INSERT ON menu_items
+WHEN EXISTS (SELECT 1 FROM menu_sections WHERE id = NEW.section_id)
+BEGIN
+    INSERT INTO menu_item_search (item_id, name, description)
+    VALUES (NEW.id, NEW.name, NEW.description);
+END;
+
+CREATE TRIGGER menu_items_search_update
+AFTER UPDATE OF name, description ON menu_items
+BEGIN
+    UPDATE menu_item_search
+    SET name = NEW.name, description = NEW.description
+    WHERE item_id = NEW.id;
+END;
+
+CREATE TRIGGER menu_items_search_delete
+AFTER DELETE ON menu_items
+BEGIN
+    DELETE FROM menu_item_search WHERE item_id = OLD.id;
+END;
+
+CREATE TRIGGER menu_item_translations_search_insert
+AFTER INSERT ON menu_item_translations
+BEGIN
+    UPDATE menu_item_search
+    SET translated_names = (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id),
+        translated_descriptions = (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id)
+    WHERE item_id = NEW.item_id;
+END;
+
+CREATE TRIGGER menu_item_translations_search_update
+AFTER UPDATE ON menu_item_translations
+BEGIN
+    UPDATE menu_item_search
+    SET translated_names = (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id),
+        translated_descriptions = (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id)
+    WHERE item_id = NEW.item_id;
+END;
+
+CREATE TRIGGER menu_item_translations_search_delete
+AFTER DELETE ON menu_item_translations
+BEGIN
+    UPDATE menu_item_search
+    SET translated_names = (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = OLD.item_id),
+        translated_descriptions = (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = OLD.item_id)
+    WHERE item_id = OLD.item_id;
+END;
@@ -0,0 +1,14 @@
+-- Sizes and other variants of a menu item, each with its own price. Orders
+-- reference the parent item and the chosen variant.
+CREATE TABLE menu_item_variants (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    item_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    price DECIMAL(10,2) NOT NULL,
+    available BOOLEAN NOT NULL DEFAULT TRUE,
+    display_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (item_id) REFERENCES menu_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_menu_item_variants_item_id ON menu_item_variants(item_id);
@@ -0,0 +1,34 @@
+-- Meal deals sold at one price. Each bundle has slots (main, side, drink)
+-- and every slot lists the menu items a guest may pick for it.
+CREATE TABLE menu_bundles (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    price DECIMAL(10,2) NOT NULL,
+    available BOOLEAN NOT NULL DEFAULT TRUE,
+    display_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+CREATE TABLE menu_bundle_slots (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    bundle_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    display_order INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (bundle_id) REFERENCES menu_bundles(id) ON DELETE CASCADE
+);
+
+CREATE TABLE menu_bundle_slot_choices (
+    slot_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    display_order INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (slot_id, item_id),
+    FOREIGN KEY (slot_id) REFERENCES menu_bundle_slots(id) ON DELETE CASCADE,
+    FOREIGN KEY (item_id) REFERENCES menu_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_menu_bundles_restaurant_id ON menu_bundles(restaurant_id);
+CREATE INDEX idx_menu_bundle_slots_bundle_id ON menu_bundle_slots(bundle_id);
+CREATE INDEX idx_menu_bundle_slot_choices_item_id ON menu_bundle_slot_choices(item_id);
@@ -0,0 +1,29 @@
+-- Scheduled price adjustments (happy hour, weekday lunch). A rule targets the
+-- whole restaurant, one section or one item and changes the price either by a
+-- percentage or by a fixed amount; negative values are discounts. Rules without
+-- windows apply at all times while active.
+CREATE TABLE pricing_rules (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK (scope IN ('restaurant', 'section', 'item')),
+    target_id TEXT, -- section or item id, NULL for restaurant-wide rules
+    adjustment_type TEXT NOT NULL CHECK (adjustment_type IN ('percentage', 'fixed')),
+    adjustment_value DECIMAL(10,2) NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+-- Same layout and rules as menu_section_schedules
+CREATE TABLE pricing_rule_windows (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    rule_id TEXT NOT NULL,
+    day_of_week INTEGER NOT NULL CHECK (day_of_week BETWEEN 0 AND 6), -- 0 = Monday
+    start_time TEXT NOT NULL, -- HH:MM
+    end_time TEXT NOT NULL, -- HH:MM
+    FOREIGN KEY (rule_id) REFERENCES pricing_rules(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_pricing_rules_restaurant_id ON pricing_rules(restaurant_id);
+CREATE INDEX idx_pricing_rule_windows_rule_id ON pricing_rule_windows(rule_id);
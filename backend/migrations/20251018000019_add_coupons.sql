@@ -0,0 +1,26 @@
+-- Discount codes entered at checkout. Codes are stored upper-case and are
+-- unique per restaurant; item_id narrows the discount to one menu item.
+CREATE TABLE coupons (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    code TEXT NOT NULL,
+    discount_type TEXT NOT NULL CHECK (discount_type IN ('percentage', 'fixed')),
+    discount_value DECIMAL(10,2) NOT NULL,
+    min_order_amount DECIMAL(10,2),
+    valid_from DATETIME,
+    valid_until DATETIME,
+    max_uses INTEGER,
+    times_used INTEGER NOT NULL DEFAULT 0,
+    item_id TEXT,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (restaurant_id, code),
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE,
+    FOREIGN KEY (item_id) REFERENCES menu_items(id) ON DELETE CASCADE
+);
+
+-- total_amount stays what the guest pays; the discount is kept beside it so
+-- gross sales can be reconstructed
+ALTER TABLE orders ADD COLUMN coupon_id TEXT;
+ALTER TABLE orders ADD COLUMN coupon_code TEXT;
+ALTER TABLE orders ADD COLUMN discount_amount DECIMAL(10,2) NOT NULL DEFAULT 0;
@@ -0,0 +1,45 @@
+-- End-of-day closings (Z reports). Closing a business day freezes its summary;
+-- reopening keeps the row for the record and only a new closing replaces it.
+CREATE TABLE day_closings (
+    id TEXT PRIMARY KEY,
+    restaurant_id TEXT NOT NULL,
+    business_date TEXT NOT NULL, -- YYYY-MM-DD of the restaurant's local day
+    summary TEXT NOT NULL, -- JSON ClosingSummary
+    closed_by TEXT NOT NULL,
+    closed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    reopened_by TEXT,
+    reopened_at DATETIME,
+    reopen_reason TEXT,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+-- At most one closing is in force per restaurant and day
+CREATE UNIQUE INDEX idx_day_closings_open
+    ON day_closings(restaurant_id, business_date) WHERE reopened_at IS NULL;
+
+CREATE TRIGGER day_closings_immutable
+BEFORE UPDATE OF restaurant_id, business_date, summary, closed_by, closed_at ON day_closings
+BEGIN
+    SELECT RAISE(ABORT, 'day closings are immutable');
+END;
+
+-- Every close and reopen, never edited
+CREATE TABLE day_closing_audit (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    closing_id TEXT NOT NULL,
+    restaurant_id TEXT NOT NULL,
+    business_date TEXT NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('closed', 'reopened')),
+    user_id TEXT NOT NULL,
+    reason TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER day_closing_audit_immutable
+BEFORE UPDATE ON day_closing_audit
+BEGIN
+    SELECT RAISE(ABORT, 'the closing audit log is append-only');
+END;
+
+CREATE INDEX idx_day_closing_audit_restaurant ON day_closing_audit(restaurant_id, business_date);
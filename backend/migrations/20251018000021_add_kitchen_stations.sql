@@ -0,0 +1,37 @@
+-- Stations (bar, grill, pastry...) that order lines are routed to. An item's
+-- own station wins over the station of its section.
+CREATE TABLE kitchen_stations (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    display_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (restaurant_id, name),
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+ALTER TABLE menu_sections ADD COLUMN station_id TEXT REFERENCES kitchen_stations(id) ON DELETE SET NULL;
+ALTER TABLE menu_items ADD COLUMN station_id TEXT REFERENCES kitchen_stations(id) ON DELETE SET NULL;
+
+-- One row per order line, routed when the order is placed. Lines without a
+-- station keep station_id NULL and only show on the all-stations view.
+CREATE TABLE order_station_lines (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    order_id TEXT NOT NULL,
+    line_index INTEGER NOT NULL,
+    station_id TEXT,
+    menu_item_id TEXT NOT NULL,
+    variant_name TEXT,
+    notes TEXT,
+    quantity INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'bumped')),
+    created_at DATETIME NOT NULL,
+    bumped_at DATETIME,
+    bumped_by TEXT,
+    recall_count INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (order_id, line_index),
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (station_id) REFERENCES kitchen_stations(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_order_station_lines_station ON order_station_lines(station_id, status);
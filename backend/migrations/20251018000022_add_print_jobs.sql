@@ -0,0 +1,25 @@
+-- Rendered ESC/POS documents waiting for a restaurant's local print agent.
+-- The payload is rendered when the job is queued, so a reprint matches the
+-- original even if the menu changes. Jobs claimed but never acknowledged
+-- become claimable again after a timeout.
+CREATE TABLE print_jobs (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    order_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('ticket', 'receipt')),
+    station_id TEXT,
+    width INTEGER NOT NULL CHECK (width IN (58, 80)),
+    payload BLOB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'printing', 'printed', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_by TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    claimed_at DATETIME,
+    printed_at DATETIME,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (station_id) REFERENCES kitchen_stations(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_print_jobs_queue ON print_jobs(restaurant_id, status, created_at);
@@ -0,0 +1,18 @@
+-- Per-restaurant keys for machine integrations. Only the SHA-256 of a key is
+-- stored; key_prefix is kept in clear so a key can be recognised in the list.
+-- scopes is a JSON array. Revoked keys are kept for the audit trail.
+CREATE TABLE api_keys (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    restaurant_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT NOT NULL,
+    created_by TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at DATETIME,
+    revoked_at DATETIME,
+    FOREIGN KEY (restaurant_id) REFERENCES restaurants(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_api_keys_restaurant ON api_keys(restaurant_id);
@@ -0,0 +1,6 @@
+-- Set when an item is switched off because its stock ran out, so restocking
+-- or returned stock only switches it back on in that case and never overrides
+-- a manager who turned the item off by hand.
+ALTER TABLE menu_items ADD COLUMN sold_out BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE menu_items SET sold_out = TRUE WHERE stock_quantity = 0 AND available = FALSE;
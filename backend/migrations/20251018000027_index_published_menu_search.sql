@@ -0,0 +1,105 @@
+-- Rebuilds the menu search index over the menu guests see instead of the
+-- draft. Item text comes from the live menu version and is rebuilt whenever a
+-- version is published or rolled back to. Until a restaurant's first publish
+-- guests see the draft, so the menu_items triggers only index restaurants
+-- without a live version. Translations are not versioned: guests see edits
+-- right away, so the translation triggers keep every restaurant's rows in
+-- sync and new translated text is searchable without a publish.
+DROP TRIGGER menu_items_search_insert;
+DROP TRIGGER menu_items_search_update;
+DROP TRIGGER menu_items_search_delete;
+DROP TRIGGER menu_item_translations_search_insert;
+DROP TRIGGER menu_item_translations_search_update;
+DROP TRIGGER menu_item_translations_search_delete;
+DROP TABLE menu_item_search;
+
+CREATE VIRTUAL TABLE menu_item_search USING fts5(
+    item_id UNINDEXED,
+    restaurant_id UNINDEXED,
+    name,
+    description,
+    translated_names,
+    translated_descriptions,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO menu_item_search (item_id, restaurant_id, name, description, translated_names, translated_descriptions)
+SELECT mi.id, ms.restaurant_id, mi.name, mi.description,
+       (SELECT group_concat(t.name, ' ') FROM menu_item_translations t WHERE t.item_id = mi.id),
+       (SELECT group_concat(t.description, ' ') FROM menu_item_translations t WHERE t.item_id = mi.id)
+FROM menu_items mi
+JOIN menu_sections ms ON ms.id = mi.section_id
+JOIN restaurants r ON r.id = ms.restaurant_id
+WHERE r.live_menu_version_id IS NULL;
+
+INSERT INTO menu_item_search (item_id, restaurant_id, name, description, translated_names, translated_descriptions)
+SELECT json_extract(item.value, '$.id'), r.id,
+       json_extract(item.value, '$.name'), json_extract(item.value, '$.description'),
+       (SELECT group_concat(t.name, ' ') FROM menu_item_translations t WHERE t.item_id = json_extract(item.value, '$.id')),
+       (SELECT group_concat(t.description, ' ') FROM menu_item_translations t WHERE t.item_id = json_extract(item.value, '$.id'))
+FROM restaurants r
+JOIN menu_versions v ON v.id = r.live_menu_version_id,
+     json_each(v.snapshot, '$.sections') AS section,
+     json_each(section.value, '$.items') AS item;
+
+-- The join finds no section for an item whose section_id is invalid, so no
+-- index row is written; the insert itself then fails its foreign key check
+CREATE TRIGGER menu_items_search_insert
+AFTER INSERT ON menu_items
+BEGIN
+    INSERT INTO menu_item_search (item_id, restaurant_id, name, description)
+    SELECT NEW.id, ms.restaurant_id, NEW.name, NEW.description
+    FROM menu_sections ms
+    JOIN restaurants r ON r.id = ms.restaurant_id
+    WHERE ms.id = NEW.section_id AND r.live_menu_version_id IS NULL;
+END;
+
+CREATE TRIGGER menu_items_search_update
+AFTER UPDATE OF name, description ON menu_items
+BEGIN
+    UPDATE menu_item_search
+    SET name = NEW.name, description = NEW.description
+    WHERE item_id = NEW.id
+      AND restaurant_id IN (SELECT id FROM restaurants WHERE live_menu_version_id IS NULL);
+END;
+
+CREATE TRIGGER menu_items_search_delete
+AFTER DELETE ON menu_items
+BEGIN
+    DELETE FROM menu_item_search
+    WHERE item_id = OLD.id
+      AND restaurant_id IN (SELECT id FROM restaurants WHERE live_menu_version_id IS NULL);
+END;
+
+CREATE TRIGGER restaurants_search_delete
+AFTER DELETE ON restaurants
+BEGIN
+    DELETE FROM menu_item_search WHERE restaurant_id = OLD.id;
+END;
+
+CREATE TRIGGER menu_item_translations_search_insert
+AFTER INSERT ON menu_item_translations
+BEGIN
+    UPDATE menu_item_search
+    SET translated_names = (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id),
+        translated_descriptions = (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id)
+    WHERE item_id = NEW.item_id;
+END;
+
+CREATE TRIGGER menu_item_translations_search_update
+AFTER UPDATE ON menu_item_translations
+BEGIN
+    UPDATE menu_item_search
+    SET translated_names = (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id),
+        translated_descriptions = (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = NEW.item_id)
+    WHERE item_id = NEW.item_id;
+END;
+
+CREATE TRIGGER menu_item_translations_search_delete
+AFTER DELETE ON menu_item_translations
+BEGIN
+    UPDATE menu_item_search
+    SET translated_names = (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = OLD.item_id),
+        translated_descriptions = (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = OLD.item_id)
+    WHERE item_id = OLD.item_id;
+END;
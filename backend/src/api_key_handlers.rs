@@ -0,0 +1,201 @@
+use crate::api_keys::{display_prefix, generate_api_key, API_KEY_SCOPES};
+use crate::email_handlers::hash_token;
+use crate::models::{ApiKey, Claims, CreateApiKeyRequest, CreatedApiKey};
+use crate::permissions::is_super_admin;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+use uuid::Uuid;
+
+const API_KEY_SELECT: &str =
+    "SELECT id, restaurant_id, name, key_prefix, scopes, created_at, last_used_at, revoked_at
+    FROM api_keys";
+
+fn validate_scopes(scopes: &[String]) -> Result<(), String> {
+    if scopes.is_empty() {
+        return Err("Grant at least one scope".to_string());
+    }
+    match scopes
+        .iter()
+        .find(|scope| !API_KEY_SCOPES.contains(&scope.as_str()))
+    {
+        Some(scope) => Err(format!(
+            "Unknown scope {scope}; scopes must be among: {}",
+            API_KEY_SCOPES.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+fn to_utc(at: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::from_naive_utc_and_offset(at, Utc)
+}
+
+fn api_key_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<ApiKey, sqlx::Error> {
+    let scopes: String = row.try_get("scopes")?;
+    let last_used_at: Option<NaiveDateTime> = row.try_get("last_used_at")?;
+    let revoked_at: Option<NaiveDateTime> = row.try_get("revoked_at")?;
+    Ok(ApiKey {
+        id: row.try_get("id")?,
+        restaurant_id: row.try_get("restaurant_id")?,
+        name: row.try_get("name")?,
+        key_prefix: row.try_get("key_prefix")?,
+        scopes: serde_json::from_str(&scopes).unwrap_or_default(),
+        created_at: to_utc(row.try_get("created_at")?),
+        last_used_at: last_used_at.map(to_utc),
+        revoked_at: revoked_at.map(to_utc),
+    })
+}
+
+async fn fetch_api_key(pool: &Pool<Sqlite>, key_id: &str) -> Result<Option<ApiKey>, sqlx::Error> {
+    let row = sqlx::query(&format!("{API_KEY_SELECT} WHERE id = ?"))
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+    row.as_ref().map(api_key_from_row).transpose()
+}
+
+// Revoked keys stay in the list so their last use remains visible
+pub async fn list_api_keys(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admins can manage API keys"
+        })));
+    }
+
+    let rows = sqlx::query(&format!(
+        "{API_KEY_SELECT} WHERE restaurant_id = ? ORDER BY created_at DESC, id"
+    ))
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rows.and_then(|rows| {
+        rows.iter()
+            .map(api_key_from_row)
+            .collect::<Result<Vec<_>, _>>()
+    }) {
+        Ok(keys) => Ok(HttpResponse::Ok().json(keys)),
+        Err(e) => {
+            log::error!("Database error listing API keys: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// The key is returned once; only its hash is stored
+pub async fn create_api_key(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admins can manage API keys"
+        })));
+    }
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Name is required"
+        })));
+    }
+    if let Err(e) = validate_scopes(&req.scopes) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": e })));
+    }
+
+    let key_id = Uuid::new_v4().to_string();
+    let key = generate_api_key();
+    let result = sqlx::query(
+        "INSERT INTO api_keys (id, restaurant_id, name, key_prefix, key_hash, scopes, created_by)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&key_id)
+    .bind(&restaurant_id)
+    .bind(name)
+    .bind(display_prefix(&key))
+    .bind(hash_token(&key))
+    .bind(serde_json::json!(req.scopes).to_string())
+    .bind(&claims.sub)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Database error creating API key: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create API key"
+        })));
+    }
+
+    match fetch_api_key(pool.get_ref(), &key_id).await {
+        Ok(Some(api_key)) => Ok(HttpResponse::Created().json(CreatedApiKey { api_key, key })),
+        Ok(None) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching API key: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Takes effect on the key's next request. Revoking twice is a no-op.
+pub async fn revoke_api_key(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let key_id = path.into_inner();
+
+    let api_key = match fetch_api_key(pool.get_ref(), &key_id).await {
+        Ok(Some(api_key)) => api_key,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "API key not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching API key: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !is_super_admin(pool.get_ref(), &api_key.restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admins can manage API keys"
+        })));
+    }
+
+    let result =
+        sqlx::query("UPDATE api_keys SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL")
+            .bind(Utc::now().naive_utc())
+            .bind(&key_id)
+            .execute(pool.get_ref())
+            .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error revoking API key: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to revoke API key"
+            })))
+        }
+    }
+}
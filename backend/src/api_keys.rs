@@ -0,0 +1,151 @@
+use crate::email_handlers::hash_token;
+use actix_web::dev::ResourceDef;
+use actix_web::http::Method;
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sqlx::{Pool, Row, Sqlite};
+
+// Keys are presented as bearer tokens; the prefix tells them apart from JWTs
+pub const API_KEY_PREFIX: &str = "lok_";
+
+pub const SCOPE_ORDERS_READ: &str = "orders:read";
+pub const SCOPE_ORDERS_STATUS: &str = "orders:status";
+pub const SCOPE_MENU_MANAGE: &str = "menu:manage";
+
+pub const API_KEY_SCOPES: [&str; 3] = [SCOPE_ORDERS_READ, SCOPE_ORDERS_STATUS, SCOPE_MENU_MANAGE];
+
+// The /api routes a key may call, by the scope each one needs. Keys are
+// refused everywhere else, so a new route stays manager-only until it is
+// listed here and its handler checks the key's restaurant.
+const ORDERS_READ_ROUTES: [(Method, &str); 8] = [
+    (Method::GET, "/api/restaurants/{id}/orders"),
+    (Method::GET, "/api/restaurants/{id}/orders/today"),
+    (Method::GET, "/api/restaurants/{id}/orders/export"),
+    (
+        Method::GET,
+        "/api/restaurants/{id}/orders/{order_id}/ticket",
+    ),
+    (
+        Method::GET,
+        "/api/restaurants/{id}/orders/{order_id}/receipt",
+    ),
+    (Method::GET, "/api/restaurants/{id}/print-jobs"),
+    (Method::POST, "/api/restaurants/{id}/print-jobs/claim"),
+    (Method::POST, "/api/print-jobs/{id}/ack"),
+];
+
+const ORDERS_STATUS_ROUTES: [(Method, &str); 1] = [(Method::PUT, "/api/orders/{order_id}/status")];
+
+const MENU_MANAGE_ROUTES: [(Method, &str); 13] = [
+    (Method::GET, "/api/restaurants/{id}/menu"),
+    (Method::GET, "/api/restaurants/{id}/menu/sections"),
+    (Method::POST, "/api/restaurants/{id}/menu/sections"),
+    (Method::PUT, "/api/sections/{id}"),
+    (Method::DELETE, "/api/sections/{id}"),
+    (Method::POST, "/api/sections/reorder"),
+    (Method::POST, "/api/sections/{id}/items"),
+    (Method::PUT, "/api/items/{id}"),
+    (Method::DELETE, "/api/items/{id}"),
+    (Method::PUT, "/api/items/{id}/availability"),
+    (Method::POST, "/api/items/reorder"),
+    (Method::POST, "/api/restaurants/{id}/menu/publish"),
+    (
+        Method::POST,
+        "/api/restaurants/{id}/menu/versions/{version_id}/rollback",
+    ),
+];
+
+// The scope a key needs to call a route, or None when keys may not call it
+pub fn route_scope(method: &Method, path: &str) -> Option<&'static str> {
+    [
+        (SCOPE_ORDERS_READ, &ORDERS_READ_ROUTES[..]),
+        (SCOPE_ORDERS_STATUS, &ORDERS_STATUS_ROUTES[..]),
+        (SCOPE_MENU_MANAGE, &MENU_MANAGE_ROUTES[..]),
+    ]
+    .into_iter()
+    .find(|(_, routes)| {
+        routes.iter().any(|(route_method, pattern)| {
+            route_method == method && ResourceDef::new(*pattern).is_match(path)
+        })
+    })
+    .map(|(scope, _)| scope)
+}
+
+// Characters of the key shown in listings, e.g. "lok_3f9a2c1d"
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+// last_used_at is only rewritten when it is older than this, so a busy
+// integration does not write on every request
+const LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
+// The key behind a request, stored in the request extensions next to the
+// synthetic Claims the middleware inserts for it
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth {
+    pub key_id: String,
+    pub restaurant_id: String,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKeyAuth {
+    // Claims.sub for key requests. It never matches a user, so manager checks
+    // fail unless a handler accepts the key explicitly. The middleware only
+    // lets a key through to the routes listed for its scopes.
+    pub fn subject(&self) -> String {
+        format!("api_key:{}", self.key_id)
+    }
+
+    pub fn allows(&self, restaurant_id: &str, scope: &str) -> bool {
+        self.restaurant_id == restaurant_id && self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+// For handlers that take `Option<web::ReqData<ApiKeyAuth>>`
+pub fn api_key_allows(api_key: Option<&ApiKeyAuth>, restaurant_id: &str, scope: &str) -> bool {
+    api_key.is_some_and(|key| key.allows(restaurant_id, scope))
+}
+
+pub fn generate_api_key() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("{API_KEY_PREFIX}{hex}")
+}
+
+pub fn display_prefix(key: &str) -> String {
+    key.chars().take(DISPLAY_PREFIX_LEN).collect()
+}
+
+// Looks up an unrevoked key and records that it was used
+pub async fn authenticate_api_key(
+    pool: &Pool<Sqlite>,
+    key: &str,
+) -> Result<Option<ApiKeyAuth>, sqlx::Error> {
+    let Some(row) = sqlx::query(
+        "SELECT id, restaurant_id, scopes FROM api_keys WHERE key_hash = ? AND revoked_at IS NULL",
+    )
+    .bind(hash_token(key))
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let scopes: String = row.try_get("scopes")?;
+    let auth = ApiKeyAuth {
+        key_id: row.try_get("id")?,
+        restaurant_id: row.try_get("restaurant_id")?,
+        scopes: serde_json::from_str(&scopes).unwrap_or_default(),
+    };
+
+    let now = Utc::now().naive_utc();
+    sqlx::query(
+        "UPDATE api_keys SET last_used_at = ?
+         WHERE id = ? AND (last_used_at IS NULL OR last_used_at < ?)",
+    )
+    .bind(now)
+    .bind(&auth.key_id)
+    .bind(now - Duration::seconds(LAST_USED_RESOLUTION_SECONDS))
+    .execute(pool)
+    .await?;
+
+    Ok(Some(auth))
+}
@@ -1,5 +1,7 @@
+use crate::api_keys::{authenticate_api_key, route_scope, API_KEY_PREFIX};
 use crate::models::{Claims, User, UserResponse};
-use actix_web::{dev::ServiceRequest, Error, HttpMessage};
+use crate::sessions::session_is_active;
+use actix_web::{dev::ServiceRequest, error::InternalError, Error, HttpMessage, HttpResponse};
 use actix_web_httpauth::extractors::bearer::{BearerAuth, Config};
 use actix_web_httpauth::extractors::AuthenticationError;
 use argon2::{
@@ -11,6 +13,7 @@ use argon2::{
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use rand_core::OsRng;
+use sqlx::{Pool, Sqlite};
 
 pub struct PasswordHasher;
 
@@ -40,26 +43,36 @@ impl PasswordHasher {
 #[derive(Clone)]
 pub struct JwtManager {
     secret: String,
-    expiration_hours: u64,
+    access_token_minutes: u64,
 }
 
 impl JwtManager {
-    pub fn new(secret: String, expiration_hours: u64) -> Self {
+    pub fn new(secret: String, access_token_minutes: u64) -> Self {
         Self {
             secret,
-            expiration_hours,
+            access_token_minutes,
         }
     }
 
-    pub fn generate_token(&self, user: &User) -> Result<String, jsonwebtoken::errors::Error> {
+    // Seconds an access token stays valid, as reported to clients
+    pub fn expires_in(&self) -> u32 {
+        (self.access_token_minutes * 60) as u32
+    }
+
+    pub fn generate_token(
+        &self,
+        user: &User,
+        session_id: &str,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
-        let exp = now + Duration::hours(self.expiration_hours as i64);
+        let exp = now + Duration::minutes(self.access_token_minutes as i64);
 
         let claims = Claims {
             sub: user.id.clone(),
             email: user.email.clone(),
             exp: exp.timestamp() as usize,
             iat: now.timestamp() as usize,
+            sid: Some(session_id.to_string()),
         };
 
         encode(
@@ -79,10 +92,18 @@ impl JwtManager {
     }
 }
 
+// Accepts either a manager's JWT or a restaurant API key. A JWT is only
+// accepted while the session it was issued for is live. A key is only accepted
+// on the routes listed in api_keys for a scope it holds; it then gets Claims
+// whose subject is not a user, plus an ApiKeyAuth describing the key.
 pub async fn jwt_validator(
     req: ServiceRequest,
     credentials: BearerAuth,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    if credentials.token().starts_with(API_KEY_PREFIX) {
+        return api_key_validator(req, credentials.token()).await;
+    }
+
     let jwt_manager = match req.app_data::<actix_web::web::Data<JwtManager>>() {
         Some(manager) => manager,
         None => {
@@ -91,12 +112,77 @@ pub async fn jwt_validator(
         }
     };
 
-    match jwt_manager.validate_token(credentials.token()) {
-        Ok(claims) => {
-            req.extensions_mut().insert(claims);
+    let claims = match jwt_manager.validate_token(credentials.token()) {
+        Ok(claims) => claims,
+        Err(_) => {
+            let config = Config::default().realm("Restricted area");
+            return Err((AuthenticationError::from(config).into(), req));
+        }
+    };
+
+    let active = match (
+        claims.sid.as_deref(),
+        req.app_data::<actix_web::web::Data<Pool<Sqlite>>>(),
+    ) {
+        (Some(session_id), Some(pool)) => {
+            match session_is_active(pool.get_ref(), session_id, &claims.sub).await {
+                Ok(active) => active,
+                Err(e) => {
+                    log::error!("Database error checking session: {e}");
+                    false
+                }
+            }
+        }
+        _ => false,
+    };
+
+    if active {
+        req.extensions_mut().insert(claims);
+        Ok(req)
+    } else {
+        let config = Config::default().realm("Restricted area");
+        Err((AuthenticationError::from(config).into(), req))
+    }
+}
+
+async fn api_key_validator(
+    req: ServiceRequest,
+    key: &str,
+) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    let auth = match req.app_data::<actix_web::web::Data<Pool<Sqlite>>>() {
+        Some(pool) => match authenticate_api_key(pool.get_ref(), key).await {
+            Ok(auth) => auth,
+            Err(e) => {
+                log::error!("Database error checking API key: {e}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    match auth {
+        Some(auth)
+            if !route_scope(req.method(), req.path())
+                .is_some_and(|scope| auth.scopes.iter().any(|s| s == scope)) =>
+        {
+            let response = HttpResponse::Forbidden().json(serde_json::json!({
+                "error": "API key not allowed for this route"
+            }));
+            Err((InternalError::from_response("", response).into(), req))
+        }
+        Some(auth) => {
+            let now = Utc::now().timestamp() as usize;
+            req.extensions_mut().insert(Claims {
+                sub: auth.subject(),
+                email: String::new(),
+                exp: now,
+                iat: now,
+                sid: None,
+            });
+            req.extensions_mut().insert(auth);
             Ok(req)
         }
-        Err(_) => {
+        None => {
             let config = Config::default().realm("Restricted area");
             Err((AuthenticationError::from(config).into(), req))
         }
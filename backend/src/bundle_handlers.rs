@@ -0,0 +1,571 @@
+use crate::models::{
+    BundleSlotRequest, Claims, CreateBundleRequest, MenuBundle, MenuBundleSlot, PublicBundle,
+    PublicBundleChoice, PublicBundleSlot, PublicMenuSection, UpdateBundleRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use actix_web::{web, HttpResponse, Result};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// Every bundle of a restaurant with its slots and choices, in menu order
+pub async fn load_bundles<'e, E>(
+    executor: E,
+    restaurant_id: &str,
+) -> Result<Vec<MenuBundle>, sqlx::Error>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let rows = sqlx::query(
+        "SELECT b.id, b.restaurant_id, b.name, b.description, CAST(b.price AS REAL) AS price,
+                b.available, b.display_order,
+                s.id AS slot_id, s.name AS slot_name, s.display_order AS slot_order, c.item_id
+         FROM menu_bundles b
+         LEFT JOIN menu_bundle_slots s ON s.bundle_id = b.id
+         LEFT JOIN menu_bundle_slot_choices c ON c.slot_id = s.id
+         WHERE b.restaurant_id = ?
+         ORDER BY b.display_order ASC, b.created_at ASC, b.id,
+                  s.display_order ASC, s.id, c.display_order ASC",
+    )
+    .bind(restaurant_id)
+    .fetch_all(executor)
+    .await?;
+
+    let mut bundles: Vec<MenuBundle> = Vec::new();
+    for row in rows {
+        let bundle_id: String = row.try_get("id")?;
+        if bundles.last().map(|bundle| &bundle.id) != Some(&bundle_id) {
+            let display_order: i64 = row.try_get("display_order")?;
+            bundles.push(MenuBundle {
+                id: bundle_id,
+                restaurant_id: row.try_get("restaurant_id")?,
+                name: row.try_get("name")?,
+                description: row.try_get("description")?,
+                price: row.try_get("price")?,
+                available: row.try_get("available")?,
+                display_order: display_order as i32,
+                slots: Vec::new(),
+            });
+        }
+        let bundle = bundles.last_mut().expect("bundle was just pushed");
+
+        let Some(slot_id) = row.try_get::<Option<String>, _>("slot_id")? else {
+            continue;
+        };
+        if bundle.slots.last().map(|slot| &slot.id) != Some(&slot_id) {
+            let slot_order: i64 = row.try_get("slot_order")?;
+            bundle.slots.push(MenuBundleSlot {
+                id: slot_id,
+                name: row.try_get("slot_name")?,
+                display_order: slot_order as i32,
+                item_ids: Vec::new(),
+            });
+        }
+        if let Some(item_id) = row.try_get::<Option<String>, _>("item_id")? {
+            let slot = bundle.slots.last_mut().expect("slot was just pushed");
+            slot.item_ids.push(item_id);
+        }
+    }
+
+    Ok(bundles)
+}
+
+// Available bundles whose every slot still has something on the menu. Choice
+// names come from the public sections so they follow the guest's language.
+pub fn public_bundles(
+    bundles: Vec<MenuBundle>,
+    sections: &[PublicMenuSection],
+) -> Vec<PublicBundle> {
+    let item_names: HashMap<&str, &str> = sections
+        .iter()
+        .flat_map(|section| &section.items)
+        .map(|item| (item.id.as_str(), item.name.as_str()))
+        .collect();
+
+    bundles
+        .into_iter()
+        .filter(|bundle| bundle.available && !bundle.slots.is_empty())
+        .filter_map(|bundle| {
+            let slots: Vec<PublicBundleSlot> = bundle
+                .slots
+                .into_iter()
+                .map(|slot| PublicBundleSlot {
+                    choices: slot
+                        .item_ids
+                        .iter()
+                        .filter_map(|item_id| {
+                            item_names
+                                .get(item_id.as_str())
+                                .map(|name| PublicBundleChoice {
+                                    item_id: item_id.clone(),
+                                    name: name.to_string(),
+                                })
+                        })
+                        .collect(),
+                    id: slot.id,
+                    name: slot.name,
+                })
+                .collect();
+
+            if slots.iter().any(|slot| slot.choices.is_empty()) {
+                return None;
+            }
+
+            Some(PublicBundle {
+                id: bundle.id,
+                name: bundle.name,
+                description: bundle.description,
+                price: bundle.price,
+                slots,
+            })
+        })
+        .collect()
+}
+
+// Spreads a bundle price over its components in proportion to their menu
+// prices, in whole cents, with any rounding remainder on the last component
+pub fn split_bundle_price(price: f64, weights: &[f64]) -> Vec<f64> {
+    let total_cents = (price * 100.0).round() as i64;
+    let weight_sum: f64 = weights.iter().sum();
+
+    let mut shares = Vec::with_capacity(weights.len());
+    let mut allocated = 0;
+    for (index, weight) in weights.iter().enumerate() {
+        let cents = if index + 1 == weights.len() {
+            total_cents - allocated
+        } else if weight_sum > 0.0 {
+            (total_cents as f64 * weight / weight_sum).round() as i64
+        } else {
+            total_cents / weights.len() as i64
+        };
+        allocated += cents;
+        shares.push(cents as f64 / 100.0);
+    }
+
+    shares
+}
+
+async fn bundle_restaurant(
+    pool: &Pool<Sqlite>,
+    bundle_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT restaurant_id FROM menu_bundles WHERE id = ?")
+        .bind(bundle_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn find_bundle(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    bundle_id: &str,
+) -> Result<Option<MenuBundle>, sqlx::Error> {
+    Ok(load_bundles(pool, restaurant_id)
+        .await?
+        .into_iter()
+        .find(|bundle| bundle.id == bundle_id))
+}
+
+fn valid_price(price: f64) -> bool {
+    price.is_finite() && price >= 0.0
+}
+
+// Checks the slots of a create or update request. Every slot needs a name and
+// at least one choice, and choices must be items of the same restaurant.
+async fn validate_slots(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    slots: &[BundleSlotRequest],
+) -> Result<Result<(), String>, sqlx::Error> {
+    if slots.is_empty() {
+        return Ok(Err("A bundle needs at least one slot".to_string()));
+    }
+
+    let restaurant_items: HashSet<String> = sqlx::query_scalar(
+        "SELECT mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    for slot in slots {
+        if slot.name.trim().is_empty() {
+            return Ok(Err("Slot name is required".to_string()));
+        }
+        if slot.item_ids.is_empty() {
+            return Ok(Err(format!(
+                "Slot {} needs at least one choice",
+                slot.name.trim()
+            )));
+        }
+        if let Some(item_id) = slot
+            .item_ids
+            .iter()
+            .find(|item_id| !restaurant_items.contains(*item_id))
+        {
+            return Ok(Err(format!("Menu item {item_id} not found")));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+async fn write_slots(
+    conn: &mut SqliteConnection,
+    bundle_id: &str,
+    slots: &[BundleSlotRequest],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM menu_bundle_slots WHERE bundle_id = ?")
+        .bind(bundle_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (slot_order, slot) in slots.iter().enumerate() {
+        let slot_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO menu_bundle_slots (id, bundle_id, name, display_order) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&slot_id)
+        .bind(bundle_id)
+        .bind(slot.name.trim())
+        .bind(slot_order as i32)
+        .execute(&mut *conn)
+        .await?;
+
+        for (choice_order, item_id) in slot.item_ids.iter().enumerate() {
+            sqlx::query(
+                "INSERT OR IGNORE INTO menu_bundle_slot_choices (slot_id, item_id, display_order) VALUES (?, ?, ?)",
+            )
+            .bind(&slot_id)
+            .bind(item_id)
+            .bind(choice_order as i32)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list_bundles(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match load_bundles(pool.get_ref(), &restaurant_id).await {
+        Ok(bundles) => Ok(HttpResponse::Ok().json(bundles)),
+        Err(e) => {
+            log::error!("Database error fetching bundles: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn create_bundle(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreateBundleRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Bundle name is required"
+        })));
+    }
+    if !valid_price(req.price) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Price cannot be negative"
+        })));
+    }
+    match validate_slots(pool.get_ref(), &restaurant_id, &req.slots).await {
+        Ok(Ok(())) => {}
+        Ok(Err(message)) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking bundle slots: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    }
+
+    let display_order = match req.display_order {
+        Some(display_order) => display_order,
+        None => sqlx::query_scalar::<_, i32>(
+            "SELECT COALESCE(MAX(display_order), 0) + 1 FROM menu_bundles WHERE restaurant_id = ?",
+        )
+        .bind(&restaurant_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(1),
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create bundle"
+            })));
+        }
+    };
+
+    let bundle_id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO menu_bundles (id, restaurant_id, name, description, price, available, display_order)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&bundle_id)
+    .bind(&restaurant_id)
+    .bind(name)
+    .bind(&req.description)
+    .bind(req.price)
+    .bind(req.available.unwrap_or(true))
+    .bind(display_order)
+    .execute(&mut *tx)
+    .await;
+
+    let result = match result {
+        Ok(_) => write_slots(&mut tx, &bundle_id, &req.slots).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        log::error!("Database error creating bundle: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create bundle"
+        })));
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error creating bundle: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create bundle"
+        })));
+    }
+
+    match find_bundle(pool.get_ref(), &restaurant_id, &bundle_id).await {
+        Ok(Some(bundle)) => Ok(HttpResponse::Created().json(bundle)),
+        Ok(None) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create bundle"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching bundle: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn update_bundle(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateBundleRequest>,
+) -> Result<HttpResponse> {
+    let bundle_id = path.into_inner();
+
+    let restaurant_id = match bundle_restaurant(pool.get_ref(), &bundle_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Bundle not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching bundle: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let mut bundle = match find_bundle(pool.get_ref(), &restaurant_id, &bundle_id).await {
+        Ok(Some(bundle)) => bundle,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Bundle not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching bundle: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if let Some(name) = &req.name {
+        let name = name.trim();
+        if name.is_empty() {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Bundle name is required"
+            })));
+        }
+        bundle.name = name.to_string();
+    }
+    if let Some(price) = req.price {
+        if !valid_price(price) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Price cannot be negative"
+            })));
+        }
+        bundle.price = price;
+    }
+    if let Some(description) = &req.description {
+        bundle.description = Some(description.clone());
+    }
+    if let Some(available) = req.available {
+        bundle.available = available;
+    }
+    if let Some(display_order) = req.display_order {
+        bundle.display_order = display_order;
+    }
+    if let Some(slots) = &req.slots {
+        match validate_slots(pool.get_ref(), &restaurant_id, slots).await {
+            Ok(Ok(())) => {}
+            Ok(Err(message)) => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": message
+                })));
+            }
+            Err(e) => {
+                log::error!("Database error checking bundle slots: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update bundle"
+            })));
+        }
+    };
+
+    let result = sqlx::query(
+        "UPDATE menu_bundles SET name = ?, description = ?, price = ?, available = ?, display_order = ? WHERE id = ?",
+    )
+    .bind(&bundle.name)
+    .bind(&bundle.description)
+    .bind(bundle.price)
+    .bind(bundle.available)
+    .bind(bundle.display_order)
+    .bind(&bundle_id)
+    .execute(&mut *tx)
+    .await;
+
+    let result = match (result, &req.slots) {
+        (Ok(_), Some(slots)) => write_slots(&mut tx, &bundle_id, slots).await,
+        (Ok(_), None) => Ok(()),
+        (Err(e), _) => Err(e),
+    };
+    if let Err(e) = result {
+        log::error!("Database error updating bundle: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update bundle"
+        })));
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error updating bundle: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update bundle"
+        })));
+    }
+
+    match find_bundle(pool.get_ref(), &restaurant_id, &bundle_id).await {
+        Ok(Some(bundle)) => Ok(HttpResponse::Ok().json(bundle)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Bundle not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching bundle: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn delete_bundle(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let bundle_id = path.into_inner();
+
+    let restaurant_id = match bundle_restaurant(pool.get_ref(), &bundle_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Bundle not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching bundle: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    match sqlx::query("DELETE FROM menu_bundles WHERE id = ?")
+        .bind(&bundle_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting bundle: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete bundle"
+            })))
+        }
+    }
+}
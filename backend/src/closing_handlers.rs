@@ -0,0 +1,584 @@
+use crate::models::{
+    Claims, CloseDayRequest, ClosingAuditEntry, ClosingPaymentMethod, ClosingPreviewQuery,
+    ClosingSummary, DayClosing, InProgressOrder, ReopenDayRequest,
+};
+use crate::permissions::{is_manager, is_super_admin};
+use crate::timezone::{business_date, day_bounds_utc, restaurant_timezone};
+use crate::webhooks::enqueue_event;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+fn round_money(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+// Orders and refunds may not touch a business day while its closing is in force
+pub async fn is_day_closed<'e, E>(
+    executor: E,
+    restaurant_id: &str,
+    business_date: &str,
+) -> Result<bool, sqlx::Error>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let closing_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM day_closings WHERE restaurant_id = ? AND business_date = ? AND reopened_at IS NULL",
+    )
+    .bind(restaurant_id)
+    .bind(business_date)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(closing_count > 0)
+}
+
+// Totals for one local business day, taken from each order's business_date.
+// Orders from before business dates were recorded fall back to their creation
+// time. Only served orders that are not awaiting online payment are sales; the
+// rest are listed as in progress and left out of every total. Guests pay at
+// the table unless they prepaid online. Orders carry no tax or tip lines, so
+// those are zero.
+pub async fn closing_summary<'e, E>(
+    executor: E,
+    restaurant_id: &str,
+    date: NaiveDate,
+    tz: Tz,
+) -> Result<ClosingSummary, sqlx::Error>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let (day_start, day_end) = day_bounds_utc(tz, date);
+    let rows = sqlx::query(
+        "SELECT o.id, o.order_number, t.name AS table_name,
+                CAST(o.total_amount AS REAL) AS total_amount,
+                CAST(o.discount_amount AS REAL) AS discount_amount,
+                o.status, o.payment_status,
+                (SELECT p.provider FROM payments p
+                 WHERE p.order_id = o.id AND p.status = 'succeeded'
+                 ORDER BY p.created_at DESC LIMIT 1) AS provider,
+                (SELECT CAST(COALESCE(SUM(p.refunded_amount), 0) AS REAL) FROM payments p
+                 WHERE p.order_id = o.id) AS refunded_amount
+         FROM orders o
+         JOIN tables t ON o.table_id = t.id
+         WHERE t.restaurant_id = ?
+           AND (o.business_date = ?
+                OR (o.business_date IS NULL AND o.created_at >= ? AND o.created_at < ?))
+         ORDER BY o.created_at",
+    )
+    .bind(restaurant_id)
+    .bind(date.format("%Y-%m-%d").to_string())
+    .bind(day_start)
+    .bind(day_end)
+    .fetch_all(executor)
+    .await?;
+
+    let mut order_count = 0;
+    let mut cancelled_count = 0;
+    let mut gross_sales = 0.0;
+    let mut discounts = 0.0;
+    let mut refunds = 0.0;
+    let mut payment_methods: BTreeMap<String, ClosingPaymentMethod> = BTreeMap::new();
+    let mut in_progress_orders = Vec::new();
+
+    for row in &rows {
+        let status: String = row.try_get("status")?;
+        if status == "cancelled" {
+            cancelled_count += 1;
+            continue;
+        }
+        let total_amount: f64 = row.try_get("total_amount")?;
+        let payment_status: String = row.try_get("payment_status")?;
+        if status != "delivered" || payment_status == "awaiting_payment" {
+            in_progress_orders.push(InProgressOrder {
+                order_id: row.try_get("id")?,
+                order_number: row.try_get("order_number")?,
+                table_name: row.try_get("table_name")?,
+                status,
+                payment_status,
+                total_amount,
+            });
+            continue;
+        }
+        let discount_amount: f64 = row.try_get("discount_amount")?;
+        let refunded_amount: f64 = row.try_get("refunded_amount")?;
+        let provider: Option<String> = row.try_get("provider")?;
+
+        order_count += 1;
+        gross_sales += total_amount + discount_amount;
+        discounts += discount_amount;
+        refunds += refunded_amount;
+
+        let method = provider.unwrap_or_else(|| "pay_at_table".to_string());
+        let totals =
+            payment_methods
+                .entry(method.clone())
+                .or_insert_with(|| ClosingPaymentMethod {
+                    method,
+                    order_count: 0,
+                    amount: 0.0,
+                });
+        totals.order_count += 1;
+        totals.amount += total_amount - refunded_amount;
+    }
+
+    Ok(ClosingSummary {
+        restaurant_id: restaurant_id.to_string(),
+        business_date: date.format("%Y-%m-%d").to_string(),
+        timezone: tz.name().to_string(),
+        order_count,
+        cancelled_count,
+        gross_sales: round_money(gross_sales),
+        discounts: round_money(discounts),
+        refunds: round_money(refunds),
+        taxes: 0.0,
+        tips: 0.0,
+        net_sales: round_money(gross_sales - discounts - refunds),
+        payment_methods: payment_methods
+            .into_values()
+            .map(|totals| ClosingPaymentMethod {
+                amount: round_money(totals.amount),
+                ..totals
+            })
+            .collect(),
+        in_progress_orders,
+    })
+}
+
+fn closing_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DayClosing, sqlx::Error> {
+    let summary: String = row.try_get("summary")?;
+    let closed_at: NaiveDateTime = row.try_get("closed_at")?;
+    let reopened_at: Option<NaiveDateTime> = row.try_get("reopened_at")?;
+    Ok(DayClosing {
+        id: row.try_get("id")?,
+        restaurant_id: row.try_get("restaurant_id")?,
+        business_date: row.try_get("business_date")?,
+        summary: serde_json::from_str(&summary).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+        closed_by: row.try_get("closed_by")?,
+        closed_at: DateTime::from_naive_utc_and_offset(closed_at, Utc),
+        reopened_by: row.try_get("reopened_by")?,
+        reopened_at: reopened_at.map(|at| DateTime::from_naive_utc_and_offset(at, Utc)),
+        reopen_reason: row.try_get("reopen_reason")?,
+    })
+}
+
+// Parses a YYYY-MM-DD business date, defaulting to the restaurant's today, and
+// refuses days that have not started yet
+fn closing_date(value: Option<&str>, tz: Tz) -> Result<NaiveDate, &'static str> {
+    let today = business_date(tz, Utc::now());
+    let date = match value {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| "Date must use the YYYY-MM-DD format")?,
+        None => today,
+    };
+    if date > today {
+        return Err("Cannot close a business day that has not started");
+    }
+    Ok(date)
+}
+
+async fn timezone_or_error(pool: &Pool<Sqlite>, restaurant_id: &str) -> Result<Tz, HttpResponse> {
+    restaurant_timezone(pool, restaurant_id).await.map_err(|e| {
+        log::error!("Database error fetching restaurant timezone: {e}");
+        HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        }))
+    })
+}
+
+pub async fn preview_day_closing(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<ClosingPreviewQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let tz = match timezone_or_error(pool.get_ref(), &restaurant_id).await {
+        Ok(tz) => tz,
+        Err(response) => return Ok(response),
+    };
+    let date = match closing_date(query.date.as_deref(), tz) {
+        Ok(date) => date,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+
+    match closing_summary(pool.get_ref(), &restaurant_id, date, tz).await {
+        Ok(summary) => Ok(HttpResponse::Ok().json(summary)),
+        Err(e) => {
+            log::error!("Database error building closing summary: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn close_day(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CloseDayRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let tz = match timezone_or_error(pool.get_ref(), &restaurant_id).await {
+        Ok(tz) => tz,
+        Err(response) => return Ok(response),
+    };
+    let date = match closing_date(req.business_date.as_deref(), tz) {
+        Ok(date) => date,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+    let business_date = date.format("%Y-%m-%d").to_string();
+    let closing_id = Uuid::new_v4().to_string();
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to close day"
+            })));
+        }
+    };
+
+    // Writing the audit entry first takes the database write lock, so no
+    // order can land in the day between the summary and the closing row
+    let result = sqlx::query(
+        "INSERT INTO day_closing_audit (closing_id, restaurant_id, business_date, action, user_id) VALUES (?, ?, ?, 'closed', ?)",
+    )
+    .bind(&closing_id)
+    .bind(&restaurant_id)
+    .bind(&business_date)
+    .bind(&claims.sub)
+    .execute(&mut *tx)
+    .await;
+    if let Err(e) = result {
+        log::error!("Database error recording day closing: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to close day"
+        })));
+    }
+
+    let summary = match closing_summary(&mut *tx, &restaurant_id, date, tz).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            log::error!("Database error building closing summary: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to close day"
+            })));
+        }
+    };
+    let summary_json = match serde_json::to_string(&summary) {
+        Ok(json) => json,
+        Err(e) => {
+            log::error!("Error serializing closing summary: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to close day"
+            })));
+        }
+    };
+
+    let closed_at = Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO day_closings (id, restaurant_id, business_date, summary, closed_by, closed_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&closing_id)
+    .bind(&restaurant_id)
+    .bind(&business_date)
+    .bind(&summary_json)
+    .bind(&claims.sub)
+    .bind(closed_at.naive_utc())
+    .execute(&mut *tx)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": format!("Business day {business_date} is already closed")
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error closing day: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to close day"
+            })));
+        }
+    }
+
+    let event = serde_json::json!({
+        "closing_id": closing_id,
+        "business_date": business_date,
+        "summary": summary,
+    });
+    if let Err(e) = enqueue_event(&mut tx, &restaurant_id, "day.closed", event).await {
+        log::error!("Database error queueing day closing webhooks: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to close day"
+        })));
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(HttpResponse::Created().json(DayClosing {
+            id: closing_id,
+            restaurant_id,
+            business_date,
+            summary,
+            closed_by: claims.sub.clone(),
+            closed_at,
+            reopened_by: None,
+            reopened_at: None,
+            reopen_reason: None,
+        })),
+        Err(e) => {
+            log::error!("Database error closing day: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to close day"
+            })))
+        }
+    }
+}
+
+pub async fn list_day_closings(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, restaurant_id, business_date, summary, closed_by, closed_at, reopened_by, reopened_at, reopen_reason
+         FROM day_closings WHERE restaurant_id = ? ORDER BY business_date DESC, closed_at DESC",
+    )
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rows.and_then(|rows| {
+        rows.iter()
+            .map(closing_from_row)
+            .collect::<Result<Vec<_>, _>>()
+    }) {
+        Ok(closings) => Ok(HttpResponse::Ok().json(closings)),
+        Err(e) => {
+            log::error!("Database error fetching day closings: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn get_day_closing(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, business_date) = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let row = sqlx::query(
+        "SELECT id, restaurant_id, business_date, summary, closed_by, closed_at, reopened_by, reopened_at, reopen_reason
+         FROM day_closings WHERE restaurant_id = ? AND business_date = ? AND reopened_at IS NULL",
+    )
+    .bind(&restaurant_id)
+    .bind(&business_date)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match row.and_then(|row| row.as_ref().map(closing_from_row).transpose()) {
+        Ok(Some(closing)) => Ok(HttpResponse::Ok().json(closing)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Business day is not closed"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching day closing: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn reopen_day(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<ReopenDayRequest>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, business_date) = path.into_inner();
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admin can reopen a closed day"
+        })));
+    }
+
+    let reason = req.reason.trim();
+    if reason.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "A reason is required to reopen a day"
+        })));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to reopen day"
+            })));
+        }
+    };
+
+    let reopened = sqlx::query_scalar::<_, String>(
+        "UPDATE day_closings SET reopened_by = ?, reopened_at = ?, reopen_reason = ?
+         WHERE restaurant_id = ? AND business_date = ? AND reopened_at IS NULL
+         RETURNING id",
+    )
+    .bind(&claims.sub)
+    .bind(Utc::now().naive_utc())
+    .bind(reason)
+    .bind(&restaurant_id)
+    .bind(&business_date)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let closing_id = match reopened {
+        Ok(Some(closing_id)) => closing_id,
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Business day is not closed"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error reopening day: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to reopen day"
+            })));
+        }
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO day_closing_audit (closing_id, restaurant_id, business_date, action, user_id, reason) VALUES (?, ?, ?, 'reopened', ?, ?)",
+    )
+    .bind(&closing_id)
+    .bind(&restaurant_id)
+    .bind(&business_date)
+    .bind(&claims.sub)
+    .bind(reason)
+    .execute(&mut *tx)
+    .await;
+    if let Err(e) = result {
+        log::error!("Database error recording day reopening: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to reopen day"
+        })));
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error reopening day: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to reopen day"
+            })))
+        }
+    }
+}
+
+pub async fn list_closing_audit(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, business_date) = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, closing_id, business_date, action, user_id, reason, created_at
+         FROM day_closing_audit WHERE restaurant_id = ? AND business_date = ?
+         ORDER BY created_at, rowid",
+    )
+    .bind(&restaurant_id)
+    .bind(&business_date)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let entries = rows.and_then(|rows| {
+        rows.iter()
+            .map(|row| {
+                let created_at: NaiveDateTime = row.try_get("created_at")?;
+                Ok(ClosingAuditEntry {
+                    id: row.try_get("id")?,
+                    closing_id: row.try_get("closing_id")?,
+                    business_date: row.try_get("business_date")?,
+                    action: row.try_get("action")?,
+                    user_id: row.try_get("user_id")?,
+                    reason: row.try_get("reason")?,
+                    created_at: DateTime::from_naive_utc_and_offset(created_at, Utc),
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()
+    });
+
+    match entries {
+        Ok(entries) => Ok(HttpResponse::Ok().json(entries)),
+        Err(e) => {
+            log::error!("Database error fetching closing audit: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use crate::email_service::EmailService;
 use crate::models::{
-    ContactResponse, ContactSubmission, ContactSubmissionRow, CreateContactRequest,
+    ContactResponse, ContactSubmission, ContactSubmissionListQuery, ContactSubmissionRow,
+    CreateContactRequest, Page,
 };
+use crate::pagination::{PageRequest, SortField};
 use crate::Settings;
 use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::NaiveDate;
 use log::{error, info};
-use sqlx::{Pool, Sqlite};
+use sqlx::{FromRow, Pool, Sqlite};
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
@@ -50,6 +53,12 @@ impl RateLimiter {
     }
 }
 
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub async fn submit_contact_form(
     pool: web::Data<Pool<Sqlite>>,
     rate_limiter: web::Data<RateLimiter>,
@@ -101,7 +110,7 @@ pub async fn submit_contact_form(
     let email_regex = regex::Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$")
         .expect("Invalid email regex pattern");
 
-    if !email_regex.is_match(&req.email.trim()) {
+    if !email_regex.is_match(req.email.trim()) {
         return Ok(HttpResponse::BadRequest().json(serde_json::json!({
             "error": "Invalid email format"
         })));
@@ -227,27 +236,98 @@ pub async fn submit_contact_form(
     }
 }
 
-pub async fn list_contact_submissions(pool: web::Data<Pool<Sqlite>>) -> Result<HttpResponse> {
-    // Note: This is a simple implementation. In production, you'd want:
-    // 1. Authentication/authorization to limit access
-    // 2. Pagination
-    // 3. Filtering by status, date range, etc.
+static CONTACT_SUBMISSION_SORTS: [SortField; 1] = [SortField {
+    name: "created_at",
+    expr: "CAST(created_at AS TEXT)",
+    numeric: false,
+    descending: true,
+}];
 
-    let submissions = sqlx::query_as::<_, ContactSubmissionRow>(
-        "SELECT id, name, email, subject, message, ip_address, user_agent, status, created_at 
-         FROM contact_submissions 
-         ORDER BY created_at DESC 
-         LIMIT 100",
-    )
-    .fetch_all(pool.get_ref())
-    .await;
+pub async fn list_contact_submissions(
+    pool: web::Data<Pool<Sqlite>>,
+    query: web::Query<ContactSubmissionListQuery>,
+) -> Result<HttpResponse> {
+    let page = match PageRequest::parse(
+        &CONTACT_SUBMISSION_SORTS,
+        query.cursor.as_deref(),
+        query.limit,
+        query.sort.as_deref(),
+        query.order.as_deref(),
+    ) {
+        Ok(page) => page,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
 
-    match submissions {
-        Ok(rows) => {
-            let submissions: Vec<ContactSubmission> =
-                rows.into_iter().map(ContactSubmission::from).collect();
-            Ok(HttpResponse::Ok().json(submissions))
+    // Submissions have no restaurant, so date filters are UTC days
+    let parse_date = |value: Option<&str>| match value {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| ()),
+        None => Ok(None),
+    };
+    let (from, to) = match (
+        parse_date(query.from.as_deref()),
+        parse_date(query.to.as_deref()),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Date must use the YYYY-MM-DD format"
+            })));
+        }
+    };
+
+    let mut submissions_query = sqlx::QueryBuilder::<Sqlite>::new(format!(
+        "SELECT id, name, email, subject, message, ip_address, user_agent, status, created_at,
+                {} AS sort_key
+         FROM contact_submissions
+         WHERE 1 = 1",
+        page.sort_expr()
+    ));
+    if let Some(status) = &query.status {
+        submissions_query.push(" AND status = ").push_bind(status);
+    }
+    if let Some(from) = from {
+        submissions_query
+            .push(" AND created_at >= ")
+            .push_bind(from.and_hms_opt(0, 0, 0));
+    }
+    if let Some(to) = to.and_then(|to| to.succ_opt()) {
+        submissions_query
+            .push(" AND created_at < ")
+            .push_bind(to.and_hms_opt(0, 0, 0));
+    }
+    if let Some(q) = query.q.as_deref().map(str::trim) {
+        if !q.is_empty() {
+            let pattern = format!("%{q}%");
+            submissions_query
+                .push(" AND (name LIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR email LIKE ")
+                .push_bind(pattern)
+                .push(")");
         }
+    }
+    page.push_after_cursor(&mut submissions_query, "id");
+    page.push_order_and_limit(&mut submissions_query, "id");
+
+    let submissions = match submissions_query.build().fetch_all(pool.get_ref()).await {
+        Ok(rows) => page.split(rows, "id").and_then(|(rows, next_cursor)| {
+            let items = rows
+                .iter()
+                .map(|row| ContactSubmissionRow::from_row(row).map(ContactSubmission::from))
+                .collect::<Result<Vec<_>, sqlx::Error>>()?;
+            Ok(Page { items, next_cursor })
+        }),
+        Err(e) => Err(e),
+    };
+
+    match submissions {
+        Ok(page) => Ok(HttpResponse::Ok().json(page)),
         Err(e) => {
             log::error!("Database error fetching contact submissions: {e}");
             Ok(HttpResponse::InternalServerError().json(serde_json::json!({
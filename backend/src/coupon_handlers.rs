@@ -0,0 +1,450 @@
+use crate::models::{Claims, Coupon, CreateCouponRequest, OrderItem, UpdateCouponRequest};
+use crate::permissions::{can_manage_menu, is_manager};
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use uuid::Uuid;
+
+const COUPON_COLUMNS: &str = "id, restaurant_id, code, discount_type,
+    CAST(discount_value AS REAL) AS discount_value,
+    CAST(min_order_amount AS REAL) AS min_order_amount,
+    valid_from, valid_until, max_uses, times_used, item_id, active";
+
+// Why a coupon cannot be used on an order, as an error code and a message
+// for the guest
+pub struct CouponRejection {
+    pub code: &'static str,
+    pub message: String,
+}
+
+fn coupon_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Coupon, sqlx::Error> {
+    let valid_from: Option<NaiveDateTime> = row.try_get("valid_from")?;
+    let valid_until: Option<NaiveDateTime> = row.try_get("valid_until")?;
+    Ok(Coupon {
+        id: row.try_get("id")?,
+        restaurant_id: row.try_get("restaurant_id")?,
+        code: row.try_get("code")?,
+        discount_type: row.try_get("discount_type")?,
+        discount_value: row.try_get("discount_value")?,
+        min_order_amount: row.try_get("min_order_amount")?,
+        valid_from: valid_from.map(|at| DateTime::from_naive_utc_and_offset(at, Utc)),
+        valid_until: valid_until.map(|at| DateTime::from_naive_utc_and_offset(at, Utc)),
+        max_uses: row.try_get("max_uses")?,
+        times_used: row.try_get("times_used")?,
+        item_id: row.try_get("item_id")?,
+        active: row.try_get("active")?,
+    })
+}
+
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+pub async fn find_coupon_by_code(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    code: &str,
+) -> Result<Option<Coupon>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        "SELECT {COUPON_COLUMNS} FROM coupons WHERE restaurant_id = ? AND code = ?"
+    ))
+    .bind(restaurant_id)
+    .bind(normalize_code(code))
+    .fetch_optional(pool)
+    .await?;
+
+    row.as_ref().map(coupon_from_row).transpose()
+}
+
+async fn find_coupon(pool: &Pool<Sqlite>, coupon_id: &str) -> Result<Option<Coupon>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        "SELECT {COUPON_COLUMNS} FROM coupons WHERE id = ?"
+    ))
+    .bind(coupon_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.as_ref().map(coupon_from_row).transpose()
+}
+
+// The discount a coupon gives on an order with these lines. Item-scoped
+// coupons only discount the matching lines, and a fixed discount never
+// exceeds what it applies to.
+pub fn coupon_discount(
+    coupon: &Coupon,
+    order_items: &[OrderItem],
+    subtotal: f64,
+    at: DateTime<Utc>,
+) -> Result<f64, CouponRejection> {
+    if !coupon.active
+        || coupon.valid_from.is_some_and(|from| at < from)
+        || coupon.valid_until.is_some_and(|until| at >= until)
+    {
+        return Err(CouponRejection {
+            code: "coupon_not_valid",
+            message: format!("Coupon {} is not valid right now", coupon.code),
+        });
+    }
+
+    if coupon
+        .max_uses
+        .is_some_and(|max_uses| coupon.times_used >= max_uses)
+    {
+        return Err(CouponRejection {
+            code: "coupon_exhausted",
+            message: format!("Coupon {} has been used up", coupon.code),
+        });
+    }
+
+    if let Some(minimum) = coupon
+        .min_order_amount
+        .filter(|minimum| subtotal < *minimum)
+    {
+        return Err(CouponRejection {
+            code: "coupon_minimum_not_met",
+            message: format!(
+                "Coupon {} needs an order of at least {minimum:.2}",
+                coupon.code
+            ),
+        });
+    }
+
+    let eligible: f64 = match &coupon.item_id {
+        Some(item_id) => order_items
+            .iter()
+            .filter(|item| item.menu_item_id == *item_id)
+            .map(|item| item.price * item.quantity as f64)
+            .sum(),
+        None => subtotal,
+    };
+    if eligible <= 0.0 {
+        return Err(CouponRejection {
+            code: "coupon_not_applicable",
+            message: format!("Coupon {} does not apply to this order", coupon.code),
+        });
+    }
+
+    let discount = match coupon.discount_type.as_str() {
+        "percentage" => eligible * coupon.discount_value / 100.0,
+        _ => coupon.discount_value,
+    };
+    Ok((discount.min(eligible) * 100.0).round() / 100.0)
+}
+
+// Counts one use of a coupon inside the order transaction; false when a
+// concurrent order took the last use
+pub async fn redeem_coupon(
+    conn: &mut SqliteConnection,
+    coupon_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE coupons SET times_used = times_used + 1
+         WHERE id = ? AND (max_uses IS NULL OR times_used < max_uses)",
+    )
+    .bind(coupon_id)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Gives back the use an order took when that order is cancelled
+pub async fn release_order_coupon(
+    conn: &mut SqliteConnection,
+    order_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE coupons SET times_used = times_used - 1
+         WHERE id = (SELECT coupon_id FROM orders WHERE id = ?) AND times_used > 0",
+    )
+    .bind(order_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+fn validate_coupon(coupon: &Coupon) -> Result<(), &'static str> {
+    if coupon.code.len() < 3
+        || coupon.code.len() > 32
+        || !coupon
+            .code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Code must be 3-32 letters, digits, dashes or underscores");
+    }
+    match coupon.discount_type.as_str() {
+        "percentage" if !(coupon.discount_value > 0.0 && coupon.discount_value <= 100.0) => {
+            Err("A percentage discount must be between 0 and 100")
+        }
+        "fixed" if !(coupon.discount_value.is_finite() && coupon.discount_value > 0.0) => {
+            Err("A fixed discount must be greater than 0")
+        }
+        "percentage" | "fixed" => Ok(()),
+        _ => Err("discount_type must be 'percentage' or 'fixed'"),
+    }?;
+    if coupon
+        .min_order_amount
+        .is_some_and(|minimum| !minimum.is_finite() || minimum < 0.0)
+    {
+        return Err("Minimum order amount cannot be negative");
+    }
+    if coupon.max_uses.is_some_and(|max_uses| max_uses <= 0) {
+        return Err("max_uses must be greater than 0");
+    }
+    if let (Some(from), Some(until)) = (coupon.valid_from, coupon.valid_until) {
+        if until <= from {
+            return Err("valid_until must be after valid_from");
+        }
+    }
+    Ok(())
+}
+
+pub async fn list_coupons(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let rows = sqlx::query(&format!(
+        "SELECT {COUPON_COLUMNS} FROM coupons WHERE restaurant_id = ? ORDER BY created_at DESC, code"
+    ))
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rows.and_then(|rows| {
+        rows.iter()
+            .map(coupon_from_row)
+            .collect::<Result<Vec<_>, _>>()
+    }) {
+        Ok(coupons) => Ok(HttpResponse::Ok().json(coupons)),
+        Err(e) => {
+            log::error!("Database error fetching coupons: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn create_coupon(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreateCouponRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let coupon = Coupon {
+        id: Uuid::new_v4().to_string(),
+        restaurant_id,
+        code: normalize_code(&req.code),
+        discount_type: req.discount_type.clone(),
+        discount_value: req.discount_value,
+        min_order_amount: req.min_order_amount,
+        valid_from: req.valid_from,
+        valid_until: req.valid_until,
+        max_uses: req.max_uses,
+        times_used: 0,
+        item_id: req.item_id.clone(),
+        active: req.active.unwrap_or(true),
+    };
+    if let Err(message) = validate_coupon(&coupon) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": message
+        })));
+    }
+
+    if let Some(item_id) = &coupon.item_id {
+        let item_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM menu_items mi
+             JOIN menu_sections ms ON mi.section_id = ms.id
+             WHERE mi.id = ? AND ms.restaurant_id = ?",
+        )
+        .bind(item_id)
+        .bind(&coupon.restaurant_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(0);
+
+        if item_count == 0 {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO coupons (id, restaurant_id, code, discount_type, discount_value, min_order_amount, valid_from, valid_until, max_uses, item_id, active)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&coupon.id)
+    .bind(&coupon.restaurant_id)
+    .bind(&coupon.code)
+    .bind(&coupon.discount_type)
+    .bind(coupon.discount_value)
+    .bind(coupon.min_order_amount)
+    .bind(coupon.valid_from.map(|at| at.naive_utc()))
+    .bind(coupon.valid_until.map(|at| at.naive_utc()))
+    .bind(coupon.max_uses)
+    .bind(&coupon.item_id)
+    .bind(coupon.active)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Created().json(coupon)),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": format!("Coupon code {} already exists", coupon.code)
+            })))
+        }
+        Err(e) => {
+            log::error!("Database error creating coupon: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create coupon"
+            })))
+        }
+    }
+}
+
+pub async fn update_coupon(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateCouponRequest>,
+) -> Result<HttpResponse> {
+    let coupon_id = path.into_inner();
+
+    let mut coupon = match find_coupon(pool.get_ref(), &coupon_id).await {
+        Ok(Some(coupon)) => coupon,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Coupon not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching coupon: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &coupon.restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if let Some(discount_type) = &req.discount_type {
+        coupon.discount_type = discount_type.clone();
+    }
+    if let Some(discount_value) = req.discount_value {
+        coupon.discount_value = discount_value;
+    }
+    if let Some(min_order_amount) = req.min_order_amount {
+        coupon.min_order_amount = Some(min_order_amount);
+    }
+    if let Some(valid_from) = req.valid_from {
+        coupon.valid_from = Some(valid_from);
+    }
+    if let Some(valid_until) = req.valid_until {
+        coupon.valid_until = Some(valid_until);
+    }
+    if let Some(max_uses) = req.max_uses {
+        coupon.max_uses = Some(max_uses);
+    }
+    if let Some(active) = req.active {
+        coupon.active = active;
+    }
+    if let Err(message) = validate_coupon(&coupon) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": message
+        })));
+    }
+
+    let result = sqlx::query(
+        "UPDATE coupons SET discount_type = ?, discount_value = ?, min_order_amount = ?, valid_from = ?, valid_until = ?, max_uses = ?, active = ?
+         WHERE id = ?",
+    )
+    .bind(&coupon.discount_type)
+    .bind(coupon.discount_value)
+    .bind(coupon.min_order_amount)
+    .bind(coupon.valid_from.map(|at| at.naive_utc()))
+    .bind(coupon.valid_until.map(|at| at.naive_utc()))
+    .bind(coupon.max_uses)
+    .bind(coupon.active)
+    .bind(&coupon_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Ok().json(coupon)),
+        Err(e) => {
+            log::error!("Database error updating coupon: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update coupon"
+            })))
+        }
+    }
+}
+
+pub async fn delete_coupon(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let coupon_id = path.into_inner();
+
+    let coupon = match find_coupon(pool.get_ref(), &coupon_id).await {
+        Ok(Some(coupon)) => coupon,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Coupon not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching coupon: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &coupon.restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    // Orders keep the code and discount they were placed with
+    match sqlx::query("DELETE FROM coupons WHERE id = ?")
+        .bind(&coupon_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting coupon: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete coupon"
+            })))
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::email_service::EmailService;
 use crate::models::*;
+use crate::sessions::revoke_user_sessions;
 use crate::Settings;
 use actix_web::{web, HttpResponse, Result};
 use chrono::{Duration, Utc};
@@ -9,7 +10,7 @@ use sqlx::{Pool, Sqlite};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-fn hash_token(token: &str) -> String {
+pub fn hash_token(token: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(token.as_bytes());
     format!("{:x}", hasher.finalize())
@@ -524,6 +525,18 @@ pub async fn confirm_password_reset(
         );
     }
 
+    // Whoever had the old password is signed out everywhere
+    if let Err(e) = revoke_user_sessions(&mut tx, user_id).await {
+        error!("Failed to revoke sessions: {}", e);
+        let _ = tx.rollback().await;
+        return Ok(
+            HttpResponse::InternalServerError().json(PasswordResetResponse {
+                success: false,
+                message: "Internal server error".to_string(),
+            }),
+        );
+    }
+
     if let Err(e) = tx.commit().await {
         error!("Failed to commit transaction: {}", e);
         return Ok(
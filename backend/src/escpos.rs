@@ -0,0 +1,369 @@
+// ESC/POS rendering for thermal printers. Everything here is pure so the byte
+// output can be compared against golden files.
+
+const ESC: u8 = 0x1b;
+const GS: u8 = 0x1d;
+
+pub const PRINT_KINDS: [&str; 2] = ["ticket", "receipt"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperWidth {
+    Mm58,
+    Mm80,
+}
+
+impl PaperWidth {
+    // 80mm is the common kitchen printer, so it is the default
+    pub fn parse(width: Option<i64>) -> Result<Self, String> {
+        match width {
+            None | Some(80) => Ok(PaperWidth::Mm80),
+            Some(58) => Ok(PaperWidth::Mm58),
+            Some(_) => Err("Width must be 58 or 80".to_string()),
+        }
+    }
+
+    pub fn mm(self) -> i64 {
+        match self {
+            PaperWidth::Mm58 => 58,
+            PaperWidth::Mm80 => 80,
+        }
+    }
+
+    // Characters per line in the printer's default font A (12x24 dots)
+    pub fn columns(self) -> usize {
+        match self {
+            PaperWidth::Mm58 => 32,
+            PaperWidth::Mm80 => 48,
+        }
+    }
+
+    fn qr_module_size(self) -> u8 {
+        match self {
+            PaperWidth::Mm58 => 4,
+            PaperWidth::Mm80 => 6,
+        }
+    }
+}
+
+pub struct PrintLine {
+    pub quantity: i32,
+    pub name: String,
+    pub variant_name: Option<String>,
+    pub bundle_name: Option<String>,
+    pub notes: Option<String>,
+    pub line_total: f64,
+}
+
+// An order as it is printed. `placed_at` is already in the restaurant's time.
+pub struct PrintOrder {
+    pub order_id: String,
+    pub order_number: Option<i64>,
+    pub restaurant_name: String,
+    pub restaurant_address: Option<String>,
+    pub table_name: String,
+    pub customer_name: Option<String>,
+    pub placed_at: String,
+    pub lines: Vec<PrintLine>,
+    pub coupon_code: Option<String>,
+    pub discount_amount: f64,
+    pub total_amount: f64,
+    pub payment_status: String,
+    pub order_url: String,
+}
+
+impl PrintOrder {
+    // Orders placed before numbering existed fall back to a short id
+    pub fn label(&self) -> String {
+        match self.order_number {
+            Some(number) => format!("#{number}"),
+            None => self.order_id.chars().take(8).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left = 0,
+    Center = 1,
+}
+
+// Printers start in code page 437, so text is folded to ASCII: common accented
+// letters lose their accent and anything else prints as '?'
+fn to_printable(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                return c as u8;
+            }
+            match c {
+                'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => b'a',
+                'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => b'A',
+                'ç' => b'c',
+                'Ç' => b'C',
+                'è' | 'é' | 'ê' | 'ë' => b'e',
+                'È' | 'É' | 'Ê' | 'Ë' => b'E',
+                'ì' | 'í' | 'î' | 'ï' => b'i',
+                'Ì' | 'Í' | 'Î' | 'Ï' => b'I',
+                'ñ' => b'n',
+                'Ñ' => b'N',
+                'ò' | 'ó' | 'ô' | 'õ' | 'ö' => b'o',
+                'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => b'O',
+                'ù' | 'ú' | 'û' | 'ü' => b'u',
+                'Ù' | 'Ú' | 'Û' | 'Ü' => b'U',
+                'ý' | 'ÿ' => b'y',
+                '\t' | '\n' | '\r' => b' ',
+                _ => b'?',
+            }
+        })
+        .collect()
+}
+
+// Word-wraps to `width` characters, splitting words that are longer than a line
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        let len = word.chars().count();
+        if current.is_empty() {
+            current = word;
+        } else if current.chars().count() + 1 + len <= width {
+            current.push(' ');
+            current.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+struct Printer {
+    width: PaperWidth,
+    bytes: Vec<u8>,
+}
+
+impl Printer {
+    fn new(width: PaperWidth) -> Self {
+        Printer {
+            width,
+            bytes: vec![ESC, b'@'],
+        }
+    }
+
+    fn align(&mut self, align: Align) {
+        self.bytes.extend([ESC, b'a', align as u8]);
+    }
+
+    fn bold(&mut self, on: bool) {
+        self.bytes.extend([ESC, b'E', on as u8]);
+    }
+
+    // Double width and height; a line then holds half as many characters
+    fn double(&mut self, on: bool) {
+        self.bytes.extend([GS, b'!', if on { 0x11 } else { 0x00 }]);
+    }
+
+    fn line(&mut self, text: &str) {
+        self.bytes.extend(to_printable(text));
+        self.bytes.push(b'\n');
+    }
+
+    fn wrapped(&mut self, text: &str, width: usize) {
+        for line in wrap(text, width) {
+            self.line(&line);
+        }
+    }
+
+    fn rule(&mut self) {
+        self.line(&"-".repeat(self.width.columns()));
+    }
+
+    // Text on the left, an amount flush right on the first line
+    fn columns(&mut self, left: &str, right: &str) {
+        let width = self.width.columns();
+        let left_width = width.saturating_sub(right.chars().count() + 1).max(1);
+        let mut lines = wrap(left, left_width).into_iter();
+        let first = lines.next().unwrap_or_default();
+        let padding = width.saturating_sub(first.chars().count() + right.chars().count());
+        self.line(&format!("{first}{}{right}", " ".repeat(padding)));
+        for line in lines {
+            self.line(&line);
+        }
+    }
+
+    // Modifiers are indented under their item and wrapped on the same indent
+    fn modifier(&mut self, prefix: &str, text: &str) {
+        let indent = 4;
+        let mut lines = wrap(text, self.width.columns() - indent - prefix.len()).into_iter();
+        if let Some(first) = lines.next() {
+            self.line(&format!("{}{prefix}{first}", " ".repeat(indent)));
+        }
+        for line in lines {
+            self.line(&format!("{}{line}", " ".repeat(indent + prefix.len())));
+        }
+    }
+
+    // QR model 2, error correction M, printed from the symbol storage area
+    fn qr(&mut self, data: &str) {
+        let data = to_printable(data);
+        let store_len = data.len() + 3;
+        self.bytes.extend([GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+        self.bytes
+            .extend([GS, b'(', b'k', 3, 0, 49, 67, self.width.qr_module_size()]);
+        self.bytes.extend([GS, b'(', b'k', 3, 0, 49, 69, 49]);
+        self.bytes.extend([
+            GS,
+            b'(',
+            b'k',
+            (store_len % 256) as u8,
+            (store_len / 256) as u8,
+            49,
+            80,
+            48,
+        ]);
+        self.bytes.extend(data);
+        self.bytes.extend([GS, b'(', b'k', 3, 0, 49, 81, 48]);
+        self.bytes.push(b'\n');
+    }
+
+    // Feeds past the cutter before a partial cut
+    fn cut(mut self) -> Vec<u8> {
+        self.bytes.extend([GS, b'V', 66, 3]);
+        self.bytes
+    }
+}
+
+fn line_modifiers(printer: &mut Printer, line: &PrintLine) {
+    if let Some(variant) = &line.variant_name {
+        printer.modifier("+ ", variant);
+    }
+    if let Some(bundle) = &line.bundle_name {
+        printer.modifier("in ", bundle);
+    }
+    if let Some(notes) = line
+        .notes
+        .as_deref()
+        .filter(|notes| !notes.trim().is_empty())
+    {
+        printer.modifier("NOTE: ", notes);
+    }
+}
+
+// A kitchen ticket has no prices. `station_name` heads tickets for one station.
+pub fn render_kitchen_ticket(
+    order: &PrintOrder,
+    station_name: Option<&str>,
+    width: PaperWidth,
+) -> Vec<u8> {
+    let mut printer = Printer::new(width);
+    let half = width.columns() / 2;
+
+    printer.align(Align::Center);
+    printer.bold(true);
+    printer.double(true);
+    printer.wrapped(&station_name.unwrap_or("Kitchen").to_uppercase(), half);
+    printer.line(&order.label());
+    printer.double(false);
+    printer.wrapped(&order.table_name, width.columns());
+    printer.bold(false);
+    printer.line(&order.placed_at);
+    if let Some(customer) = &order.customer_name {
+        printer.wrapped(customer, width.columns());
+    }
+
+    printer.align(Align::Left);
+    printer.rule();
+    for line in &order.lines {
+        printer.bold(true);
+        printer.wrapped(
+            &format!("{} x {}", line.quantity, line.name),
+            width.columns(),
+        );
+        printer.bold(false);
+        line_modifiers(&mut printer, line);
+    }
+    printer.rule();
+    printer.bytes.extend([ESC, b'd', 3]);
+    printer.cut()
+}
+
+fn money(amount: f64) -> String {
+    format!("{amount:.2}")
+}
+
+fn payment_label(payment_status: &str) -> Option<&'static str> {
+    match payment_status {
+        "paid" => Some("PAID"),
+        "awaiting_payment" => Some("PAYMENT DUE"),
+        "partially_refunded" => Some("PARTIALLY REFUNDED"),
+        "refunded" => Some("REFUNDED"),
+        _ => None,
+    }
+}
+
+// A customer receipt: priced lines, totals and a QR code linking to the order
+pub fn render_receipt(order: &PrintOrder, width: PaperWidth) -> Vec<u8> {
+    let mut printer = Printer::new(width);
+    let half = width.columns() / 2;
+
+    printer.align(Align::Center);
+    printer.bold(true);
+    printer.double(true);
+    printer.wrapped(&order.restaurant_name, half);
+    printer.double(false);
+    printer.bold(false);
+    if let Some(address) = &order.restaurant_address {
+        printer.wrapped(address, width.columns());
+    }
+    printer.line("");
+    printer.bold(true);
+    printer.line(&format!("Order {}", order.label()));
+    printer.bold(false);
+    printer.wrapped(&order.table_name, width.columns());
+    printer.line(&order.placed_at);
+
+    printer.align(Align::Left);
+    printer.rule();
+    let mut subtotal = 0.0;
+    for line in &order.lines {
+        subtotal += line.line_total;
+        printer.columns(
+            &format!("{} x {}", line.quantity, line.name),
+            &money(line.line_total),
+        );
+        line_modifiers(&mut printer, line);
+    }
+    printer.rule();
+    if order.discount_amount > 0.0 {
+        printer.columns("Subtotal", &money(subtotal));
+        let label = match &order.coupon_code {
+            Some(code) => format!("Discount ({code})"),
+            None => "Discount".to_string(),
+        };
+        printer.columns(&label, &money(-order.discount_amount));
+    }
+    printer.bold(true);
+    printer.columns("TOTAL", &money(order.total_amount));
+    printer.bold(false);
+    if let Some(label) = payment_label(&order.payment_status) {
+        printer.line(label);
+    }
+
+    printer.line("");
+    printer.align(Align::Center);
+    printer.qr(&order.order_url);
+    printer.line("Scan to view your order");
+    printer.line("Thank you!");
+    printer.bytes.extend([ESC, b'd', 3]);
+    printer.cut()
+}
@@ -44,6 +44,104 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     OrderResponse::export_all_to(&temp_dir)?;
     OrderItemResponse::export_all_to(&temp_dir)?;
     CreateOrderResponse::export_all_to(&temp_dir)?;
+    PaymentIntentResponse::export_all_to(&temp_dir)?;
+    ConfirmPaymentRequest::export_all_to(&temp_dir)?;
+    ConfirmPaymentResponse::export_all_to(&temp_dir)?;
+    RefundPaymentRequest::export_all_to(&temp_dir)?;
+    RefundPaymentResponse::export_all_to(&temp_dir)?;
+    OrderingAvailability::export_all_to(&temp_dir)?;
+    WeeklyOpeningWindow::export_all_to(&temp_dir)?;
+    DailyWindow::export_all_to(&temp_dir)?;
+    SpecialDay::export_all_to(&temp_dir)?;
+    OpeningHoursResponse::export_all_to(&temp_dir)?;
+    UpdateOpeningHoursRequest::export_all_to(&temp_dir)?;
+    UpsertSpecialDayRequest::export_all_to(&temp_dir)?;
+    UpdateClosureRequest::export_all_to(&temp_dir)?;
+    SectionScheduleWindow::export_all_to(&temp_dir)?;
+    SectionScheduleResponse::export_all_to(&temp_dir)?;
+    UpdateSectionScheduleRequest::export_all_to(&temp_dir)?;
+    MenuItemStock::export_all_to(&temp_dir)?;
+    UpdateStockRequest::export_all_to(&temp_dir)?;
+    RestockRequest::export_all_to(&temp_dir)?;
+    MenuSnapshot::export_all_to(&temp_dir)?;
+    SnapshotSection::export_all_to(&temp_dir)?;
+    SnapshotItem::export_all_to(&temp_dir)?;
+    MenuVersionSummary::export_all_to(&temp_dir)?;
+    MenuVersionDetail::export_all_to(&temp_dir)?;
+    PublishMenuRequest::export_all_to(&temp_dir)?;
+    FieldChange::export_all_to(&temp_dir)?;
+    MenuDiffEntry::export_all_to(&temp_dir)?;
+    MenuDiff::export_all_to(&temp_dir)?;
+    ImportMenu::export_all_to(&temp_dir)?;
+    ImportMenuSection::export_all_to(&temp_dir)?;
+    ImportMenuItem::export_all_to(&temp_dir)?;
+    MenuImportError::export_all_to(&temp_dir)?;
+    MenuImportResult::export_all_to(&temp_dir)?;
+    CloneMenuRequest::export_all_to(&temp_dir)?;
+    RestaurantTranslation::export_all_to(&temp_dir)?;
+    SectionTranslation::export_all_to(&temp_dir)?;
+    ItemTranslation::export_all_to(&temp_dir)?;
+    RestaurantTranslations::export_all_to(&temp_dir)?;
+    UpsertRestaurantTranslationRequest::export_all_to(&temp_dir)?;
+    UpsertSectionTranslationRequest::export_all_to(&temp_dir)?;
+    UpsertItemTranslationRequest::export_all_to(&temp_dir)?;
+    MenuSearchResult::export_all_to(&temp_dir)?;
+    MenuSearchResponse::export_all_to(&temp_dir)?;
+    PublicMenuVariant::export_all_to(&temp_dir)?;
+    SnapshotVariant::export_all_to(&temp_dir)?;
+    MenuItemVariant::export_all_to(&temp_dir)?;
+    CreateMenuItemVariantRequest::export_all_to(&temp_dir)?;
+    UpdateMenuItemVariantRequest::export_all_to(&temp_dir)?;
+    MenuBundle::export_all_to(&temp_dir)?;
+    MenuBundleSlot::export_all_to(&temp_dir)?;
+    BundleSlotRequest::export_all_to(&temp_dir)?;
+    CreateBundleRequest::export_all_to(&temp_dir)?;
+    UpdateBundleRequest::export_all_to(&temp_dir)?;
+    PublicBundle::export_all_to(&temp_dir)?;
+    PublicBundleSlot::export_all_to(&temp_dir)?;
+    PublicBundleChoice::export_all_to(&temp_dir)?;
+    PricingRuleWindow::export_all_to(&temp_dir)?;
+    PricingRule::export_all_to(&temp_dir)?;
+    CreatePricingRuleRequest::export_all_to(&temp_dir)?;
+    UpdatePricingRuleRequest::export_all_to(&temp_dir)?;
+    Coupon::export_all_to(&temp_dir)?;
+    CreateCouponRequest::export_all_to(&temp_dir)?;
+    UpdateCouponRequest::export_all_to(&temp_dir)?;
+    OrderDiscount::export_all_to(&temp_dir)?;
+    SalesReport::export_all_to(&temp_dir)?;
+    ItemSales::export_all_to(&temp_dir)?;
+    SectionSales::export_all_to(&temp_dir)?;
+    TableSales::export_all_to(&temp_dir)?;
+    HourlySales::export_all_to(&temp_dir)?;
+    OrderExportLine::export_all_to(&temp_dir)?;
+    CloseDayRequest::export_all_to(&temp_dir)?;
+    ReopenDayRequest::export_all_to(&temp_dir)?;
+    ClosingPaymentMethod::export_all_to(&temp_dir)?;
+    InProgressOrder::export_all_to(&temp_dir)?;
+    ClosingSummary::export_all_to(&temp_dir)?;
+    DayClosing::export_all_to(&temp_dir)?;
+    ClosingAuditEntry::export_all_to(&temp_dir)?;
+    Page::<()>::export_all_to(&temp_dir)?;
+    KitchenStation::export_all_to(&temp_dir)?;
+    CreateKitchenStationRequest::export_all_to(&temp_dir)?;
+    UpdateKitchenStationRequest::export_all_to(&temp_dir)?;
+    AssignStationRequest::export_all_to(&temp_dir)?;
+    StationLine::export_all_to(&temp_dir)?;
+    CreatePrintJobRequest::export_all_to(&temp_dir)?;
+    PrintJob::export_all_to(&temp_dir)?;
+    ClaimedPrintJob::export_all_to(&temp_dir)?;
+    AckPrintJobRequest::export_all_to(&temp_dir)?;
+    WebhookSubscription::export_all_to(&temp_dir)?;
+    CreateWebhookRequest::export_all_to(&temp_dir)?;
+    UpdateWebhookRequest::export_all_to(&temp_dir)?;
+    WebhookDelivery::export_all_to(&temp_dir)?;
+    WebhookDeliveryAttempt::export_all_to(&temp_dir)?;
+    WebhookDeliveryDetail::export_all_to(&temp_dir)?;
+    ApiKey::export_all_to(&temp_dir)?;
+    CreateApiKeyRequest::export_all_to(&temp_dir)?;
+    CreatedApiKey::export_all_to(&temp_dir)?;
+    RefreshTokenRequest::export_all_to(&temp_dir)?;
+    SessionInfo::export_all_to(&temp_dir)?;
     AuthResponse::export_all_to(&temp_dir)?;
     UserResponse::export_all_to(&temp_dir)?;
     QrCodeResponse::export_all_to(&temp_dir)?;
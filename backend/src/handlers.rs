@@ -1,19 +1,39 @@
 use crate::auth::{JwtManager, PasswordHasher};
+use crate::locale::{normalize_locale, DEFAULT_LANGUAGE};
 use crate::models::{
-    AuthResponse, Claims, CreateRestaurantRequest, InviteManagerRequest, InviteResponse,
-    JoinRestaurantRequest, LoginRequest, ManagerInfo, ManagerInvite, ManagerInviteRow,
+    Claims, CreateRestaurantRequest, InviteManagerRequest, InviteResponse, JoinRestaurantRequest,
+    LoginRequest, ManagerInfo, ManagerInvite, ManagerInviteRow, ManagerListQuery, Page,
     RegisterRequest, Restaurant, RestaurantRow, UpdateManagerPermissionsRequest,
-    UpdateRestaurantRequest, User, UserResponse, UserRow,
+    UpdateRestaurantRequest, User, UserRow,
 };
-use actix_web::{web, HttpResponse, Result};
+use crate::pagination::{PageRequest, SortField};
+use crate::sessions::start_session;
+use crate::timezone::{parse_timezone, DEFAULT_TIMEZONE};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
 use chrono::{Duration, Utc};
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Row, Sqlite};
 use uuid::Uuid;
 
+static MANAGER_SORTS: [SortField; 2] = [
+    SortField {
+        name: "created_at",
+        expr: "CAST(rm.created_at AS TEXT)",
+        numeric: false,
+        descending: false,
+    },
+    SortField {
+        name: "email",
+        expr: "u.email",
+        numeric: false,
+        descending: false,
+    },
+];
+
 pub async fn register(
     pool: web::Data<Pool<Sqlite>>,
     jwt_manager: web::Data<JwtManager>,
     req: web::Json<RegisterRequest>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse> {
     // Check if user already exists
     let existing_user = sqlx::query_as::<_, UserRow>(
@@ -74,17 +94,13 @@ pub async fn register(
             match user_row {
                 Ok(user_row) => {
                     let user = User::from(user_row);
-                    // Generate JWT token
-                    match jwt_manager.generate_token(&user) {
-                        Ok(token) => {
-                            let response = AuthResponse {
-                                token,
-                                user: UserResponse::from(user),
-                            };
-                            Ok(HttpResponse::Created().json(response))
-                        }
+                    // Sign the new user in
+                    match start_session(pool.get_ref(), jwt_manager.get_ref(), user, &http_req)
+                        .await
+                    {
+                        Ok(response) => Ok(HttpResponse::Created().json(response)),
                         Err(e) => {
-                            log::error!("JWT generation error: {e}");
+                            log::error!("Error starting session: {e}");
                             Ok(HttpResponse::InternalServerError().json(serde_json::json!({
                                 "error": "Internal server error"
                             })))
@@ -112,6 +128,7 @@ pub async fn login(
     pool: web::Data<Pool<Sqlite>>,
     jwt_manager: web::Data<JwtManager>,
     req: web::Json<LoginRequest>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse> {
     // Find user by email
     let user_row = sqlx::query_as::<_, UserRow>(
@@ -127,17 +144,12 @@ pub async fn login(
             // Verify password
             match PasswordHasher::verify_password(&req.password, &user.password_hash) {
                 Ok(true) => {
-                    // Generate JWT token
-                    match jwt_manager.generate_token(&user) {
-                        Ok(token) => {
-                            let response = AuthResponse {
-                                token,
-                                user: UserResponse::from(user),
-                            };
-                            Ok(HttpResponse::Ok().json(response))
-                        }
+                    match start_session(pool.get_ref(), jwt_manager.get_ref(), user, &http_req)
+                        .await
+                    {
+                        Ok(response) => Ok(HttpResponse::Ok().json(response)),
                         Err(e) => {
-                            log::error!("JWT generation error: {e}");
+                            log::error!("Error starting session: {e}");
                             Ok(HttpResponse::InternalServerError().json(serde_json::json!({
                                 "error": "Internal server error"
                             })))
@@ -184,6 +196,23 @@ pub async fn create_restaurant(
 ) -> Result<HttpResponse> {
     let restaurant_id = Uuid::new_v4().to_string();
 
+    let timezone = req.timezone.as_deref().unwrap_or(DEFAULT_TIMEZONE);
+    if parse_timezone(timezone).is_none() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid timezone, expected an IANA name like Asia/Kolkata"
+        })));
+    }
+
+    let default_language =
+        match normalize_locale(req.default_language.as_deref().unwrap_or(DEFAULT_LANGUAGE)) {
+            Some(language) => language,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid default language, expected a language tag like en or hi-IN"
+                })));
+            }
+        };
+
     // Start a transaction
     let mut tx = match pool.begin().await {
         Ok(tx) => tx,
@@ -197,12 +226,14 @@ pub async fn create_restaurant(
 
     // Create the restaurant
     let result = sqlx::query!(
-        "INSERT INTO restaurants (id, name, address, establishment_year, google_maps_link) VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO restaurants (id, name, address, establishment_year, google_maps_link, timezone, default_language) VALUES (?, ?, ?, ?, ?, ?, ?)",
         restaurant_id,
         req.name,
         req.address,
         req.establishment_year,
-        req.google_maps_link
+        req.google_maps_link,
+        timezone,
+        default_language
     )
     .execute(&mut *tx)
     .await;
@@ -240,7 +271,7 @@ pub async fn create_restaurant(
 
     // Fetch the created restaurant
     let restaurant_row = sqlx::query_as::<_, RestaurantRow>(
-        "SELECT id, name, address, establishment_year, google_maps_link, created_at FROM restaurants WHERE id = ?"
+        "SELECT id, name, address, establishment_year, google_maps_link, timezone, default_language, created_at FROM restaurants WHERE id = ?"
     )
     .bind(&restaurant_id)
     .fetch_one(pool.get_ref())
@@ -293,7 +324,7 @@ pub async fn get_restaurant(
 
     // Fetch restaurant details
     let restaurant_row = sqlx::query_as::<_, RestaurantRow>(
-        "SELECT id, name, address, establishment_year, google_maps_link, created_at FROM restaurants WHERE id = ?"
+        "SELECT id, name, address, establishment_year, google_maps_link, timezone, default_language, created_at FROM restaurants WHERE id = ?"
     )
     .bind(&restaurant_id)
     .fetch_optional(pool.get_ref())
@@ -348,6 +379,24 @@ pub async fn update_restaurant(
         }
     }
 
+    if let Some(ref timezone) = req.timezone {
+        if parse_timezone(timezone).is_none() {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid timezone, expected an IANA name like Asia/Kolkata"
+            })));
+        }
+    }
+
+    let default_language = match req.default_language.as_deref().map(normalize_locale) {
+        Some(None) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid default language, expected a language tag like en or hi-IN"
+            })));
+        }
+        Some(Some(language)) => Some(language),
+        None => None,
+    };
+
     // Build dynamic update query
     let mut query_parts = Vec::new();
     let mut params: Vec<&(dyn sqlx::Encode<sqlx::Sqlite> + Send + Sync)> = Vec::new();
@@ -368,6 +417,14 @@ pub async fn update_restaurant(
         query_parts.push("google_maps_link = ?");
         params.push(maps_link);
     }
+    if let Some(ref timezone) = req.timezone {
+        query_parts.push("timezone = ?");
+        params.push(timezone);
+    }
+    if let Some(ref language) = default_language {
+        query_parts.push("default_language = ?");
+        params.push(language);
+    }
 
     if query_parts.is_empty() {
         return Ok(HttpResponse::BadRequest().json(serde_json::json!({
@@ -395,6 +452,12 @@ pub async fn update_restaurant(
     if let Some(ref maps_link) = req.google_maps_link {
         query_builder = query_builder.bind(maps_link);
     }
+    if let Some(ref timezone) = req.timezone {
+        query_builder = query_builder.bind(timezone);
+    }
+    if let Some(ref language) = default_language {
+        query_builder = query_builder.bind(language);
+    }
 
     query_builder = query_builder.bind(&restaurant_id);
 
@@ -409,7 +472,7 @@ pub async fn update_restaurant(
             } else {
                 // Fetch updated restaurant
                 let restaurant_row = sqlx::query_as::<_, RestaurantRow>(
-                    "SELECT id, name, address, establishment_year, google_maps_link, created_at FROM restaurants WHERE id = ?"
+                    "SELECT id, name, address, establishment_year, google_maps_link, timezone, default_language, created_at FROM restaurants WHERE id = ?"
                 )
                 .bind(&restaurant_id)
                 .fetch_one(pool.get_ref())
@@ -611,6 +674,7 @@ pub async fn join_restaurant(
     jwt_manager: web::Data<JwtManager>,
     path: web::Path<(String, String)>,
     req: web::Json<JoinRestaurantRequest>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse> {
     let (restaurant_id, token) = path.into_inner();
 
@@ -770,7 +834,7 @@ pub async fn join_restaurant(
         })));
     }
 
-    // Fetch user and generate token
+    // Fetch user and sign them in
     let user_row = sqlx::query_as::<_, UserRow>(
         "SELECT id, email, phone, password_hash, created_at FROM users WHERE id = ?",
     )
@@ -781,16 +845,10 @@ pub async fn join_restaurant(
     match user_row {
         Ok(user_row) => {
             let user = User::from(user_row);
-            match jwt_manager.generate_token(&user) {
-                Ok(token) => {
-                    let response = AuthResponse {
-                        token,
-                        user: UserResponse::from(user),
-                    };
-                    Ok(HttpResponse::Ok().json(response))
-                }
+            match start_session(pool.get_ref(), jwt_manager.get_ref(), user, &http_req).await {
+                Ok(response) => Ok(HttpResponse::Ok().json(response)),
                 Err(e) => {
-                    log::error!("JWT generation error: {e}");
+                    log::error!("Error starting session: {e}");
                     Ok(HttpResponse::InternalServerError().json(serde_json::json!({
                         "error": "Internal server error"
                     })))
@@ -810,6 +868,7 @@ pub async fn list_managers(
     pool: web::Data<Pool<Sqlite>>,
     claims: web::ReqData<Claims>,
     path: web::Path<String>,
+    query: web::Query<ManagerListQuery>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
 
@@ -837,32 +896,77 @@ pub async fn list_managers(
         }
     }
 
-    // Fetch managers
-    let managers = sqlx::query!(
-        "SELECT u.id as user_id, u.email, u.phone, rm.role, rm.can_manage_menu, rm.created_at 
-         FROM restaurant_managers rm 
-         JOIN users u ON rm.user_id = u.id 
-         WHERE rm.restaurant_id = ? 
-         ORDER BY rm.created_at ASC",
-        restaurant_id
-    )
-    .fetch_all(pool.get_ref())
-    .await;
+    let page = match PageRequest::parse(
+        &MANAGER_SORTS,
+        query.cursor.as_deref(),
+        query.limit,
+        query.sort.as_deref(),
+        query.order.as_deref(),
+    ) {
+        Ok(page) => page,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+
+    // Fetch one page of managers
+    let mut managers_query = sqlx::QueryBuilder::<Sqlite>::new(format!(
+        "SELECT u.id as user_id, u.email, u.phone, rm.role, rm.can_manage_menu, rm.created_at,
+                {} AS sort_key
+         FROM restaurant_managers rm
+         JOIN users u ON rm.user_id = u.id
+         WHERE rm.restaurant_id = ",
+        page.sort_expr()
+    ));
+    managers_query.push_bind(&restaurant_id);
+    if let Some(role) = &query.role {
+        managers_query.push(" AND rm.role = ").push_bind(role);
+    }
+    if let Some(q) = query.q.as_deref().map(str::trim) {
+        if !q.is_empty() {
+            managers_query
+                .push(" AND u.email LIKE ")
+                .push_bind(format!("%{q}%"));
+        }
+    }
+    page.push_after_cursor(&mut managers_query, "u.id");
+    page.push_order_and_limit(&mut managers_query, "u.id");
+
+    let managers = match managers_query.build().fetch_all(pool.get_ref()).await {
+        Ok(rows) => page.split(rows, "user_id"),
+        Err(e) => Err(e),
+    };
 
     match managers {
-        Ok(managers) => {
-            let manager_infos: Vec<ManagerInfo> = managers
-                .into_iter()
-                .map(|row| ManagerInfo {
-                    user_id: row.user_id.unwrap_or_default(),
-                    email: row.email,
-                    phone: row.phone,
-                    role: row.role,
-                    can_manage_menu: row.can_manage_menu,
-                    created_at: chrono::DateTime::from_naive_utc_and_offset(row.created_at, Utc),
+        Ok((rows, next_cursor)) => {
+            let manager_infos = rows
+                .iter()
+                .map(|row| {
+                    Ok(ManagerInfo {
+                        user_id: row.try_get("user_id")?,
+                        email: row.try_get("email")?,
+                        phone: row.try_get("phone")?,
+                        role: row.try_get("role")?,
+                        can_manage_menu: row.try_get("can_manage_menu")?,
+                        created_at: chrono::DateTime::from_naive_utc_and_offset(
+                            row.try_get("created_at")?,
+                            Utc,
+                        ),
+                    })
                 })
-                .collect();
-            Ok(HttpResponse::Ok().json(manager_infos))
+                .collect::<Result<Vec<ManagerInfo>, sqlx::Error>>();
+
+            match manager_infos {
+                Ok(items) => Ok(HttpResponse::Ok().json(Page { items, next_cursor })),
+                Err(e) => {
+                    log::error!("Error reading managers: {e}");
+                    Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Internal server error"
+                    })))
+                }
+            }
         }
         Err(e) => {
             log::error!("Database error fetching managers: {e}");
@@ -1006,7 +1110,7 @@ pub async fn get_user_restaurants(
 ) -> Result<HttpResponse> {
     // Query restaurants where the user is a manager
     let restaurant_rows = sqlx::query_as::<_, RestaurantRow>(
-        "SELECT r.id, r.name, r.address, r.establishment_year, r.google_maps_link, r.created_at 
+        "SELECT r.id, r.name, r.address, r.establishment_year, r.google_maps_link, r.timezone, r.default_language, r.created_at 
          FROM restaurants r 
          JOIN restaurant_managers rm ON r.id = rm.restaurant_id 
          WHERE rm.user_id = ?
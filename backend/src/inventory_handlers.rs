@@ -0,0 +1,321 @@
+use crate::models::{Claims, MenuItemStock, OrderItem, RestockRequest, UpdateStockRequest};
+use crate::permissions::{can_manage_menu, is_manager};
+use actix_web::{web, HttpResponse, Result};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::BTreeMap;
+
+// Takes `quantity` units of an item inside the order transaction. Untracked
+// items always succeed; tracked items fail when not enough stock is left and
+// are marked unavailable (and sold out) once they reach zero.
+pub async fn take_stock(
+    conn: &mut SqliteConnection,
+    menu_item_id: &str,
+    quantity: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE menu_items
+         SET stock_quantity = stock_quantity - ?,
+             sold_out = CASE WHEN stock_quantity - ? <= 0 THEN available OR sold_out ELSE sold_out END,
+             available = CASE WHEN stock_quantity - ? <= 0 THEN FALSE ELSE available END
+         WHERE id = ? AND (stock_quantity IS NULL OR stock_quantity >= ?)",
+    )
+    .bind(quantity)
+    .bind(quantity)
+    .bind(quantity)
+    .bind(menu_item_id)
+    .bind(quantity)
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() > 0 {
+        return Ok(true);
+    }
+
+    // An item deleted from the draft is still sold from the live menu until
+    // the next publish, untracked
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM menu_items WHERE id = ?)")
+        .bind(menu_item_id)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(!exists)
+}
+
+// Puts units taken by an order back. An item only comes back on if running out
+// is what switched it off.
+pub async fn return_stock(
+    conn: &mut SqliteConnection,
+    menu_item_id: &str,
+    quantity: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE menu_items
+         SET stock_quantity = stock_quantity + ?,
+             available = available OR sold_out,
+             sold_out = FALSE
+         WHERE id = ? AND stock_quantity IS NOT NULL",
+    )
+    .bind(quantity)
+    .bind(menu_item_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// Returns the stock taken for every line of an order that will not be served.
+pub async fn return_order_stock(
+    conn: &mut SqliteConnection,
+    order_id: &str,
+) -> Result<(), sqlx::Error> {
+    let items_json: String = sqlx::query_scalar("SELECT items FROM orders WHERE id = ?")
+        .bind(order_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let items: Vec<OrderItem> =
+        serde_json::from_str(&items_json).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+    let mut quantities: BTreeMap<String, i32> = BTreeMap::new();
+    for item in items {
+        *quantities.entry(item.menu_item_id).or_default() += item.quantity;
+    }
+
+    for (menu_item_id, quantity) in quantities {
+        return_stock(&mut *conn, &menu_item_id, quantity).await?;
+    }
+
+    Ok(())
+}
+
+async fn fetch_item_stock(
+    pool: &Pool<Sqlite>,
+    item_id: &str,
+) -> Result<Option<(String, MenuItemStock)>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT ms.restaurant_id, mi.id, mi.section_id, mi.name, mi.stock_quantity, mi.available
+         FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.id = ?",
+    )
+    .bind(item_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(|row| {
+        Ok((
+            row.try_get("restaurant_id")?,
+            MenuItemStock {
+                item_id: row.try_get("id")?,
+                section_id: row.try_get("section_id")?,
+                name: row.try_get("name")?,
+                stock_quantity: row.try_get("stock_quantity")?,
+                available: row.try_get("available")?,
+            },
+        ))
+    })
+    .transpose()
+}
+
+pub async fn list_stock_levels(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    // Only tracked items, lowest stock first so the kitchen sees what runs out next
+    let rows = sqlx::query(
+        "SELECT mi.id, mi.section_id, mi.name, mi.stock_quantity, mi.available
+         FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ? AND mi.stock_quantity IS NOT NULL
+         ORDER BY mi.stock_quantity ASC, mi.name ASC",
+    )
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            log::error!("Database error fetching stock levels: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let stock: Vec<MenuItemStock> = rows
+        .into_iter()
+        .map(|row| MenuItemStock {
+            item_id: row.get("id"),
+            section_id: row.get("section_id"),
+            name: row.get("name"),
+            stock_quantity: row.get("stock_quantity"),
+            available: row.get("available"),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(stock))
+}
+
+pub async fn update_item_stock(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateStockRequest>,
+) -> Result<HttpResponse> {
+    let item_id = path.into_inner();
+
+    let restaurant_id = match fetch_item_stock(pool.get_ref(), &item_id).await {
+        Ok(Some((restaurant_id, _))) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking item: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if req.stock_quantity.is_some_and(|stock| stock < 0) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Stock quantity cannot be negative"
+        })));
+    }
+
+    // Setting a count to zero sells the item out and a positive count brings
+    // back an item that had sold out; a manually disabled item stays off.
+    // Clearing the count stops tracking and leaves availability to the manual
+    // switch
+    let result = match req.stock_quantity {
+        Some(stock) => {
+            sqlx::query(
+                "UPDATE menu_items
+                 SET stock_quantity = ?,
+                     sold_out = CASE WHEN ? > 0 THEN FALSE ELSE available OR sold_out END,
+                     available = CASE WHEN ? > 0 THEN available OR sold_out ELSE FALSE END
+                 WHERE id = ?",
+            )
+            .bind(stock)
+            .bind(stock)
+            .bind(stock)
+            .bind(&item_id)
+            .execute(pool.get_ref())
+            .await
+        }
+        None => {
+            sqlx::query(
+                "UPDATE menu_items SET stock_quantity = NULL, sold_out = FALSE WHERE id = ?",
+            )
+            .bind(&item_id)
+            .execute(pool.get_ref())
+            .await
+        }
+    };
+
+    if let Err(e) = result {
+        log::error!("Database error updating stock: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update stock"
+        })));
+    }
+
+    match fetch_item_stock(pool.get_ref(), &item_id).await {
+        Ok(Some((_, stock))) => Ok(HttpResponse::Ok().json(stock)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Menu item not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching stock: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn restock_menu_item(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<RestockRequest>,
+) -> Result<HttpResponse> {
+    let item_id = path.into_inner();
+
+    let restaurant_id = match fetch_item_stock(pool.get_ref(), &item_id).await {
+        Ok(Some((restaurant_id, _))) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking item: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if req.quantity <= 0 {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Restock quantity must be greater than 0"
+        })));
+    }
+
+    // Restocking an untracked item starts tracking it from the given quantity.
+    // Only an item that sold out is switched back on
+    let result = sqlx::query(
+        "UPDATE menu_items
+         SET stock_quantity = COALESCE(stock_quantity, 0) + ?,
+             available = available OR sold_out,
+             sold_out = FALSE
+         WHERE id = ?",
+    )
+    .bind(req.quantity)
+    .bind(&item_id)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Database error restocking item: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to restock item"
+        })));
+    }
+
+    match fetch_item_stock(pool.get_ref(), &item_id).await {
+        Ok(Some((_, stock))) => Ok(HttpResponse::Ok().json(stock)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Menu item not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching stock: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
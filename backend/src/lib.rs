@@ -7,17 +7,48 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
 use ts_rs::TS;
 
+pub mod api_key_handlers;
+pub mod api_keys;
 pub mod auth;
+pub mod bundle_handlers;
+pub mod closing_handlers;
 pub mod contact_handlers;
+pub mod coupon_handlers;
 pub mod email_handlers;
 pub mod email_service;
+pub mod escpos;
 pub mod handlers;
+pub mod inventory_handlers;
+pub mod locale;
 pub mod menu_handlers;
+pub mod menu_import;
+pub mod menu_import_handlers;
+pub mod menu_version_handlers;
+pub mod menu_versions;
 pub mod models;
+pub mod opening_hours_handlers;
 pub mod order_handlers;
+pub mod pagination;
+pub mod payment_handlers;
+pub mod payments;
+pub mod permissions;
+pub mod pricing_rule_handlers;
+pub mod print_handlers;
 pub mod qr_handlers;
+pub mod report_handlers;
+pub mod reports;
+pub mod schedule;
+pub mod section_schedule_handlers;
 pub mod seed;
+pub mod session_handlers;
+pub mod sessions;
+pub mod station_handlers;
 pub mod table_handlers;
+pub mod timezone;
+pub mod translation_handlers;
+pub mod variant_handlers;
+pub mod webhook_handlers;
+pub mod webhooks;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Settings {
@@ -26,6 +57,8 @@ pub struct Settings {
     pub litestream: Option<LitestreamSettings>,
     pub jwt: JwtSettings,
     pub email: Option<EmailSettings>,
+    pub payments: Option<PaymentSettings>,
+    pub webhooks: Option<WebhookSettings>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -47,9 +80,51 @@ pub struct LitestreamSettings {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "JwtSettingsFile")]
 pub struct JwtSettings {
     pub secret: String,
-    pub expiration_hours: u64,
+    // Access tokens are short-lived; clients renew them with their refresh token
+    pub access_token_minutes: u64,
+}
+
+fn default_access_token_minutes() -> u64 {
+    15
+}
+
+// The [jwt] section as written. expiration_hours is the old name for the
+// token lifetime; settings files that still use it keep their lifetime.
+#[derive(Deserialize)]
+struct JwtSettingsFile {
+    secret: String,
+    access_token_minutes: Option<u64>,
+    expiration_hours: Option<u64>,
+}
+
+impl TryFrom<JwtSettingsFile> for JwtSettings {
+    type Error = String;
+
+    fn try_from(file: JwtSettingsFile) -> Result<Self, Self::Error> {
+        let access_token_minutes =
+            match (file.access_token_minutes, file.expiration_hours) {
+                (Some(_), Some(_)) => return Err(
+                    "jwt.expiration_hours is replaced by jwt.access_token_minutes; set only one"
+                        .to_string(),
+                ),
+                (Some(minutes), None) => minutes,
+                (None, Some(hours)) => {
+                    log::warn!(
+                        "jwt.expiration_hours is deprecated; using access_token_minutes = {}",
+                        hours * 60
+                    );
+                    hours * 60
+                }
+                (None, None) => default_access_token_minutes(),
+            };
+        Ok(Self {
+            secret: file.secret,
+            access_token_minutes,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -61,6 +136,27 @@ pub struct EmailSettings {
     pub enabled: bool,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaymentSettings {
+    pub provider: String,
+    pub currency: String,
+    // Never read from settings files; see PAYMENT_WEBHOOK_SECRET_ENV
+    #[serde(skip)]
+    pub webhook_secret: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookSettings {
+    // Lets webhooks reach loopback and private network addresses. Only for
+    // local development; in production these would expose internal services.
+    #[serde(default)]
+    pub allow_private_targets: bool,
+}
+
+// The payment provider's webhook secret is only taken from the environment
+pub const PAYMENT_WEBHOOK_SECRET_ENV: &str = "LETSORDER_PAYMENT_WEBHOOK_SECRET";
+
 impl Settings {
     pub fn new() -> Result<Self, config::ConfigError> {
         let settings = config::Config::builder()
@@ -69,7 +165,19 @@ impl Settings {
             .add_source(config::Environment::with_prefix("LETSORDER"))
             .build()?;
 
-        settings.try_deserialize()
+        let mut settings: Self = settings.try_deserialize()?;
+        if let Some(payments) = settings.payments.as_mut() {
+            payments.webhook_secret = std::env::var(PAYMENT_WEBHOOK_SECRET_ENV)
+                .ok()
+                .filter(|secret| !secret.is_empty());
+        }
+        Ok(settings)
+    }
+
+    pub fn allow_private_webhook_targets(&self) -> bool {
+        self.webhooks
+            .as_ref()
+            .is_some_and(|webhooks| webhooks.allow_private_targets)
     }
 }
 
@@ -87,7 +195,7 @@ impl Default for Settings {
             litestream: None,
             jwt: JwtSettings {
                 secret: "default-secret-change-in-production".to_string(),
-                expiration_hours: 24,
+                access_token_minutes: default_access_token_minutes(),
             },
             email: Some(EmailSettings {
                 api_key: "your-resend-api-key-here".to_string(),
@@ -96,6 +204,13 @@ impl Default for Settings {
                 admin_email: "admin@letsorder.app".to_string(),
                 enabled: false,
             }),
+            payments: Some(PaymentSettings {
+                provider: "fake".to_string(),
+                currency: "INR".to_string(),
+                webhook_secret: None,
+                enabled: false,
+            }),
+            webhooks: None,
         }
     }
 }
@@ -156,6 +271,9 @@ pub fn create_app(
 > {
     let auth_middleware = HttpAuthentication::bearer(auth::jwt_validator);
     let rate_limiter = web::Data::new(contact_handlers::RateLimiter::new());
+    let payment_gateway = web::Data::new(payments::PaymentGateway::from_settings(
+        settings.payments.as_ref(),
+    ));
 
     App::new()
         .wrap(
@@ -168,8 +286,13 @@ pub fn create_app(
                 .allowed_origin("https://menu.letsorder.app")
                 .allowed_origin("https://m.letsorder.app")
                 .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-                .allowed_headers(vec!["Content-Type", "Authorization", "Cache-Control"])
-                .expose_headers(vec!["Content-Type"])
+                .allowed_headers(vec![
+                    "Content-Type",
+                    "Authorization",
+                    "Cache-Control",
+                    "Idempotency-Key",
+                ])
+                .expose_headers(vec!["Content-Type", "Idempotent-Replayed"])
                 .supports_credentials()
                 .max_age(3600),
         )
@@ -177,16 +300,50 @@ pub fn create_app(
         .app_data(web::Data::new(jwt_manager))
         .app_data(web::Data::new(settings))
         .app_data(rate_limiter)
+        .app_data(payment_gateway)
         .route("/health", web::get().to(health))
         .service(
             web::scope("/auth")
                 .route("/register", web::post().to(handlers::register))
-                .route("/login", web::post().to(handlers::login)),
+                .route("/login", web::post().to(handlers::login))
+                .route("/refresh", web::post().to(session_handlers::refresh_token))
+                // Email verification routes. They live in this scope because
+                // it answers every /auth path.
+                .route(
+                    "/verify-email",
+                    web::post().to(email_handlers::verify_email_token),
+                )
+                .route(
+                    "/resend-verification",
+                    web::post().to(email_handlers::resend_verification_email),
+                )
+                .route(
+                    "/request-password-reset",
+                    web::post().to(email_handlers::request_password_reset),
+                )
+                .route(
+                    "/confirm-password-reset",
+                    web::post().to(email_handlers::confirm_password_reset),
+                ),
         )
         .service(
             web::scope("/api")
                 .wrap(auth_middleware)
                 .route("/test", web::get().to(handlers::protected_test))
+                // Session routes
+                .route("/auth/logout", web::post().to(session_handlers::logout))
+                .route(
+                    "/auth/logout-all",
+                    web::post().to(session_handlers::logout_all),
+                )
+                .route(
+                    "/auth/sessions",
+                    web::get().to(session_handlers::list_sessions),
+                )
+                .route(
+                    "/auth/sessions/{session_id}",
+                    web::delete().to(session_handlers::revoke_session),
+                )
                 // User routes
                 .route(
                     "/user/restaurants",
@@ -237,11 +394,56 @@ pub fn create_app(
                     "/sections/{id}",
                     web::delete().to(menu_handlers::delete_menu_section),
                 )
+                .route(
+                    "/sections/{id}/schedule",
+                    web::get().to(section_schedule_handlers::get_section_schedule),
+                )
+                .route(
+                    "/sections/{id}/schedule",
+                    web::put().to(section_schedule_handlers::update_section_schedule),
+                )
                 // Menu management route
                 .route(
                     "/restaurants/{id}/menu",
                     web::get().to(menu_handlers::get_restaurant_menu),
                 )
+                .route(
+                    "/restaurants/{id}/menu/preview",
+                    web::get().to(menu_handlers::preview_public_menu),
+                )
+                .route(
+                    "/restaurants/{id}/menu/export",
+                    web::get().to(menu_import_handlers::export_menu),
+                )
+                .route(
+                    "/restaurants/{id}/menu/import",
+                    web::post().to(menu_import_handlers::import_menu),
+                )
+                .route(
+                    "/restaurants/{id}/menu/clone",
+                    web::post().to(menu_import_handlers::clone_menu),
+                )
+                // Draft/publish routes
+                .route(
+                    "/restaurants/{id}/menu/publish",
+                    web::post().to(menu_version_handlers::publish_menu),
+                )
+                .route(
+                    "/restaurants/{id}/menu/diff",
+                    web::get().to(menu_version_handlers::diff_menu),
+                )
+                .route(
+                    "/restaurants/{id}/menu/versions",
+                    web::get().to(menu_version_handlers::list_menu_versions),
+                )
+                .route(
+                    "/restaurants/{id}/menu/versions/{version_id}",
+                    web::get().to(menu_version_handlers::get_menu_version),
+                )
+                .route(
+                    "/restaurants/{id}/menu/versions/{version_id}/rollback",
+                    web::post().to(menu_version_handlers::rollback_menu),
+                )
                 // Menu item CRUD routes
                 .route(
                     "/sections/{id}/items",
@@ -259,6 +461,116 @@ pub fn create_app(
                     "/items/{id}/availability",
                     web::put().to(menu_handlers::toggle_menu_item_availability),
                 )
+                // Variant routes
+                .route(
+                    "/items/{id}/variants",
+                    web::get().to(variant_handlers::list_variants),
+                )
+                .route(
+                    "/items/{id}/variants",
+                    web::post().to(variant_handlers::create_variant),
+                )
+                .route(
+                    "/variants/{id}",
+                    web::put().to(variant_handlers::update_variant),
+                )
+                .route(
+                    "/variants/{id}",
+                    web::delete().to(variant_handlers::delete_variant),
+                )
+                // Bundle routes
+                .route(
+                    "/restaurants/{id}/bundles",
+                    web::get().to(bundle_handlers::list_bundles),
+                )
+                .route(
+                    "/restaurants/{id}/bundles",
+                    web::post().to(bundle_handlers::create_bundle),
+                )
+                .route(
+                    "/bundles/{id}",
+                    web::put().to(bundle_handlers::update_bundle),
+                )
+                .route(
+                    "/bundles/{id}",
+                    web::delete().to(bundle_handlers::delete_bundle),
+                )
+                // Pricing rule routes
+                .route(
+                    "/restaurants/{id}/pricing-rules",
+                    web::get().to(pricing_rule_handlers::list_pricing_rules),
+                )
+                .route(
+                    "/restaurants/{id}/pricing-rules",
+                    web::post().to(pricing_rule_handlers::create_pricing_rule),
+                )
+                .route(
+                    "/pricing-rules/{id}",
+                    web::put().to(pricing_rule_handlers::update_pricing_rule),
+                )
+                .route(
+                    "/pricing-rules/{id}",
+                    web::delete().to(pricing_rule_handlers::delete_pricing_rule),
+                )
+                // Coupon routes
+                .route(
+                    "/restaurants/{id}/coupons",
+                    web::get().to(coupon_handlers::list_coupons),
+                )
+                .route(
+                    "/restaurants/{id}/coupons",
+                    web::post().to(coupon_handlers::create_coupon),
+                )
+                .route(
+                    "/coupons/{id}",
+                    web::put().to(coupon_handlers::update_coupon),
+                )
+                .route(
+                    "/coupons/{id}",
+                    web::delete().to(coupon_handlers::delete_coupon),
+                )
+                // Inventory routes
+                .route(
+                    "/items/{id}/stock",
+                    web::put().to(inventory_handlers::update_item_stock),
+                )
+                .route(
+                    "/items/{id}/restock",
+                    web::post().to(inventory_handlers::restock_menu_item),
+                )
+                .route(
+                    "/restaurants/{id}/menu/stock",
+                    web::get().to(inventory_handlers::list_stock_levels),
+                )
+                // Translation routes
+                .route(
+                    "/restaurants/{id}/translations",
+                    web::get().to(translation_handlers::list_translations),
+                )
+                .route(
+                    "/restaurants/{id}/translations/{locale}",
+                    web::put().to(translation_handlers::upsert_restaurant_translation),
+                )
+                .route(
+                    "/restaurants/{id}/translations/{locale}",
+                    web::delete().to(translation_handlers::delete_restaurant_translation),
+                )
+                .route(
+                    "/sections/{id}/translations/{locale}",
+                    web::put().to(translation_handlers::upsert_section_translation),
+                )
+                .route(
+                    "/sections/{id}/translations/{locale}",
+                    web::delete().to(translation_handlers::delete_section_translation),
+                )
+                .route(
+                    "/items/{id}/translations/{locale}",
+                    web::put().to(translation_handlers::upsert_item_translation),
+                )
+                .route(
+                    "/items/{id}/translations/{locale}",
+                    web::delete().to(translation_handlers::delete_item_translation),
+                )
                 .route(
                     "/items/reorder",
                     web::post().to(menu_handlers::reorder_menu_items),
@@ -310,6 +622,10 @@ pub fn create_app(
                     "/restaurants/{id}/orders",
                     web::get().to(order_handlers::list_restaurant_orders),
                 )
+                .route(
+                    "/restaurants/{id}/orders/export",
+                    web::get().to(report_handlers::export_orders),
+                )
                 .route(
                     "/restaurants/{id}/orders/today",
                     web::get().to(order_handlers::list_today_orders),
@@ -318,6 +634,169 @@ pub fn create_app(
                     "/restaurants/{id}/tables/{table_id}/orders",
                     web::get().to(order_handlers::list_table_orders),
                 )
+                .route(
+                    "/orders/{order_id}/status",
+                    web::put().to(order_handlers::update_order_status),
+                )
+                // Kitchen station routes
+                .route(
+                    "/restaurants/{id}/stations",
+                    web::get().to(station_handlers::list_stations),
+                )
+                .route(
+                    "/restaurants/{id}/stations",
+                    web::post().to(station_handlers::create_station),
+                )
+                .route(
+                    "/stations/{id}",
+                    web::put().to(station_handlers::update_station),
+                )
+                .route(
+                    "/stations/{id}",
+                    web::delete().to(station_handlers::delete_station),
+                )
+                .route(
+                    "/sections/{id}/station",
+                    web::put().to(station_handlers::assign_section_station),
+                )
+                .route(
+                    "/items/{id}/station",
+                    web::put().to(station_handlers::assign_item_station),
+                )
+                .route(
+                    "/restaurants/{id}/kitchen/lines",
+                    web::get().to(station_handlers::list_station_lines),
+                )
+                .route(
+                    "/kitchen/lines/{id}/bump",
+                    web::post().to(station_handlers::bump_station_line),
+                )
+                .route(
+                    "/kitchen/lines/{id}/recall",
+                    web::post().to(station_handlers::recall_station_line),
+                )
+                // Printing routes
+                .route(
+                    "/restaurants/{id}/orders/{order_id}/ticket",
+                    web::get().to(print_handlers::download_ticket),
+                )
+                .route(
+                    "/restaurants/{id}/orders/{order_id}/receipt",
+                    web::get().to(print_handlers::download_receipt),
+                )
+                .route(
+                    "/restaurants/{id}/print-jobs",
+                    web::get().to(print_handlers::list_print_jobs),
+                )
+                .route(
+                    "/restaurants/{id}/print-jobs",
+                    web::post().to(print_handlers::create_print_job),
+                )
+                .route(
+                    "/restaurants/{id}/print-jobs/claim",
+                    web::post().to(print_handlers::claim_print_job),
+                )
+                .route(
+                    "/print-jobs/{id}/ack",
+                    web::post().to(print_handlers::ack_print_job),
+                )
+                // Webhook routes
+                .route(
+                    "/restaurants/{id}/webhooks",
+                    web::get().to(webhook_handlers::list_webhooks),
+                )
+                .route(
+                    "/restaurants/{id}/webhooks",
+                    web::post().to(webhook_handlers::create_webhook),
+                )
+                .route(
+                    "/webhooks/{id}",
+                    web::put().to(webhook_handlers::update_webhook),
+                )
+                .route(
+                    "/webhooks/{id}",
+                    web::delete().to(webhook_handlers::delete_webhook),
+                )
+                .route(
+                    "/webhooks/{id}/deliveries",
+                    web::get().to(webhook_handlers::list_webhook_deliveries),
+                )
+                .route(
+                    "/webhook-deliveries/{id}",
+                    web::get().to(webhook_handlers::get_webhook_delivery),
+                )
+                .route(
+                    "/webhook-deliveries/{id}/redeliver",
+                    web::post().to(webhook_handlers::redeliver_webhook),
+                )
+                // API key routes
+                .route(
+                    "/restaurants/{id}/api-keys",
+                    web::get().to(api_key_handlers::list_api_keys),
+                )
+                .route(
+                    "/restaurants/{id}/api-keys",
+                    web::post().to(api_key_handlers::create_api_key),
+                )
+                .route(
+                    "/api-keys/{id}",
+                    web::delete().to(api_key_handlers::revoke_api_key),
+                )
+                // Report routes
+                .route(
+                    "/restaurants/{id}/reports/sales",
+                    web::get().to(report_handlers::sales_report),
+                )
+                // Day closing routes
+                .route(
+                    "/restaurants/{id}/closings",
+                    web::get().to(closing_handlers::list_day_closings),
+                )
+                .route(
+                    "/restaurants/{id}/closings",
+                    web::post().to(closing_handlers::close_day),
+                )
+                .route(
+                    "/restaurants/{id}/closings/preview",
+                    web::get().to(closing_handlers::preview_day_closing),
+                )
+                .route(
+                    "/restaurants/{id}/closings/{date}",
+                    web::get().to(closing_handlers::get_day_closing),
+                )
+                .route(
+                    "/restaurants/{id}/closings/{date}/reopen",
+                    web::post().to(closing_handlers::reopen_day),
+                )
+                .route(
+                    "/restaurants/{id}/closings/{date}/audit",
+                    web::get().to(closing_handlers::list_closing_audit),
+                )
+                .route(
+                    "/restaurants/{id}/orders/{order_id}/refund",
+                    web::post().to(payment_handlers::refund_order_payment),
+                )
+                // Opening hours routes
+                .route(
+                    "/restaurants/{id}/opening-hours",
+                    web::get().to(opening_hours_handlers::get_opening_hours),
+                )
+                .route(
+                    "/restaurants/{id}/opening-hours",
+                    web::put().to(opening_hours_handlers::update_opening_hours),
+                )
+                .route(
+                    "/restaurants/{id}/special-days/{date}",
+                    web::put().to(opening_hours_handlers::upsert_special_day),
+                )
+                .route(
+                    "/restaurants/{id}/special-days/{date}",
+                    web::delete().to(opening_hours_handlers::delete_special_day),
+                )
+                .route(
+                    "/restaurants/{id}/closure",
+                    web::put().to(opening_hours_handlers::update_closure),
+                )
                 // Contact form management routes (admin only)
                 .route(
                     "/contact/submissions",
@@ -347,6 +826,10 @@ pub fn create_app(
             "/menu/{restaurant_code}/{table_code}",
             web::get().to(menu_handlers::get_public_menu),
         )
+        .route(
+            "/menu/{restaurant_code}/{table_code}/search",
+            web::get().to(menu_handlers::search_public_menu),
+        )
         // Public order routes (no auth required)
         .route("/orders", web::post().to(order_handlers::create_order))
         .route(
@@ -357,27 +840,19 @@ pub fn create_app(
             "/orders/{order_id}",
             web::get().to(order_handlers::get_order),
         )
-        // Public contact form route
-        .route(
-            "/contact",
-            web::post().to(contact_handlers::submit_contact_form),
-        )
-        // Email verification routes (public)
         .route(
-            "/auth/verify-email",
-            web::post().to(email_handlers::verify_email_token),
+            "/orders/{order_id}/payment/confirm",
+            web::post().to(payment_handlers::confirm_order_payment),
         )
+        // Payment provider callbacks (verified by signature, not auth)
         .route(
-            "/auth/resend-verification",
-            web::post().to(email_handlers::resend_verification_email),
-        )
-        .route(
-            "/auth/request-password-reset",
-            web::post().to(email_handlers::request_password_reset),
+            "/payments/webhook",
+            web::post().to(payment_handlers::payment_webhook),
         )
+        // Public contact form route
         .route(
-            "/auth/confirm-password-reset",
-            web::post().to(email_handlers::confirm_password_reset),
+            "/contact",
+            web::post().to(contact_handlers::submit_contact_form),
         )
         // Support ticket routes (public)
         .route(
@@ -406,8 +881,14 @@ pub async fn run_server() -> std::io::Result<()> {
         log::warn!("Failed to seed database: {e}");
     }
 
+    // Deliver queued webhooks in the background
+    webhooks::spawn_webhook_worker(pool.clone(), settings.allow_private_webhook_targets());
+
     // Initialize JWT manager
-    let jwt_manager = JwtManager::new(settings.jwt.secret.clone(), settings.jwt.expiration_hours);
+    let jwt_manager = JwtManager::new(
+        settings.jwt.secret.clone(),
+        settings.jwt.access_token_minutes,
+    );
 
     let bind_address = format!("{}:{}", settings.server.host, settings.server.port);
     info!("Starting server at http://{bind_address}");
@@ -0,0 +1,79 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// Accepts BCP 47 style tags such as "hi", "en-IN" or "zh-Hant-TW" and stores
+// them lowercased so lookups never depend on how a client spelled them
+pub fn normalize_locale(tag: &str) -> Option<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN
+        .get_or_init(|| Regex::new(r"^[a-zA-Z]{2,3}(-[a-zA-Z0-9]{2,8})*$").expect("valid regex"));
+
+    let tag = tag.trim().replace('_', "-");
+    pattern.is_match(&tag).then(|| tag.to_lowercase())
+}
+
+// Languages from an Accept-Language header, most preferred first. Entries
+// with q=0, wildcards and malformed tags are dropped.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = normalize_locale(parts.next()?)?;
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            (quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+
+    // Stable, so equally weighted languages keep the client's order
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    weighted.into_iter().map(|(tag, _)| tag).collect()
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+// Picks the first requested language the restaurant offers. A request for
+// "hi-in" is served by "hi", and "en" by "en-gb" when that is all there is.
+pub fn negotiate_locale(requested: &[String], available: &[String], default: &str) -> String {
+    for tag in requested {
+        if let Some(exact) = available.iter().find(|locale| *locale == tag) {
+            return exact.clone();
+        }
+
+        let mut prefix = tag.as_str();
+        while let Some((shorter, _)) = prefix.rsplit_once('-') {
+            prefix = shorter;
+            if let Some(locale) = available.iter().find(|locale| *locale == prefix) {
+                return locale.clone();
+            }
+        }
+
+        if let Some(locale) = available
+            .iter()
+            .find(|locale| primary_language(locale) == primary_language(tag))
+        {
+            return locale.clone();
+        }
+    }
+
+    default.to_string()
+}
+
+// An explicit ?lang= wins over the browser's Accept-Language preferences
+pub fn requested_locales(lang: Option<&str>, accept_language: Option<&str>) -> Vec<String> {
+    lang.and_then(normalize_locale)
+        .into_iter()
+        .chain(
+            accept_language
+                .map(parse_accept_language)
+                .unwrap_or_default(),
+        )
+        .collect()
+}
@@ -1,11 +1,24 @@
+use crate::api_keys::{api_key_allows, ApiKeyAuth, SCOPE_MENU_MANAGE};
+use crate::bundle_handlers::{load_bundles, public_bundles};
+use crate::locale::{negotiate_locale, requested_locales};
+use crate::menu_versions::live_snapshot;
 use crate::models::{
-    Claims, CreateMenuItemFromSectionRequest, CreateMenuSectionRequest, MenuItem, MenuSection,
-    MenuSectionWithItems, PublicMenu, PublicRestaurantInfo, ReorderItemsRequest,
+    Claims, CreateMenuItemFromSectionRequest, CreateMenuSectionRequest, MenuItem, MenuPreviewQuery,
+    MenuSearchQuery, MenuSearchResponse, MenuSearchResult, MenuSection, MenuSectionWithItems,
+    PublicMenu, PublicMenuQuery, PublicMenuVariant, PublicRestaurantInfo, ReorderItemsRequest,
     ReorderSectionsRequest, RestaurantMenu, ToggleAvailabilityRequest, UpdateMenuItemRequest,
     UpdateMenuSectionRequest,
 };
-use actix_web::{web, HttpResponse, Result};
-use sqlx::{Pool, Sqlite};
+use crate::opening_hours_handlers::restaurant_availability;
+use crate::permissions::is_manager;
+use crate::pricing_rule_handlers::PricingRules;
+use crate::section_schedule_handlers::SectionSchedules;
+use crate::translation_handlers::{menu_languages, translate_public_menu};
+use crate::variant_handlers::load_variants;
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // Menu Section Handlers
@@ -14,6 +27,7 @@ pub async fn create_menu_section(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<CreateMenuSectionRequest>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
@@ -28,6 +42,7 @@ pub async fn create_menu_section(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -110,6 +125,7 @@ pub async fn list_menu_sections(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
 
@@ -123,6 +139,7 @@ pub async fn list_menu_sections(
     .await;
 
     match manager_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User is a manager
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -175,6 +192,7 @@ pub async fn update_menu_section(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<UpdateMenuSectionRequest>,
 ) -> Result<HttpResponse> {
     let section_id = path.into_inner();
@@ -212,6 +230,7 @@ pub async fn update_menu_section(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -296,6 +315,7 @@ pub async fn delete_menu_section(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
 ) -> Result<HttpResponse> {
     let section_id = path.into_inner();
 
@@ -332,6 +352,7 @@ pub async fn delete_menu_section(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -417,6 +438,7 @@ pub async fn get_restaurant_menu(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
 
@@ -430,6 +452,7 @@ pub async fn get_restaurant_menu(
     .await;
 
     match manager_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User is a manager
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -444,54 +467,52 @@ pub async fn get_restaurant_menu(
         }
     }
 
-    // Fetch menu sections
-    let sections_result = sqlx::query_as::<_, crate::models::MenuSectionRow>(
+    match load_restaurant_menu(pool.get_ref(), &restaurant_id).await {
+        Ok(restaurant_menu) => Ok(HttpResponse::Ok().json(restaurant_menu)),
+        Err(e) => {
+            log::error!("Database error fetching restaurant menu: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn load_restaurant_menu(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<RestaurantMenu, sqlx::Error> {
+    let sections = sqlx::query_as::<_, crate::models::MenuSectionRow>(
         "SELECT id, restaurant_id, name, display_order, created_at 
          FROM menu_sections 
          WHERE restaurant_id = ? 
          ORDER BY display_order ASC",
     )
-    .bind(restaurant_id.clone())
-    .fetch_all(pool.get_ref())
-    .await;
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(MenuSection::from);
 
-    let sections = match sections_result {
-        Ok(rows) => rows.into_iter().map(MenuSection::from).collect::<Vec<_>>(),
-        Err(e) => {
-            log::error!("Database error fetching menu sections: {e}");
-            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Internal server error"
-            })));
-        }
-    };
-
-    // Fetch menu items for all sections
+    let mut variants = load_variants(pool, restaurant_id).await?;
     let mut sections_with_items = Vec::new();
 
     for section in sections {
-        let items_result = sqlx::query_as::<_, crate::models::MenuItemRow>(
-            "SELECT id, section_id, name, description, CAST(price AS REAL) as price, available, display_order, created_at 
+        let items = sqlx::query_as::<_, crate::models::MenuItemRow>(
+            "SELECT id, section_id, name, description, CAST(price AS REAL) as price, available, display_order, stock_quantity, created_at 
              FROM menu_items 
              WHERE section_id = ? 
              ORDER BY display_order ASC",
         )
         .bind(&section.id)
-        .fetch_all(pool.get_ref())
-        .await;
-
-        let items = match items_result {
-            Ok(rows) => rows.into_iter().map(MenuItem::from).collect(),
-            Err(e) => {
-                log::error!(
-                    "Database error fetching menu items for section {}: {}",
-                    section.id,
-                    e
-                );
-                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": "Internal server error"
-                })));
-            }
-        };
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| MenuItem {
+            variants: variants.remove(row.id.as_deref().unwrap_or_default()).unwrap_or_default(),
+            ..MenuItem::from(row)
+        })
+        .collect();
 
         sections_with_items.push(MenuSectionWithItems {
             id: section.id,
@@ -503,12 +524,10 @@ pub async fn get_restaurant_menu(
         });
     }
 
-    let restaurant_menu = RestaurantMenu {
-        restaurant_id,
+    Ok(RestaurantMenu {
+        restaurant_id: restaurant_id.to_string(),
         sections: sections_with_items,
-    };
-
-    Ok(HttpResponse::Ok().json(restaurant_menu))
+    })
 }
 
 // Public Menu Access
@@ -516,10 +535,55 @@ pub async fn get_restaurant_menu(
 pub async fn get_public_menu(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<(String, String)>,
+    query: web::Query<PublicMenuQuery>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse> {
     let (restaurant_code, table_code) = path.into_inner();
+    let requested = requested_locales(query.lang.as_deref(), accept_language(&http_req));
 
-    // Find restaurant and table by codes
+    let restaurant = match table_restaurant(pool.get_ref(), &restaurant_code, &table_code).await {
+        Ok(Some(restaurant)) => restaurant,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Restaurant or table not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching restaurant/table: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    match build_public_menu(
+        pool.get_ref(),
+        &restaurant_code,
+        restaurant,
+        Utc::now(),
+        &requested,
+    )
+    .await
+    {
+        Ok(public_menu) => Ok(HttpResponse::Ok()
+            .insert_header(("Content-Language", public_menu.language.clone()))
+            .insert_header(("Vary", "Accept-Language"))
+            .json(public_menu)),
+        Err(e) => {
+            log::error!("Database error building public menu: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Find restaurant and table by codes
+async fn table_restaurant(
+    pool: &Pool<Sqlite>,
+    restaurant_code: &str,
+    table_code: &str,
+) -> Result<Option<PublicRestaurantInfo>, sqlx::Error> {
     let restaurant_table = sqlx::query!(
         "SELECT r.id as restaurant_id, r.name as restaurant_name, r.address, t.id as table_id 
          FROM restaurants r 
@@ -528,11 +592,47 @@ pub async fn get_public_menu(
         restaurant_code,
         table_code
     )
-    .fetch_optional(pool.get_ref())
-    .await;
+    .fetch_optional(pool)
+    .await?;
 
-    let (_restaurant_id, restaurant_name, restaurant_address) = match restaurant_table {
-        Ok(Some(row)) => (row.restaurant_id, row.restaurant_name, row.address),
+    Ok(restaurant_table.map(|row| PublicRestaurantInfo {
+        name: row.restaurant_name,
+        address: row.address,
+    }))
+}
+
+const MAX_SEARCH_RESULTS: usize = 50;
+
+// Turns free text into an FTS5 query. Every word is quoted so operators and
+// punctuation typed by guests are matched literally, and prefix-matched so
+// results show up while they are still typing.
+fn search_expression(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+pub async fn search_public_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    query: web::Query<MenuSearchQuery>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse> {
+    let (restaurant_code, table_code) = path.into_inner();
+    let search = query.q.as_deref().unwrap_or("").trim().to_string();
+
+    let Some(expression) = search_expression(&search) else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Search query is required"
+        })));
+    };
+
+    let restaurant = match table_restaurant(pool.get_ref(), &restaurant_code, &table_code).await {
+        Ok(Some(restaurant)) => restaurant,
         Ok(None) => {
             return Ok(HttpResponse::NotFound().json(serde_json::json!({
                 "error": "Restaurant or table not found"
@@ -546,83 +646,253 @@ pub async fn get_public_menu(
         }
     };
 
-    // Fetch menu sections for this restaurant
-    let sections_result = sqlx::query_as::<_, crate::models::MenuSectionRow>(
-        "SELECT id, restaurant_id, name, display_order, created_at 
-         FROM menu_sections 
-         WHERE restaurant_id = ? 
-         ORDER BY display_order ASC",
+    // Names weigh more than descriptions, in any language
+    let matches: Result<Vec<String>, sqlx::Error> = sqlx::query_scalar(
+        "SELECT item_id FROM menu_item_search
+         WHERE menu_item_search MATCH ? AND restaurant_id = ?
+         ORDER BY bm25(menu_item_search, 0.0, 0.0, 10.0, 1.0, 10.0, 1.0)",
     )
-    .bind(restaurant_code.clone())
+    .bind(&expression)
+    .bind(&restaurant_code)
     .fetch_all(pool.get_ref())
     .await;
 
-    let sections = match sections_result {
-        Ok(rows) => rows.into_iter().map(MenuSection::from).collect::<Vec<_>>(),
+    let ranks: HashMap<String, usize> = match matches {
+        Ok(item_ids) => item_ids
+            .into_iter()
+            .enumerate()
+            .map(|(rank, item_id)| (item_id, rank))
+            .collect(),
         Err(e) => {
-            log::error!("Database error fetching menu sections: {e}");
+            log::error!("Database error searching menu: {e}");
             return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": "Internal server error"
             })));
         }
     };
 
-    // Fetch menu items for all sections
-    let mut sections_with_items = Vec::new();
-    for section in sections {
-        let items_result = sqlx::query_as::<_, crate::models::MenuItemRow>(
-            "SELECT id, section_id, name, description, CAST(price AS REAL) as price, available, display_order, created_at 
-             FROM menu_items 
-             WHERE section_id = ? AND available = TRUE
-             ORDER BY display_order ASC",
-        )
-        .bind(&section.id)
-        .fetch_all(pool.get_ref())
+    // Matches are narrowed to what the guest menu shows right now, so hidden
+    // sections, unavailable items and unpublished drafts never leak through
+    let requested = requested_locales(query.lang.as_deref(), accept_language(&http_req));
+    let public_menu = match build_public_menu(
+        pool.get_ref(),
+        &restaurant_code,
+        restaurant,
+        Utc::now(),
+        &requested,
+    )
+    .await
+    {
+        Ok(public_menu) => public_menu,
+        Err(e) => {
+            log::error!("Database error building public menu: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let mut results = Vec::new();
+    for section in public_menu.sections {
+        for item in section.items {
+            if let Some(&rank) = ranks.get(&item.id) {
+                let result = MenuSearchResult {
+                    section_id: section.id.clone(),
+                    section_name: section.name.clone(),
+                    item,
+                };
+                results.push((rank, result));
+            }
+        }
+    }
+    results.sort_by_key(|(rank, _)| *rank);
+    results.truncate(MAX_SEARCH_RESULTS);
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Content-Language", public_menu.language.clone()))
+        .insert_header(("Vary", "Accept-Language"))
+        .json(MenuSearchResponse {
+            query: search,
+            language: public_menu.language,
+            results: results.into_iter().map(|(_, result)| result).collect(),
+        }))
+}
+
+// Managers can check what guests will see at another time of day
+pub async fn preview_public_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<MenuPreviewQuery>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let restaurant_row = sqlx::query("SELECT name, address FROM restaurants WHERE id = ?")
+        .bind(&restaurant_id)
+        .fetch_optional(pool.get_ref())
         .await;
 
-        let items = match items_result {
-            Ok(rows) => rows
+    let restaurant = match restaurant_row {
+        Ok(Some(row)) => PublicRestaurantInfo {
+            name: row.get("name"),
+            address: row.get("address"),
+        },
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Restaurant not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching restaurant: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let at = query.at.unwrap_or_else(Utc::now);
+    let requested = requested_locales(query.lang.as_deref(), accept_language(&http_req));
+    match build_public_menu(pool.get_ref(), &restaurant_id, restaurant, at, &requested).await {
+        Ok(public_menu) => Ok(HttpResponse::Ok().json(public_menu)),
+        Err(e) => {
+            log::error!("Database error building menu preview: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn accept_language(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+}
+
+// The guest-facing menu at a given instant: the live version restricted to
+// sections whose schedule is active. Availability and stock are operational
+// and come from the current menu_items rows, published or not; an item or
+// variant deleted from the draft keeps its published availability until the
+// next publish. `requested` lists the guest's languages in order of preference.
+pub async fn build_public_menu(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    restaurant: PublicRestaurantInfo,
+    at: DateTime<Utc>,
+    requested: &[String],
+) -> Result<PublicMenu, sqlx::Error> {
+    let menu = live_snapshot(pool, restaurant_id).await?;
+    let section_schedules = SectionSchedules::load(pool, restaurant_id).await?;
+    let pricing_rules = PricingRules::load(pool, restaurant_id).await?;
+
+    let item_availability: HashMap<String, bool> = sqlx::query_as(
+        "SELECT mi.id, mi.available FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let variant_availability: HashMap<String, bool> = sqlx::query_as(
+        "SELECT v.id, v.available FROM menu_item_variants v
+         JOIN menu_items mi ON v.item_id = mi.id
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let sections = menu
+        .sections
+        .into_iter()
+        .filter(|section| section_schedules.is_active(&section.id, at))
+        .map(|section| crate::models::PublicMenuSection {
+            items: section
+                .items
                 .into_iter()
-                .map(|row| {
-                    let item_id = row.id.unwrap_or_default();
-                    log::debug!(
-                        "Menu item: {} -> ID: {}",
-                        row.name.as_ref().unwrap_or(&"Unknown".to_string()),
-                        item_id
-                    );
-                    crate::models::PublicMenuItem {
-                        id: item_id,
-                        name: row.name.unwrap_or_default(),
-                        description: row.description,
-                        price: row.price.unwrap_or(0.0),
-                    }
+                .filter(|item| {
+                    item_availability
+                        .get(&item.id)
+                        .copied()
+                        .unwrap_or(item.available)
                 })
-                .collect::<Vec<_>>(),
-            Err(e) => {
-                log::error!(
-                    "Database error fetching menu items for section {}: {e}",
-                    section.id
-                );
-                continue; // Skip this section on error
-            }
-        };
+                .filter_map(|item| {
+                    let has_variants = !item.variants.is_empty();
+                    let variants: Vec<PublicMenuVariant> = item
+                        .variants
+                        .into_iter()
+                        .filter(|variant| {
+                            variant_availability
+                                .get(&variant.id)
+                                .copied()
+                                .unwrap_or(true)
+                        })
+                        .map(|variant| PublicMenuVariant {
+                            id: variant.id,
+                            name: variant.name,
+                            price: pricing_rules
+                                .apply(&item.id, &section.id, variant.price, at)
+                                .0,
+                            original_price: variant.price,
+                        })
+                        .collect();
+
+                    // An item whose variants are all unavailable cannot be ordered
+                    if has_variants && variants.is_empty() {
+                        return None;
+                    }
 
-        sections_with_items.push(crate::models::PublicMenuSection {
+                    let (price, rule) = pricing_rules.apply(&item.id, &section.id, item.price, at);
+                    Some(crate::models::PublicMenuItem {
+                        id: item.id,
+                        name: item.name,
+                        description: item.description,
+                        price,
+                        original_price: item.price,
+                        pricing_rule: rule.map(|rule| rule.name.clone()),
+                        variants,
+                    })
+                })
+                .collect(),
             id: section.id,
             name: section.name,
-            items,
-        });
+        })
+        .collect();
+
+    let (default_language, languages) = menu_languages(pool, restaurant_id).await?;
+    let language = negotiate_locale(requested, &languages, &default_language);
+
+    let mut public_menu = PublicMenu {
+        restaurant,
+        sections,
+        availability: restaurant_availability(pool, restaurant_id, at).await?,
+        language: default_language.clone(),
+        available_languages: languages,
+        bundles: Vec::new(),
+    };
+
+    if language != default_language {
+        translate_public_menu(pool, restaurant_id, &language, &mut public_menu).await?;
+        public_menu.language = language;
     }
 
-    let public_menu = PublicMenu {
-        restaurant: PublicRestaurantInfo {
-            name: restaurant_name,
-            address: restaurant_address,
-        },
-        sections: sections_with_items,
-    };
+    let bundles = load_bundles(pool, restaurant_id).await?;
+    public_menu.bundles = public_bundles(bundles, &public_menu.sections);
 
-    Ok(HttpResponse::Ok().json(public_menu))
+    Ok(public_menu)
 }
 
 // Menu Item CRUD Handlers
@@ -631,6 +901,7 @@ pub async fn create_menu_item(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<CreateMenuItemFromSectionRequest>,
 ) -> Result<HttpResponse> {
     let section_id = path.into_inner();
@@ -668,6 +939,7 @@ pub async fn create_menu_item(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -735,6 +1007,7 @@ pub async fn update_menu_item(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<UpdateMenuItemRequest>,
 ) -> Result<HttpResponse> {
     let item_id = path.into_inner();
@@ -774,6 +1047,7 @@ pub async fn update_menu_item(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -978,6 +1252,7 @@ pub async fn delete_menu_item(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
 ) -> Result<HttpResponse> {
     let item_id = path.into_inner();
 
@@ -1016,6 +1291,7 @@ pub async fn delete_menu_item(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -1059,6 +1335,7 @@ pub async fn toggle_menu_item_availability(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<ToggleAvailabilityRequest>,
 ) -> Result<HttpResponse> {
     let item_id = path.into_inner();
@@ -1098,6 +1375,7 @@ pub async fn toggle_menu_item_availability(
     .await;
 
     match permission_check {
+        _ if api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
         Ok(row) if row.count > 0 => {} // User has menu permission
         Ok(_) => {
             return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -1112,8 +1390,10 @@ pub async fn toggle_menu_item_availability(
         }
     }
 
+    // A manual switch takes over from the sold-out flag, so restocking will not
+    // undo it
     let result = sqlx::query!(
-        "UPDATE menu_items SET available = ? WHERE id = ?",
+        "UPDATE menu_items SET available = ?, sold_out = FALSE WHERE id = ?",
         req.available,
         item_id
     )
@@ -1146,6 +1426,7 @@ pub async fn toggle_menu_item_availability(
 pub async fn reorder_menu_items(
     pool: web::Data<Pool<Sqlite>>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<ReorderItemsRequest>,
 ) -> Result<HttpResponse> {
     if req.item_orders.is_empty() {
@@ -1197,6 +1478,7 @@ pub async fn reorder_menu_items(
         .await;
 
         match permission_check {
+            _ if api_key_allows(api_key.as_deref(), restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
             Ok(row) if row.count > 0 => {} // User has menu permission
             Ok(_) => {
                 return Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -1243,6 +1525,7 @@ pub async fn reorder_menu_items(
 pub async fn reorder_sections(
     pool: web::Data<Pool<Sqlite>>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     req: web::Json<ReorderSectionsRequest>,
 ) -> Result<HttpResponse> {
     if req.section_orders.is_empty() {
@@ -1294,6 +1577,7 @@ pub async fn reorder_sections(
         .await;
 
         match permission_check {
+            _ if api_key_allows(api_key.as_deref(), restaurant_id, SCOPE_MENU_MANAGE) => {} // Scoped API key
             Ok(row) if row.count > 0 => {} // User has menu permission
             Ok(_) => {
                 return Ok(HttpResponse::Forbidden().json(serde_json::json!({
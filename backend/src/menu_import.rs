@@ -0,0 +1,625 @@
+use crate::models::{
+    ImportMenu, ImportMenuItem, ImportMenuSection, MenuImportError, MenuImportResult,
+    RestaurantMenu,
+};
+use sqlx::SqliteConnection;
+use std::collections::HashSet;
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub const CSV_COLUMNS: [&str; 7] = [
+    "section",
+    "section_order",
+    "name",
+    "description",
+    "price",
+    "available",
+    "display_order",
+];
+
+const REQUIRED_CSV_COLUMNS: [&str; 3] = ["section", "name", "price"];
+
+// One line of an import before validation. Rows without an item only
+// declare a section, which lets empty sections round-trip through CSV.
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub row: u32,
+    pub section: String,
+    pub section_order: Option<i32>,
+    pub item: Option<ImportMenuItem>,
+}
+
+fn import_error(row: u32, field: Option<&str>, message: impl Into<String>) -> MenuImportError {
+    MenuImportError {
+        row,
+        field: field.map(str::to_string),
+        message: message.into(),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) || value.trim() != value {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn csv_line(fields: &[String]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    format!("{}\n", fields.join(","))
+}
+
+pub fn menu_to_csv(menu: &RestaurantMenu) -> String {
+    let header: Vec<String> = CSV_COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .collect();
+    let mut csv = csv_line(&header);
+
+    for section in &menu.sections {
+        if section.items.is_empty() {
+            csv.push_str(&csv_line(&[
+                section.name.clone(),
+                section.display_order.to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]));
+        }
+        for item in &section.items {
+            csv.push_str(&csv_line(&[
+                section.name.clone(),
+                section.display_order.to_string(),
+                item.name.clone(),
+                item.description.clone().unwrap_or_default(),
+                format!("{:.2}", item.price),
+                item.available.to_string(),
+                item.display_order.to_string(),
+            ]));
+        }
+    }
+
+    csv
+}
+
+// Minimal RFC 4180 reader. Quoted fields may contain commas, escaped quotes
+// and line breaks. Fails with the record number of an unterminated quote.
+fn parse_records(input: &str) -> Result<Vec<Vec<String>>, u32> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(records.len() as u32 + 1);
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn parse_optional<T: FromStr>(
+    value: &str,
+    row: u32,
+    field: &str,
+    message: &str,
+    errors: &mut Vec<MenuImportError>,
+) -> Option<T> {
+    if value.is_empty() {
+        return None;
+    }
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            errors.push(import_error(row, Some(field), message));
+            None
+        }
+    }
+}
+
+fn parse_available(value: &str) -> Result<Option<bool>, ()> {
+    match value.to_lowercase().as_str() {
+        "" => Ok(None),
+        "true" | "yes" | "y" | "1" => Ok(Some(true)),
+        "false" | "no" | "n" | "0" => Ok(Some(false)),
+        _ => Err(()),
+    }
+}
+
+// Rows are numbered like a spreadsheet, with the header on row 1. Rows that
+// fail to parse are reported alongside the ones that did, so validation can
+// still run over the rest of the file.
+pub fn rows_from_csv(input: &str) -> (Vec<ImportRow>, Vec<MenuImportError>) {
+    let records = match parse_records(input) {
+        Ok(records) => records,
+        Err(row) => {
+            return (
+                Vec::new(),
+                vec![import_error(row, None, "Unterminated quoted field")],
+            )
+        }
+    };
+    let mut records = records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| (index as u32 + 1, record));
+
+    let Some((_, header)) = records.next() else {
+        return (Vec::new(), vec![import_error(1, None, "The file is empty")]);
+    };
+    let header: Vec<String> = header
+        .iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+
+    let missing: Vec<MenuImportError> = REQUIRED_CSV_COLUMNS
+        .iter()
+        .filter(|column| !header.iter().any(|name| name == *column))
+        .map(|column| import_error(1, Some(column), format!("Missing column '{column}'")))
+        .collect();
+    if !missing.is_empty() {
+        return (Vec::new(), missing);
+    }
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    for (row, record) in records {
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+
+        let get = |column: &str| {
+            header
+                .iter()
+                .position(|name| name == column)
+                .and_then(|index| record.get(index))
+                .map(|value| value.trim())
+                .unwrap_or_default()
+        };
+
+        let section_order = parse_optional(
+            get("section_order"),
+            row,
+            "section_order",
+            "Section order must be a whole number",
+            &mut errors,
+        );
+
+        let item_fields = ["name", "description", "price", "available", "display_order"];
+        let item = if item_fields.iter().all(|column| get(column).is_empty()) {
+            None
+        } else {
+            let price = match get("price") {
+                "" => {
+                    errors.push(import_error(row, Some("price"), "Price is required"));
+                    None
+                }
+                price => parse_optional::<f64>(
+                    price,
+                    row,
+                    "price",
+                    "Price must be a number",
+                    &mut errors,
+                ),
+            };
+            let available = parse_available(get("available")).unwrap_or_else(|_| {
+                errors.push(import_error(
+                    row,
+                    Some("available"),
+                    "Available must be true or false",
+                ));
+                None
+            });
+            let display_order = parse_optional(
+                get("display_order"),
+                row,
+                "display_order",
+                "Display order must be a whole number",
+                &mut errors,
+            );
+
+            price.map(|price| ImportMenuItem {
+                name: get("name").to_string(),
+                description: Some(get("description").to_string()),
+                price,
+                available,
+                display_order,
+            })
+        };
+
+        rows.push(ImportRow {
+            row,
+            section: get("section").to_string(),
+            section_order,
+            item,
+        });
+    }
+
+    (rows, errors)
+}
+
+// JSON rows are numbered in document order, one per item and one for each
+// section without items
+pub fn rows_from_json(menu: ImportMenu) -> Vec<ImportRow> {
+    let mut rows = Vec::new();
+
+    for section in menu.sections {
+        if section.items.is_empty() {
+            rows.push(ImportRow {
+                row: rows.len() as u32 + 1,
+                section: section.name.clone(),
+                section_order: section.display_order,
+                item: None,
+            });
+        }
+        for item in section.items {
+            rows.push(ImportRow {
+                row: rows.len() as u32 + 1,
+                section: section.name.clone(),
+                section_order: section.display_order,
+                item: Some(item),
+            });
+        }
+    }
+
+    rows
+}
+
+// Validates every row and groups them by section. Nothing is applied unless
+// the whole import is valid.
+pub fn plan_import(rows: Vec<ImportRow>) -> Result<ImportMenu, Vec<MenuImportError>> {
+    let mut sections: Vec<ImportMenuSection> = Vec::new();
+    let mut seen_items: HashSet<(String, String)> = HashSet::new();
+    let mut errors = Vec::new();
+
+    for row in rows {
+        let section_name = row.section.trim();
+        if section_name.is_empty() {
+            errors.push(import_error(
+                row.row,
+                Some("section"),
+                "Section name is required",
+            ));
+            continue;
+        }
+        if row.section_order.is_some_and(|order| order < 0) {
+            errors.push(import_error(
+                row.row,
+                Some("section_order"),
+                "Section order cannot be negative",
+            ));
+        }
+
+        let section_key = section_name.to_lowercase();
+        let index = match sections
+            .iter()
+            .position(|section| section.name.to_lowercase() == section_key)
+        {
+            Some(index) => index,
+            None => {
+                sections.push(ImportMenuSection {
+                    name: section_name.to_string(),
+                    display_order: None,
+                    items: Vec::new(),
+                });
+                sections.len() - 1
+            }
+        };
+        let section = &mut sections[index];
+        if section.display_order.is_none() {
+            section.display_order = row.section_order;
+        }
+
+        let Some(item) = row.item else {
+            continue;
+        };
+
+        let name = item.name.trim();
+        if name.is_empty() {
+            errors.push(import_error(row.row, Some("name"), "Item name is required"));
+            continue;
+        }
+        if !item.price.is_finite() || item.price < 0.0 {
+            errors.push(import_error(
+                row.row,
+                Some("price"),
+                "Price cannot be negative",
+            ));
+        }
+        if item.display_order.is_some_and(|order| order < 0) {
+            errors.push(import_error(
+                row.row,
+                Some("display_order"),
+                "Display order cannot be negative",
+            ));
+        }
+        if !seen_items.insert((section_key, name.to_lowercase())) {
+            errors.push(import_error(
+                row.row,
+                Some("name"),
+                format!("Duplicate item '{name}' in section '{}'", section.name),
+            ));
+            continue;
+        }
+
+        section.items.push(ImportMenuItem {
+            name: name.to_string(),
+            description: item
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|description| !description.is_empty())
+                .map(str::to_string),
+            ..item
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(ImportMenu { sections })
+    } else {
+        Err(errors)
+    }
+}
+
+// Turns another restaurant's menu into an import, keeping ordering and
+// availability. Prices are scaled and rounded to cents.
+pub fn clone_plan(
+    menu: &RestaurantMenu,
+    price_multiplier: f64,
+    skip_unavailable: bool,
+) -> ImportMenu {
+    let sections = menu
+        .sections
+        .iter()
+        .map(|section| ImportMenuSection {
+            name: section.name.clone(),
+            display_order: Some(section.display_order),
+            items: section
+                .items
+                .iter()
+                .filter(|item| item.available || !skip_unavailable)
+                .map(|item| ImportMenuItem {
+                    name: item.name.clone(),
+                    description: item.description.clone(),
+                    price: (item.price * price_multiplier * 100.0).round() / 100.0,
+                    available: Some(item.available),
+                    display_order: Some(item.display_order),
+                })
+                .collect(),
+        })
+        .collect();
+
+    ImportMenu { sections }
+}
+
+#[derive(sqlx::FromRow)]
+struct ExistingSection {
+    id: String,
+    name: String,
+    display_order: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExistingItem {
+    id: String,
+    name: String,
+    description: Option<String>,
+    price: f64,
+    available: bool,
+    display_order: i64,
+}
+
+// Sections and items are matched by name, case-insensitively. Merge only
+// creates and updates; replace also removes whatever the import left out.
+// Missing availability and display order keep the current values, new
+// entries default to available and go after the existing ones.
+pub async fn apply_import(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+    menu: &ImportMenu,
+    replace: bool,
+) -> Result<MenuImportResult, sqlx::Error> {
+    let mut result = MenuImportResult::default();
+
+    let existing_sections: Vec<ExistingSection> =
+        sqlx::query_as("SELECT id, name, display_order FROM menu_sections WHERE restaurant_id = ?")
+            .bind(restaurant_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let mut next_section_order = existing_sections
+        .iter()
+        .map(|section| section.display_order)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut kept_sections = HashSet::new();
+
+    for section in &menu.sections {
+        let matched = existing_sections.iter().find(|existing| {
+            !kept_sections.contains(&existing.id)
+                && existing.name.trim().to_lowercase() == section.name.to_lowercase()
+        });
+
+        let section_id = match matched {
+            Some(existing) => {
+                let display_order = section
+                    .display_order
+                    .map(i64::from)
+                    .unwrap_or(existing.display_order);
+                if existing.name != section.name || existing.display_order != display_order {
+                    sqlx::query(
+                        "UPDATE menu_sections SET name = ?, display_order = ? WHERE id = ?",
+                    )
+                    .bind(&section.name)
+                    .bind(display_order)
+                    .bind(&existing.id)
+                    .execute(&mut *conn)
+                    .await?;
+                    result.sections_updated += 1;
+                }
+                existing.id.clone()
+            }
+            None => {
+                let section_id = Uuid::new_v4().to_string();
+                let display_order = section.display_order.map(i64::from).unwrap_or_else(|| {
+                    next_section_order += 1;
+                    next_section_order - 1
+                });
+                sqlx::query(
+                    "INSERT INTO menu_sections (id, restaurant_id, name, display_order) VALUES (?, ?, ?, ?)",
+                )
+                .bind(&section_id)
+                .bind(restaurant_id)
+                .bind(&section.name)
+                .bind(display_order)
+                .execute(&mut *conn)
+                .await?;
+                result.sections_created += 1;
+                section_id
+            }
+        };
+        kept_sections.insert(section_id.clone());
+
+        let existing_items: Vec<ExistingItem> = sqlx::query_as(
+            "SELECT id, name, description, CAST(price AS REAL) AS price, available, display_order
+             FROM menu_items WHERE section_id = ?",
+        )
+        .bind(&section_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut next_item_order = existing_items
+            .iter()
+            .map(|item| item.display_order)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mut kept_items = HashSet::new();
+
+        for item in &section.items {
+            let matched = existing_items.iter().find(|existing| {
+                !kept_items.contains(&existing.id)
+                    && existing.name.trim().to_lowercase() == item.name.to_lowercase()
+            });
+
+            match matched {
+                Some(existing) => {
+                    let available = item.available.unwrap_or(existing.available);
+                    let display_order = item
+                        .display_order
+                        .map(i64::from)
+                        .unwrap_or(existing.display_order);
+                    let changed = existing.name != item.name
+                        || existing.description != item.description
+                        || format!("{:.2}", existing.price) != format!("{:.2}", item.price)
+                        || existing.available != available
+                        || existing.display_order != display_order;
+                    if changed {
+                        sqlx::query(
+                            "UPDATE menu_items SET name = ?, description = ?, price = ?, available = ?, display_order = ?
+                             WHERE id = ?",
+                        )
+                        .bind(&item.name)
+                        .bind(&item.description)
+                        .bind(item.price)
+                        .bind(available)
+                        .bind(display_order)
+                        .bind(&existing.id)
+                        .execute(&mut *conn)
+                        .await?;
+                        result.items_updated += 1;
+                    }
+                    kept_items.insert(existing.id.clone());
+                }
+                None => {
+                    let display_order = item.display_order.map(i64::from).unwrap_or_else(|| {
+                        next_item_order += 1;
+                        next_item_order - 1
+                    });
+                    sqlx::query(
+                        "INSERT INTO menu_items (id, section_id, name, description, price, available, display_order)
+                         VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    )
+                    .bind(Uuid::new_v4().to_string())
+                    .bind(&section_id)
+                    .bind(&item.name)
+                    .bind(&item.description)
+                    .bind(item.price)
+                    .bind(item.available.unwrap_or(true))
+                    .bind(display_order)
+                    .execute(&mut *conn)
+                    .await?;
+                    result.items_created += 1;
+                }
+            }
+        }
+
+        if replace {
+            for existing in existing_items
+                .iter()
+                .filter(|existing| !kept_items.contains(&existing.id))
+            {
+                sqlx::query("DELETE FROM menu_items WHERE id = ?")
+                    .bind(&existing.id)
+                    .execute(&mut *conn)
+                    .await?;
+                result.items_removed += 1;
+            }
+        }
+    }
+
+    if replace {
+        for existing in existing_sections
+            .iter()
+            .filter(|existing| !kept_sections.contains(&existing.id))
+        {
+            let removed_items = sqlx::query("DELETE FROM menu_items WHERE section_id = ?")
+                .bind(&existing.id)
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("DELETE FROM menu_sections WHERE id = ?")
+                .bind(&existing.id)
+                .execute(&mut *conn)
+                .await?;
+            result.sections_removed += 1;
+            result.items_removed += removed_items.rows_affected() as u32;
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,236 @@
+use crate::menu_handlers::load_restaurant_menu;
+use crate::menu_import::{
+    apply_import, clone_plan, menu_to_csv, plan_import, rows_from_csv, rows_from_json,
+};
+use crate::models::{
+    Claims, CloneMenuRequest, ImportMenu, MenuExportQuery, MenuImportQuery, MenuImportResult,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use actix_web::{web, HttpResponse, Result};
+use sqlx::{Pool, Sqlite};
+
+pub async fn export_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<MenuExportQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let format = query.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "csv" {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Format must be 'json' or 'csv'"
+        })));
+    }
+
+    let menu = match load_restaurant_menu(pool.get_ref(), &restaurant_id).await {
+        Ok(menu) => menu,
+        Err(e) => {
+            log::error!("Database error exporting menu: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let disposition = format!("attachment; filename=\"menu.{format}\"");
+    if format == "csv" {
+        Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(("Content-Disposition", disposition))
+            .body(menu_to_csv(&menu)))
+    } else {
+        Ok(HttpResponse::Ok()
+            .insert_header(("Content-Disposition", disposition))
+            .json(menu))
+    }
+}
+
+pub async fn import_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<MenuImportQuery>,
+    body: String,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let mode = query.mode.as_deref().unwrap_or("merge");
+    if mode != "merge" && mode != "replace" {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Mode must be 'merge' or 'replace'"
+        })));
+    }
+    let dry_run = query.dry_run.unwrap_or(false);
+
+    let (rows, mut errors) = match query.format.as_deref().unwrap_or("json") {
+        "json" => match serde_json::from_str::<ImportMenu>(&body) {
+            Ok(menu) => (rows_from_json(menu), Vec::new()),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Invalid menu JSON: {e}")
+                })));
+            }
+        },
+        "csv" => rows_from_csv(&body),
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Format must be 'json' or 'csv'"
+            })));
+        }
+    };
+
+    let menu = match plan_import(rows) {
+        Ok(menu) if errors.is_empty() => menu,
+        plan => {
+            if let Err(plan_errors) = plan {
+                errors.extend(plan_errors);
+            }
+            errors.sort_by_key(|error| error.row);
+            return Ok(HttpResponse::UnprocessableEntity().json(MenuImportResult {
+                mode: mode.to_string(),
+                dry_run,
+                errors,
+                ..MenuImportResult::default()
+            }));
+        }
+    };
+
+    // Imports edit the draft like any other menu change
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let result = match apply_import(&mut tx, &restaurant_id, &menu, mode == "replace").await {
+        Ok(result) => result,
+        Err(e) => {
+            log::error!("Database error importing menu: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to import menu"
+            })));
+        }
+    };
+
+    // A dry run goes through the same writes so the counts are exact
+    let finished = if dry_run {
+        tx.rollback().await
+    } else {
+        tx.commit().await
+    };
+    if let Err(e) = finished {
+        log::error!("Database error finishing menu import: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to import menu"
+        })));
+    }
+
+    Ok(HttpResponse::Ok().json(MenuImportResult {
+        mode: mode.to_string(),
+        dry_run,
+        ..result
+    }))
+}
+
+// Copies another restaurant's menu into this one. Sections and items that
+// already exist by name are updated in place, so cloning again re-syncs.
+pub async fn clone_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CloneMenuRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if req.source_restaurant_id == restaurant_id {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Source and target restaurant must be different"
+        })));
+    }
+
+    if !is_manager(pool.get_ref(), &req.source_restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied to source restaurant"
+        })));
+    }
+
+    let price_multiplier = req.price_multiplier.unwrap_or(1.0);
+    if !price_multiplier.is_finite() || price_multiplier <= 0.0 {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Price multiplier must be greater than 0"
+        })));
+    }
+
+    let source = match load_restaurant_menu(pool.get_ref(), &req.source_restaurant_id).await {
+        Ok(menu) => menu,
+        Err(e) => {
+            log::error!("Database error fetching source menu: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+    let menu = clone_plan(
+        &source,
+        price_multiplier,
+        req.skip_unavailable.unwrap_or(false),
+    );
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let result = match apply_import(&mut tx, &restaurant_id, &menu, false).await {
+        Ok(result) => result,
+        Err(e) => {
+            log::error!("Database error cloning menu: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to clone menu"
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error committing menu clone: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to clone menu"
+        })));
+    }
+
+    Ok(HttpResponse::Ok().json(MenuImportResult {
+        mode: "merge".to_string(),
+        ..result
+    }))
+}
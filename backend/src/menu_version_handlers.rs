@@ -0,0 +1,436 @@
+use crate::api_keys::{api_key_allows, ApiKeyAuth, SCOPE_MENU_MANAGE};
+use crate::menu_versions::{
+    diff_snapshots, draft_snapshot, index_live_menu, live_snapshot, live_version_id,
+    version_snapshot,
+};
+use crate::models::{
+    Claims, MenuDiffQuery, MenuSnapshot, MenuVersionDetail, MenuVersionSummary, PublishMenuRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use crate::webhooks::enqueue_event;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use uuid::Uuid;
+
+async fn load_versions(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    version_id: Option<&str>,
+) -> Result<Vec<(MenuVersionSummary, MenuSnapshot)>, sqlx::Error> {
+    let live_id = live_version_id(pool, restaurant_id).await?;
+    let rows = sqlx::query(
+        "SELECT v.id, v.version_number, v.note, COALESCE(u.email, v.published_by) AS published_by,
+                v.published_at, v.snapshot
+         FROM menu_versions v
+         LEFT JOIN users u ON u.id = v.published_by
+         WHERE v.restaurant_id = ? AND (? IS NULL OR v.id = ?)
+         ORDER BY v.version_number DESC",
+    )
+    .bind(restaurant_id)
+    .bind(version_id)
+    .bind(version_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let id: String = row.try_get("id")?;
+            let snapshot: String = row.try_get("snapshot")?;
+            let menu: MenuSnapshot =
+                serde_json::from_str(&snapshot).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let published_at: NaiveDateTime = row.try_get("published_at")?;
+            let summary = MenuVersionSummary {
+                is_live: live_id.as_deref() == Some(id.as_str()),
+                id,
+                version_number: row.try_get("version_number")?,
+                note: row.try_get("note")?,
+                published_by: row.try_get("published_by")?,
+                published_at: DateTime::from_naive_utc_and_offset(published_at, Utc),
+                section_count: menu.sections.len() as u32,
+                item_count: menu.item_count() as u32,
+            };
+            Ok((summary, menu))
+        })
+        .collect()
+}
+
+async fn version_summary(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    version_id: &str,
+) -> Result<Option<MenuVersionSummary>, sqlx::Error> {
+    Ok(load_versions(pool, restaurant_id, Some(version_id))
+        .await?
+        .into_iter()
+        .next()
+        .map(|(summary, _)| summary))
+}
+
+// Also sent when a rollback puts an older version live again
+async fn enqueue_menu_published(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+    version_id: &str,
+    rollback: bool,
+) -> Result<(), sqlx::Error> {
+    let row = sqlx::query("SELECT version_number, note FROM menu_versions WHERE id = ?")
+        .bind(version_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let version_number: i64 = row.try_get("version_number")?;
+    let note: Option<String> = row.try_get("note")?;
+
+    enqueue_event(
+        conn,
+        restaurant_id,
+        "menu.published",
+        serde_json::json!({
+            "version_id": version_id,
+            "version_number": version_number,
+            "note": note,
+            "rollback": rollback,
+        }),
+    )
+    .await
+}
+
+pub async fn publish_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    req: web::Json<PublishMenuRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE)
+        && !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let live = match live_version_id(pool.get_ref(), &restaurant_id).await {
+        Ok(Some(live_id)) => version_snapshot(pool.get_ref(), &restaurant_id, &live_id).await,
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+    let live = match live {
+        Ok(live) => live,
+        Err(e) => {
+            log::error!("Database error fetching live menu: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let draft = match draft_snapshot(&mut tx, &restaurant_id).await {
+        Ok(draft) => draft,
+        Err(e) => {
+            log::error!("Database error reading draft menu: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to publish menu"
+            })));
+        }
+    };
+
+    // Publishing the same menu twice would only add noise to the history
+    if live.is_some_and(|live| diff_snapshots(&live, &draft).is_empty()) {
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "The draft has no changes since the live version"
+        })));
+    }
+
+    let snapshot = match serde_json::to_string(&draft) {
+        Ok(json) => json,
+        Err(e) => {
+            log::error!("Error serializing menu snapshot: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let version_id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO menu_versions (id, restaurant_id, version_number, snapshot, note, published_by)
+         SELECT ?, ?, COALESCE(MAX(version_number), 0) + 1, ?, ?, ?
+         FROM menu_versions WHERE restaurant_id = ?",
+    )
+    .bind(&version_id)
+    .bind(&restaurant_id)
+    .bind(&snapshot)
+    .bind(&req.note)
+    .bind(&claims.sub)
+    .bind(&restaurant_id)
+    .execute(&mut *tx)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": "Another publish is in progress, please retry"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error saving menu version: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to publish menu"
+            })));
+        }
+    }
+
+    let result = sqlx::query("UPDATE restaurants SET live_menu_version_id = ? WHERE id = ?")
+        .bind(&version_id)
+        .bind(&restaurant_id)
+        .execute(&mut *tx)
+        .await;
+    let result = match result {
+        Ok(_) => index_live_menu(&mut tx, &restaurant_id, &draft).await,
+        Err(e) => Err(e),
+    };
+    let result = match result {
+        Ok(_) => enqueue_menu_published(&mut tx, &restaurant_id, &version_id, false).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        log::error!("Database error updating live menu version: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to publish menu"
+        })));
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error committing menu version: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to publish menu"
+        })));
+    }
+
+    match version_summary(pool.get_ref(), &restaurant_id, &version_id).await {
+        Ok(Some(summary)) => Ok(HttpResponse::Created().json(summary)),
+        Ok(None) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching menu version: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn list_menu_versions(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match load_versions(pool.get_ref(), &restaurant_id, None).await {
+        Ok(versions) => {
+            let summaries: Vec<MenuVersionSummary> =
+                versions.into_iter().map(|(summary, _)| summary).collect();
+            Ok(HttpResponse::Ok().json(summaries))
+        }
+        Err(e) => {
+            log::error!("Database error fetching menu versions: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn get_menu_version(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, version_id) = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match load_versions(pool.get_ref(), &restaurant_id, Some(&version_id)).await {
+        Ok(versions) => match versions.into_iter().next() {
+            Some((version, menu)) => {
+                Ok(HttpResponse::Ok().json(MenuVersionDetail { version, menu }))
+            }
+            None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu version not found"
+            }))),
+        },
+        Err(e) => {
+            log::error!("Database error fetching menu version: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// `from` and `to` accept "live", "draft" or a version id
+async fn resolve_snapshot(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    selector: &str,
+) -> Result<Option<MenuSnapshot>, sqlx::Error> {
+    match selector {
+        "live" => live_snapshot(pool, restaurant_id).await.map(Some),
+        "draft" => {
+            let mut conn = pool.acquire().await?;
+            draft_snapshot(&mut conn, restaurant_id).await.map(Some)
+        }
+        version_id => version_snapshot(pool, restaurant_id, version_id).await,
+    }
+}
+
+pub async fn diff_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<MenuDiffQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let from = query.from.as_deref().unwrap_or("live");
+    let to = query.to.as_deref().unwrap_or("draft");
+
+    let snapshots = (
+        resolve_snapshot(pool.get_ref(), &restaurant_id, from).await,
+        resolve_snapshot(pool.get_ref(), &restaurant_id, to).await,
+    );
+    match snapshots {
+        (Ok(Some(from)), Ok(Some(to))) => Ok(HttpResponse::Ok().json(diff_snapshots(&from, &to))),
+        (Ok(_), Ok(_)) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Menu version not found"
+        }))),
+        (Err(e), _) | (_, Err(e)) => {
+            log::error!("Database error diffing menus: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Points guests back at an earlier version; the draft is left untouched
+pub async fn rollback_menu(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, version_id) = path.into_inner();
+
+    if !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_MENU_MANAGE)
+        && !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let result = sqlx::query(
+        "UPDATE restaurants SET live_menu_version_id = ?
+         WHERE id = ? AND EXISTS (SELECT 1 FROM menu_versions WHERE id = ? AND restaurant_id = ?)",
+    )
+    .bind(&version_id)
+    .bind(&restaurant_id)
+    .bind(&version_id)
+    .bind(&restaurant_id)
+    .execute(&mut *tx)
+    .await;
+
+    let result = match result {
+        Ok(result) if result.rows_affected() == 0 => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu version not found"
+            })));
+        }
+        Ok(_) => match version_snapshot(&mut *tx, &restaurant_id, &version_id).await {
+            Ok(Some(snapshot)) => index_live_menu(&mut tx, &restaurant_id, &snapshot).await,
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+    let result = match result {
+        Ok(_) => enqueue_menu_published(&mut tx, &restaurant_id, &version_id, true).await,
+        Err(e) => Err(e),
+    };
+    let result = match result {
+        Ok(_) => tx.commit().await,
+        Err(e) => {
+            let _ = tx.rollback().await;
+            Err(e)
+        }
+    };
+    if let Err(e) = result {
+        log::error!("Database error rolling back menu: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to roll back menu"
+        })));
+    }
+
+    match version_summary(pool.get_ref(), &restaurant_id, &version_id).await {
+        Ok(Some(summary)) => Ok(HttpResponse::Ok().json(summary)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Menu version not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching menu version: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
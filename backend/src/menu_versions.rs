@@ -0,0 +1,311 @@
+use crate::models::{
+    FieldChange, MenuDiff, MenuDiffEntry, MenuSnapshot, SnapshotItem, SnapshotSection,
+    SnapshotVariant,
+};
+use crate::variant_handlers::load_variants;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection, SqliteExecutor};
+use std::collections::HashMap;
+
+impl MenuSnapshot {
+    pub fn find_item(&self, item_id: &str) -> Option<(&SnapshotSection, &SnapshotItem)> {
+        self.sections.iter().find_map(|section| {
+            section
+                .items
+                .iter()
+                .find(|item| item.id == item_id)
+                .map(|item| (section, item))
+        })
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| section.items.len())
+            .sum()
+    }
+}
+
+// The draft is whatever is currently in menu_sections and menu_items
+pub async fn draft_snapshot(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+) -> Result<MenuSnapshot, sqlx::Error> {
+    let section_rows = sqlx::query(
+        "SELECT id, name, display_order FROM menu_sections
+         WHERE restaurant_id = ? ORDER BY display_order ASC, created_at ASC",
+    )
+    .bind(restaurant_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let item_rows = sqlx::query(
+        "SELECT mi.id, mi.section_id, mi.name, mi.description, CAST(mi.price AS REAL) AS price, mi.available, mi.display_order
+         FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?
+         ORDER BY mi.display_order ASC, mi.created_at ASC",
+    )
+    .bind(restaurant_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut variants = load_variants(&mut *conn, restaurant_id).await?;
+    let mut items_by_section: HashMap<String, Vec<SnapshotItem>> = HashMap::new();
+    for row in item_rows {
+        let id: String = row.try_get("id")?;
+        let section_id: String = row.try_get("section_id")?;
+        let display_order: i64 = row.try_get("display_order")?;
+        items_by_section
+            .entry(section_id)
+            .or_default()
+            .push(SnapshotItem {
+                variants: variants
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|variant| SnapshotVariant {
+                        id: variant.id,
+                        name: variant.name,
+                        price: variant.price,
+                        display_order: variant.display_order,
+                    })
+                    .collect(),
+                id,
+                name: row.try_get("name")?,
+                description: row.try_get("description")?,
+                price: row.try_get("price")?,
+                available: row.try_get("available")?,
+                display_order: display_order as i32,
+            });
+    }
+
+    let mut sections = Vec::new();
+    for row in section_rows {
+        let id: String = row.try_get("id")?;
+        let display_order: i64 = row.try_get("display_order")?;
+        sections.push(SnapshotSection {
+            items: items_by_section.remove(&id).unwrap_or_default(),
+            name: row.try_get("name")?,
+            display_order: display_order as i32,
+            id,
+        });
+    }
+
+    Ok(MenuSnapshot { sections })
+}
+
+pub async fn version_snapshot(
+    executor: impl SqliteExecutor<'_>,
+    restaurant_id: &str,
+    version_id: &str,
+) -> Result<Option<MenuSnapshot>, sqlx::Error> {
+    let snapshot: Option<String> =
+        sqlx::query_scalar("SELECT snapshot FROM menu_versions WHERE id = ? AND restaurant_id = ?")
+            .bind(version_id)
+            .bind(restaurant_id)
+            .fetch_optional(executor)
+            .await?;
+
+    snapshot
+        .map(|json| serde_json::from_str(&json).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+        .transpose()
+}
+
+pub async fn live_version_id(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT live_menu_version_id FROM restaurants WHERE id = ?")
+        .bind(restaurant_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+// What guests see: the live version, or the draft until the first publish
+pub async fn live_snapshot(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<MenuSnapshot, sqlx::Error> {
+    if let Some(version_id) = live_version_id(pool, restaurant_id).await? {
+        if let Some(snapshot) = version_snapshot(pool, restaurant_id, &version_id).await? {
+            return Ok(snapshot);
+        }
+    }
+
+    let mut conn = pool.acquire().await?;
+    draft_snapshot(&mut conn, restaurant_id).await
+}
+
+// Rebuilds a restaurant's search index from the version that just went live,
+// so guests only find published text
+pub async fn index_live_menu(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+    snapshot: &MenuSnapshot,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM menu_item_search WHERE restaurant_id = ?")
+        .bind(restaurant_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for item in snapshot.sections.iter().flat_map(|section| &section.items) {
+        sqlx::query(
+            "INSERT INTO menu_item_search (item_id, restaurant_id, name, description, translated_names, translated_descriptions)
+             VALUES (?, ?, ?, ?,
+                     (SELECT group_concat(name, ' ') FROM menu_item_translations WHERE item_id = ?),
+                     (SELECT group_concat(description, ' ') FROM menu_item_translations WHERE item_id = ?))",
+        )
+        .bind(&item.id)
+        .bind(restaurant_id)
+        .bind(&item.name)
+        .bind(&item.description)
+        .bind(&item.id)
+        .bind(&item.id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn change(field: &str, before: impl ToString, after: impl ToString) -> Option<FieldChange> {
+    let (before, after) = (before.to_string(), after.to_string());
+    (before != after).then(|| FieldChange {
+        field: field.to_string(),
+        before,
+        after,
+    })
+}
+
+fn entry(id: &str, name: &str, changes: Vec<FieldChange>) -> MenuDiffEntry {
+    MenuDiffEntry {
+        id: id.to_string(),
+        name: name.to_string(),
+        changes,
+    }
+}
+
+// "Small 2.50, Large 3.50", so a diff shows renamed, repriced and
+// added sizes in one line
+fn variant_summary(variants: &[SnapshotVariant]) -> String {
+    variants
+        .iter()
+        .map(|variant| format!("{} {:.2}", variant.name, variant.price))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn diff_snapshots(from: &MenuSnapshot, to: &MenuSnapshot) -> MenuDiff {
+    let mut diff = MenuDiff::default();
+
+    let from_sections: HashMap<&str, &SnapshotSection> = from
+        .sections
+        .iter()
+        .map(|section| (section.id.as_str(), section))
+        .collect();
+    let to_sections: HashMap<&str, &SnapshotSection> = to
+        .sections
+        .iter()
+        .map(|section| (section.id.as_str(), section))
+        .collect();
+
+    for section in &to.sections {
+        match from_sections.get(section.id.as_str()) {
+            None => diff
+                .added_sections
+                .push(entry(&section.id, &section.name, Vec::new())),
+            Some(before) => {
+                let changes: Vec<FieldChange> = [
+                    change("name", &before.name, &section.name),
+                    change("display_order", before.display_order, section.display_order),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                if !changes.is_empty() {
+                    diff.changed_sections
+                        .push(entry(&section.id, &section.name, changes));
+                }
+            }
+        }
+    }
+    for section in &from.sections {
+        if !to_sections.contains_key(section.id.as_str()) {
+            diff.removed_sections
+                .push(entry(&section.id, &section.name, Vec::new()));
+        }
+    }
+
+    let section_name = |sections: &HashMap<&str, &SnapshotSection>, id: &str| {
+        sections
+            .get(id)
+            .map(|section| section.name.clone())
+            .unwrap_or_default()
+    };
+
+    for section in &to.sections {
+        for item in &section.items {
+            match from.find_item(&item.id) {
+                None => diff
+                    .added_items
+                    .push(entry(&item.id, &item.name, Vec::new())),
+                Some((before_section, before)) => {
+                    let changes: Vec<FieldChange> = [
+                        change(
+                            "section",
+                            section_name(&from_sections, &before_section.id),
+                            &section.name,
+                        ),
+                        change("name", &before.name, &item.name),
+                        change(
+                            "description",
+                            before.description.as_deref().unwrap_or_default(),
+                            item.description.as_deref().unwrap_or_default(),
+                        ),
+                        change(
+                            "price",
+                            format!("{:.2}", before.price),
+                            format!("{:.2}", item.price),
+                        ),
+                        change("available", before.available, item.available),
+                        change("display_order", before.display_order, item.display_order),
+                        change(
+                            "variants",
+                            variant_summary(&before.variants),
+                            variant_summary(&item.variants),
+                        ),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                    if !changes.is_empty() {
+                        diff.changed_items
+                            .push(entry(&item.id, &item.name, changes));
+                    }
+                }
+            }
+        }
+    }
+    for section in &from.sections {
+        for item in &section.items {
+            if to.find_item(&item.id).is_none() {
+                diff.removed_items
+                    .push(entry(&item.id, &item.name, Vec::new()));
+            }
+        }
+    }
+
+    diff
+}
+
+impl MenuDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_sections.is_empty()
+            && self.removed_sections.is_empty()
+            && self.changed_sections.is_empty()
+            && self.added_items.is_empty()
+            && self.removed_items.is_empty()
+            && self.changed_items.is_empty()
+    }
+}
@@ -47,6 +47,8 @@ pub struct Restaurant {
     pub address: Option<String>,
     pub establishment_year: Option<i32>,
     pub google_maps_link: Option<String>,
+    pub timezone: String,
+    pub default_language: String,
     pub created_at: DateTime<Utc>,
 }
 
@@ -57,6 +59,8 @@ pub struct RestaurantRow {
     pub address: Option<String>,
     pub establishment_year: Option<i32>,
     pub google_maps_link: Option<String>,
+    pub timezone: String,
+    pub default_language: String,
     pub created_at: NaiveDateTime,
 }
 
@@ -68,6 +72,8 @@ impl From<RestaurantRow> for Restaurant {
             address: row.address,
             establishment_year: row.establishment_year,
             google_maps_link: row.google_maps_link,
+            timezone: row.timezone,
+            default_language: row.default_language,
             created_at: DateTime::from_naive_utc_and_offset(row.created_at, Utc),
         }
     }
@@ -112,7 +118,11 @@ pub struct MenuItem {
     pub price: f64,
     pub available: bool,
     pub display_order: i32,
+    pub stock_quantity: Option<i32>,
     pub created_at: DateTime<Utc>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub variants: Vec<MenuItemVariant>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
@@ -159,6 +169,18 @@ pub struct OrderItem {
     pub quantity: i32,
     pub price: f64,
     pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing_rule_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing_rule_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,6 +196,8 @@ pub struct CreateRestaurantRequest {
     pub address: Option<String>,
     pub establishment_year: Option<i32>,
     pub google_maps_link: Option<String>,
+    pub timezone: Option<String>,
+    pub default_language: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -210,6 +234,12 @@ pub struct CreateOrderRequest {
     pub table_code: String,
     pub items: Vec<CreateOrderItem>,
     pub customer_name: Option<String>,
+    pub pay_before_kitchen: Option<bool>,
+    pub idempotency_key: Option<String>,
+    #[serde(default)]
+    pub bundles: Vec<CreateOrderBundle>,
+    #[serde(default)]
+    pub coupon_code: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -217,6 +247,10 @@ pub struct CreateOrderItem {
     pub menu_item_id: String,
     pub quantity: i32,
     pub special_requests: Option<String>,
+    // Left out when absent so idempotency fingerprints of orders without
+    // variants stay the same
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -236,6 +270,9 @@ pub struct LoginRequest {
 #[ts(export)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    // Seconds until the access token expires
+    pub expires_in: u32,
     pub user: UserResponse,
 }
 
@@ -254,6 +291,9 @@ pub struct Claims {
     pub email: String,
     pub exp: usize,
     pub iat: usize,
+    // The user_sessions row the token was issued for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -262,6 +302,8 @@ pub struct UpdateRestaurantRequest {
     pub address: Option<String>,
     pub establishment_year: Option<i32>,
     pub google_maps_link: Option<String>,
+    pub timezone: Option<String>,
+    pub default_language: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -386,6 +428,10 @@ pub struct ToggleAvailabilityRequest {
 pub struct PublicMenu {
     pub restaurant: PublicRestaurantInfo,
     pub sections: Vec<PublicMenuSection>,
+    pub availability: OrderingAvailability,
+    pub language: String,
+    pub available_languages: Vec<String>,
+    pub bundles: Vec<PublicBundle>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -409,7 +455,20 @@ pub struct PublicMenuItem {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    // `price` is what the guest pays right now, after any pricing rule
     pub price: f64,
+    pub original_price: f64,
+    pub pricing_rule: Option<String>,
+    pub variants: Vec<PublicMenuVariant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PublicMenuVariant {
+    pub id: String,
+    pub name: String,
+    pub price: f64,
+    pub original_price: f64,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -445,6 +504,9 @@ pub struct MenuItemRow {
     pub price: Option<f64>,
     pub available: Option<bool>,
     pub display_order: Option<i64>,
+    // Absent from queries that predate inventory tracking
+    #[sqlx(default)]
+    pub stock_quantity: Option<i64>,
     pub created_at: Option<NaiveDateTime>,
 }
 
@@ -458,10 +520,12 @@ impl From<MenuItemRow> for MenuItem {
             price: row.price.unwrap_or(0.0),
             available: row.available.unwrap_or(true),
             display_order: row.display_order.unwrap_or(0) as i32,
+            stock_quantity: row.stock_quantity.map(|stock| stock as i32),
             created_at: DateTime::from_naive_utc_and_offset(
                 row.created_at.unwrap_or_default(),
                 Utc,
             ),
+            variants: Vec::new(),
         }
     }
 }
@@ -519,13 +583,16 @@ pub struct RefreshCodeResponse {
 #[ts(export)]
 pub struct OrderResponse {
     pub id: String,
+    pub order_number: Option<i32>,
     pub table_id: String,
     pub table_name: String,
     pub restaurant_name: String,
     pub items: Vec<OrderItemResponse>,
     pub total_amount: f64,
     pub status: String,
+    pub payment_status: String,
     pub customer_name: Option<String>,
+    pub discount: Option<OrderDiscount>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -537,14 +604,24 @@ pub struct OrderItemResponse {
     pub quantity: i32,
     pub price: f64,
     pub special_requests: Option<String>,
+    pub variant_id: Option<String>,
+    pub variant_name: Option<String>,
+    pub bundle_id: Option<String>,
+    pub bundle_name: Option<String>,
+    pub pricing_rule_id: Option<String>,
+    pub pricing_rule_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct CreateOrderResponse {
     pub order_id: String,
+    pub order_number: i32,
     pub total_amount: f64,
     pub status: String,
+    pub payment_status: String,
+    pub payment: Option<PaymentIntentResponse>,
+    pub discount: Option<OrderDiscount>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -788,3 +865,1176 @@ pub struct SupportResponseEmailResponse {
     pub success: bool,
     pub message: String,
 }
+
+// Payment models
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub id: String,
+    pub order_id: String,
+    pub provider: String,
+    pub provider_intent_id: String,
+    pub amount: f64,
+    pub currency: String,
+    pub status: String,
+    pub refunded_amount: f64,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PaymentRow {
+    pub id: String,
+    pub order_id: String,
+    pub provider: String,
+    pub provider_intent_id: String,
+    pub amount: f64,
+    pub currency: String,
+    pub status: String,
+    pub refunded_amount: f64,
+    pub failure_reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<PaymentRow> for Payment {
+    fn from(row: PaymentRow) -> Self {
+        Self {
+            id: row.id,
+            order_id: row.order_id,
+            provider: row.provider,
+            provider_intent_id: row.provider_intent_id,
+            amount: row.amount,
+            currency: row.currency,
+            status: row.status,
+            refunded_amount: row.refunded_amount,
+            failure_reason: row.failure_reason,
+            created_at: DateTime::from_naive_utc_and_offset(row.created_at, Utc),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PaymentIntentResponse {
+    pub provider: String,
+    pub intent_id: String,
+    pub client_secret: String,
+    pub amount: f64,
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ConfirmPaymentRequest {
+    pub payment_method: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ConfirmPaymentResponse {
+    pub order_id: String,
+    pub status: String,
+    pub payment_status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RefundPaymentRequest {
+    pub amount: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RefundPaymentResponse {
+    pub order_id: String,
+    pub refund_id: String,
+    pub amount: f64,
+    pub refunded_total: f64,
+    pub payment_status: String,
+}
+
+// Opening hours models
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OrderingAvailability {
+    pub is_open: bool,
+    pub reason: Option<String>,
+    pub closure_message: Option<String>,
+    pub next_open_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WeeklyOpeningWindow {
+    pub day_of_week: u32,
+    pub open_time: String,
+    pub close_time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DailyWindow {
+    pub open_time: String,
+    pub close_time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SpecialDay {
+    pub date: String,
+    pub closed: bool,
+    pub windows: Vec<DailyWindow>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OpeningHoursResponse {
+    pub timezone: String,
+    pub weekly: Vec<WeeklyOpeningWindow>,
+    pub special_days: Vec<SpecialDay>,
+    pub closed_until: Option<DateTime<Utc>>,
+    pub closure_reason: Option<String>,
+    pub availability: OrderingAvailability,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateOpeningHoursRequest {
+    pub weekly: Vec<WeeklyOpeningWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertSpecialDayRequest {
+    pub closed: bool,
+    #[serde(default)]
+    pub windows: Vec<DailyWindow>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateClosureRequest {
+    pub closed_until: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+}
+
+// Section schedule models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SectionScheduleWindow {
+    pub day_of_week: u32,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SectionScheduleResponse {
+    pub section_id: String,
+    pub windows: Vec<SectionScheduleWindow>,
+    pub active_now: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateSectionScheduleRequest {
+    pub windows: Vec<SectionScheduleWindow>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MenuPreviewQuery {
+    pub at: Option<DateTime<Utc>>,
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublicMenuQuery {
+    pub lang: Option<String>,
+}
+
+// Inventory models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuItemStock {
+    pub item_id: String,
+    pub section_id: String,
+    pub name: String,
+    pub stock_quantity: Option<i32>,
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateStockRequest {
+    pub stock_quantity: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RestockRequest {
+    pub quantity: i32,
+}
+
+// Menu version models
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuSnapshot {
+    pub sections: Vec<SnapshotSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SnapshotSection {
+    pub id: String,
+    pub name: String,
+    pub display_order: i32,
+    pub items: Vec<SnapshotItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SnapshotItem {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: f64,
+    pub available: bool,
+    pub display_order: i32,
+    // Versions published before variants existed have none
+    #[serde(default)]
+    pub variants: Vec<SnapshotVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SnapshotVariant {
+    pub id: String,
+    pub name: String,
+    pub price: f64,
+    pub display_order: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuVersionSummary {
+    pub id: String,
+    pub version_number: i32,
+    pub note: Option<String>,
+    pub published_by: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub is_live: bool,
+    pub section_count: u32,
+    pub item_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuVersionDetail {
+    pub version: MenuVersionSummary,
+    pub menu: MenuSnapshot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PublishMenuRequest {
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuDiffEntry {
+    pub id: String,
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuDiff {
+    pub added_sections: Vec<MenuDiffEntry>,
+    pub removed_sections: Vec<MenuDiffEntry>,
+    pub changed_sections: Vec<MenuDiffEntry>,
+    pub added_items: Vec<MenuDiffEntry>,
+    pub removed_items: Vec<MenuDiffEntry>,
+    pub changed_items: Vec<MenuDiffEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MenuDiffQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// Menu import/export models
+
+#[derive(Debug, Deserialize)]
+pub struct MenuExportQuery {
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MenuImportQuery {
+    pub format: Option<String>,
+    pub mode: Option<String>,
+    pub dry_run: Option<bool>,
+}
+
+// Also accepts an exported RestaurantMenu, whose extra fields are ignored
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImportMenu {
+    pub sections: Vec<ImportMenuSection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImportMenuSection {
+    pub name: String,
+    #[serde(default)]
+    pub display_order: Option<i32>,
+    #[serde(default)]
+    pub items: Vec<ImportMenuItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImportMenuItem {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub price: f64,
+    #[serde(default)]
+    pub available: Option<bool>,
+    #[serde(default)]
+    pub display_order: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuImportError {
+    pub row: u32,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuImportResult {
+    pub mode: String,
+    pub dry_run: bool,
+    pub sections_created: u32,
+    pub sections_updated: u32,
+    pub sections_removed: u32,
+    pub items_created: u32,
+    pub items_updated: u32,
+    pub items_removed: u32,
+    pub errors: Vec<MenuImportError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CloneMenuRequest {
+    pub source_restaurant_id: String,
+    #[serde(default)]
+    pub price_multiplier: Option<f64>,
+    #[serde(default)]
+    pub skip_unavailable: Option<bool>,
+}
+
+// Translation models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RestaurantTranslation {
+    pub locale: String,
+    pub name: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SectionTranslation {
+    pub section_id: String,
+    pub locale: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ItemTranslation {
+    pub item_id: String,
+    pub locale: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RestaurantTranslations {
+    pub default_language: String,
+    pub languages: Vec<String>,
+    pub restaurant: Vec<RestaurantTranslation>,
+    pub sections: Vec<SectionTranslation>,
+    pub items: Vec<ItemTranslation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertRestaurantTranslationRequest {
+    pub name: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertSectionTranslationRequest {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertItemTranslationRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+// Menu search models
+
+#[derive(Debug, Deserialize)]
+pub struct MenuSearchQuery {
+    pub q: Option<String>,
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuSearchResult {
+    pub section_id: String,
+    pub section_name: String,
+    pub item: PublicMenuItem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuSearchResponse {
+    pub query: String,
+    pub language: String,
+    pub results: Vec<MenuSearchResult>,
+}
+
+// Menu item variant models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuItemVariant {
+    pub id: String,
+    pub item_id: String,
+    pub name: String,
+    pub price: f64,
+    pub available: bool,
+    pub display_order: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateMenuItemVariantRequest {
+    pub name: String,
+    pub price: f64,
+    pub available: Option<bool>,
+    pub display_order: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateMenuItemVariantRequest {
+    pub name: Option<String>,
+    pub price: Option<f64>,
+    pub available: Option<bool>,
+    pub display_order: Option<i32>,
+}
+
+// Bundle models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuBundle {
+    pub id: String,
+    pub restaurant_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: f64,
+    pub available: bool,
+    pub display_order: i32,
+    pub slots: Vec<MenuBundleSlot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MenuBundleSlot {
+    pub id: String,
+    pub name: String,
+    pub display_order: i32,
+    pub item_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BundleSlotRequest {
+    pub name: String,
+    pub item_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateBundleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub price: f64,
+    pub available: Option<bool>,
+    pub display_order: Option<i32>,
+    pub slots: Vec<BundleSlotRequest>,
+}
+
+// Slots, when given, replace the bundle's existing slots
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateBundleRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<f64>,
+    pub available: Option<bool>,
+    pub display_order: Option<i32>,
+    pub slots: Option<Vec<BundleSlotRequest>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PublicBundle {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: f64,
+    pub slots: Vec<PublicBundleSlot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PublicBundleSlot {
+    pub id: String,
+    pub name: String,
+    pub choices: Vec<PublicBundleChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PublicBundleChoice {
+    pub item_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrderBundle {
+    pub bundle_id: String,
+    pub quantity: i32,
+    pub choices: Vec<OrderBundleChoice>,
+    pub special_requests: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBundleChoice {
+    pub slot_id: String,
+    pub menu_item_id: String,
+    pub variant_id: Option<String>,
+}
+
+// Pricing rule models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PricingRuleWindow {
+    pub day_of_week: u32,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PricingRule {
+    pub id: String,
+    pub restaurant_id: String,
+    pub name: String,
+    pub scope: String,
+    pub target_id: Option<String>,
+    pub adjustment_type: String,
+    pub adjustment_value: f64,
+    pub active: bool,
+    pub windows: Vec<PricingRuleWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreatePricingRuleRequest {
+    pub name: String,
+    pub scope: String,
+    pub target_id: Option<String>,
+    pub adjustment_type: String,
+    pub adjustment_value: f64,
+    pub active: Option<bool>,
+    pub windows: Vec<PricingRuleWindow>,
+}
+
+// Scope and target stay fixed once a rule exists
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdatePricingRuleRequest {
+    pub name: Option<String>,
+    pub adjustment_type: Option<String>,
+    pub adjustment_value: Option<f64>,
+    pub active: Option<bool>,
+    pub windows: Option<Vec<PricingRuleWindow>>,
+}
+
+// Coupon models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Coupon {
+    pub id: String,
+    pub restaurant_id: String,
+    pub code: String,
+    pub discount_type: String,
+    pub discount_value: f64,
+    pub min_order_amount: Option<f64>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub times_used: i32,
+    pub item_id: Option<String>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateCouponRequest {
+    pub code: String,
+    pub discount_type: String,
+    pub discount_value: f64,
+    pub min_order_amount: Option<f64>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub item_id: Option<String>,
+    pub active: Option<bool>,
+}
+
+// The code and item scope are fixed once a coupon exists
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateCouponRequest {
+    pub discount_type: Option<String>,
+    pub discount_value: Option<f64>,
+    pub min_order_amount: Option<f64>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OrderDiscount {
+    pub coupon_code: String,
+    pub amount: f64,
+}
+
+// Sales report models
+
+#[derive(Debug, Deserialize)]
+pub struct SalesReportQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub format: Option<String>,
+    pub breakdown: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ItemSales {
+    pub menu_item_id: String,
+    pub name: String,
+    pub section_name: String,
+    pub quantity: i64,
+    pub revenue: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SectionSales {
+    pub section_id: Option<String>,
+    pub name: String,
+    pub quantity: i64,
+    pub revenue: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TableSales {
+    pub table_id: String,
+    pub name: String,
+    pub order_count: i64,
+    pub revenue: f64,
+}
+
+// One cell of the weekday/hour heatmap; day_of_week 0 is Monday
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HourlySales {
+    pub day_of_week: u32,
+    pub hour: u32,
+    pub order_count: i64,
+    pub revenue: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SalesReport {
+    pub restaurant_id: String,
+    pub from: String,
+    pub to: String,
+    pub timezone: String,
+    pub order_count: i64,
+    pub cancelled_count: i64,
+    pub cancellation_rate: f64,
+    pub gross_sales: f64,
+    pub discounts: f64,
+    pub refunds: f64,
+    pub net_sales: f64,
+    pub average_order_value: f64,
+    pub items: Vec<ItemSales>,
+    pub sections: Vec<SectionSales>,
+    pub tables: Vec<TableSales>,
+    pub hours: Vec<HourlySales>,
+}
+
+// Order export models
+
+#[derive(Debug, Deserialize)]
+pub struct OrderExportQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub format: Option<String>,
+}
+
+// One exported row per order line
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OrderExportLine {
+    pub order_id: String,
+    pub order_number: Option<i32>,
+    pub created_at: String,
+    pub business_date: String,
+    pub table_name: String,
+    pub menu_item_id: String,
+    pub item_name: String,
+    pub variant_name: Option<String>,
+    pub quantity: i32,
+    pub unit_price: f64,
+    pub line_total: f64,
+    pub tax: f64,
+    pub status: String,
+    pub payment_status: String,
+}
+
+// Day closing models
+
+#[derive(Debug, Deserialize)]
+pub struct ClosingPreviewQuery {
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CloseDayRequest {
+    pub business_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReopenDayRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ClosingPaymentMethod {
+    pub method: String,
+    pub order_count: i64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct InProgressOrder {
+    pub order_id: String,
+    pub order_number: Option<i32>,
+    pub table_name: String,
+    pub status: String,
+    pub payment_status: String,
+    pub total_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ClosingSummary {
+    pub restaurant_id: String,
+    pub business_date: String,
+    pub timezone: String,
+    pub order_count: i64,
+    pub cancelled_count: i64,
+    pub gross_sales: f64,
+    pub discounts: f64,
+    pub refunds: f64,
+    pub taxes: f64,
+    pub tips: f64,
+    pub net_sales: f64,
+    pub payment_methods: Vec<ClosingPaymentMethod>,
+    pub in_progress_orders: Vec<InProgressOrder>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayClosing {
+    pub id: String,
+    pub restaurant_id: String,
+    pub business_date: String,
+    pub summary: ClosingSummary,
+    pub closed_by: String,
+    pub closed_at: DateTime<Utc>,
+    pub reopened_by: Option<String>,
+    pub reopened_at: Option<DateTime<Utc>>,
+    pub reopen_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ClosingAuditEntry {
+    pub id: String,
+    pub closing_id: String,
+    pub business_date: String,
+    pub action: String,
+    pub user_id: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// List pagination models
+
+// Shared envelope for paginated lists; `next_cursor` is null on the last page
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub status: Option<String>,
+    pub table_id: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub customer: Option<String>,
+    // Orders still awaiting payment are hidden unless this is set
+    pub include_unpaid: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TableListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManagerListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub role: Option<String>,
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContactSubmissionListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub status: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub q: Option<String>,
+}
+
+// Kitchen station models
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct KitchenStation {
+    pub id: String,
+    pub restaurant_id: String,
+    pub name: String,
+    pub display_order: i32,
+    pub section_ids: Vec<String>,
+    pub item_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateKitchenStationRequest {
+    pub name: String,
+    pub display_order: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateKitchenStationRequest {
+    pub name: Option<String>,
+    pub display_order: Option<i32>,
+}
+
+// Routes a section or item to a station; null clears the mapping
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AssignStationRequest {
+    pub station_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StationLineQuery {
+    pub station_id: Option<String>,
+    pub status: Option<String>,
+}
+
+// An order line as shown on a kitchen display. elapsed_seconds runs from the
+// order being placed until the line is bumped, or until now.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StationLine {
+    pub id: String,
+    pub order_id: String,
+    pub order_number: Option<i32>,
+    pub order_status: String,
+    pub table_name: String,
+    pub station_id: Option<String>,
+    pub menu_item_id: String,
+    pub item_name: String,
+    pub variant_name: Option<String>,
+    pub notes: Option<String>,
+    pub quantity: i32,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub bumped_at: Option<DateTime<Utc>>,
+    pub recall_count: i32,
+    pub elapsed_seconds: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrintQuery {
+    pub width: Option<i64>,
+    pub station_id: Option<String>,
+}
+
+// Queues a rendered ticket or receipt for the restaurant's print agent.
+// station_id limits a kitchen ticket to the lines routed to that station.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreatePrintJobRequest {
+    pub order_id: String,
+    pub kind: String,
+    pub width: Option<i64>,
+    pub station_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrintJobQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PrintJob {
+    pub id: String,
+    pub restaurant_id: String,
+    pub order_id: String,
+    pub order_number: Option<i32>,
+    pub kind: String,
+    pub station_id: Option<String>,
+    pub width: i64,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub printed_at: Option<DateTime<Utc>>,
+}
+
+// A job handed to a print agent, with the ESC/POS bytes to send to the printer
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ClaimedPrintJob {
+    pub job: PrintJob,
+    pub payload_base64: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AckPrintJobRequest {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub restaurant_id: String,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+    pub description: Option<String>,
+}
+
+// Omitted fields are left unchanged
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveryQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub subscription_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub redelivery_of: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WebhookDeliveryAttempt {
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub response_body: Option<String>,
+    pub duration_ms: i64,
+    pub attempted_at: DateTime<Utc>,
+}
+
+// A delivery with the exact JSON body that was signed and every attempt made
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WebhookDeliveryDetail {
+    pub delivery: WebhookDelivery,
+    pub payload: String,
+    pub attempts: Vec<WebhookDeliveryAttempt>,
+}
+
+// A restaurant API key as listed; the key itself is only shown on creation
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ApiKey {
+    pub id: String,
+    pub restaurant_id: String,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreatedApiKey {
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+// The admin app types the status as a union, so this is not exported
+#[derive(Debug, Deserialize)]
+pub struct UpdateOrderStatusRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+// A signed-in device as listed to its user
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SessionInfo {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    // True for the session the request was made with
+    pub current: bool,
+}
@@ -0,0 +1,537 @@
+use crate::models::{
+    Claims, DailyWindow, OpeningHoursResponse, OrderingAvailability, SpecialDay,
+    UpdateClosureRequest, UpdateOpeningHoursRequest, UpsertSpecialDayRequest, WeeklyOpeningWindow,
+};
+use crate::permissions::is_manager;
+use crate::schedule::{format_time, Schedule, TimeRange, WeeklyWindow};
+use crate::timezone::{local_to_utc, parse_timezone};
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+
+// Machine-readable reasons returned when a restaurant is not taking orders
+pub const CLOSED_TEMPORARILY: &str = "temporarily_closed";
+pub const CLOSED_SPECIAL_DAY: &str = "closed_today";
+pub const CLOSED_OUTSIDE_HOURS: &str = "outside_opening_hours";
+
+pub async fn load_opening_schedule(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<Schedule, sqlx::Error> {
+    let weekly_rows = sqlx::query(
+        "SELECT day_of_week, open_time, close_time FROM restaurant_opening_hours
+         WHERE restaurant_id = ? ORDER BY day_of_week, open_time",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut schedule = Schedule::default();
+    for row in weekly_rows {
+        let day_of_week: u32 = row.try_get("day_of_week")?;
+        let open_time: String = row.try_get("open_time")?;
+        let close_time: String = row.try_get("close_time")?;
+        match TimeRange::parse(&open_time, &close_time) {
+            Some(range) => schedule.weekly.push(WeeklyWindow { day_of_week, range }),
+            None => log::warn!("Skipping invalid opening hours {open_time}-{close_time}"),
+        }
+    }
+
+    let special_rows = sqlx::query(
+        "SELECT date, closed, open_time, close_time FROM restaurant_special_days
+         WHERE restaurant_id = ? ORDER BY date, open_time",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    for row in special_rows {
+        let date: String = row.try_get("date")?;
+        let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+            log::warn!("Skipping special day with invalid date {date}");
+            continue;
+        };
+        let ranges = schedule.overrides.entry(date).or_default();
+        let closed: bool = row.try_get("closed")?;
+        if closed {
+            continue;
+        }
+        let open_time: Option<String> = row.try_get("open_time")?;
+        let close_time: Option<String> = row.try_get("close_time")?;
+        if let Some(range) = open_time
+            .zip(close_time)
+            .and_then(|(open, close)| TimeRange::parse(&open, &close))
+        {
+            ranges.push(range);
+        }
+    }
+
+    Ok(schedule)
+}
+
+pub fn availability_at(
+    schedule: &Schedule,
+    tz: Tz,
+    closed_until: Option<DateTime<Utc>>,
+    closure_reason: Option<String>,
+    now: DateTime<Utc>,
+) -> OrderingAvailability {
+    let next_opening = |from: DateTime<Utc>| {
+        let local = from.with_timezone(&tz).naive_local();
+        schedule
+            .next_start_after(local)
+            .map(|start| local_to_utc(tz, start))
+    };
+
+    if let Some(until) = closed_until.filter(|until| *until > now) {
+        let until_local = until.with_timezone(&tz).naive_local();
+        let next_open_at = if schedule.is_active_at(until_local) {
+            Some(until)
+        } else {
+            next_opening(until)
+        };
+        return OrderingAvailability {
+            is_open: false,
+            reason: Some(CLOSED_TEMPORARILY.to_string()),
+            closure_message: closure_reason,
+            next_open_at,
+        };
+    }
+
+    let local = now.with_timezone(&tz).naive_local();
+    if schedule.is_active_at(local) {
+        return OrderingAvailability {
+            is_open: true,
+            reason: None,
+            closure_message: None,
+            next_open_at: None,
+        };
+    }
+
+    let reason = if schedule.is_closed_all_day(local.date()) {
+        CLOSED_SPECIAL_DAY
+    } else {
+        CLOSED_OUTSIDE_HOURS
+    };
+    OrderingAvailability {
+        is_open: false,
+        reason: Some(reason.to_string()),
+        closure_message: None,
+        next_open_at: next_opening(now),
+    }
+}
+
+pub async fn restaurant_availability(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    now: DateTime<Utc>,
+) -> Result<OrderingAvailability, sqlx::Error> {
+    let row =
+        sqlx::query("SELECT timezone, closed_until, closure_reason FROM restaurants WHERE id = ?")
+            .bind(restaurant_id)
+            .fetch_one(pool)
+            .await?;
+
+    let timezone: String = row.try_get("timezone")?;
+    let closed_until: Option<NaiveDateTime> = row.try_get("closed_until")?;
+    let closure_reason: Option<String> = row.try_get("closure_reason")?;
+    let schedule = load_opening_schedule(pool, restaurant_id).await?;
+
+    Ok(availability_at(
+        &schedule,
+        parse_timezone(&timezone).unwrap_or(Tz::UTC),
+        closed_until.map(|until| DateTime::from_naive_utc_and_offset(until, Utc)),
+        closure_reason,
+        now,
+    ))
+}
+
+async fn opening_hours_response(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<OpeningHoursResponse, sqlx::Error> {
+    let row =
+        sqlx::query("SELECT timezone, closed_until, closure_reason FROM restaurants WHERE id = ?")
+            .bind(restaurant_id)
+            .fetch_one(pool)
+            .await?;
+    let timezone: String = row.try_get("timezone")?;
+    let closed_until: Option<NaiveDateTime> = row.try_get("closed_until")?;
+    let closure_reason: Option<String> = row.try_get("closure_reason")?;
+
+    let schedule = load_opening_schedule(pool, restaurant_id).await?;
+
+    let weekly = schedule
+        .weekly
+        .iter()
+        .map(|window| WeeklyOpeningWindow {
+            day_of_week: window.day_of_week,
+            open_time: format_time(window.range.start),
+            close_time: format_time(window.range.end),
+        })
+        .collect();
+
+    let notes: HashMap<String, String> = sqlx::query_as(
+        "SELECT date, note FROM restaurant_special_days WHERE restaurant_id = ? AND note IS NOT NULL",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut special_days: Vec<SpecialDay> = schedule
+        .overrides
+        .iter()
+        .map(|(date, ranges)| {
+            let date = date.format("%Y-%m-%d").to_string();
+            let note = notes.get(&date).cloned();
+            SpecialDay {
+                closed: ranges.is_empty(),
+                windows: ranges
+                    .iter()
+                    .map(|range| DailyWindow {
+                        open_time: format_time(range.start),
+                        close_time: format_time(range.end),
+                    })
+                    .collect(),
+                date,
+                note,
+            }
+        })
+        .collect();
+    special_days.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let closed_until = closed_until.map(|until| DateTime::from_naive_utc_and_offset(until, Utc));
+    let tz = parse_timezone(&timezone).unwrap_or(Tz::UTC);
+
+    Ok(OpeningHoursResponse {
+        availability: availability_at(
+            &schedule,
+            tz,
+            closed_until,
+            closure_reason.clone(),
+            Utc::now(),
+        ),
+        timezone,
+        weekly,
+        special_days,
+        closed_until,
+        closure_reason,
+    })
+}
+
+fn parse_daily_window(window: &DailyWindow) -> Option<TimeRange> {
+    TimeRange::parse(&window.open_time, &window.close_time)
+}
+
+pub async fn get_opening_hours(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match opening_hours_response(pool.get_ref(), &restaurant_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching opening hours: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn update_opening_hours(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<String>,
+    req: web::Json<UpdateOpeningHoursRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let mut windows = Vec::new();
+    for window in &req.weekly {
+        if window.day_of_week > 6 {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "day_of_week must be between 0 (Monday) and 6 (Sunday)"
+            })));
+        }
+        match TimeRange::parse(&window.open_time, &window.close_time) {
+            Some(range) => windows.push((window.day_of_week, range)),
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Times must use the HH:MM format"
+                })));
+            }
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let result = sqlx::query("DELETE FROM restaurant_opening_hours WHERE restaurant_id = ?")
+        .bind(&restaurant_id)
+        .execute(&mut *tx)
+        .await;
+    if let Err(e) = result {
+        log::error!("Database error clearing opening hours: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update opening hours"
+        })));
+    }
+
+    for (day_of_week, range) in windows {
+        let result = sqlx::query(
+            "INSERT INTO restaurant_opening_hours (restaurant_id, day_of_week, open_time, close_time) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&restaurant_id)
+        .bind(day_of_week)
+        .bind(format_time(range.start))
+        .bind(format_time(range.end))
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = result {
+            log::error!("Database error saving opening hours: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update opening hours"
+            })));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error committing opening hours: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update opening hours"
+        })));
+    }
+
+    match opening_hours_response(pool.get_ref(), &restaurant_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching opening hours: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn upsert_special_day(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(String, String)>,
+    req: web::Json<UpsertSpecialDayRequest>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, date) = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+        Ok(date) => date.format("%Y-%m-%d").to_string(),
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Date must use the YYYY-MM-DD format"
+            })));
+        }
+    };
+
+    let mut ranges = Vec::new();
+    if !req.closed {
+        for window in &req.windows {
+            match parse_daily_window(window) {
+                Some(range) => ranges.push(range),
+                None => {
+                    return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                        "error": "Times must use the HH:MM format"
+                    })));
+                }
+            }
+        }
+        if ranges.is_empty() {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Provide at least one window or mark the day as closed"
+            })));
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let result =
+        sqlx::query("DELETE FROM restaurant_special_days WHERE restaurant_id = ? AND date = ?")
+            .bind(&restaurant_id)
+            .bind(&date)
+            .execute(&mut *tx)
+            .await;
+    if let Err(e) = result {
+        log::error!("Database error clearing special day: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to save special day"
+        })));
+    }
+
+    // A closed day is stored as a single row without times
+    let rows: Vec<Option<TimeRange>> = if req.closed {
+        vec![None]
+    } else {
+        ranges.into_iter().map(Some).collect()
+    };
+    for range in rows {
+        let result = sqlx::query(
+            "INSERT INTO restaurant_special_days (restaurant_id, date, closed, open_time, close_time, note) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&restaurant_id)
+        .bind(&date)
+        .bind(req.closed)
+        .bind(range.map(|range| format_time(range.start)))
+        .bind(range.map(|range| format_time(range.end)))
+        .bind(&req.note)
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = result {
+            log::error!("Database error saving special day: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to save special day"
+            })));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error committing special day: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to save special day"
+        })));
+    }
+
+    match opening_hours_response(pool.get_ref(), &restaurant_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching opening hours: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn delete_special_day(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, date) = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let result =
+        sqlx::query("DELETE FROM restaurant_special_days WHERE restaurant_id = ? AND date = ?")
+            .bind(&restaurant_id)
+            .bind(&date)
+            .execute(pool.get_ref())
+            .await;
+
+    match result {
+        Ok(result) if result.rows_affected() == 0 => {
+            Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Special day not found"
+            })))
+        }
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting special day: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete special day"
+            })))
+        }
+    }
+}
+
+pub async fn update_closure(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<String>,
+    req: web::Json<UpdateClosureRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    if req.closed_until.is_some_and(|until| until <= Utc::now()) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "closed_until must be in the future"
+        })));
+    }
+
+    let result =
+        sqlx::query("UPDATE restaurants SET closed_until = ?, closure_reason = ? WHERE id = ?")
+            .bind(req.closed_until.map(|until| until.naive_utc()))
+            .bind(req.closed_until.and(req.reason.as_ref()))
+            .bind(&restaurant_id)
+            .execute(pool.get_ref())
+            .await;
+
+    if let Err(e) = result {
+        log::error!("Database error updating closure: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update closure"
+        })));
+    }
+
+    match opening_hours_response(pool.get_ref(), &restaurant_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching opening hours: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
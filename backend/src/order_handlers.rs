@@ -1,12 +1,64 @@
+use crate::api_keys::{api_key_allows, ApiKeyAuth, SCOPE_ORDERS_READ, SCOPE_ORDERS_STATUS};
+use crate::bundle_handlers::{load_bundles, split_bundle_price};
+use crate::closing_handlers::is_day_closed;
+use crate::coupon_handlers::{
+    coupon_discount, find_coupon_by_code, redeem_coupon, release_order_coupon,
+};
+use crate::inventory_handlers::{return_order_stock, take_stock};
+use crate::menu_versions::live_snapshot;
 use crate::models::{
-    Claims, CreateOrderRequest, CreateOrderResponse, MenuItem, MenuItemRow, OrderItem,
-    OrderItemResponse, OrderResponse, Restaurant, RestaurantRow, Table, TableRow,
+    Claims, CreateOrderItem, CreateOrderRequest, CreateOrderResponse, MenuItem, MenuItemRow,
+    OrderDiscount, OrderItem, OrderItemResponse, OrderListQuery, OrderResponse, Page,
+    PaymentIntentResponse, Restaurant, RestaurantRow, Table, TableRow, UpdateOrderStatusRequest,
 };
-use actix_web::{web, HttpResponse, Result};
+use crate::opening_hours_handlers::restaurant_availability;
+use crate::pagination::{PageRequest, SortField};
+use crate::payment_handlers::cancel_order_payments;
+use crate::payments::PaymentGateway;
+use crate::permissions::is_manager;
+use crate::pricing_rule_handlers::PricingRules;
+use crate::section_schedule_handlers::SectionSchedules;
+use crate::station_handlers::route_order_lines;
+use crate::timezone::{business_date, day_bounds_utc, restaurant_timezone};
+use crate::webhooks::{enqueue_event, enqueue_order_status_changed};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
 use chrono::Utc;
-use sqlx::{Pool, Row, Sqlite};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+pub const ORDER_STATUSES: [&str; 6] = [
+    "pending",
+    "confirmed",
+    "preparing",
+    "ready",
+    "delivered",
+    "cancelled",
+];
+
+// Orders placed before numbering existed sort as number 0
+static ORDER_SORTS: [SortField; 3] = [
+    SortField {
+        name: "created_at",
+        expr: "CAST(o.created_at AS TEXT)",
+        numeric: false,
+        descending: true,
+    },
+    SortField {
+        name: "order_number",
+        expr: "CAST(COALESCE(o.order_number, 0) AS REAL)",
+        numeric: true,
+        descending: true,
+    },
+    SortField {
+        name: "total_amount",
+        expr: "CAST(o.total_amount AS REAL)",
+        numeric: true,
+        descending: true,
+    },
+];
+
 // Debug endpoint to capture raw JSON
 pub async fn debug_order_payload(payload: web::Bytes) -> Result<HttpResponse> {
     let payload_str = String::from_utf8_lossy(&payload);
@@ -23,6 +75,8 @@ pub async fn debug_order_payload(payload: web::Bytes) -> Result<HttpResponse> {
 
 pub async fn create_order(
     pool: web::Data<Pool<Sqlite>>,
+    gateway: web::Data<PaymentGateway>,
+    http_req: HttpRequest,
     req: web::Json<CreateOrderRequest>,
 ) -> Result<HttpResponse> {
     log::debug!("Successfully deserialized order request: {req:?}");
@@ -49,35 +103,209 @@ pub async fn create_order(
         }
     };
 
+    // Retried submissions carry the same key and get the original response back
+    let idempotency_key = match idempotency_key(&http_req, &req) {
+        Ok(key) => key,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+    let fingerprint = request_fingerprint(&req);
+
+    if let Some(key) = &idempotency_key {
+        match find_idempotent_order(pool.get_ref(), &table.id, key).await {
+            Ok(Some(stored)) => return Ok(replay_idempotent_order(stored, &fingerprint)),
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Database error checking idempotency key: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        }
+    }
+
+    let now = Utc::now();
+    match restaurant_availability(pool.get_ref(), &table.restaurant_id, now).await {
+        Ok(availability) if !availability.is_open => {
+            return Ok(HttpResponse::UnprocessableEntity().json(serde_json::json!({
+                "error": "Restaurant is not accepting orders right now",
+                "code": availability.reason,
+                "next_open_at": availability.next_open_at
+            })));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            log::error!("Database error checking opening hours: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    }
+
+    let section_schedules = match SectionSchedules::load(pool.get_ref(), &table.restaurant_id).await
+    {
+        Ok(section_schedules) => section_schedules,
+        Err(e) => {
+            log::error!("Database error loading section schedules: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let pricing_rules = match PricingRules::load(pool.get_ref(), &table.restaurant_id).await {
+        Ok(pricing_rules) => pricing_rules,
+        Err(e) => {
+            log::error!("Database error loading pricing rules: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Guests order from the live menu version, so names, prices and sections
+    // come from it even while managers have unpublished edits
+    let live_menu = match live_snapshot(pool.get_ref(), &table.restaurant_id).await {
+        Ok(live_menu) => live_menu,
+        Err(e) => {
+            log::error!("Database error loading live menu: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let bundles = match load_bundles(pool.get_ref(), &table.restaurant_id).await {
+        Ok(bundles) => bundles,
+        Err(e) => {
+            log::error!("Database error loading bundles: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Each ordered bundle becomes one line per slot so the kitchen sees every
+    // component; the lines go through the same checks as items ordered alone
+    let mut lines: Vec<(CreateOrderItem, Option<usize>)> =
+        req.items.iter().map(|item| (item.clone(), None)).collect();
+    let mut ordered_bundles = Vec::new();
+
+    for ordered in &req.bundles {
+        let bundle = match bundles.iter().find(|bundle| {
+            bundle.id == ordered.bundle_id && bundle.available && !bundle.slots.is_empty()
+        }) {
+            Some(bundle) => bundle,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Bundle {} not found or not available", ordered.bundle_id)
+                })));
+            }
+        };
+
+        if ordered.quantity <= 0 {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Bundle quantity must be greater than 0"
+            })));
+        }
+
+        let unknown_slot = ordered
+            .choices
+            .iter()
+            .any(|choice| !bundle.slots.iter().any(|slot| slot.id == choice.slot_id));
+        for (slot_index, slot) in bundle.slots.iter().enumerate() {
+            let mut picks = ordered
+                .choices
+                .iter()
+                .filter(|choice| choice.slot_id == slot.id);
+            let choice = match (picks.next(), picks.next()) {
+                (Some(choice), None)
+                    if !unknown_slot && slot.item_ids.contains(&choice.menu_item_id) =>
+                {
+                    choice
+                }
+                _ => {
+                    return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                        "error": format!("Choose one {} for {}", slot.name, bundle.name),
+                        "code": "invalid_bundle_choice",
+                        "slot_id": slot.id
+                    })));
+                }
+            };
+
+            lines.push((
+                CreateOrderItem {
+                    menu_item_id: choice.menu_item_id.clone(),
+                    quantity: ordered.quantity,
+                    special_requests: ordered.special_requests.clone().filter(|_| slot_index == 0),
+                    variant_id: choice.variant_id.clone(),
+                },
+                Some(ordered_bundles.len()),
+            ));
+        }
+        ordered_bundles.push((bundle, ordered.quantity));
+    }
+
     // Validate menu items and calculate total
     let mut order_items = Vec::new();
     let mut total_amount = 0.0;
+    let mut requested_quantities: HashMap<String, i32> = HashMap::new();
 
-    for item in &req.items {
+    for (item, bundle_index) in &lines {
         log::debug!(
             "Looking for menu item ID: {} in restaurant: {}",
             item.menu_item_id,
             table.restaurant_id
         );
         let menu_item_row = sqlx::query_as::<_, MenuItemRow>(
-            "SELECT mi.id, mi.section_id, mi.name, mi.description, mi.price, mi.available, mi.display_order, mi.created_at 
+            "SELECT mi.id, mi.section_id, mi.name, mi.description, mi.price, mi.available, mi.display_order, mi.stock_quantity, mi.created_at 
              FROM menu_items mi 
              JOIN menu_sections ms ON mi.section_id = ms.id 
-             WHERE mi.id = ? AND ms.restaurant_id = ? AND mi.available = TRUE"
+             WHERE mi.id = ? AND ms.restaurant_id = ?"
         )
         .bind(&item.menu_item_id)
         .bind(&table.restaurant_id)
         .fetch_optional(pool.get_ref())
         .await;
 
-        let menu_item = match menu_item_row {
-            Ok(Some(menu_item_row)) => MenuItem::from(menu_item_row),
-            Ok(None) => {
+        let live_item = live_menu.find_item(&item.menu_item_id);
+        let live_variants = live_item
+            .map(|(_, live_item)| live_item.variants.clone())
+            .unwrap_or_default();
+        let menu_item = match (menu_item_row, live_item) {
+            (Ok(Some(menu_item_row)), Some((live_section, live_item)))
+                if menu_item_row.available.unwrap_or(true) =>
+            {
+                MenuItem {
+                    section_id: live_section.id.clone(),
+                    name: live_item.name.clone(),
+                    price: live_item.price,
+                    ..MenuItem::from(menu_item_row)
+                }
+            }
+            // Deleted from the draft but still published: it stays orderable
+            // as published, without stock tracking, until the next publish
+            (Ok(None), Some((live_section, live_item))) if live_item.available => MenuItem {
+                id: live_item.id.clone(),
+                section_id: live_section.id.clone(),
+                name: live_item.name.clone(),
+                description: live_item.description.clone(),
+                price: live_item.price,
+                available: true,
+                display_order: live_item.display_order,
+                stock_quantity: None,
+                created_at: now,
+                variants: Vec::new(),
+            },
+            (Ok(_), _) => {
                 return Ok(HttpResponse::BadRequest().json(serde_json::json!({
                     "error": format!("Menu item {} not found or not available", item.menu_item_id)
                 })));
             }
-            Err(e) => {
+            (Err(e), _) => {
                 log::error!("Database error finding menu item: {e}");
                 return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
                     "error": "Internal server error"
@@ -85,29 +313,162 @@ pub async fn create_order(
             }
         };
 
+        if !section_schedules.is_active(&menu_item.section_id, now) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Menu item {} is not served at this time", item.menu_item_id),
+                "code": "section_not_available"
+            })));
+        }
+
         if item.quantity <= 0 {
             return Ok(HttpResponse::BadRequest().json(serde_json::json!({
                 "error": "Item quantity must be greater than 0"
             })));
         }
 
-        let item_total = menu_item.price * item.quantity as f64;
-        total_amount += item_total;
+        // Items that come in variants are ordered and priced by variant
+        let variant = match &item.variant_id {
+            None if live_variants.is_empty() => None,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Menu item {} requires a variant", item.menu_item_id),
+                    "code": "variant_required"
+                })));
+            }
+            Some(variant_id) => {
+                let available = sqlx::query_scalar::<_, bool>(
+                    "SELECT available FROM menu_item_variants WHERE id = ? AND item_id = ?",
+                )
+                .bind(variant_id)
+                .bind(&menu_item.id)
+                .fetch_optional(pool.get_ref())
+                .await;
+
+                let live_variant = live_variants
+                    .iter()
+                    .find(|variant| variant.id == *variant_id);
+                // A published variant deleted from the draft stays orderable
+                match (live_variant, available) {
+                    (Some(variant), Ok(Some(true) | None)) => Some(variant.clone()),
+                    (_, Ok(_)) => {
+                        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                            "error": format!("Variant {variant_id} not found or not available")
+                        })));
+                    }
+                    (_, Err(e)) => {
+                        log::error!("Database error finding variant: {e}");
+                        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                            "error": "Internal server error"
+                        })));
+                    }
+                }
+            }
+        };
+        let base_price = variant
+            .as_ref()
+            .map_or(menu_item.price, |variant| variant.price);
+        let (price, pricing_rule) =
+            pricing_rules.apply(&menu_item.id, &menu_item.section_id, base_price, now);
+
+        // Variants share their parent item's stock
+        let requested = requested_quantities
+            .entry(menu_item.id.clone())
+            .or_insert(0);
+        *requested += item.quantity;
+        if let Some(stock) = menu_item.stock_quantity.filter(|stock| *requested > *stock) {
+            return Ok(insufficient_stock(&menu_item, stock));
+        }
+
+        // Bundle components are priced below, once all of them are known, and
+        // the bundle price replaces any pricing rule
+        let bundle = bundle_index.map(|index| ordered_bundles[index].0);
+        let pricing_rule = pricing_rule.filter(|_| bundle.is_none());
+        if bundle.is_none() {
+            total_amount += price * item.quantity as f64;
+        }
 
         order_items.push(OrderItem {
             menu_item_id: item.menu_item_id.clone(),
             quantity: item.quantity,
-            price: menu_item.price,
+            price,
             notes: item.special_requests.clone(),
+            variant_id: variant.as_ref().map(|variant| variant.id.clone()),
+            variant_name: variant.map(|variant| variant.name),
+            bundle_id: bundle.map(|bundle| bundle.id.clone()),
+            bundle_name: bundle.map(|bundle| bundle.name.clone()),
+            pricing_rule_id: pricing_rule.map(|rule| rule.id.clone()),
+            pricing_rule_name: pricing_rule.map(|rule| rule.name.clone()),
         });
     }
 
+    // A bundle is sold at its own price, spread over the components in
+    // proportion to their menu prices so the lines still add up to the total
+    for (index, (bundle, quantity)) in ordered_bundles.iter().enumerate() {
+        let components: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, bundle_index))| *bundle_index == Some(index))
+            .map(|(line, _)| line)
+            .collect();
+        let weights: Vec<f64> = components
+            .iter()
+            .map(|line| order_items[*line].price)
+            .collect();
+        for (line, price) in components
+            .iter()
+            .zip(split_bundle_price(bundle.price, &weights))
+        {
+            order_items[*line].price = price;
+        }
+        total_amount += bundle.price * *quantity as f64;
+    }
+
     if order_items.is_empty() {
         return Ok(HttpResponse::BadRequest().json(serde_json::json!({
             "error": "Order must contain at least one item"
         })));
     }
 
+    // The discount comes off the total the guest pays and is kept on the order
+    // as its own line
+    let coupon = match &req.coupon_code {
+        Some(code) => {
+            let coupon = match find_coupon_by_code(pool.get_ref(), &table.restaurant_id, code).await
+            {
+                Ok(Some(coupon)) => coupon,
+                Ok(None) => {
+                    return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                        "error": "Coupon code not found",
+                        "code": "coupon_not_found"
+                    })));
+                }
+                Err(e) => {
+                    log::error!("Database error finding coupon: {e}");
+                    return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Internal server error"
+                    })));
+                }
+            };
+            match coupon_discount(&coupon, &order_items, total_amount, now) {
+                Ok(amount) => Some((coupon, amount)),
+                Err(rejection) => {
+                    return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                        "error": rejection.message,
+                        "code": rejection.code
+                    })));
+                }
+            }
+        }
+        None => None,
+    };
+    if let Some((_, amount)) = &coupon {
+        total_amount = ((total_amount - amount) * 100.0).round() / 100.0;
+    }
+    let discount = coupon.as_ref().map(|(coupon, amount)| OrderDiscount {
+        coupon_code: coupon.code.clone(),
+        amount: *amount,
+    });
+
     // Create order
     let order_id = Uuid::new_v4().to_string();
     let items_json = match serde_json::to_string(&order_items) {
@@ -120,27 +481,274 @@ pub async fn create_order(
         }
     };
 
+    // In pay-before-kitchen mode the order stays pending until the provider
+    // confirms payment, so nothing reaches the kitchen unpaid
+    let pay_before_kitchen = req.pay_before_kitchen.unwrap_or(false);
+    let intent = if pay_before_kitchen {
+        let provider = match gateway.provider() {
+            Some(provider) => provider,
+            None => {
+                return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                    "error": "Online payments are not available"
+                })));
+            }
+        };
+
+        match provider.create_intent(total_amount, gateway.currency(), &order_id) {
+            Ok(intent) => Some((provider.name().to_string(), intent)),
+            Err(e) => {
+                log::error!("Payment provider error creating intent: {e}");
+                return Ok(HttpResponse::BadGateway().json(serde_json::json!({
+                    "error": "Failed to start payment"
+                })));
+            }
+        }
+    } else {
+        None
+    };
+    let payment_status = if intent.is_some() {
+        "awaiting_payment"
+    } else {
+        "not_required"
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create order"
+            })));
+        }
+    };
+
+    // Stock is taken inside the order transaction so two guests cannot both
+    // get the last portion; a rollback puts it back
+    for (menu_item_id, quantity) in &requested_quantities {
+        match take_stock(&mut tx, menu_item_id, *quantity).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = tx.rollback().await;
+                return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                    "error": format!("Menu item {menu_item_id} just sold out"),
+                    "code": "insufficient_stock",
+                    "menu_item_id": menu_item_id
+                })));
+            }
+            Err(e) => {
+                log::error!("Database error updating stock: {e}");
+                let _ = tx.rollback().await;
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Failed to create order"
+                })));
+            }
+        }
+    }
+
+    if let Some((coupon, _)) = &coupon {
+        match redeem_coupon(&mut tx, &coupon.id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = tx.rollback().await;
+                return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                    "error": format!("Coupon {} has been used up", coupon.code),
+                    "code": "coupon_exhausted"
+                })));
+            }
+            Err(e) => {
+                log::error!("Database error redeeming coupon: {e}");
+                let _ = tx.rollback().await;
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Failed to create order"
+                })));
+            }
+        }
+    }
+
+    // Tickets are numbered per restaurant and business day; the counter row
+    // is bumped inside the order transaction so concurrent orders never share a number
+    let business_date = match restaurant_timezone(&mut *tx, &table.restaurant_id).await {
+        Ok(tz) => business_date(tz, now).format("%Y-%m-%d").to_string(),
+        Err(e) => {
+            log::error!("Database error fetching restaurant timezone: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create order"
+            })));
+        }
+    };
+    match is_day_closed(&mut *tx, &table.restaurant_id, &business_date).await {
+        Ok(false) => {}
+        Ok(true) => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::UnprocessableEntity().json(serde_json::json!({
+                "error": "Restaurant is not accepting orders right now",
+                "code": "day_closed"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking day closing: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create order"
+            })));
+        }
+    }
+    let order_number = match next_order_number(&mut tx, &table.restaurant_id, &business_date).await
+    {
+        Ok(number) => number,
+        Err(e) => {
+            log::error!("Database error allocating order number: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create order"
+            })));
+        }
+    };
+
+    let response = CreateOrderResponse {
+        order_id: order_id.clone(),
+        order_number,
+        total_amount,
+        status: "pending".to_string(),
+        payment_status: payment_status.to_string(),
+        payment: intent
+            .as_ref()
+            .map(|(provider, intent)| PaymentIntentResponse {
+                provider: provider.clone(),
+                intent_id: intent.id.clone(),
+                client_secret: intent.client_secret.clone(),
+                amount: intent.amount,
+                currency: intent.currency.clone(),
+            }),
+        discount: discount.clone(),
+        created_at: now,
+    };
+    let stored_response = match &idempotency_key {
+        Some(_) => match serde_json::to_string(&response) {
+            Ok(json) => Some(json),
+            Err(e) => {
+                log::error!("Error serializing order response: {e}");
+                let _ = tx.rollback().await;
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        },
+        None => None,
+    };
+
     let result = sqlx::query(
-        "INSERT INTO orders (id, table_id, items, total_amount, customer_name) VALUES (?, ?, ?, ?, ?)"
+        "INSERT INTO orders (id, table_id, items, total_amount, customer_name, payment_status, idempotency_key, idempotency_fingerprint, idempotency_response, order_number, business_date, coupon_id, coupon_code, discount_amount, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&order_id)
     .bind(&table.id)
     .bind(&items_json)
     .bind(total_amount)
     .bind(&req.customer_name)
-    .execute(pool.get_ref())
+    .bind(payment_status)
+    .bind(&idempotency_key)
+    .bind(idempotency_key.as_ref().map(|_| &fingerprint))
+    .bind(&stored_response)
+    .bind(order_number)
+    .bind(&business_date)
+    .bind(coupon.as_ref().map(|(coupon, _)| &coupon.id))
+    .bind(discount.as_ref().map(|discount| &discount.coupon_code))
+    .bind(discount.as_ref().map_or(0.0, |discount| discount.amount))
+    .bind(now.naive_utc())
+    .execute(&mut *tx)
     .await;
 
     match result {
-        Ok(_) => {
-            let response = CreateOrderResponse {
-                order_id: order_id.clone(),
-                total_amount,
-                status: "pending".to_string(),
-                created_at: Utc::now(),
+        Ok(_) => {}
+        // A concurrent request with the same key won the race
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            let _ = tx.rollback().await;
+            let key = idempotency_key.as_deref().unwrap_or_default();
+            return match find_idempotent_order(pool.get_ref(), &table.id, key).await {
+                Ok(Some(stored)) => Ok(replay_idempotent_order(stored, &fingerprint)),
+                Ok(None) => Ok(HttpResponse::Conflict().json(serde_json::json!({
+                    "error": "Order is already being processed"
+                }))),
+                Err(e) => {
+                    log::error!("Database error checking idempotency key: {e}");
+                    Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Internal server error"
+                    })))
+                }
             };
-            Ok(HttpResponse::Created().json(response))
         }
+        Err(e) => {
+            log::error!("Database error creating order: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create order"
+            })));
+        }
+    }
+
+    if let Err(e) = route_order_lines(
+        &mut tx,
+        &table.restaurant_id,
+        &order_id,
+        &order_items,
+        now.naive_utc(),
+    )
+    .await
+    {
+        log::error!("Database error routing order lines to stations: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create order"
+        })));
+    }
+
+    let event = serde_json::json!({
+        "order_id": order_id,
+        "order_number": order_number,
+        "table_id": table.id,
+        "table_name": table.name,
+        "customer_name": req.customer_name,
+        "items": order_items,
+        "total_amount": total_amount,
+        "discount": discount,
+        "status": "pending",
+        "payment_status": payment_status,
+        "created_at": now,
+    });
+    if let Err(e) = enqueue_event(&mut tx, &table.restaurant_id, "order.created", event).await {
+        log::error!("Database error queueing order webhooks: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create order"
+        })));
+    }
+
+    if let Some((provider_name, intent)) = &intent {
+        let result = sqlx::query(
+            "INSERT INTO payments (order_id, provider, provider_intent_id, amount, currency, status) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&order_id)
+        .bind(provider_name)
+        .bind(&intent.id)
+        .bind(intent.amount)
+        .bind(&intent.currency)
+        .bind(intent.status.as_str())
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = result {
+            log::error!("Database error recording payment intent: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create order"
+            })));
+        }
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(HttpResponse::Created().json(response)),
         Err(e) => {
             log::error!("Database error creating order: {e}");
             Ok(HttpResponse::InternalServerError().json(serde_json::json!({
@@ -150,6 +758,122 @@ pub async fn create_order(
     }
 }
 
+fn order_discount(row: &sqlx::sqlite::SqliteRow) -> Option<OrderDiscount> {
+    let coupon_code: Option<String> = row.try_get("coupon_code").ok().flatten();
+    coupon_code.map(|coupon_code| OrderDiscount {
+        coupon_code,
+        amount: row.try_get("discount_amount").unwrap_or_default(),
+    })
+}
+
+async fn next_order_number(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+    business_date: &str,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO order_number_counters (restaurant_id, business_date, last_number) VALUES (?, ?, 1)
+         ON CONFLICT(restaurant_id, business_date) DO UPDATE SET last_number = last_number + 1
+         RETURNING last_number",
+    )
+    .bind(restaurant_id)
+    .bind(business_date)
+    .fetch_one(conn)
+    .await
+}
+
+fn idempotency_key(
+    http_req: &HttpRequest,
+    req: &CreateOrderRequest,
+) -> Result<Option<String>, &'static str> {
+    let header = match http_req.headers().get("Idempotency-Key") {
+        Some(value) => match value.to_str() {
+            Ok(value) => Some(value.to_string()),
+            Err(_) => return Err("Invalid Idempotency-Key header"),
+        },
+        None => None,
+    };
+
+    match header.or_else(|| req.idempotency_key.clone()) {
+        Some(key) => {
+            let key = key.trim();
+            if key.is_empty() || key.len() > 255 {
+                Err("Idempotency key must be between 1 and 255 characters")
+            } else {
+                Ok(Some(key.to_string()))
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+// Everything that shapes the order, excluding the key itself
+fn request_fingerprint(req: &CreateOrderRequest) -> String {
+    let mut payload = serde_json::json!({
+        "table_code": req.table_code,
+        "items": req.items,
+        "customer_name": req.customer_name,
+        "pay_before_kitchen": req.pay_before_kitchen.unwrap_or(false),
+    });
+    // Only orders with bundles carry the field, so older fingerprints still match
+    if !req.bundles.is_empty() {
+        payload["bundles"] = serde_json::json!(req.bundles);
+    }
+    if let Some(code) = &req.coupon_code {
+        payload["coupon_code"] = serde_json::json!(code);
+    }
+    format!("{:x}", Sha256::digest(payload.to_string().as_bytes()))
+}
+
+struct IdempotentOrder {
+    fingerprint: String,
+    response: String,
+}
+
+fn insufficient_stock(menu_item: &MenuItem, remaining: i32) -> HttpResponse {
+    HttpResponse::Conflict().json(serde_json::json!({
+        "error": format!("Only {remaining} left of {}", menu_item.name),
+        "code": "insufficient_stock",
+        "menu_item_id": menu_item.id,
+        "remaining": remaining
+    }))
+}
+
+async fn find_idempotent_order(
+    pool: &Pool<Sqlite>,
+    table_id: &str,
+    key: &str,
+) -> Result<Option<IdempotentOrder>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT idempotency_fingerprint, idempotency_response FROM orders WHERE table_id = ? AND idempotency_key = ?",
+    )
+    .bind(table_id)
+    .bind(key)
+    .fetch_optional(pool)
+    .await?;
+
+    match row {
+        Some(row) => Ok(Some(IdempotentOrder {
+            fingerprint: row.try_get("idempotency_fingerprint")?,
+            response: row.try_get("idempotency_response")?,
+        })),
+        None => Ok(None),
+    }
+}
+
+fn replay_idempotent_order(stored: IdempotentOrder, fingerprint: &str) -> HttpResponse {
+    if stored.fingerprint != fingerprint {
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Idempotency key was already used for a different order"
+        }));
+    }
+
+    HttpResponse::Created()
+        .content_type("application/json")
+        .insert_header(("Idempotent-Replayed", "true"))
+        .body(stored.response)
+}
+
 pub async fn get_order(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
@@ -158,7 +882,7 @@ pub async fn get_order(
 
     // Fetch order with table and restaurant info using dynamic query
     let order_data = sqlx::query(
-        "SELECT o.id, o.table_id, o.items, o.total_amount, o.status, o.customer_name, o.created_at,
+        "SELECT o.id, o.order_number, o.table_id, o.items, o.total_amount, o.status, o.payment_status, o.customer_name, o.coupon_code, CAST(o.discount_amount AS REAL) AS discount_amount, o.created_at,
                 t.name as table_name, r.name as restaurant_name
          FROM orders o
          JOIN tables t ON o.table_id = t.id
@@ -187,7 +911,7 @@ pub async fn get_order(
             let mut response_items = Vec::new();
             for item in order_items {
                 let menu_item = sqlx::query_as::<_, MenuItemRow>(
-                    "SELECT id, section_id, name, description, price, available, display_order, created_at FROM menu_items WHERE id = ?"
+                    "SELECT id, section_id, name, description, price, available, display_order, stock_quantity, created_at FROM menu_items WHERE id = ?"
                 )
                 .bind(&item.menu_item_id)
                 .fetch_optional(pool.get_ref())
@@ -202,6 +926,12 @@ pub async fn get_order(
                             quantity: item.quantity,
                             price: item.price,
                             special_requests: item.notes,
+                            variant_id: item.variant_id,
+                            variant_name: item.variant_name,
+                            bundle_id: item.bundle_id,
+                            bundle_name: item.bundle_name,
+                            pricing_rule_id: item.pricing_rule_id,
+                            pricing_rule_name: item.pricing_rule_name,
                         });
                     }
                     Ok(None) => {
@@ -211,6 +941,12 @@ pub async fn get_order(
                             quantity: item.quantity,
                             price: item.price,
                             special_requests: item.notes,
+                            variant_id: item.variant_id,
+                            variant_name: item.variant_name,
+                            bundle_id: item.bundle_id,
+                            bundle_name: item.bundle_name,
+                            pricing_rule_id: item.pricing_rule_id,
+                            pricing_rule_name: item.pricing_rule_name,
                         });
                     }
                     Err(e) => {
@@ -221,6 +957,12 @@ pub async fn get_order(
                             quantity: item.quantity,
                             price: item.price,
                             special_requests: item.notes,
+                            variant_id: item.variant_id,
+                            variant_name: item.variant_name,
+                            bundle_id: item.bundle_id,
+                            bundle_name: item.bundle_name,
+                            pricing_rule_id: item.pricing_rule_id,
+                            pricing_rule_name: item.pricing_rule_name,
                         });
                     }
                 }
@@ -228,13 +970,16 @@ pub async fn get_order(
 
             let response = OrderResponse {
                 id: row.try_get("id").unwrap_or_default(),
+                order_number: row.try_get("order_number").ok(),
                 table_id: row.try_get("table_id").unwrap_or_default(),
                 table_name: row.try_get("table_name").unwrap_or_default(),
                 restaurant_name: row.try_get("restaurant_name").unwrap_or_default(),
                 items: response_items,
                 total_amount: row.try_get("total_amount").unwrap_or_default(),
                 status: row.try_get("status").unwrap_or_default(),
+                payment_status: row.try_get("payment_status").unwrap_or_default(),
                 customer_name: row.try_get("customer_name").ok(),
+                discount: order_discount(&row),
                 created_at: {
                     let created_at: chrono::NaiveDateTime =
                         row.try_get("created_at").unwrap_or_default();
@@ -259,46 +1004,129 @@ pub async fn get_order(
 pub async fn list_restaurant_orders(
     pool: web::Data<Pool<Sqlite>>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     path: web::Path<String>,
+    query: web::Query<OrderListQuery>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
 
     // Check if user is a manager of this restaurant
-    let manager_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM restaurant_managers WHERE restaurant_id = ? AND user_id = ?",
-    )
-    .bind(&restaurant_id)
-    .bind(&claims.sub)
-    .fetch_one(pool.get_ref())
-    .await
-    .unwrap_or(0);
-
-    if manager_count == 0 {
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_ORDERS_READ)
+    {
         return Ok(HttpResponse::Forbidden().json(serde_json::json!({
             "error": "Access denied"
         })));
     }
 
-    // Fetch orders for the restaurant
-    let orders = sqlx::query(
-        "SELECT o.id, o.table_id, o.items, o.total_amount, o.status, o.customer_name, o.created_at,
-                t.name as table_name
+    let page = match PageRequest::parse(
+        &ORDER_SORTS,
+        query.cursor.as_deref(),
+        query.limit,
+        query.sort.as_deref(),
+        query.order.as_deref(),
+    ) {
+        Ok(page) => page,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+    if query
+        .status
+        .as_deref()
+        .is_some_and(|status| !ORDER_STATUSES.contains(&status))
+    {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Status must be one of: {}", ORDER_STATUSES.join(", "))
+        })));
+    }
+
+    // Date filters are local business days of the restaurant, both ends included
+    let parse_date = |value: Option<&str>| match value {
+        Some(value) => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| ()),
+        None => Ok(None),
+    };
+    let (from, to) = match (
+        parse_date(query.from.as_deref()),
+        parse_date(query.to.as_deref()),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Date must use the YYYY-MM-DD format"
+            })));
+        }
+    };
+    let tz = if from.is_some() || to.is_some() {
+        match restaurant_timezone(pool.get_ref(), &restaurant_id).await {
+            Ok(tz) => tz,
+            Err(e) => {
+                log::error!("Database error fetching restaurant timezone: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        }
+    } else {
+        chrono_tz::Tz::UTC
+    };
+
+    // Fetch one page of orders for the restaurant
+    let mut orders_query = sqlx::QueryBuilder::<Sqlite>::new(format!(
+        "SELECT o.id, o.order_number, o.table_id, o.items, o.total_amount, o.status, o.payment_status, o.customer_name, o.coupon_code, CAST(o.discount_amount AS REAL) AS discount_amount, o.created_at,
+                t.name as table_name, {} AS sort_key
          FROM orders o
          JOIN tables t ON o.table_id = t.id
-         WHERE t.restaurant_id = ?
-         ORDER BY o.created_at DESC",
-    )
-    .bind(&restaurant_id)
-    .fetch_all(pool.get_ref())
-    .await;
+         WHERE t.restaurant_id = ",
+        page.sort_expr()
+    ));
+    orders_query.push_bind(&restaurant_id);
+    // Unpaid checkouts have not reached the kitchen, so staff do not see them
+    if !query.include_unpaid.unwrap_or(false) {
+        orders_query.push(" AND o.payment_status != 'awaiting_payment'");
+    }
+    if let Some(status) = &query.status {
+        orders_query.push(" AND o.status = ").push_bind(status);
+    }
+    if let Some(table_id) = &query.table_id {
+        orders_query.push(" AND o.table_id = ").push_bind(table_id);
+    }
+    if let Some(from) = from {
+        orders_query
+            .push(" AND o.created_at >= ")
+            .push_bind(day_bounds_utc(tz, from).0);
+    }
+    if let Some(to) = to {
+        orders_query
+            .push(" AND o.created_at < ")
+            .push_bind(day_bounds_utc(tz, to).1);
+    }
+    if let Some(customer) = query.customer.as_deref().map(str::trim) {
+        if !customer.is_empty() {
+            orders_query
+                .push(" AND o.customer_name LIKE ")
+                .push_bind(format!("%{customer}%"));
+        }
+    }
+    page.push_after_cursor(&mut orders_query, "o.id");
+    page.push_order_and_limit(&mut orders_query, "o.id");
+
+    let orders = match orders_query.build().fetch_all(pool.get_ref()).await {
+        Ok(rows) => page.split(rows, "id"),
+        Err(e) => Err(e),
+    };
 
     match orders {
-        Ok(orders) => {
+        Ok((orders, next_cursor)) => {
             let mut order_responses = Vec::new();
 
             // Get restaurant name once
             let restaurant = sqlx::query_as::<_, RestaurantRow>(
-                "SELECT id, name, address, establishment_year, google_maps_link, created_at FROM restaurants WHERE id = ?"
+                "SELECT id, name, address, establishment_year, google_maps_link, timezone, default_language, created_at FROM restaurants WHERE id = ?"
             )
             .bind(&restaurant_id)
             .fetch_optional(pool.get_ref())
@@ -324,7 +1152,7 @@ pub async fn list_restaurant_orders(
                 let mut response_items = Vec::new();
                 for item in order_items {
                     let menu_item = sqlx::query_as::<_, MenuItemRow>(
-                        "SELECT id, section_id, name, description, price, available, display_order, created_at FROM menu_items WHERE id = ?"
+                        "SELECT id, section_id, name, description, price, available, display_order, stock_quantity, created_at FROM menu_items WHERE id = ?"
                     )
                     .bind(&item.menu_item_id)
                     .fetch_optional(pool.get_ref())
@@ -339,6 +1167,12 @@ pub async fn list_restaurant_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                         Ok(None) => {
@@ -348,6 +1182,12 @@ pub async fn list_restaurant_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                         Err(_) => {
@@ -357,6 +1197,12 @@ pub async fn list_restaurant_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                     }
@@ -364,13 +1210,16 @@ pub async fn list_restaurant_orders(
 
                 order_responses.push(OrderResponse {
                     id: row.try_get("id").unwrap_or_default(),
+                    order_number: row.try_get("order_number").ok(),
                     table_id: row.try_get("table_id").unwrap_or_default(),
                     table_name: row.try_get("table_name").unwrap_or_default(),
                     restaurant_name: restaurant_name.clone(),
                     items: response_items,
                     total_amount: row.try_get("total_amount").unwrap_or_default(),
                     status: row.try_get("status").unwrap_or_default(),
+                    payment_status: row.try_get("payment_status").unwrap_or_default(),
                     customer_name: row.try_get("customer_name").ok(),
+                    discount: order_discount(&row),
                     created_at: {
                         let created_at: chrono::NaiveDateTime =
                             row.try_get("created_at").unwrap_or_default();
@@ -379,7 +1228,10 @@ pub async fn list_restaurant_orders(
                 });
             }
 
-            Ok(HttpResponse::Ok().json(order_responses))
+            Ok(HttpResponse::Ok().json(Page {
+                items: order_responses,
+                next_cursor,
+            }))
         }
         Err(e) => {
             log::error!("Database error fetching orders: {e}");
@@ -393,36 +1245,44 @@ pub async fn list_restaurant_orders(
 pub async fn list_today_orders(
     pool: web::Data<Pool<Sqlite>>,
     claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
     path: web::Path<String>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
 
     // Check if user is a manager of this restaurant
-    let manager_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM restaurant_managers WHERE restaurant_id = ? AND user_id = ?",
-    )
-    .bind(&restaurant_id)
-    .bind(&claims.sub)
-    .fetch_one(pool.get_ref())
-    .await
-    .unwrap_or(0);
-
-    if manager_count == 0 {
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_ORDERS_READ)
+    {
         return Ok(HttpResponse::Forbidden().json(serde_json::json!({
             "error": "Access denied"
         })));
     }
 
+    // "Today" is the restaurant's local calendar day
+    let (day_start, day_end) = match restaurant_timezone(pool.get_ref(), &restaurant_id).await {
+        Ok(tz) => day_bounds_utc(tz, business_date(tz, Utc::now())),
+        Err(e) => {
+            log::error!("Database error fetching restaurant timezone: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
     // Fetch today's orders for the restaurant
     let orders = sqlx::query(
-        "SELECT o.id, o.table_id, o.items, o.total_amount, o.status, o.customer_name, o.created_at,
+        "SELECT o.id, o.order_number, o.table_id, o.items, o.total_amount, o.status, o.payment_status, o.customer_name, o.coupon_code, CAST(o.discount_amount AS REAL) AS discount_amount, o.created_at,
                 t.name as table_name
          FROM orders o
          JOIN tables t ON o.table_id = t.id
-         WHERE t.restaurant_id = ? AND date(o.created_at) = date('now')
+         WHERE t.restaurant_id = ? AND o.created_at >= ? AND o.created_at < ?
+           AND o.payment_status != 'awaiting_payment'
          ORDER BY o.created_at DESC",
     )
     .bind(&restaurant_id)
+    .bind(day_start)
+    .bind(day_end)
     .fetch_all(pool.get_ref())
     .await;
 
@@ -432,7 +1292,7 @@ pub async fn list_today_orders(
 
             // Get restaurant name once
             let restaurant = sqlx::query_as::<_, RestaurantRow>(
-                "SELECT id, name, address, establishment_year, google_maps_link, created_at FROM restaurants WHERE id = ?"
+                "SELECT id, name, address, establishment_year, google_maps_link, timezone, default_language, created_at FROM restaurants WHERE id = ?"
             )
             .bind(&restaurant_id)
             .fetch_optional(pool.get_ref())
@@ -458,7 +1318,7 @@ pub async fn list_today_orders(
                 let mut response_items = Vec::new();
                 for item in order_items {
                     let menu_item = sqlx::query_as::<_, MenuItemRow>(
-                        "SELECT id, section_id, name, description, price, available, display_order, created_at FROM menu_items WHERE id = ?"
+                        "SELECT id, section_id, name, description, price, available, display_order, stock_quantity, created_at FROM menu_items WHERE id = ?"
                     )
                     .bind(&item.menu_item_id)
                     .fetch_optional(pool.get_ref())
@@ -473,6 +1333,12 @@ pub async fn list_today_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                         Ok(None) => {
@@ -482,6 +1348,12 @@ pub async fn list_today_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                         Err(_) => {
@@ -491,6 +1363,12 @@ pub async fn list_today_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                     }
@@ -498,13 +1376,16 @@ pub async fn list_today_orders(
 
                 order_responses.push(OrderResponse {
                     id: row.try_get("id").unwrap_or_default(),
+                    order_number: row.try_get("order_number").ok(),
                     table_id: row.try_get("table_id").unwrap_or_default(),
                     table_name: row.try_get("table_name").unwrap_or_default(),
                     restaurant_name: restaurant_name.clone(),
                     items: response_items,
                     total_amount: row.try_get("total_amount").unwrap_or_default(),
                     status: row.try_get("status").unwrap_or_default(),
+                    payment_status: row.try_get("payment_status").unwrap_or_default(),
                     customer_name: row.try_get("customer_name").ok(),
+                    discount: order_discount(&row),
                     created_at: {
                         let created_at: chrono::NaiveDateTime =
                             row.try_get("created_at").unwrap_or_default();
@@ -532,16 +1413,7 @@ pub async fn list_table_orders(
     let (restaurant_id, table_id) = path.into_inner();
 
     // Check if user is a manager of this restaurant
-    let manager_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM restaurant_managers WHERE restaurant_id = ? AND user_id = ?",
-    )
-    .bind(&restaurant_id)
-    .bind(&claims.sub)
-    .fetch_one(pool.get_ref())
-    .await
-    .unwrap_or(0);
-
-    if manager_count == 0 {
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
         return Ok(HttpResponse::Forbidden().json(serde_json::json!({
             "error": "Access denied"
         })));
@@ -564,11 +1436,12 @@ pub async fn list_table_orders(
 
     // Fetch orders for the specific table
     let orders = sqlx::query(
-        "SELECT o.id, o.table_id, o.items, o.total_amount, o.status, o.customer_name, o.created_at,
+        "SELECT o.id, o.order_number, o.table_id, o.items, o.total_amount, o.status, o.payment_status, o.customer_name, o.coupon_code, CAST(o.discount_amount AS REAL) AS discount_amount, o.created_at,
                 t.name as table_name
          FROM orders o
          JOIN tables t ON o.table_id = t.id
          WHERE o.table_id = ?
+           AND o.payment_status != 'awaiting_payment'
          ORDER BY o.created_at DESC",
     )
     .bind(&table_id)
@@ -581,7 +1454,7 @@ pub async fn list_table_orders(
 
             // Get restaurant name once
             let restaurant = sqlx::query_as::<_, RestaurantRow>(
-                "SELECT id, name, address, establishment_year, google_maps_link, created_at FROM restaurants WHERE id = ?"
+                "SELECT id, name, address, establishment_year, google_maps_link, timezone, default_language, created_at FROM restaurants WHERE id = ?"
             )
             .bind(&restaurant_id)
             .fetch_optional(pool.get_ref())
@@ -607,7 +1480,7 @@ pub async fn list_table_orders(
                 let mut response_items = Vec::new();
                 for item in order_items {
                     let menu_item = sqlx::query_as::<_, MenuItemRow>(
-                        "SELECT id, section_id, name, description, price, available, display_order, created_at FROM menu_items WHERE id = ?"
+                        "SELECT id, section_id, name, description, price, available, display_order, stock_quantity, created_at FROM menu_items WHERE id = ?"
                     )
                     .bind(&item.menu_item_id)
                     .fetch_optional(pool.get_ref())
@@ -622,6 +1495,12 @@ pub async fn list_table_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                         Ok(None) => {
@@ -631,6 +1510,12 @@ pub async fn list_table_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                         Err(_) => {
@@ -640,6 +1525,12 @@ pub async fn list_table_orders(
                                 quantity: item.quantity,
                                 price: item.price,
                                 special_requests: item.notes,
+                                variant_id: item.variant_id,
+                                variant_name: item.variant_name,
+                                bundle_id: item.bundle_id,
+                                bundle_name: item.bundle_name,
+                                pricing_rule_id: item.pricing_rule_id,
+                                pricing_rule_name: item.pricing_rule_name,
                             });
                         }
                     }
@@ -647,13 +1538,16 @@ pub async fn list_table_orders(
 
                 order_responses.push(OrderResponse {
                     id: row.try_get("id").unwrap_or_default(),
+                    order_number: row.try_get("order_number").ok(),
                     table_id: row.try_get("table_id").unwrap_or_default(),
                     table_name: row.try_get("table_name").unwrap_or_default(),
                     restaurant_name: restaurant_name.clone(),
                     items: response_items,
                     total_amount: row.try_get("total_amount").unwrap_or_default(),
                     status: row.try_get("status").unwrap_or_default(),
+                    payment_status: row.try_get("payment_status").unwrap_or_default(),
                     customer_name: row.try_get("customer_name").ok(),
+                    discount: order_discount(&row),
                     created_at: {
                         let created_at: chrono::NaiveDateTime =
                             row.try_get("created_at").unwrap_or_default();
@@ -672,3 +1566,194 @@ pub async fn list_table_orders(
         }
     }
 }
+
+// Sets an order's status directly, for staff and for integrations such as a
+// POS marking orders delivered. Delivered and cancelled orders are final.
+pub async fn update_order_status(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    gateway: web::Data<PaymentGateway>,
+    path: web::Path<String>,
+    req: web::Json<UpdateOrderStatusRequest>,
+) -> Result<HttpResponse> {
+    let order_id = path.into_inner();
+
+    let restaurant_id: Option<String> = match sqlx::query_scalar(
+        "SELECT t.restaurant_id FROM orders o JOIN tables t ON o.table_id = t.id WHERE o.id = ?",
+    )
+    .bind(&order_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(restaurant_id) => restaurant_id,
+        Err(e) => {
+            log::error!("Database error fetching order: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+    let Some(restaurant_id) = restaurant_id else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Order not found"
+        })));
+    };
+
+    // Check if user is a manager of this restaurant
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_ORDERS_STATUS)
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    if !ORDER_STATUSES.contains(&req.status.as_str()) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Status must be one of: {}", ORDER_STATUSES.join(", "))
+        })));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Failed to start transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let order =
+        sqlx::query("SELECT status, payment_status, business_date FROM orders WHERE id = ?")
+            .bind(&order_id)
+            .fetch_one(&mut *tx)
+            .await;
+    let (previous, payment_status, business_date): (String, String, Option<String>) = match order {
+        Ok(row) => (
+            row.try_get("status").unwrap_or_default(),
+            row.try_get("payment_status").unwrap_or_default(),
+            row.try_get("business_date").unwrap_or_default(),
+        ),
+        Err(e) => {
+            log::error!("Database error fetching order: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Setting the current status again changes nothing, so retries are safe
+    if previous != req.status {
+        // Checked inside the transaction so a closing cannot slip in between
+        if let Some(business_date) = &business_date {
+            match is_day_closed(&mut *tx, &restaurant_id, business_date).await {
+                Ok(false) => {}
+                Ok(true) => {
+                    let _ = tx.rollback().await;
+                    return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                        "error": format!("Business day {business_date} is closed; reopen it before changing its orders"),
+                        "code": "day_closed"
+                    })));
+                }
+                Err(e) => {
+                    log::error!("Database error checking day closing: {e}");
+                    let _ = tx.rollback().await;
+                    return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Internal server error"
+                    })));
+                }
+            }
+        }
+
+        let conflict = match previous.as_str() {
+            "delivered" | "cancelled" => Some(format!("Order is already {previous}")),
+            _ if payment_status == "awaiting_payment" && req.status != "cancelled" => {
+                Some("Order is awaiting payment".to_string())
+            }
+            // The guest's money has to go back through a refund first
+            _ if matches!(payment_status.as_str(), "paid" | "partially_refunded")
+                && req.status == "cancelled" =>
+            {
+                Some("Refund the payment before cancelling a paid order".to_string())
+            }
+            _ => None,
+        };
+        if let Some(message) = conflict {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": message
+            })));
+        }
+
+        // The payment is voided with the order, so the guest cannot pay for a
+        // cancelled order afterwards
+        if req.status == "cancelled" && payment_status == "awaiting_payment" {
+            let intent_ids = match cancel_order_payments(&mut tx, &order_id).await {
+                Ok(intent_ids) => intent_ids,
+                Err(e) => {
+                    log::error!("Database error cancelling order payments: {e}");
+                    let _ = tx.rollback().await;
+                    return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Internal server error"
+                    })));
+                }
+            };
+            if let Some(provider) = gateway.provider() {
+                for intent_id in &intent_ids {
+                    if let Err(e) = provider.cancel_intent(intent_id) {
+                        log::error!("Payment provider error voiding intent {intent_id}: {e}");
+                        let _ = tx.rollback().await;
+                        return Ok(HttpResponse::BadGateway().json(serde_json::json!({
+                            "error": "Failed to void the order's payment"
+                        })));
+                    }
+                }
+            }
+        }
+
+        let result = sqlx::query("UPDATE orders SET status = ? WHERE id = ?")
+            .bind(&req.status)
+            .bind(&order_id)
+            .execute(&mut *tx)
+            .await;
+        // A cancelled order will not be served, so its portions go back on sale
+        // and its coupon use is given back
+        let result = match result {
+            Ok(_) if req.status == "cancelled" => {
+                match return_order_stock(&mut tx, &order_id).await {
+                    Ok(_) => release_order_coupon(&mut tx, &order_id).await,
+                    Err(e) => Err(e),
+                }
+            }
+            result => result.map(|_| ()),
+        };
+        let result = match result {
+            Ok(_) => enqueue_order_status_changed(&mut tx, &order_id, &previous, &req.status).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            log::error!("Database error updating order status: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    }
+
+    match tx.commit().await {
+        Ok(_) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "order_id": order_id,
+            "previous_status": previous,
+            "status": req.status
+        }))),
+        Err(e) => {
+            log::error!("Database error committing order status: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{QueryBuilder, Row, Sqlite};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+// A column a list can be sorted by. `expr` is trusted SQL and is selected as
+// `sort_key` so the last row of a page can be turned into a cursor.
+pub struct SortField {
+    pub name: &'static str,
+    pub expr: &'static str,
+    pub numeric: bool,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+// Cursors are opaque to clients: base64 JSON of the sort and the last row's position
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    descending: bool,
+    key: SortKey,
+    id: String,
+}
+
+pub struct PageRequest {
+    pub limit: i64,
+    sort: &'static SortField,
+    descending: bool,
+    after: Option<(SortKey, String)>,
+}
+
+fn parse_direction(order: &str) -> Result<bool, String> {
+    match order {
+        "asc" => Ok(false),
+        "desc" => Ok(true),
+        _ => Err("Order must be 'asc' or 'desc'".to_string()),
+    }
+}
+
+fn decode_cursor(cursor: &str) -> Option<Cursor> {
+    let bytes = general_purpose::URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+impl PageRequest {
+    // The first field is the default sort. A cursor carries its own sort, so
+    // `sort` and `order` may be left out when fetching later pages.
+    pub fn parse(
+        fields: &'static [SortField],
+        cursor: Option<&str>,
+        limit: Option<i64>,
+        sort: Option<&str>,
+        order: Option<&str>,
+    ) -> Result<Self, String> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(format!("Limit must be between 1 and {MAX_PAGE_SIZE}"));
+        }
+
+        let requested = match sort {
+            Some(name) => match fields.iter().find(|field| field.name == name) {
+                Some(field) => Some(field),
+                None => {
+                    let names: Vec<&str> = fields.iter().map(|field| field.name).collect();
+                    return Err(format!("Sort must be one of: {}", names.join(", ")));
+                }
+            },
+            None => None,
+        };
+        let requested_direction = order.map(parse_direction).transpose()?;
+
+        let Some(cursor) = cursor else {
+            let sort = requested.unwrap_or(&fields[0]);
+            return Ok(PageRequest {
+                limit,
+                sort,
+                descending: requested_direction.unwrap_or(sort.descending),
+                after: None,
+            });
+        };
+
+        let cursor = decode_cursor(cursor).ok_or("Invalid cursor")?;
+        let sort = fields
+            .iter()
+            .find(|field| field.name == cursor.sort)
+            .ok_or("Invalid cursor")?;
+        let key_matches = matches!(
+            (&cursor.key, sort.numeric),
+            (SortKey::Number(_), true) | (SortKey::Text(_), false)
+        );
+        if !key_matches {
+            return Err("Invalid cursor".to_string());
+        }
+        if requested.is_some_and(|field| field.name != sort.name)
+            || requested_direction.is_some_and(|descending| descending != cursor.descending)
+        {
+            return Err("Cursor does not match the requested sort".to_string());
+        }
+
+        Ok(PageRequest {
+            limit,
+            sort,
+            descending: cursor.descending,
+            after: Some((cursor.key, cursor.id)),
+        })
+    }
+
+    pub fn sort_expr(&self) -> &'static str {
+        self.sort.expr
+    }
+
+    fn push_key(&self, query: &mut QueryBuilder<'_, Sqlite>, key: &SortKey) {
+        match key {
+            SortKey::Number(value) => query.push_bind(*value),
+            SortKey::Text(value) => query.push_bind(value.clone()),
+        };
+    }
+
+    // Appends `AND <after the cursor>`, so the query must already have a WHERE
+    pub fn push_after_cursor(&self, query: &mut QueryBuilder<'_, Sqlite>, id_expr: &str) {
+        let Some((key, id)) = &self.after else {
+            return;
+        };
+        let op = if self.descending { "<" } else { ">" };
+        let expr = self.sort.expr;
+
+        query.push(format!(" AND ({expr} {op} "));
+        self.push_key(query, key);
+        query.push(format!(" OR ({expr} = "));
+        self.push_key(query, key);
+        query.push(format!(" AND {id_expr} {op} "));
+        query.push_bind(id.clone());
+        query.push("))");
+    }
+
+    // Ties on the sort column are broken by id; one extra row is read to tell
+    // whether another page follows
+    pub fn push_order_and_limit(&self, query: &mut QueryBuilder<'_, Sqlite>, id_expr: &str) {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        query.push(format!(
+            " ORDER BY {} {direction}, {id_expr} {direction} LIMIT ",
+            self.sort.expr
+        ));
+        query.push_bind(self.limit + 1);
+    }
+
+    // Drops the look-ahead row and returns the cursor for the page after it
+    pub fn split(
+        &self,
+        mut rows: Vec<SqliteRow>,
+        id_column: &str,
+    ) -> Result<(Vec<SqliteRow>, Option<String>), sqlx::Error> {
+        if rows.len() as i64 <= self.limit {
+            return Ok((rows, None));
+        }
+        rows.truncate(self.limit as usize);
+
+        let last = rows.last().expect("limit is at least one");
+        let key = if self.sort.numeric {
+            SortKey::Number(last.try_get("sort_key")?)
+        } else {
+            SortKey::Text(last.try_get("sort_key")?)
+        };
+        let cursor = Cursor {
+            sort: self.sort.name.to_string(),
+            descending: self.descending,
+            key,
+            id: last.try_get(id_column)?,
+        };
+        let json = serde_json::to_vec(&cursor).unwrap_or_default();
+
+        Ok((rows, Some(general_purpose::URL_SAFE_NO_PAD.encode(json))))
+    }
+}
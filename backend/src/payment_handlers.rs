@@ -0,0 +1,651 @@
+use crate::closing_handlers::is_day_closed;
+use crate::coupon_handlers::release_order_coupon;
+use crate::inventory_handlers::return_order_stock;
+use crate::models::{
+    Claims, ConfirmPaymentRequest, ConfirmPaymentResponse, Payment, PaymentRow,
+    RefundPaymentRequest, RefundPaymentResponse,
+};
+use crate::payments::{PaymentError, PaymentGateway, PaymentIntentStatus, WebhookEvent};
+use crate::permissions::is_super_admin;
+use crate::webhooks::{enqueue_order_status_changed, enqueue_payment_status_changed};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+
+// Absorbs floating point drift when comparing refunded totals to the amount paid
+const REFUND_TOLERANCE: f64 = 0.000_001;
+
+// What became of a success report for an intent
+enum PaymentRecorded {
+    Recorded,
+    // The order's business day is closed, so nothing was written
+    DayClosed(String),
+}
+
+// Marks the intent as paid and releases the order to the kitchen. Safe to call
+// more than once, since the webhook and the confirm call may both report success.
+// A cancelled order is never marked paid.
+async fn mark_payment_succeeded(
+    pool: &Pool<Sqlite>,
+    intent_id: &str,
+) -> Result<PaymentRecorded, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let updated = sqlx::query(
+        "UPDATE payments SET status = 'succeeded', failure_reason = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE provider_intent_id = ? AND status = 'requires_confirmation'
+           AND order_id NOT IN (SELECT id FROM orders WHERE status = 'cancelled')",
+    )
+    .bind(intent_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Checked after the first write, which takes the database write lock, so a
+    // closing cannot slip in and concurrent reports do not deadlock on upgrade
+    if let Some(business_date) = closed_business_date(&mut tx, intent_id).await? {
+        tx.rollback().await?;
+        return Ok(PaymentRecorded::DayClosed(business_date));
+    }
+
+    if updated.rows_affected() > 0 {
+        let order = sqlx::query(
+            "SELECT o.id, o.status FROM orders o
+             JOIN payments p ON p.order_id = o.id
+             WHERE p.provider_intent_id = ? AND o.payment_status = 'awaiting_payment'",
+        )
+        .bind(intent_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(order) = order {
+            let order_id: String = order.try_get("id")?;
+            let status: String = order.try_get("status")?;
+            sqlx::query(
+                "UPDATE orders SET payment_status = 'paid',
+                        status = CASE WHEN status = 'pending' THEN 'confirmed' ELSE status END
+                 WHERE id = ?",
+            )
+            .bind(&order_id)
+            .execute(&mut *tx)
+            .await?;
+
+            enqueue_payment_status_changed(&mut tx, &order_id, "paid").await?;
+            if status == "pending" {
+                enqueue_order_status_changed(&mut tx, &order_id, &status, "confirmed").await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
+    Ok(PaymentRecorded::Recorded)
+}
+
+// The business day an intent's order belongs to, if that day has been closed.
+// A closed day's takings are frozen until a super admin reopens it.
+async fn closed_business_date(
+    conn: &mut SqliteConnection,
+    intent_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let order = sqlx::query(
+        "SELECT t.restaurant_id, o.business_date FROM payments p
+         JOIN orders o ON p.order_id = o.id
+         JOIN tables t ON o.table_id = t.id
+         WHERE p.provider_intent_id = ?",
+    )
+    .bind(intent_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(order) = order else {
+        return Ok(None);
+    };
+    let restaurant_id: String = order.try_get("restaurant_id")?;
+    let Some(business_date) = order.try_get::<Option<String>, _>("business_date")? else {
+        return Ok(None);
+    };
+    if is_day_closed(&mut *conn, &restaurant_id, &business_date).await? {
+        Ok(Some(business_date))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn record_payment_failure(
+    pool: &Pool<Sqlite>,
+    intent_id: &str,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE payments SET failure_reason = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE provider_intent_id = ? AND status = 'requires_confirmation'",
+    )
+    .bind(reason)
+    .bind(intent_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Cancels the order of an intent that can no longer be paid and gives back its
+// stock and coupon use. A declined card is not final, since the guest can retry with another
+// one; only a canceled intent is.
+async fn cancel_unpaid_order(pool: &Pool<Sqlite>, intent_id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let updated = sqlx::query(
+        "UPDATE payments SET status = 'canceled', updated_at = CURRENT_TIMESTAMP
+         WHERE provider_intent_id = ? AND status = 'requires_confirmation'",
+    )
+    .bind(intent_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if updated.rows_affected() > 0 {
+        let order = sqlx::query(
+            "SELECT o.id, o.status FROM orders o
+             JOIN payments p ON p.order_id = o.id
+             WHERE p.provider_intent_id = ? AND o.payment_status = 'awaiting_payment'
+               AND o.status != 'cancelled'",
+        )
+        .bind(intent_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(order) = order {
+            let order_id: String = order.try_get("id")?;
+            let status: String = order.try_get("status")?;
+            sqlx::query("UPDATE orders SET status = 'cancelled' WHERE id = ?")
+                .bind(&order_id)
+                .execute(&mut *tx)
+                .await?;
+
+            return_order_stock(&mut tx, &order_id).await?;
+            release_order_coupon(&mut tx, &order_id).await?;
+            enqueue_order_status_changed(&mut tx, &order_id, &status, "cancelled").await?;
+        }
+    }
+
+    tx.commit().await
+}
+
+// Marks an order's unpaid intents canceled and returns their ids, so the caller
+// can void them with the provider before committing
+pub async fn cancel_order_payments(
+    conn: &mut SqliteConnection,
+    order_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "UPDATE payments SET status = 'canceled', updated_at = CURRENT_TIMESTAMP
+         WHERE order_id = ? AND status = 'requires_confirmation'
+         RETURNING provider_intent_id",
+    )
+    .bind(order_id)
+    .fetch_all(conn)
+    .await
+}
+
+async fn payment_state_response(
+    pool: &Pool<Sqlite>,
+    order_id: &str,
+) -> Result<ConfirmPaymentResponse, sqlx::Error> {
+    let row = sqlx::query("SELECT status, payment_status FROM orders WHERE id = ?")
+        .bind(order_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(ConfirmPaymentResponse {
+        order_id: order_id.to_string(),
+        status: row.try_get("status")?,
+        payment_status: row.try_get("payment_status")?,
+    })
+}
+
+pub async fn confirm_order_payment(
+    pool: web::Data<Pool<Sqlite>>,
+    gateway: web::Data<PaymentGateway>,
+    path: web::Path<String>,
+    req: web::Json<ConfirmPaymentRequest>,
+) -> Result<HttpResponse> {
+    let order_id = path.into_inner();
+
+    let provider = match gateway.provider() {
+        Some(provider) => provider,
+        None => {
+            return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Online payments are not available"
+            })));
+        }
+    };
+
+    let payment_row = sqlx::query_as::<_, PaymentRow>(
+        "SELECT id, order_id, provider, provider_intent_id, amount, currency, status, refunded_amount, failure_reason, created_at
+         FROM payments WHERE order_id = ? ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(&order_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let payment = match payment_row {
+        Ok(Some(payment_row)) => Payment::from(payment_row),
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "No payment found for this order"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching payment: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if payment.status == "requires_confirmation" {
+        // Checked before charging the guest; mark_payment_succeeded checks
+        // again in the transaction that records the payment
+        let closed = match pool.acquire().await {
+            Ok(mut conn) => closed_business_date(&mut conn, &payment.provider_intent_id).await,
+            Err(e) => Err(e),
+        };
+        match closed {
+            Ok(None) => {}
+            Ok(Some(business_date)) => {
+                return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                    "error": format!("Business day {business_date} is closed; reopen it before taking payments"),
+                    "code": "day_closed"
+                })));
+            }
+            Err(e) => {
+                log::error!("Database error checking day closing: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        }
+
+        match provider.confirm_intent(&payment.provider_intent_id, &req.payment_method) {
+            Ok(PaymentIntentStatus::Succeeded) => {
+                match mark_payment_succeeded(pool.get_ref(), &payment.provider_intent_id).await {
+                    Ok(PaymentRecorded::Recorded) => {}
+                    // The day was closed while the card was being charged; the
+                    // provider's webhook records the payment once it reopens
+                    Ok(PaymentRecorded::DayClosed(business_date)) => {
+                        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                            "error": format!("Business day {business_date} is closed; reopen it before taking payments"),
+                            "code": "day_closed"
+                        })));
+                    }
+                    Err(e) => {
+                        log::error!("Database error recording payment: {e}");
+                        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                            "error": "Internal server error"
+                        })));
+                    }
+                }
+            }
+            Ok(status) => {
+                if status == PaymentIntentStatus::Canceled {
+                    if let Err(e) =
+                        cancel_unpaid_order(pool.get_ref(), &payment.provider_intent_id).await
+                    {
+                        log::error!("Database error cancelling unpaid order: {e}");
+                    }
+                }
+                return Ok(HttpResponse::PaymentRequired().json(serde_json::json!({
+                    "error": "Payment was not completed",
+                    "code": status.as_str()
+                })));
+            }
+            Err(PaymentError::Declined(reason)) => {
+                if let Err(e) =
+                    record_payment_failure(pool.get_ref(), &payment.provider_intent_id, &reason)
+                        .await
+                {
+                    log::error!("Database error recording payment failure: {e}");
+                }
+                return Ok(HttpResponse::PaymentRequired().json(serde_json::json!({
+                    "error": "Payment declined",
+                    "code": reason
+                })));
+            }
+            Err(PaymentError::InvalidRequest(msg)) => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": msg
+                })));
+            }
+            Err(e) => {
+                log::error!("Payment provider error confirming intent: {e}");
+                return Ok(HttpResponse::BadGateway().json(serde_json::json!({
+                    "error": "Failed to confirm payment"
+                })));
+            }
+        }
+    }
+
+    match payment_state_response(pool.get_ref(), &order_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching order: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn payment_webhook(
+    pool: web::Data<Pool<Sqlite>>,
+    gateway: web::Data<PaymentGateway>,
+    http_req: HttpRequest,
+    payload: web::Bytes,
+) -> Result<HttpResponse> {
+    let provider = match gateway.provider() {
+        Some(provider) => provider,
+        None => {
+            return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Online payments are not available"
+            })));
+        }
+    };
+
+    let signature = match http_req
+        .headers()
+        .get("X-Payment-Signature")
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(signature) => signature,
+        None => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Missing webhook signature"
+            })));
+        }
+    };
+
+    let event = match provider.verify_webhook(&payload, signature) {
+        Ok(event) => event,
+        Err(PaymentError::InvalidSignature) => {
+            log::warn!("Rejected payment webhook with invalid signature");
+            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid webhook signature"
+            })));
+        }
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    let result = match &event {
+        WebhookEvent::PaymentSucceeded { intent_id } => {
+            match mark_payment_succeeded(pool.get_ref(), intent_id).await {
+                // Refused rather than acknowledged, so the provider keeps
+                // retrying the delivery until the day is reopened
+                Ok(PaymentRecorded::DayClosed(business_date)) => {
+                    log::warn!("Payment webhook for closed business day {business_date} refused");
+                    return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                        "error": format!("Business day {business_date} is closed"),
+                        "code": "day_closed"
+                    })));
+                }
+                result => result.map(|_| ()),
+            }
+        }
+        WebhookEvent::PaymentFailed { intent_id, reason } => {
+            record_payment_failure(pool.get_ref(), intent_id, reason).await
+        }
+        WebhookEvent::PaymentCanceled { intent_id } => {
+            cancel_unpaid_order(pool.get_ref(), intent_id).await
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Ok().json(serde_json::json!({"received": true}))),
+        Err(e) => {
+            // A 5xx makes the provider retry the delivery later
+            log::error!("Database error handling payment webhook: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn refund_order_payment(
+    pool: web::Data<Pool<Sqlite>>,
+    gateway: web::Data<PaymentGateway>,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(String, String)>,
+    req: web::Json<RefundPaymentRequest>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, order_id) = path.into_inner();
+
+    // Only super admins can give money back
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admin can refund payments"
+        })));
+    }
+
+    let provider = match gateway.provider() {
+        Some(provider) => provider,
+        None => {
+            return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": "Online payments are not available"
+            })));
+        }
+    };
+
+    let payment_row = sqlx::query_as::<_, PaymentRow>(
+        "SELECT p.id, p.order_id, p.provider, p.provider_intent_id, p.amount, p.currency, p.status, p.refunded_amount, p.failure_reason, p.created_at
+         FROM payments p
+         JOIN orders o ON p.order_id = o.id
+         JOIN tables t ON o.table_id = t.id
+         WHERE p.order_id = ? AND t.restaurant_id = ? AND p.status = 'succeeded'
+         ORDER BY p.created_at DESC LIMIT 1",
+    )
+    .bind(&order_id)
+    .bind(&restaurant_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let payment = match payment_row {
+        Ok(Some(payment_row)) => Payment::from(payment_row),
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "No completed payment found for this order"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching payment: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let refundable = payment.amount - payment.refunded_amount;
+    let amount = req.amount.unwrap_or(refundable);
+    if amount <= 0.0 || amount > refundable + f64::EPSILON {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Refund amount must be between 0 and {refundable:.2}")
+        })));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Reserve the amount first. The check and the increment are one
+    // statement, so concurrent refunds cannot exceed what was paid.
+    let reserved: Result<Option<f64>, sqlx::Error> = sqlx::query_scalar(
+        "UPDATE payments SET refunded_amount = refunded_amount + ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ? AND refunded_amount + ? <= amount + ?
+         RETURNING refunded_amount",
+    )
+    .bind(amount)
+    .bind(&payment.id)
+    .bind(amount)
+    .bind(REFUND_TOLERANCE)
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let refunded_total = match reserved {
+        Ok(Some(refunded_total)) => refunded_total,
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": "Refund exceeds the amount still refundable"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error reserving refund: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // A closed day's takings are frozen until a super admin reopens it. Checked
+    // after the reservation, which takes the database write lock, so a closing
+    // cannot slip in between.
+    let business_date: Result<Option<String>, sqlx::Error> =
+        sqlx::query_scalar("SELECT business_date FROM orders WHERE id = ?")
+            .bind(&order_id)
+            .fetch_one(&mut *tx)
+            .await;
+    let closed = match business_date {
+        Ok(Some(business_date)) => is_day_closed(&mut *tx, &restaurant_id, &business_date)
+            .await
+            .map(|closed| closed.then_some(business_date)),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+    match closed {
+        Ok(None) => {}
+        Ok(Some(business_date)) => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": format!("Business day {business_date} is closed; reopen it before refunding"),
+                "code": "day_closed"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking day closing: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error reserving refund: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        })));
+    }
+
+    let refund = match provider.refund(&payment.provider_intent_id, amount) {
+        Ok(refund) => refund,
+        Err(e) => {
+            if let Err(db_error) = sqlx::query(
+                "UPDATE payments SET refunded_amount = refunded_amount - ?, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?",
+            )
+            .bind(amount)
+            .bind(&payment.id)
+            .execute(pool.get_ref())
+            .await
+            {
+                log::error!(
+                    "Database error releasing refund on payment {}: {db_error}",
+                    payment.id
+                );
+            }
+            return Ok(match e {
+                PaymentError::InvalidRequest(msg) => {
+                    HttpResponse::BadRequest().json(serde_json::json!({ "error": msg }))
+                }
+                e => {
+                    log::error!("Payment provider error refunding payment: {e}");
+                    HttpResponse::BadGateway().json(serde_json::json!({
+                        "error": "Failed to refund payment"
+                    }))
+                }
+            });
+        }
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Derived from the stored total so the last of several concurrent
+    // refunds cannot leave a stale status behind
+    let result: Result<String, sqlx::Error> = sqlx::query_scalar(
+        "UPDATE orders SET payment_status = (
+            SELECT CASE WHEN refunded_amount + ? >= amount THEN 'refunded' ELSE 'partially_refunded' END
+            FROM payments WHERE id = ?
+         )
+         WHERE id = ?
+         RETURNING payment_status",
+    )
+    .bind(REFUND_TOLERANCE)
+    .bind(&payment.id)
+    .bind(&order_id)
+    .fetch_one(&mut *tx)
+    .await;
+    let result = match result {
+        Ok(payment_status) => enqueue_payment_status_changed(&mut tx, &order_id, &payment_status)
+            .await
+            .map(|_| payment_status),
+        Err(e) => Err(e),
+    };
+
+    let payment_status = match result {
+        Ok(payment_status) => payment_status,
+        Err(e) => {
+            // The provider already refunded, so this needs manual reconciliation
+            log::error!(
+                "Database error recording refund {} for order {order_id}: {e}",
+                refund.id
+            );
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        log::error!(
+            "Database error recording refund {} for order {order_id}: {e}",
+            refund.id
+        );
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        })));
+    }
+
+    Ok(HttpResponse::Ok().json(RefundPaymentResponse {
+        order_id,
+        refund_id: refund.id,
+        amount: refund.amount,
+        refunded_total,
+        payment_status,
+    }))
+}
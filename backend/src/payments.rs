@@ -0,0 +1,289 @@
+use crate::{PaymentSettings, PAYMENT_WEBHOOK_SECRET_ENV};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Test payment methods understood by the fake gateway
+pub const FAKE_CARD_SUCCESS: &str = "pm_fake_visa";
+pub const FAKE_CARD_DECLINED: &str = "pm_fake_declined";
+pub const FAKE_CARD_INSUFFICIENT_FUNDS: &str = "pm_fake_insufficient_funds";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentIntentStatus {
+    RequiresConfirmation,
+    Succeeded,
+    Canceled,
+}
+
+impl PaymentIntentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentIntentStatus::RequiresConfirmation => "requires_confirmation",
+            PaymentIntentStatus::Succeeded => "succeeded",
+            PaymentIntentStatus::Canceled => "canceled",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PaymentIntent {
+    pub id: String,
+    pub client_secret: String,
+    pub amount: f64,
+    pub currency: String,
+    pub status: PaymentIntentStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct Refund {
+    pub id: String,
+    pub intent_id: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WebhookEvent {
+    #[serde(rename = "payment_intent.succeeded")]
+    PaymentSucceeded { intent_id: String },
+    #[serde(rename = "payment_intent.payment_failed")]
+    PaymentFailed { intent_id: String, reason: String },
+    // The provider gave up on the intent, so it can no longer be paid
+    #[serde(rename = "payment_intent.canceled")]
+    PaymentCanceled { intent_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaymentError {
+    Declined(String),
+    InvalidRequest(String),
+    InvalidSignature,
+    Provider(String),
+}
+
+impl fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentError::Declined(reason) => write!(f, "Payment declined: {reason}"),
+            PaymentError::InvalidRequest(msg) => write!(f, "Invalid payment request: {msg}"),
+            PaymentError::InvalidSignature => write!(f, "Invalid webhook signature"),
+            PaymentError::Provider(msg) => write!(f, "Payment provider error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+pub trait PaymentProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    // `reference` is our order id, kept by the provider for reconciliation
+    fn create_intent(
+        &self,
+        amount: f64,
+        currency: &str,
+        reference: &str,
+    ) -> Result<PaymentIntent, PaymentError>;
+
+    fn confirm_intent(
+        &self,
+        intent_id: &str,
+        payment_method: &str,
+    ) -> Result<PaymentIntentStatus, PaymentError>;
+
+    fn verify_webhook(&self, payload: &[u8], signature: &str)
+        -> Result<WebhookEvent, PaymentError>;
+
+    fn refund(&self, intent_id: &str, amount: f64) -> Result<Refund, PaymentError>;
+
+    // Voids an unpaid intent so it can no longer be confirmed
+    fn cancel_intent(&self, intent_id: &str) -> Result<(), PaymentError>;
+}
+
+// Fully local gateway for tests and the demo. It keeps no state of its own:
+// intents live in the payments table and outcomes depend on the test payment
+// method used when confirming.
+#[derive(Debug, Clone)]
+pub struct FakePaymentGateway {
+    webhook_secret: String,
+}
+
+impl FakePaymentGateway {
+    pub fn new(webhook_secret: impl Into<String>) -> Self {
+        Self {
+            webhook_secret: webhook_secret.into(),
+        }
+    }
+
+    pub fn sign_webhook(&self, payload: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.webhook_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+
+    pub fn webhook_payload(event: &WebhookEvent) -> Vec<u8> {
+        serde_json::to_vec(event).expect("webhook events always serialize")
+    }
+}
+
+impl PaymentProvider for FakePaymentGateway {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn create_intent(
+        &self,
+        amount: f64,
+        currency: &str,
+        reference: &str,
+    ) -> Result<PaymentIntent, PaymentError> {
+        if amount <= 0.0 {
+            return Err(PaymentError::InvalidRequest(
+                "Amount must be greater than 0".to_string(),
+            ));
+        }
+
+        let id = format!("fpi_{}", Uuid::new_v4().simple());
+        log::debug!("Fake gateway created intent {id} for {reference}");
+
+        Ok(PaymentIntent {
+            client_secret: format!("{id}_secret_{}", Uuid::new_v4().simple()),
+            id,
+            amount,
+            currency: currency.to_string(),
+            status: PaymentIntentStatus::RequiresConfirmation,
+        })
+    }
+
+    fn confirm_intent(
+        &self,
+        intent_id: &str,
+        payment_method: &str,
+    ) -> Result<PaymentIntentStatus, PaymentError> {
+        if !intent_id.starts_with("fpi_") {
+            return Err(PaymentError::InvalidRequest(
+                "Unknown payment intent".to_string(),
+            ));
+        }
+
+        match payment_method {
+            FAKE_CARD_SUCCESS => Ok(PaymentIntentStatus::Succeeded),
+            FAKE_CARD_DECLINED => Err(PaymentError::Declined("card_declined".to_string())),
+            FAKE_CARD_INSUFFICIENT_FUNDS => {
+                Err(PaymentError::Declined("insufficient_funds".to_string()))
+            }
+            _ => Err(PaymentError::InvalidRequest(
+                "Unsupported payment method".to_string(),
+            )),
+        }
+    }
+
+    fn verify_webhook(
+        &self,
+        payload: &[u8],
+        signature: &str,
+    ) -> Result<WebhookEvent, PaymentError> {
+        let expected = self.sign_webhook(payload);
+        let signature = signature.trim().to_ascii_lowercase();
+
+        // Compare without short-circuiting so timing does not leak the signature
+        let matches = expected.len() == signature.len()
+            && expected
+                .bytes()
+                .zip(signature.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+        if !matches {
+            return Err(PaymentError::InvalidSignature);
+        }
+
+        serde_json::from_slice(payload)
+            .map_err(|e| PaymentError::InvalidRequest(format!("Malformed webhook payload: {e}")))
+    }
+
+    fn refund(&self, intent_id: &str, amount: f64) -> Result<Refund, PaymentError> {
+        if amount <= 0.0 {
+            return Err(PaymentError::InvalidRequest(
+                "Refund amount must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(Refund {
+            id: format!("fre_{}", Uuid::new_v4().simple()),
+            intent_id: intent_id.to_string(),
+            amount,
+        })
+    }
+
+    fn cancel_intent(&self, intent_id: &str) -> Result<(), PaymentError> {
+        if !intent_id.starts_with("fpi_") {
+            return Err(PaymentError::InvalidRequest(
+                "Unknown payment intent".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// Shared app data; `provider` is None when online payments are switched off
+#[derive(Clone)]
+pub struct PaymentGateway {
+    provider: Option<Arc<dyn PaymentProvider>>,
+    currency: String,
+}
+
+impl PaymentGateway {
+    pub fn new(provider: Arc<dyn PaymentProvider>, currency: impl Into<String>) -> Self {
+        Self {
+            provider: Some(provider),
+            currency: currency.into(),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            provider: None,
+            currency: String::new(),
+        }
+    }
+
+    pub fn from_settings(settings: Option<&PaymentSettings>) -> Self {
+        match settings {
+            Some(settings) if settings.enabled => {
+                let Some(webhook_secret) = settings.webhook_secret.clone() else {
+                    log::error!(
+                        "{PAYMENT_WEBHOOK_SECRET_ENV} is not set, online payments disabled"
+                    );
+                    return Self::disabled();
+                };
+                match settings.provider.as_str() {
+                    "fake" => Self::new(
+                        Arc::new(FakePaymentGateway::new(webhook_secret)),
+                        settings.currency.clone(),
+                    ),
+                    other => {
+                        log::error!("Unknown payment provider '{other}', online payments disabled");
+                        Self::disabled()
+                    }
+                }
+            }
+            _ => Self::disabled(),
+        }
+    }
+
+    pub fn provider(&self) -> Option<&dyn PaymentProvider> {
+        self.provider.as_deref()
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+}
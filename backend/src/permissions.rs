@@ -0,0 +1,43 @@
+use sqlx::{Pool, Sqlite};
+
+// Role checks shared by the restaurant-scoped handlers. A failed lookup is
+// treated as "not allowed".
+
+pub async fn is_manager(pool: &Pool<Sqlite>, restaurant_id: &str, user_id: &str) -> bool {
+    let manager_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM restaurant_managers WHERE restaurant_id = ? AND user_id = ?",
+    )
+    .bind(restaurant_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(0);
+
+    manager_count > 0
+}
+
+pub async fn can_manage_menu(pool: &Pool<Sqlite>, restaurant_id: &str, user_id: &str) -> bool {
+    let permission_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM restaurant_managers WHERE restaurant_id = ? AND user_id = ? AND can_manage_menu = TRUE",
+    )
+    .bind(restaurant_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(0);
+
+    permission_count > 0
+}
+
+pub async fn is_super_admin(pool: &Pool<Sqlite>, restaurant_id: &str, user_id: &str) -> bool {
+    let admin_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM restaurant_managers WHERE restaurant_id = ? AND user_id = ? AND role = 'super_admin'",
+    )
+    .bind(restaurant_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap_or(0);
+
+    admin_count > 0
+}
@@ -0,0 +1,564 @@
+use crate::models::{
+    Claims, CreatePricingRuleRequest, PricingRule, PricingRuleWindow, UpdatePricingRuleRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use crate::schedule::{format_time, Schedule, TimeRange, WeeklyWindow};
+use crate::timezone::restaurant_timezone;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// The pricing rules of one restaurant with their schedules, ready to price
+// items at a given moment
+pub struct PricingRules {
+    tz: Tz,
+    rules: Vec<(PricingRule, Schedule)>,
+}
+
+impl PricingRules {
+    pub async fn load(pool: &Pool<Sqlite>, restaurant_id: &str) -> Result<Self, sqlx::Error> {
+        let tz = restaurant_timezone(pool, restaurant_id).await?;
+        let rules = load_pricing_rules(pool, restaurant_id)
+            .await?
+            .into_iter()
+            .map(|rule| {
+                let weekly = rule
+                    .windows
+                    .iter()
+                    .filter_map(|window| {
+                        TimeRange::parse(&window.start_time, &window.end_time).map(|range| {
+                            WeeklyWindow {
+                                day_of_week: window.day_of_week,
+                                range,
+                            }
+                        })
+                    })
+                    .collect();
+                (rule, Schedule::new(weekly))
+            })
+            .collect();
+
+        Ok(Self { tz, rules })
+    }
+
+    // The price of an item at `at` and the rule that set it. When several
+    // rules apply the most specific scope wins, then the lowest price.
+    pub fn apply(
+        &self,
+        item_id: &str,
+        section_id: &str,
+        base_price: f64,
+        at: DateTime<Utc>,
+    ) -> (f64, Option<&PricingRule>) {
+        let local = at.with_timezone(&self.tz).naive_local();
+
+        self.rules
+            .iter()
+            .filter(|(rule, schedule)| {
+                rule.active && applies_to(rule, item_id, section_id) && schedule.is_active_at(local)
+            })
+            .map(|(rule, _)| (adjusted_price(rule, base_price), rule))
+            .min_by(|(a_price, a_rule), (b_price, b_rule)| {
+                scope_rank(b_rule)
+                    .cmp(&scope_rank(a_rule))
+                    .then(a_price.total_cmp(b_price))
+            })
+            .map_or((base_price, None), |(price, rule)| (price, Some(rule)))
+    }
+}
+
+fn applies_to(rule: &PricingRule, item_id: &str, section_id: &str) -> bool {
+    match rule.scope.as_str() {
+        "restaurant" => true,
+        "section" => rule.target_id.as_deref() == Some(section_id),
+        "item" => rule.target_id.as_deref() == Some(item_id),
+        _ => false,
+    }
+}
+
+fn scope_rank(rule: &PricingRule) -> u8 {
+    match rule.scope.as_str() {
+        "item" => 2,
+        "section" => 1,
+        _ => 0,
+    }
+}
+
+// Adjusted prices are rounded to cents and never go below zero
+fn adjusted_price(rule: &PricingRule, base_price: f64) -> f64 {
+    let price = match rule.adjustment_type.as_str() {
+        "percentage" => base_price * (1.0 + rule.adjustment_value / 100.0),
+        _ => base_price + rule.adjustment_value,
+    };
+    (price.max(0.0) * 100.0).round() / 100.0
+}
+
+pub async fn load_pricing_rules(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<Vec<PricingRule>, sqlx::Error> {
+    let window_rows = sqlx::query(
+        "SELECT w.rule_id, w.day_of_week, w.start_time, w.end_time
+         FROM pricing_rule_windows w
+         JOIN pricing_rules r ON r.id = w.rule_id
+         WHERE r.restaurant_id = ?
+         ORDER BY w.day_of_week, w.start_time",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut windows: HashMap<String, Vec<PricingRuleWindow>> = HashMap::new();
+    for row in window_rows {
+        windows
+            .entry(row.try_get("rule_id")?)
+            .or_default()
+            .push(PricingRuleWindow {
+                day_of_week: row.try_get("day_of_week")?,
+                start_time: row.try_get("start_time")?,
+                end_time: row.try_get("end_time")?,
+            });
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, restaurant_id, name, scope, target_id, adjustment_type,
+                CAST(adjustment_value AS REAL) AS adjustment_value, active
+         FROM pricing_rules
+         WHERE restaurant_id = ?
+         ORDER BY created_at ASC, id",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let id: String = row.try_get("id")?;
+            Ok(PricingRule {
+                windows: windows.remove(&id).unwrap_or_default(),
+                id,
+                restaurant_id: row.try_get("restaurant_id")?,
+                name: row.try_get("name")?,
+                scope: row.try_get("scope")?,
+                target_id: row.try_get("target_id")?,
+                adjustment_type: row.try_get("adjustment_type")?,
+                adjustment_value: row.try_get("adjustment_value")?,
+                active: row.try_get("active")?,
+            })
+        })
+        .collect()
+}
+
+async fn find_pricing_rule(
+    pool: &Pool<Sqlite>,
+    rule_id: &str,
+) -> Result<Option<PricingRule>, sqlx::Error> {
+    let restaurant_id: Option<String> =
+        sqlx::query_scalar("SELECT restaurant_id FROM pricing_rules WHERE id = ?")
+            .bind(rule_id)
+            .fetch_optional(pool)
+            .await?;
+
+    match restaurant_id {
+        Some(restaurant_id) => Ok(load_pricing_rules(pool, &restaurant_id)
+            .await?
+            .into_iter()
+            .find(|rule| rule.id == rule_id)),
+        None => Ok(None),
+    }
+}
+
+fn validate_adjustment(adjustment_type: &str, adjustment_value: f64) -> Result<(), &'static str> {
+    if !adjustment_value.is_finite() {
+        return Err("Adjustment value must be a number");
+    }
+    match adjustment_type {
+        "percentage" if adjustment_value < -100.0 => {
+            Err("A percentage discount cannot exceed 100%")
+        }
+        "percentage" | "fixed" => Ok(()),
+        _ => Err("adjustment_type must be 'percentage' or 'fixed'"),
+    }
+}
+
+fn parse_windows(windows: &[PricingRuleWindow]) -> Result<Vec<(u32, TimeRange)>, &'static str> {
+    windows
+        .iter()
+        .map(|window| {
+            if window.day_of_week > 6 {
+                return Err("day_of_week must be between 0 (Monday) and 6 (Sunday)");
+            }
+            TimeRange::parse(&window.start_time, &window.end_time)
+                .map(|range| (window.day_of_week, range))
+                .ok_or("Times must use the HH:MM format")
+        })
+        .collect()
+}
+
+// Restaurant-wide rules have no target; section and item rules must point at
+// something in the same restaurant
+async fn validate_target(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    scope: &str,
+    target_id: Option<&str>,
+) -> Result<Result<(), &'static str>, sqlx::Error> {
+    let query = match (scope, target_id) {
+        ("restaurant", None) => return Ok(Ok(())),
+        ("restaurant", Some(_)) => {
+            return Ok(Err("Restaurant-wide rules cannot have a target"));
+        }
+        ("section" | "item", None) => return Ok(Err("target_id is required for this scope")),
+        ("section", Some(_)) => {
+            "SELECT COUNT(*) FROM menu_sections WHERE id = ? AND restaurant_id = ?"
+        }
+        ("item", Some(_)) => {
+            "SELECT COUNT(*) FROM menu_items mi
+             JOIN menu_sections ms ON mi.section_id = ms.id
+             WHERE mi.id = ? AND ms.restaurant_id = ?"
+        }
+        _ => return Ok(Err("scope must be 'restaurant', 'section' or 'item'")),
+    };
+
+    let count: i64 = sqlx::query_scalar(query)
+        .bind(target_id)
+        .bind(restaurant_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(if count > 0 {
+        Ok(())
+    } else {
+        Err("Pricing rule target not found")
+    })
+}
+
+async fn write_windows(
+    conn: &mut SqliteConnection,
+    rule_id: &str,
+    windows: &[(u32, TimeRange)],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM pricing_rule_windows WHERE rule_id = ?")
+        .bind(rule_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (day_of_week, range) in windows {
+        sqlx::query(
+            "INSERT INTO pricing_rule_windows (rule_id, day_of_week, start_time, end_time) VALUES (?, ?, ?, ?)",
+        )
+        .bind(rule_id)
+        .bind(day_of_week)
+        .bind(format_time(range.start))
+        .bind(format_time(range.end))
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn list_pricing_rules(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match load_pricing_rules(pool.get_ref(), &restaurant_id).await {
+        Ok(rules) => Ok(HttpResponse::Ok().json(rules)),
+        Err(e) => {
+            log::error!("Database error fetching pricing rules: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn create_pricing_rule(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreatePricingRuleRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Pricing rule name is required"
+        })));
+    }
+    if let Err(message) = validate_adjustment(&req.adjustment_type, req.adjustment_value) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": message
+        })));
+    }
+    let windows = match parse_windows(&req.windows) {
+        Ok(windows) => windows,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+    match validate_target(
+        pool.get_ref(),
+        &restaurant_id,
+        &req.scope,
+        req.target_id.as_deref(),
+    )
+    .await
+    {
+        Ok(Ok(())) => {}
+        Ok(Err(message)) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking pricing rule target: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create pricing rule"
+            })));
+        }
+    };
+
+    let rule_id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO pricing_rules (id, restaurant_id, name, scope, target_id, adjustment_type, adjustment_value, active)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&rule_id)
+    .bind(&restaurant_id)
+    .bind(name)
+    .bind(&req.scope)
+    .bind(&req.target_id)
+    .bind(&req.adjustment_type)
+    .bind(req.adjustment_value)
+    .bind(req.active.unwrap_or(true))
+    .execute(&mut *tx)
+    .await;
+
+    let result = match result {
+        Ok(_) => write_windows(&mut tx, &rule_id, &windows).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        log::error!("Database error creating pricing rule: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create pricing rule"
+        })));
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error creating pricing rule: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create pricing rule"
+        })));
+    }
+
+    match find_pricing_rule(pool.get_ref(), &rule_id).await {
+        Ok(Some(rule)) => Ok(HttpResponse::Created().json(rule)),
+        Ok(None) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create pricing rule"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching pricing rule: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn update_pricing_rule(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdatePricingRuleRequest>,
+) -> Result<HttpResponse> {
+    let rule_id = path.into_inner();
+
+    let mut rule = match find_pricing_rule(pool.get_ref(), &rule_id).await {
+        Ok(Some(rule)) => rule,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Pricing rule not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching pricing rule: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &rule.restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if let Some(name) = &req.name {
+        let name = name.trim();
+        if name.is_empty() {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Pricing rule name is required"
+            })));
+        }
+        rule.name = name.to_string();
+    }
+    if let Some(adjustment_type) = &req.adjustment_type {
+        rule.adjustment_type = adjustment_type.clone();
+    }
+    if let Some(adjustment_value) = req.adjustment_value {
+        rule.adjustment_value = adjustment_value;
+    }
+    if let Err(message) = validate_adjustment(&rule.adjustment_type, rule.adjustment_value) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": message
+        })));
+    }
+    if let Some(active) = req.active {
+        rule.active = active;
+    }
+    let windows = match req.windows.as_deref().map(parse_windows).transpose() {
+        Ok(windows) => windows,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update pricing rule"
+            })));
+        }
+    };
+
+    let result = sqlx::query(
+        "UPDATE pricing_rules SET name = ?, adjustment_type = ?, adjustment_value = ?, active = ? WHERE id = ?",
+    )
+    .bind(&rule.name)
+    .bind(&rule.adjustment_type)
+    .bind(rule.adjustment_value)
+    .bind(rule.active)
+    .bind(&rule_id)
+    .execute(&mut *tx)
+    .await;
+
+    let result = match (result, &windows) {
+        (Ok(_), Some(windows)) => write_windows(&mut tx, &rule_id, windows).await,
+        (Ok(_), None) => Ok(()),
+        (Err(e), _) => Err(e),
+    };
+    if let Err(e) = result {
+        log::error!("Database error updating pricing rule: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update pricing rule"
+        })));
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error updating pricing rule: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update pricing rule"
+        })));
+    }
+
+    match find_pricing_rule(pool.get_ref(), &rule_id).await {
+        Ok(Some(rule)) => Ok(HttpResponse::Ok().json(rule)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pricing rule not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching pricing rule: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn delete_pricing_rule(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let rule_id = path.into_inner();
+
+    let restaurant_id: Option<String> =
+        match sqlx::query_scalar("SELECT restaurant_id FROM pricing_rules WHERE id = ?")
+            .bind(&rule_id)
+            .fetch_optional(pool.get_ref())
+            .await
+        {
+            Ok(restaurant_id) => restaurant_id,
+            Err(e) => {
+                log::error!("Database error fetching pricing rule: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        };
+    let Some(restaurant_id) = restaurant_id else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Pricing rule not found"
+        })));
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    match sqlx::query("DELETE FROM pricing_rules WHERE id = ?")
+        .bind(&rule_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting pricing rule: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete pricing rule"
+            })))
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use crate::api_keys::{api_key_allows, ApiKeyAuth, SCOPE_ORDERS_READ};
+use crate::escpos::{
+    render_kitchen_ticket, render_receipt, PaperWidth, PrintLine, PrintOrder, PRINT_KINDS,
+};
+use crate::models::{
+    AckPrintJobRequest, ClaimedPrintJob, Claims, CreatePrintJobRequest, OrderItem, PrintJob,
+    PrintJobQuery, PrintQuery,
+};
+use crate::permissions::is_manager;
+use crate::qr_handlers::generate_order_url;
+use crate::timezone::parse_timezone;
+use actix_web::{web, HttpResponse, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const PRINT_JOB_STATUSES: [&str; 4] = ["queued", "printing", "printed", "failed"];
+
+// A failed job is queued again until it has been tried this many times
+pub const MAX_PRINT_ATTEMPTS: i32 = 3;
+
+// A claimed job that is not acknowledged in time is handed out again, so a
+// crashed print agent does not lose tickets
+pub const PRINT_CLAIM_TIMEOUT_SECONDS: i64 = 120;
+
+const PRINT_JOB_LIST_LIMIT: i64 = 200;
+
+const PRINT_JOB_SELECT: &str =
+    "SELECT j.id, j.restaurant_id, j.order_id, o.order_number, j.kind, j.station_id, j.width,
+        j.status, j.attempts, j.last_error, j.created_at, j.claimed_at, j.printed_at
+    FROM print_jobs j
+    JOIN orders o ON j.order_id = o.id";
+
+enum RenderError {
+    NotFound(&'static str),
+    BadRequest(&'static str),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for RenderError {
+    fn from(e: sqlx::Error) -> Self {
+        RenderError::Database(e)
+    }
+}
+
+impl RenderError {
+    fn response(self) -> HttpResponse {
+        match self {
+            RenderError::NotFound(error) => {
+                HttpResponse::NotFound().json(serde_json::json!({ "error": error }))
+            }
+            RenderError::BadRequest(error) => {
+                HttpResponse::BadRequest().json(serde_json::json!({ "error": error }))
+            }
+            RenderError::Database(e) => {
+                log::error!("Database error rendering print document: {e}");
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                }))
+            }
+        }
+    }
+}
+
+async fn load_print_order(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    order_id: &str,
+) -> Result<Option<PrintOrder>, sqlx::Error> {
+    let Some(row) = sqlx::query(
+        "SELECT o.id, o.order_number, o.items, CAST(o.total_amount AS REAL) AS total_amount,
+            CAST(o.discount_amount AS REAL) AS discount_amount, o.coupon_code, o.payment_status,
+            o.customer_name, o.created_at, t.name AS table_name, r.name AS restaurant_name,
+            r.address, r.timezone
+         FROM orders o
+         JOIN tables t ON o.table_id = t.id
+         JOIN restaurants r ON t.restaurant_id = r.id
+         WHERE o.id = ? AND r.id = ?",
+    )
+    .bind(order_id)
+    .bind(restaurant_id)
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let name_rows = sqlx::query(
+        "SELECT mi.id, mi.name FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+    let mut names: HashMap<String, String> = HashMap::new();
+    for name_row in name_rows {
+        names.insert(name_row.try_get("id")?, name_row.try_get("name")?);
+    }
+
+    let items: String = row.try_get("items")?;
+    let items: Vec<OrderItem> = serde_json::from_str(&items).unwrap_or_default();
+    let timezone: String = row.try_get("timezone")?;
+    let tz = parse_timezone(&timezone).unwrap_or(Tz::UTC);
+    let created_at: NaiveDateTime = row.try_get("created_at")?;
+    let placed_at = DateTime::<Utc>::from_naive_utc_and_offset(created_at, Utc)
+        .with_timezone(&tz)
+        .format("%Y-%m-%d %H:%M")
+        .to_string();
+
+    Ok(Some(PrintOrder {
+        order_id: row.try_get("id")?,
+        order_number: row.try_get("order_number")?,
+        restaurant_name: row.try_get("restaurant_name")?,
+        restaurant_address: row.try_get("address")?,
+        table_name: row.try_get("table_name")?,
+        customer_name: row.try_get("customer_name")?,
+        placed_at,
+        lines: items
+            .into_iter()
+            .map(|item| PrintLine {
+                name: names
+                    .get(&item.menu_item_id)
+                    .cloned()
+                    .unwrap_or_else(|| "Unknown Item".to_string()),
+                line_total: item.price * item.quantity as f64,
+                quantity: item.quantity,
+                variant_name: item.variant_name,
+                bundle_name: item.bundle_name,
+                notes: item.notes,
+            })
+            .collect(),
+        coupon_code: row.try_get("coupon_code")?,
+        discount_amount: row.try_get("discount_amount")?,
+        total_amount: row.try_get("total_amount")?,
+        payment_status: row.try_get("payment_status")?,
+        order_url: generate_order_url(order_id),
+    }))
+}
+
+// Renders a ticket or receipt for an order of this restaurant. A station
+// limits a kitchen ticket to the lines routed to it.
+async fn render_document(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    order_id: &str,
+    kind: &str,
+    width: PaperWidth,
+    station_id: Option<&str>,
+) -> Result<Vec<u8>, RenderError> {
+    if !PRINT_KINDS.contains(&kind) {
+        return Err(RenderError::BadRequest(
+            "Kind must be 'ticket' or 'receipt'",
+        ));
+    }
+    if station_id.is_some() && kind != "ticket" {
+        return Err(RenderError::BadRequest(
+            "Only kitchen tickets can be printed for a station",
+        ));
+    }
+
+    let mut order = load_print_order(pool, restaurant_id, order_id)
+        .await?
+        .ok_or(RenderError::NotFound("Order not found"))?;
+
+    if kind == "receipt" {
+        return Ok(render_receipt(&order, width));
+    }
+
+    let Some(station_id) = station_id else {
+        return Ok(render_kitchen_ticket(&order, None, width));
+    };
+    let station_name: String =
+        sqlx::query_scalar("SELECT name FROM kitchen_stations WHERE id = ? AND restaurant_id = ?")
+            .bind(station_id)
+            .bind(restaurant_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(RenderError::BadRequest("Kitchen station not found"))?;
+    let line_indexes: Vec<i64> = sqlx::query_scalar(
+        "SELECT line_index FROM order_station_lines WHERE order_id = ? AND station_id = ?",
+    )
+    .bind(order_id)
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+    if line_indexes.is_empty() {
+        return Err(RenderError::NotFound("Order has no lines for this station"));
+    }
+
+    order.lines = order
+        .lines
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| line_indexes.contains(&(*index as i64)))
+        .map(|(_, line)| line)
+        .collect();
+    Ok(render_kitchen_ticket(&order, Some(&station_name), width))
+}
+
+fn print_job_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<PrintJob, sqlx::Error> {
+    let created_at: NaiveDateTime = row.try_get("created_at")?;
+    let claimed_at: Option<NaiveDateTime> = row.try_get("claimed_at")?;
+    let printed_at: Option<NaiveDateTime> = row.try_get("printed_at")?;
+    Ok(PrintJob {
+        id: row.try_get("id")?,
+        restaurant_id: row.try_get("restaurant_id")?,
+        order_id: row.try_get("order_id")?,
+        order_number: row.try_get("order_number")?,
+        kind: row.try_get("kind")?,
+        station_id: row.try_get("station_id")?,
+        width: row.try_get("width")?,
+        status: row.try_get("status")?,
+        attempts: row.try_get("attempts")?,
+        last_error: row.try_get("last_error")?,
+        created_at: DateTime::from_naive_utc_and_offset(created_at, Utc),
+        claimed_at: claimed_at.map(|at| DateTime::from_naive_utc_and_offset(at, Utc)),
+        printed_at: printed_at.map(|at| DateTime::from_naive_utc_and_offset(at, Utc)),
+    })
+}
+
+async fn fetch_print_job(pool: &Pool<Sqlite>, job_id: &str) -> Result<PrintJob, sqlx::Error> {
+    let row = sqlx::query(&format!("{PRINT_JOB_SELECT} WHERE j.id = ?"))
+        .bind(job_id)
+        .fetch_one(pool)
+        .await?;
+    print_job_from_row(&row)
+}
+
+async fn download_document(
+    pool: &Pool<Sqlite>,
+    claims: &Claims,
+    api_key: Option<&ApiKeyAuth>,
+    (restaurant_id, order_id): (String, String),
+    query: &PrintQuery,
+    kind: &str,
+) -> Result<HttpResponse> {
+    if !is_manager(pool, &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key, &restaurant_id, SCOPE_ORDERS_READ)
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let width = match PaperWidth::parse(query.width) {
+        Ok(width) => width,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": e })));
+        }
+    };
+
+    match render_document(
+        pool,
+        &restaurant_id,
+        &order_id,
+        kind,
+        width,
+        query.station_id.as_deref(),
+    )
+    .await
+    {
+        Ok(bytes) => Ok(HttpResponse::Ok()
+            .content_type("application/octet-stream")
+            .insert_header((
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{kind}-{order_id}-{}mm.bin\"",
+                    width.mm()
+                ),
+            ))
+            .body(bytes)),
+        Err(e) => Ok(e.response()),
+    }
+}
+
+pub async fn download_ticket(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    query: web::Query<PrintQuery>,
+) -> Result<HttpResponse> {
+    download_document(
+        pool.get_ref(),
+        &claims,
+        api_key.as_deref(),
+        path.into_inner(),
+        &query,
+        "ticket",
+    )
+    .await
+}
+
+pub async fn download_receipt(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    query: web::Query<PrintQuery>,
+) -> Result<HttpResponse> {
+    download_document(
+        pool.get_ref(),
+        &claims,
+        api_key.as_deref(),
+        path.into_inner(),
+        &query,
+        "receipt",
+    )
+    .await
+}
+
+pub async fn create_print_job(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreatePrintJobRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let width = match PaperWidth::parse(req.width) {
+        Ok(width) => width,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": e })));
+        }
+    };
+
+    let payload = match render_document(
+        pool.get_ref(),
+        &restaurant_id,
+        &req.order_id,
+        &req.kind,
+        width,
+        req.station_id.as_deref(),
+    )
+    .await
+    {
+        Ok(payload) => payload,
+        Err(e) => return Ok(e.response()),
+    };
+
+    let job_id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO print_jobs (id, restaurant_id, order_id, kind, station_id, width, payload, created_by, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&job_id)
+    .bind(&restaurant_id)
+    .bind(&req.order_id)
+    .bind(&req.kind)
+    .bind(&req.station_id)
+    .bind(width.mm())
+    .bind(payload)
+    .bind(&claims.sub)
+    .bind(Utc::now().naive_utc())
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Database error queueing print job: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to queue print job"
+        })));
+    }
+
+    match fetch_print_job(pool.get_ref(), &job_id).await {
+        Ok(job) => Ok(HttpResponse::Created().json(job)),
+        Err(e) => {
+            log::error!("Database error fetching print job: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn list_print_jobs(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    query: web::Query<PrintJobQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_ORDERS_READ)
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    if let Some(status) = &query.status {
+        if !PRINT_JOB_STATUSES.contains(&status.as_str()) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Status must be one of: {}", PRINT_JOB_STATUSES.join(", "))
+            })));
+        }
+    }
+
+    let rows = sqlx::query(&format!(
+        "{PRINT_JOB_SELECT} WHERE j.restaurant_id = ? AND (? IS NULL OR j.status = ?)
+         ORDER BY j.created_at DESC, j.rowid DESC LIMIT ?"
+    ))
+    .bind(&restaurant_id)
+    .bind(&query.status)
+    .bind(&query.status)
+    .bind(PRINT_JOB_LIST_LIMIT)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rows.and_then(|rows| {
+        rows.iter()
+            .map(print_job_from_row)
+            .collect::<Result<Vec<_>, _>>()
+    }) {
+        Ok(jobs) => Ok(HttpResponse::Ok().json(jobs)),
+        Err(e) => {
+            log::error!("Database error listing print jobs: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Called by a restaurant's print agent, which may use an orders:read API key:
+// hands out the oldest queued job, or answers 204 when there is nothing to print
+pub async fn claim_print_job(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_ORDERS_READ)
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let now = Utc::now().naive_utc();
+    let stale_before = now - Duration::seconds(PRINT_CLAIM_TIMEOUT_SECONDS);
+    // A single statement, so two agents polling at once cannot claim the same job
+    let claimed = sqlx::query(
+        "UPDATE print_jobs SET status = 'printing', claimed_at = ?, attempts = attempts + 1
+         WHERE id = (
+            SELECT id FROM print_jobs
+            WHERE restaurant_id = ?
+              AND (status = 'queued' OR (status = 'printing' AND claimed_at < ?))
+            ORDER BY created_at, rowid LIMIT 1
+         )
+         RETURNING id, payload",
+    )
+    .bind(now)
+    .bind(&restaurant_id)
+    .bind(stale_before)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    let (job_id, payload): (String, Vec<u8>) = match claimed {
+        Ok(Some(row)) => match (row.try_get("id"), row.try_get("payload")) {
+            (Ok(id), Ok(payload)) => (id, payload),
+            (Err(e), _) | (_, Err(e)) => {
+                log::error!("Database error reading claimed print job: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        },
+        Ok(None) => return Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error claiming print job: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    match fetch_print_job(pool.get_ref(), &job_id).await {
+        Ok(job) => Ok(HttpResponse::Ok().json(ClaimedPrintJob {
+            job,
+            payload_base64: general_purpose::STANDARD.encode(payload),
+        })),
+        Err(e) => {
+            log::error!("Database error fetching print job: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Reports the outcome of a claimed job. Failures go back on the queue until
+// MAX_PRINT_ATTEMPTS is reached; acknowledging a printed job again is a no-op.
+pub async fn ack_print_job(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    req: web::Json<AckPrintJobRequest>,
+) -> Result<HttpResponse> {
+    let job_id = path.into_inner();
+
+    let job = match fetch_print_job(pool.get_ref(), &job_id).await {
+        Ok(job) => job,
+        Err(sqlx::Error::RowNotFound) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Print job not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching print job: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !is_manager(pool.get_ref(), &job.restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &job.restaurant_id, SCOPE_ORDERS_READ)
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    if job.status == "printed" && req.success {
+        return Ok(HttpResponse::Ok().json(job));
+    }
+    if job.status != "printing" {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Print job has not been claimed"
+        })));
+    }
+
+    let result = if req.success {
+        sqlx::query(
+            "UPDATE print_jobs SET status = 'printed', printed_at = ?, last_error = NULL
+             WHERE id = ? AND status = 'printing'",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(&job_id)
+        .execute(pool.get_ref())
+        .await
+    } else {
+        let status = if job.attempts >= MAX_PRINT_ATTEMPTS {
+            "failed"
+        } else {
+            "queued"
+        };
+        sqlx::query(
+            "UPDATE print_jobs SET status = ?, claimed_at = NULL, last_error = ?
+             WHERE id = ? AND status = 'printing'",
+        )
+        .bind(status)
+        .bind(req.error.as_deref().unwrap_or("Printer error"))
+        .bind(&job_id)
+        .execute(pool.get_ref())
+        .await
+    };
+
+    if let Err(e) = result {
+        log::error!("Database error acknowledging print job: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to acknowledge print job"
+        })));
+    }
+
+    match fetch_print_job(pool.get_ref(), &job_id).await {
+        Ok(job) => Ok(HttpResponse::Ok().json(job)),
+        Err(e) => {
+            log::error!("Database error fetching print job: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
@@ -42,6 +42,11 @@ fn generate_qr_url(restaurant_code: &str, table_code: &str) -> String {
     format!("{}/m/{}-{}", get_base_url(), restaurant_code, table_code)
 }
 
+// The menu app's order status page, printed as a QR code on receipts
+pub fn generate_order_url(order_id: &str) -> String {
+    format!("{}/order/{}", get_base_url(), order_id)
+}
+
 // Helper function to generate QR code as PNG base64
 fn generate_qr_code_png(url: &str) -> Result<String, Box<dyn std::error::Error>> {
     let code = QrCode::new(url)?;
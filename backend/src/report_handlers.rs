@@ -0,0 +1,376 @@
+use crate::api_keys::{api_key_allows, ApiKeyAuth, SCOPE_ORDERS_READ};
+use crate::models::{Claims, OrderExportQuery, OrderItem, SalesReportQuery};
+use crate::permissions::is_manager;
+use crate::reports::{
+    build_sales_report, order_export_csv_header, order_export_csv_line, order_export_lines,
+    sales_report_csv, ExportOrder, ReportMenuItem, ReportOrder, MAX_REPORT_DAYS, REPORT_BREAKDOWNS,
+};
+use crate::timezone::{business_date, day_bounds_utc, restaurant_timezone};
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use futures_util::stream::{self, StreamExt};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+
+// Orders are read and written out this many at a time while exporting
+const EXPORT_BATCH_SIZE: i64 = 200;
+
+fn parse_report_date(value: Option<&str>, today: NaiveDate) -> Result<NaiveDate, ()> {
+    match value {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| ()),
+        None => Ok(today),
+    }
+}
+
+async fn load_report_orders(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<ReportOrder>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT o.table_id, t.name AS table_name, o.items,
+                CAST(o.total_amount AS REAL) AS total_amount,
+                CAST(o.discount_amount AS REAL) AS discount_amount,
+                (SELECT CAST(COALESCE(SUM(p.refunded_amount), 0) AS REAL) FROM payments p
+                 WHERE p.order_id = o.id) AS refunded_amount,
+                o.status, o.created_at
+         FROM orders o
+         JOIN tables t ON o.table_id = t.id
+         WHERE t.restaurant_id = ? AND o.created_at >= ? AND o.created_at < ?
+           AND o.payment_status != 'awaiting_payment'",
+    )
+    .bind(restaurant_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    let mut orders = Vec::with_capacity(rows.len());
+    for row in rows {
+        let items: String = row.try_get("items")?;
+        let created_at: NaiveDateTime = row.try_get("created_at")?;
+        orders.push(ReportOrder {
+            table_id: row.try_get("table_id")?,
+            table_name: row.try_get("table_name")?,
+            items: serde_json::from_str::<Vec<OrderItem>>(&items).unwrap_or_default(),
+            total_amount: row.try_get("total_amount")?,
+            discount_amount: row.try_get("discount_amount")?,
+            refunded_amount: row.try_get("refunded_amount")?,
+            status: row.try_get("status")?,
+            created_at: DateTime::from_naive_utc_and_offset(created_at, Utc),
+        });
+    }
+
+    Ok(orders)
+}
+
+async fn load_report_menu_items(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<HashMap<String, ReportMenuItem>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT mi.id, mi.name, ms.id AS section_id, ms.name AS section_name
+         FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            Ok((
+                row.try_get("id")?,
+                ReportMenuItem {
+                    name: row.try_get("name")?,
+                    section_id: row.try_get("section_id")?,
+                    section_name: row.try_get("section_name")?,
+                },
+            ))
+        })
+        .collect()
+}
+
+pub async fn sales_report(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<SalesReportQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let format = query.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "csv" {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Format must be 'json' or 'csv'"
+        })));
+    }
+    let breakdown = query.breakdown.as_deref().unwrap_or("summary");
+    if !REPORT_BREAKDOWNS.contains(&breakdown) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Breakdown must be one of: {}", REPORT_BREAKDOWNS.join(", "))
+        })));
+    }
+
+    let tz = match restaurant_timezone(pool.get_ref(), &restaurant_id).await {
+        Ok(tz) => tz,
+        Err(e) => {
+            log::error!("Database error fetching restaurant timezone: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Dates are local business days and both ends are included
+    let today = business_date(tz, Utc::now());
+    let (from, to) = match (
+        parse_report_date(query.from.as_deref(), today),
+        parse_report_date(query.to.as_deref(), today),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Date must use the YYYY-MM-DD format"
+            })));
+        }
+    };
+    if to < from {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'to' must not be before 'from'"
+        })));
+    }
+    if (to - from).num_days() >= MAX_REPORT_DAYS {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Reports cover at most {MAX_REPORT_DAYS} days")
+        })));
+    }
+    let (start, _) = day_bounds_utc(tz, from);
+    let (_, end) = day_bounds_utc(tz, to);
+
+    let orders = match load_report_orders(pool.get_ref(), &restaurant_id, start, end).await {
+        Ok(orders) => orders,
+        Err(e) => {
+            log::error!("Database error loading orders for report: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+    let menu_items = match load_report_menu_items(pool.get_ref(), &restaurant_id).await {
+        Ok(menu_items) => menu_items,
+        Err(e) => {
+            log::error!("Database error loading menu items for report: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let report = build_sales_report(&restaurant_id, from, to, tz, &orders, &menu_items);
+
+    if format == "csv" {
+        // The breakdown was checked above, so there is always a table to write
+        let csv = sales_report_csv(&report, breakdown).unwrap_or_default();
+        Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"sales-{breakdown}-{from}-{to}.csv\""),
+            ))
+            .body(csv))
+    } else {
+        Ok(HttpResponse::Ok().json(report))
+    }
+}
+
+// Keyset position of an export; only orders after (created_at, id) are read next
+struct ExportCursor {
+    pool: Pool<Sqlite>,
+    restaurant_id: String,
+    menu_items: HashMap<String, ReportMenuItem>,
+    tz: Tz,
+    csv: bool,
+    end: NaiveDateTime,
+    after_created_at: NaiveDateTime,
+    after_id: String,
+}
+
+async fn load_export_batch(cursor: &ExportCursor) -> Result<Vec<ExportOrder>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT o.id, o.order_number, t.name AS table_name, o.items,
+                CAST(o.discount_amount AS REAL) AS discount_amount, o.coupon_code,
+                o.status, o.payment_status, o.created_at
+         FROM orders o
+         JOIN tables t ON o.table_id = t.id
+         WHERE t.restaurant_id = ? AND o.created_at < ?
+           AND (o.created_at > ? OR (o.created_at = ? AND o.id > ?))
+         ORDER BY o.created_at, o.id
+         LIMIT ?",
+    )
+    .bind(&cursor.restaurant_id)
+    .bind(cursor.end)
+    .bind(cursor.after_created_at)
+    .bind(cursor.after_created_at)
+    .bind(&cursor.after_id)
+    .bind(EXPORT_BATCH_SIZE)
+    .fetch_all(&cursor.pool)
+    .await?;
+
+    let mut orders = Vec::with_capacity(rows.len());
+    for row in rows {
+        let items: String = row.try_get("items")?;
+        let created_at: NaiveDateTime = row.try_get("created_at")?;
+        orders.push(ExportOrder {
+            id: row.try_get("id")?,
+            order_number: row.try_get("order_number")?,
+            table_name: row.try_get("table_name")?,
+            items: serde_json::from_str::<Vec<OrderItem>>(&items).unwrap_or_default(),
+            discount_amount: row.try_get("discount_amount")?,
+            coupon_code: row.try_get("coupon_code")?,
+            status: row.try_get("status")?,
+            payment_status: row.try_get("payment_status")?,
+            created_at: DateTime::from_naive_utc_and_offset(created_at, Utc),
+        });
+    }
+
+    Ok(orders)
+}
+
+// Reads the next batch and renders it; None once the range is exhausted
+async fn next_export_chunk(
+    mut cursor: ExportCursor,
+) -> Option<(Result<web::Bytes, actix_web::Error>, Option<ExportCursor>)> {
+    let orders = match load_export_batch(&cursor).await {
+        Ok(orders) => orders,
+        Err(e) => {
+            log::error!("Database error exporting orders: {e}");
+            let error = actix_web::error::ErrorInternalServerError("Order export failed");
+            return Some((Err(error), None));
+        }
+    };
+    let last = orders.last()?;
+    cursor.after_created_at = last.created_at.naive_utc();
+    cursor.after_id = last.id.clone();
+
+    let mut chunk = String::new();
+    for order in &orders {
+        for line in order_export_lines(order, &cursor.menu_items, cursor.tz) {
+            if cursor.csv {
+                chunk.push_str(&order_export_csv_line(&line));
+            } else {
+                chunk.push_str(&serde_json::to_string(&line).unwrap_or_default());
+                chunk.push('\n');
+            }
+        }
+    }
+
+    let more = orders.len() as i64 == EXPORT_BATCH_SIZE;
+    Some((Ok(web::Bytes::from(chunk)), more.then_some(cursor)))
+}
+
+pub async fn export_orders(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    api_key: Option<web::ReqData<ApiKeyAuth>>,
+    query: web::Query<OrderExportQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await
+        && !api_key_allows(api_key.as_deref(), &restaurant_id, SCOPE_ORDERS_READ)
+    {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let format = query.format.as_deref().unwrap_or("csv");
+    if format != "csv" && format != "jsonl" {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Format must be 'csv' or 'jsonl'"
+        })));
+    }
+
+    let tz = match restaurant_timezone(pool.get_ref(), &restaurant_id).await {
+        Ok(tz) => tz,
+        Err(e) => {
+            log::error!("Database error fetching restaurant timezone: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    // Same local business days as the sales report, but any length of range
+    // since rows are streamed in batches
+    let today = business_date(tz, Utc::now());
+    let (from, to) = match (
+        parse_report_date(query.from.as_deref(), today),
+        parse_report_date(query.to.as_deref(), today),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Date must use the YYYY-MM-DD format"
+            })));
+        }
+    };
+    if to < from {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'to' must not be before 'from'"
+        })));
+    }
+    let (start, _) = day_bounds_utc(tz, from);
+    let (_, end) = day_bounds_utc(tz, to);
+
+    let menu_items = match load_report_menu_items(pool.get_ref(), &restaurant_id).await {
+        Ok(menu_items) => menu_items,
+        Err(e) => {
+            log::error!("Database error loading menu items for export: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let csv = format == "csv";
+    let cursor = ExportCursor {
+        pool: pool.get_ref().clone(),
+        restaurant_id,
+        menu_items,
+        tz,
+        csv,
+        end,
+        after_created_at: start,
+        // Every id sorts after the empty string, so orders at `start` are kept
+        after_id: String::new(),
+    };
+    let header = csv.then(|| Ok(web::Bytes::from(order_export_csv_header())));
+    let body = stream::iter(header).chain(stream::unfold(Some(cursor), |cursor| async move {
+        next_export_chunk(cursor?).await
+    }));
+
+    let (content_type, extension) = if csv {
+        ("text/csv; charset=utf-8", "csv")
+    } else {
+        ("application/x-ndjson", "jsonl")
+    };
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"orders-{from}-{to}.{extension}\""),
+        ))
+        .streaming(body))
+}
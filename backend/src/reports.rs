@@ -0,0 +1,403 @@
+use crate::menu_import::csv_line;
+use crate::models::{
+    HourlySales, ItemSales, OrderExportLine, OrderItem, SalesReport, SectionSales, TableSales,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+pub const REPORT_BREAKDOWNS: [&str; 5] = ["summary", "items", "sections", "tables", "hours"];
+
+// Ranges longer than a year are refused rather than aggregated in memory
+pub const MAX_REPORT_DAYS: i64 = 366;
+
+// An order as stored, with its lines decoded
+pub struct ReportOrder {
+    pub table_id: String,
+    pub table_name: String,
+    pub items: Vec<OrderItem>,
+    pub total_amount: f64,
+    pub discount_amount: f64,
+    pub refunded_amount: f64,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// An order as exported for accounting
+pub struct ExportOrder {
+    pub id: String,
+    pub order_number: Option<i32>,
+    pub table_name: String,
+    pub items: Vec<OrderItem>,
+    pub discount_amount: f64,
+    pub coupon_code: Option<String>,
+    pub status: String,
+    pub payment_status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub const ORDER_EXPORT_COLUMNS: [&str; 14] = [
+    "order_id",
+    "order_number",
+    "created_at",
+    "business_date",
+    "table_name",
+    "menu_item_id",
+    "item_name",
+    "variant",
+    "quantity",
+    "unit_price",
+    "line_total",
+    "tax",
+    "status",
+    "payment_status",
+];
+
+// Where a menu item currently sits; orders only keep the item id
+pub struct ReportMenuItem {
+    pub name: String,
+    pub section_id: String,
+    pub section_name: String,
+}
+
+fn round_money(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+// Item and section revenue is what the lines were charged before any coupon
+// or refund; table and hour revenue is what guests paid and kept paid.
+// Cancelled orders only count towards the cancellation rate, and orders still
+// awaiting online payment are left out by the caller.
+pub fn build_sales_report(
+    restaurant_id: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    tz: Tz,
+    orders: &[ReportOrder],
+    menu_items: &HashMap<String, ReportMenuItem>,
+) -> SalesReport {
+    let mut items: HashMap<String, ItemSales> = HashMap::new();
+    let mut sections: HashMap<Option<String>, SectionSales> = HashMap::new();
+    let mut tables: HashMap<String, TableSales> = HashMap::new();
+    let mut hours: HashMap<(u32, u32), HourlySales> = HashMap::new();
+    let mut order_count = 0;
+    let mut cancelled_count = 0;
+    let mut gross_sales = 0.0;
+    let mut discounts = 0.0;
+    let mut refunds = 0.0;
+    let mut net_sales = 0.0;
+
+    for order in orders {
+        if order.status == "cancelled" {
+            cancelled_count += 1;
+            continue;
+        }
+        order_count += 1;
+        let kept = order.total_amount - order.refunded_amount;
+        discounts += order.discount_amount;
+        refunds += order.refunded_amount;
+        net_sales += kept;
+
+        for line in &order.items {
+            let revenue = line.price * line.quantity as f64;
+            gross_sales += revenue;
+
+            let menu_item = menu_items.get(&line.menu_item_id);
+            let item = items
+                .entry(line.menu_item_id.clone())
+                .or_insert_with(|| ItemSales {
+                    menu_item_id: line.menu_item_id.clone(),
+                    name: menu_item.map_or("Unknown Item".to_string(), |item| item.name.clone()),
+                    section_name: menu_item.map_or("Unknown Section".to_string(), |item| {
+                        item.section_name.clone()
+                    }),
+                    quantity: 0,
+                    revenue: 0.0,
+                });
+            item.quantity += line.quantity as i64;
+            item.revenue += revenue;
+
+            let section_id = menu_item.map(|item| item.section_id.clone());
+            let section = sections
+                .entry(section_id.clone())
+                .or_insert_with(|| SectionSales {
+                    section_id,
+                    name: menu_item.map_or("Unknown Section".to_string(), |item| {
+                        item.section_name.clone()
+                    }),
+                    quantity: 0,
+                    revenue: 0.0,
+                });
+            section.quantity += line.quantity as i64;
+            section.revenue += revenue;
+        }
+
+        let table = tables
+            .entry(order.table_id.clone())
+            .or_insert_with(|| TableSales {
+                table_id: order.table_id.clone(),
+                name: order.table_name.clone(),
+                order_count: 0,
+                revenue: 0.0,
+            });
+        table.order_count += 1;
+        table.revenue += kept;
+
+        let local = order.created_at.with_timezone(&tz);
+        let day_of_week = local.weekday().num_days_from_monday();
+        let hour = local.hour();
+        let cell = hours
+            .entry((day_of_week, hour))
+            .or_insert_with(|| HourlySales {
+                day_of_week,
+                hour,
+                order_count: 0,
+                revenue: 0.0,
+            });
+        cell.order_count += 1;
+        cell.revenue += kept;
+    }
+
+    let mut items: Vec<ItemSales> = items.into_values().collect();
+    for item in &mut items {
+        item.revenue = round_money(item.revenue);
+    }
+    items.sort_by(|a, b| {
+        b.revenue
+            .total_cmp(&a.revenue)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut sections: Vec<SectionSales> = sections.into_values().collect();
+    for section in &mut sections {
+        section.revenue = round_money(section.revenue);
+    }
+    sections.sort_by(|a, b| {
+        b.revenue
+            .total_cmp(&a.revenue)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut tables: Vec<TableSales> = tables.into_values().collect();
+    for table in &mut tables {
+        table.revenue = round_money(table.revenue);
+    }
+    tables.sort_by(|a, b| {
+        b.revenue
+            .total_cmp(&a.revenue)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut hours: Vec<HourlySales> = hours.into_values().collect();
+    for cell in &mut hours {
+        cell.revenue = round_money(cell.revenue);
+    }
+    hours.sort_by_key(|cell| (cell.day_of_week, cell.hour));
+
+    let all_orders = order_count + cancelled_count;
+    SalesReport {
+        restaurant_id: restaurant_id.to_string(),
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        timezone: tz.name().to_string(),
+        order_count,
+        cancelled_count,
+        cancellation_rate: if all_orders > 0 {
+            (cancelled_count as f64 / all_orders as f64 * 10000.0).round() / 10000.0
+        } else {
+            0.0
+        },
+        gross_sales: round_money(gross_sales),
+        discounts: round_money(discounts),
+        refunds: round_money(refunds),
+        net_sales: round_money(net_sales),
+        average_order_value: if order_count > 0 {
+            round_money(net_sales / order_count as f64)
+        } else {
+            0.0
+        },
+        items,
+        sections,
+        tables,
+        hours,
+    }
+}
+
+fn header(columns: &[&str]) -> String {
+    let columns: Vec<String> = columns.iter().map(|column| column.to_string()).collect();
+    csv_line(&columns)
+}
+
+// CSV holds one table per file, so the caller picks which breakdown to export
+pub fn sales_report_csv(report: &SalesReport, breakdown: &str) -> Option<String> {
+    let csv = match breakdown {
+        "summary" => {
+            let mut csv = header(&[
+                "from",
+                "to",
+                "timezone",
+                "order_count",
+                "cancelled_count",
+                "cancellation_rate",
+                "gross_sales",
+                "discounts",
+                "refunds",
+                "net_sales",
+                "average_order_value",
+            ]);
+            csv.push_str(&csv_line(&[
+                report.from.clone(),
+                report.to.clone(),
+                report.timezone.clone(),
+                report.order_count.to_string(),
+                report.cancelled_count.to_string(),
+                format!("{:.4}", report.cancellation_rate),
+                format!("{:.2}", report.gross_sales),
+                format!("{:.2}", report.discounts),
+                format!("{:.2}", report.refunds),
+                format!("{:.2}", report.net_sales),
+                format!("{:.2}", report.average_order_value),
+            ]));
+            csv
+        }
+        "items" => {
+            let mut csv = header(&["menu_item_id", "name", "section", "quantity", "revenue"]);
+            for item in &report.items {
+                csv.push_str(&csv_line(&[
+                    item.menu_item_id.clone(),
+                    item.name.clone(),
+                    item.section_name.clone(),
+                    item.quantity.to_string(),
+                    format!("{:.2}", item.revenue),
+                ]));
+            }
+            csv
+        }
+        "sections" => {
+            let mut csv = header(&["section_id", "name", "quantity", "revenue"]);
+            for section in &report.sections {
+                csv.push_str(&csv_line(&[
+                    section.section_id.clone().unwrap_or_default(),
+                    section.name.clone(),
+                    section.quantity.to_string(),
+                    format!("{:.2}", section.revenue),
+                ]));
+            }
+            csv
+        }
+        "tables" => {
+            let mut csv = header(&["table_id", "name", "order_count", "revenue"]);
+            for table in &report.tables {
+                csv.push_str(&csv_line(&[
+                    table.table_id.clone(),
+                    table.name.clone(),
+                    table.order_count.to_string(),
+                    format!("{:.2}", table.revenue),
+                ]));
+            }
+            csv
+        }
+        "hours" => {
+            let mut csv = header(&["day_of_week", "hour", "order_count", "revenue"]);
+            for cell in &report.hours {
+                csv.push_str(&csv_line(&[
+                    cell.day_of_week.to_string(),
+                    cell.hour.to_string(),
+                    cell.order_count.to_string(),
+                    format!("{:.2}", cell.revenue),
+                ]));
+            }
+            csv
+        }
+        _ => return None,
+    };
+    Some(csv)
+}
+
+// Orders carry no tax lines yet, so tax is exported as zero. Coupon discounts
+// apply to the whole order, so they follow its lines as one negative line
+// without a menu item; an order's line totals then add up to what was charged.
+pub fn order_export_lines(
+    order: &ExportOrder,
+    menu_items: &HashMap<String, ReportMenuItem>,
+    tz: Tz,
+) -> Vec<OrderExportLine> {
+    let created_at = order.created_at.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let business_date = order
+        .created_at
+        .with_timezone(&tz)
+        .date_naive()
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut lines: Vec<OrderExportLine> = order
+        .items
+        .iter()
+        .map(|line| OrderExportLine {
+            order_id: order.id.clone(),
+            order_number: order.order_number,
+            created_at: created_at.clone(),
+            business_date: business_date.clone(),
+            table_name: order.table_name.clone(),
+            menu_item_id: line.menu_item_id.clone(),
+            item_name: menu_items
+                .get(&line.menu_item_id)
+                .map_or("Unknown Item".to_string(), |item| item.name.clone()),
+            variant_name: line.variant_name.clone(),
+            quantity: line.quantity,
+            unit_price: line.price,
+            line_total: round_money(line.price * line.quantity as f64),
+            tax: 0.0,
+            status: order.status.clone(),
+            payment_status: order.payment_status.clone(),
+        })
+        .collect();
+
+    if order.discount_amount > 0.0 {
+        let discount = round_money(order.discount_amount);
+        lines.push(OrderExportLine {
+            order_id: order.id.clone(),
+            order_number: order.order_number,
+            created_at,
+            business_date,
+            table_name: order.table_name.clone(),
+            menu_item_id: String::new(),
+            item_name: match &order.coupon_code {
+                Some(code) => format!("Discount ({code})"),
+                None => "Discount".to_string(),
+            },
+            variant_name: None,
+            quantity: 1,
+            unit_price: -discount,
+            line_total: -discount,
+            tax: 0.0,
+            status: order.status.clone(),
+            payment_status: order.payment_status.clone(),
+        });
+    }
+
+    lines
+}
+
+pub fn order_export_csv_header() -> String {
+    header(&ORDER_EXPORT_COLUMNS)
+}
+
+pub fn order_export_csv_line(line: &OrderExportLine) -> String {
+    csv_line(&[
+        line.order_id.clone(),
+        line.order_number.map(|n| n.to_string()).unwrap_or_default(),
+        line.created_at.clone(),
+        line.business_date.clone(),
+        line.table_name.clone(),
+        line.menu_item_id.clone(),
+        line.item_name.clone(),
+        line.variant_name.clone().unwrap_or_default(),
+        line.quantity.to_string(),
+        format!("{:.2}", line.unit_price),
+        format!("{:.2}", line.line_total),
+        format!("{:.2}", line.tax),
+        line.status.clone(),
+        line.payment_status.clone(),
+    ])
+}
@@ -0,0 +1,127 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::HashMap;
+
+// Days are numbered 0 = Monday .. 6 = Sunday everywhere schedules are stored
+pub fn weekday_index(date: NaiveDate) -> u32 {
+    date.weekday().num_days_from_monday()
+}
+
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+pub fn format_time(time: NaiveTime) -> String {
+    time.format("%H:%M").to_string()
+}
+
+// A daily time range. When `end` is not after `start` the range runs past
+// midnight into the next day, so 18:00-02:00 is a late shift and 00:00-00:00
+// covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeRange {
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self { start, end }
+    }
+
+    pub fn parse(start: &str, end: &str) -> Option<Self> {
+        Some(Self::new(parse_time(start)?, parse_time(end)?))
+    }
+
+    pub fn all_day() -> Self {
+        Self::new(NaiveTime::MIN, NaiveTime::MIN)
+    }
+
+    pub fn crosses_midnight(&self) -> bool {
+        self.end <= self.start
+    }
+
+    fn contains_on_start_day(&self, time: NaiveTime) -> bool {
+        time >= self.start && (self.crosses_midnight() || time < self.end)
+    }
+
+    fn contains_on_next_day(&self, time: NaiveTime) -> bool {
+        self.crosses_midnight() && time < self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeeklyWindow {
+    pub day_of_week: u32,
+    pub range: TimeRange,
+}
+
+// Weekly windows with optional per-date overrides. An empty weekly list means
+// no restriction; an override with no ranges means closed for that date.
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    pub weekly: Vec<WeeklyWindow>,
+    pub overrides: HashMap<NaiveDate, Vec<TimeRange>>,
+}
+
+impl Schedule {
+    pub fn new(weekly: Vec<WeeklyWindow>) -> Self {
+        Self {
+            weekly,
+            overrides: HashMap::new(),
+        }
+    }
+
+    pub fn ranges_on(&self, date: NaiveDate) -> Vec<TimeRange> {
+        if let Some(ranges) = self.overrides.get(&date) {
+            return ranges.clone();
+        }
+        if self.weekly.is_empty() {
+            return vec![TimeRange::all_day()];
+        }
+
+        let day = weekday_index(date);
+        self.weekly
+            .iter()
+            .filter(|window| window.day_of_week == day)
+            .map(|window| window.range)
+            .collect()
+    }
+
+    pub fn is_closed_all_day(&self, date: NaiveDate) -> bool {
+        self.overrides
+            .get(&date)
+            .is_some_and(|ranges| ranges.is_empty())
+    }
+
+    pub fn is_active_at(&self, local: NaiveDateTime) -> bool {
+        let date = local.date();
+        let time = local.time();
+
+        if self
+            .ranges_on(date)
+            .iter()
+            .any(|range| range.contains_on_start_day(time))
+        {
+            return true;
+        }
+
+        // Late ranges that started yesterday
+        date.pred_opt().is_some_and(|yesterday| {
+            self.ranges_on(yesterday)
+                .iter()
+                .any(|range| range.contains_on_next_day(time))
+        })
+    }
+
+    // Earliest range start strictly after `local`, looking up to two weeks ahead
+    pub fn next_start_after(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..=14).find_map(|offset| {
+            let date = local.date() + Duration::days(offset);
+            self.ranges_on(date)
+                .iter()
+                .map(|range| date.and_time(range.start))
+                .filter(|start| *start > local)
+                .min()
+        })
+    }
+}
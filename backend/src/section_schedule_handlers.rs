@@ -0,0 +1,243 @@
+use crate::models::{
+    Claims, SectionScheduleResponse, SectionScheduleWindow, UpdateSectionScheduleRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use crate::schedule::{format_time, Schedule, TimeRange, WeeklyWindow};
+use crate::timezone::restaurant_timezone;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+
+// Schedules for every section of one restaurant. Sections without a schedule
+// are always active.
+pub struct SectionSchedules {
+    tz: Tz,
+    schedules: HashMap<String, Schedule>,
+}
+
+impl SectionSchedules {
+    pub async fn load(pool: &Pool<Sqlite>, restaurant_id: &str) -> Result<Self, sqlx::Error> {
+        let tz = restaurant_timezone(pool, restaurant_id).await?;
+        let rows = sqlx::query(
+            "SELECT s.section_id, s.day_of_week, s.start_time, s.end_time
+             FROM menu_section_schedules s
+             JOIN menu_sections ms ON ms.id = s.section_id
+             WHERE ms.restaurant_id = ?
+             ORDER BY s.day_of_week, s.start_time",
+        )
+        .bind(restaurant_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut schedules: HashMap<String, Schedule> = HashMap::new();
+        for row in rows {
+            let section_id: String = row.try_get("section_id")?;
+            let day_of_week: u32 = row.try_get("day_of_week")?;
+            let start_time: String = row.try_get("start_time")?;
+            let end_time: String = row.try_get("end_time")?;
+            match TimeRange::parse(&start_time, &end_time) {
+                Some(range) => schedules
+                    .entry(section_id)
+                    .or_default()
+                    .weekly
+                    .push(WeeklyWindow { day_of_week, range }),
+                None => log::warn!("Skipping invalid section schedule {start_time}-{end_time}"),
+            }
+        }
+
+        Ok(Self { tz, schedules })
+    }
+
+    pub fn is_active(&self, section_id: &str, at: DateTime<Utc>) -> bool {
+        self.schedules
+            .get(section_id)
+            .is_none_or(|schedule| schedule.is_active_at(at.with_timezone(&self.tz).naive_local()))
+    }
+
+    fn windows(&self, section_id: &str) -> Vec<SectionScheduleWindow> {
+        self.schedules
+            .get(section_id)
+            .map(|schedule| {
+                schedule
+                    .weekly
+                    .iter()
+                    .map(|window| SectionScheduleWindow {
+                        day_of_week: window.day_of_week,
+                        start_time: format_time(window.range.start),
+                        end_time: format_time(window.range.end),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+async fn section_restaurant_id(
+    pool: &Pool<Sqlite>,
+    section_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT restaurant_id FROM menu_sections WHERE id = ?")
+        .bind(section_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn section_schedule_response(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    section_id: String,
+) -> Result<SectionScheduleResponse, sqlx::Error> {
+    let schedules = SectionSchedules::load(pool, restaurant_id).await?;
+    Ok(SectionScheduleResponse {
+        windows: schedules.windows(&section_id),
+        active_now: schedules.is_active(&section_id, Utc::now()),
+        section_id,
+    })
+}
+
+pub async fn get_section_schedule(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let section_id = path.into_inner();
+
+    let restaurant_id = match section_restaurant_id(pool.get_ref(), &section_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu section not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking section: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match section_schedule_response(pool.get_ref(), &restaurant_id, section_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching section schedule: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn update_section_schedule(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateSectionScheduleRequest>,
+) -> Result<HttpResponse> {
+    let section_id = path.into_inner();
+
+    let restaurant_id = match section_restaurant_id(pool.get_ref(), &section_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu section not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking section: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let mut windows = Vec::new();
+    for window in &req.windows {
+        if window.day_of_week > 6 {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "day_of_week must be between 0 (Monday) and 6 (Sunday)"
+            })));
+        }
+        match TimeRange::parse(&window.start_time, &window.end_time) {
+            Some(range) => windows.push((window.day_of_week, range)),
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Times must use the HH:MM format"
+                })));
+            }
+        }
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let result = sqlx::query("DELETE FROM menu_section_schedules WHERE section_id = ?")
+        .bind(&section_id)
+        .execute(&mut *tx)
+        .await;
+    if let Err(e) = result {
+        log::error!("Database error clearing section schedule: {e}");
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update section schedule"
+        })));
+    }
+
+    for (day_of_week, range) in windows {
+        let result = sqlx::query(
+            "INSERT INTO menu_section_schedules (section_id, day_of_week, start_time, end_time) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&section_id)
+        .bind(day_of_week)
+        .bind(format_time(range.start))
+        .bind(format_time(range.end))
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = result {
+            log::error!("Database error saving section schedule: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update section schedule"
+            })));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Database error committing section schedule: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update section schedule"
+        })));
+    }
+
+    match section_schedule_response(pool.get_ref(), &restaurant_id, section_id).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => {
+            log::error!("Database error fetching section schedule: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::auth::JwtManager;
+use crate::models::{Claims, RefreshTokenRequest, SessionInfo};
+use crate::sessions::{self, SessionError};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+
+fn to_utc(at: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::from_naive_utc_and_offset(at, Utc)
+}
+
+// Public: the access token has usually expired by the time this is called
+pub async fn refresh_token(
+    pool: web::Data<Pool<Sqlite>>,
+    jwt_manager: web::Data<JwtManager>,
+    req: web::Json<RefreshTokenRequest>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse> {
+    match sessions::refresh_session(
+        pool.get_ref(),
+        jwt_manager.get_ref(),
+        &req.refresh_token,
+        &http_req,
+    )
+    .await
+    {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(SessionError::InvalidRefreshToken) => {
+            Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid or expired refresh token"
+            })))
+        }
+        Err(e) => {
+            log::error!("Error refreshing session: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn logout(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let Some(session_id) = claims.sid.as_deref() else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Only user sessions can log out"
+        })));
+    };
+
+    match sessions::revoke_session(pool.get_ref(), session_id, &claims.sub).await {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error revoking session: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to log out"
+            })))
+        }
+    }
+}
+
+// Includes the session making the request
+pub async fn logout_all(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    if claims.sid.is_none() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Only user sessions can log out"
+        })));
+    }
+
+    let result = match pool.acquire().await {
+        Ok(mut conn) => sessions::revoke_user_sessions(&mut conn, &claims.sub).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error revoking sessions: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to log out"
+            })))
+        }
+    }
+}
+
+// Live sessions only, most recently used first
+pub async fn list_sessions(
+    pool: web::Data<Pool<Sqlite>>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let rows = sqlx::query(
+        "SELECT id, user_agent, ip_address, created_at, last_used_at, expires_at
+         FROM user_sessions
+         WHERE user_id = ? AND revoked_at IS NULL AND expires_at > ?
+         ORDER BY last_used_at DESC, id",
+    )
+    .bind(&claims.sub)
+    .bind(Utc::now().naive_utc())
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let sessions = rows.and_then(|rows| {
+        rows.iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                Ok(SessionInfo {
+                    current: claims.sid.as_deref() == Some(id.as_str()),
+                    id,
+                    user_agent: row.try_get("user_agent")?,
+                    ip_address: row.try_get("ip_address")?,
+                    created_at: to_utc(row.try_get("created_at")?),
+                    last_used_at: to_utc(row.try_get("last_used_at")?),
+                    expires_at: to_utc(row.try_get("expires_at")?),
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()
+    });
+
+    match sessions {
+        Ok(sessions) => Ok(HttpResponse::Ok().json(sessions)),
+        Err(e) => {
+            log::error!("Database error listing sessions: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn revoke_session(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let session_id = path.into_inner();
+
+    match sessions::revoke_session(pool.get_ref(), &session_id, &claims.sub).await {
+        Ok(true) => Ok(HttpResponse::NoContent().finish()),
+        Ok(false) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Session not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error revoking session: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to revoke session"
+            })))
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::auth::JwtManager;
+use crate::email_handlers::hash_token;
+use crate::models::{AuthResponse, User, UserResponse, UserRow};
+use actix_web::HttpRequest;
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::fmt;
+use uuid::Uuid;
+
+// A session stays alive this long after its refresh token was last used
+pub const REFRESH_TOKEN_DAYS: i64 = 30;
+
+#[derive(Debug)]
+pub enum SessionError {
+    // Unknown, expired, revoked or already rotated out
+    InvalidRefreshToken,
+    Database(sqlx::Error),
+    Token(jsonwebtoken::errors::Error),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::InvalidRefreshToken => write!(f, "Invalid or expired refresh token"),
+            SessionError::Database(e) => write!(f, "Database error: {e}"),
+            SessionError::Token(e) => write!(f, "JWT generation error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<sqlx::Error> for SessionError {
+    fn from(e: sqlx::Error) -> Self {
+        SessionError::Database(e)
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for SessionError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        SessionError::Token(e)
+    }
+}
+
+fn generate_refresh_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// User agent and client address, shown in the session list
+fn client_details(req: &HttpRequest) -> (Option<String>, Option<String>) {
+    let user_agent = req
+        .headers()
+        .get("user-agent")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let ip_address = req
+        .connection_info()
+        .realip_remote_addr()
+        .map(|s| s.to_string());
+    (user_agent, ip_address)
+}
+
+fn auth_response(
+    jwt_manager: &JwtManager,
+    user: User,
+    session_id: &str,
+    refresh_token: String,
+) -> Result<AuthResponse, SessionError> {
+    let token = jwt_manager.generate_token(&user, session_id)?;
+    Ok(AuthResponse {
+        token,
+        refresh_token,
+        expires_in: jwt_manager.expires_in(),
+        user: UserResponse::from(user),
+    })
+}
+
+// Opens a session for a user who has just authenticated
+pub async fn start_session(
+    pool: &Pool<Sqlite>,
+    jwt_manager: &JwtManager,
+    user: User,
+    req: &HttpRequest,
+) -> Result<AuthResponse, SessionError> {
+    let session_id = Uuid::new_v4().to_string();
+    let refresh_token = generate_refresh_token();
+    let (user_agent, ip_address) = client_details(req);
+    let now = Utc::now().naive_utc();
+
+    sqlx::query(
+        "INSERT INTO user_sessions
+            (id, user_id, refresh_token_hash, user_agent, ip_address, created_at, last_used_at, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&session_id)
+    .bind(&user.id)
+    .bind(hash_token(&refresh_token))
+    .bind(user_agent)
+    .bind(ip_address)
+    .bind(now)
+    .bind(now)
+    .bind(now + Duration::days(REFRESH_TOKEN_DAYS))
+    .execute(pool)
+    .await?;
+
+    auth_response(jwt_manager, user, &session_id, refresh_token)
+}
+
+// Swaps a refresh token for a new access token and a new refresh token. A
+// token that was already rotated out can only be presented again if it
+// leaked, so doing so revokes the whole session.
+pub async fn refresh_session(
+    pool: &Pool<Sqlite>,
+    jwt_manager: &JwtManager,
+    refresh_token: &str,
+    req: &HttpRequest,
+) -> Result<AuthResponse, SessionError> {
+    let token_hash = hash_token(refresh_token);
+    let new_refresh_token = generate_refresh_token();
+    let (user_agent, ip_address) = client_details(req);
+    let now = Utc::now().naive_utc();
+
+    let rotated = sqlx::query(
+        "UPDATE user_sessions
+         SET refresh_token_hash = ?, previous_token_hash = refresh_token_hash,
+             last_used_at = ?, expires_at = ?,
+             user_agent = COALESCE(?, user_agent), ip_address = COALESCE(?, ip_address)
+         WHERE refresh_token_hash = ? AND revoked_at IS NULL AND expires_at > ?
+         RETURNING id, user_id",
+    )
+    .bind(hash_token(&new_refresh_token))
+    .bind(now)
+    .bind(now + Duration::days(REFRESH_TOKEN_DAYS))
+    .bind(user_agent)
+    .bind(ip_address)
+    .bind(&token_hash)
+    .bind(now)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = rotated else {
+        let replayed = sqlx::query(
+            "UPDATE user_sessions SET revoked_at = ?
+             WHERE previous_token_hash = ? AND revoked_at IS NULL",
+        )
+        .bind(now)
+        .bind(&token_hash)
+        .execute(pool)
+        .await?;
+        if replayed.rows_affected() > 0 {
+            log::warn!("Rotated-out refresh token presented again; session revoked");
+        }
+        return Err(SessionError::InvalidRefreshToken);
+    };
+    let session_id: String = row.try_get("id")?;
+    let user_id: String = row.try_get("user_id")?;
+
+    let user_row = sqlx::query_as::<_, UserRow>(
+        "SELECT id, email, phone, password_hash, email_verified, created_at FROM users WHERE id = ?",
+    )
+    .bind(&user_id)
+    .fetch_one(pool)
+    .await?;
+
+    auth_response(
+        jwt_manager,
+        User::from(user_row),
+        &session_id,
+        new_refresh_token,
+    )
+}
+
+// Checked by the middleware on every request made with an access token
+pub async fn session_is_active(
+    pool: &Pool<Sqlite>,
+    session_id: &str,
+    user_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_sessions
+         WHERE id = ? AND user_id = ? AND revoked_at IS NULL AND expires_at > ?",
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .bind(Utc::now().naive_utc())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
+// Returns false if the session is not the user's or was already revoked
+pub async fn revoke_session(
+    pool: &Pool<Sqlite>,
+    session_id: &str,
+    user_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE user_sessions SET revoked_at = ?
+         WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(Utc::now().naive_utc())
+    .bind(session_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Signs the user out everywhere. Takes a connection so a password reset can
+// do it in the same transaction that changes the password.
+pub async fn revoke_user_sessions(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE user_sessions SET revoked_at = ? WHERE user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(Utc::now().naive_utc())
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,741 @@
+use crate::models::{
+    AssignStationRequest, Claims, CreateKitchenStationRequest, KitchenStation, OrderItem,
+    StationLine, StationLineQuery, UpdateKitchenStationRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use crate::webhooks::enqueue_order_status_changed;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Kitchen displays poll the feed, so it is capped rather than paginated
+const STATION_FEED_LIMIT: i64 = 200;
+
+const STATION_LINE_SELECT: &str =
+    "SELECT l.id, l.order_id, o.order_number, o.status AS order_status,
+        o.payment_status, t.name AS table_name, t.restaurant_id, l.station_id, l.menu_item_id,
+        COALESCE(mi.name, 'Unknown Item') AS item_name, l.variant_name, l.notes, l.quantity,
+        l.status, l.created_at, l.bumped_at, l.recall_count
+    FROM order_station_lines l
+    JOIN orders o ON l.order_id = o.id
+    JOIN tables t ON o.table_id = t.id
+    LEFT JOIN menu_items mi ON l.menu_item_id = mi.id";
+
+async fn station_restaurant_id(
+    pool: &Pool<Sqlite>,
+    station_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT restaurant_id FROM kitchen_stations WHERE id = ?")
+        .bind(station_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn load_stations(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<Vec<KitchenStation>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, restaurant_id, name, display_order FROM kitchen_stations
+         WHERE restaurant_id = ? ORDER BY display_order, name",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    let section_rows = sqlx::query(
+        "SELECT id, station_id FROM menu_sections
+         WHERE restaurant_id = ? AND station_id IS NOT NULL ORDER BY display_order",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+    let item_rows = sqlx::query(
+        "SELECT mi.id, mi.station_id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ? AND mi.station_id IS NOT NULL
+         ORDER BY ms.display_order, mi.display_order",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut section_ids: HashMap<String, Vec<String>> = HashMap::new();
+    for row in section_rows {
+        section_ids
+            .entry(row.try_get("station_id")?)
+            .or_default()
+            .push(row.try_get("id")?);
+    }
+    let mut item_ids: HashMap<String, Vec<String>> = HashMap::new();
+    for row in item_rows {
+        item_ids
+            .entry(row.try_get("station_id")?)
+            .or_default()
+            .push(row.try_get("id")?);
+    }
+
+    rows.iter()
+        .map(|row| {
+            let id: String = row.try_get("id")?;
+            Ok(KitchenStation {
+                section_ids: section_ids.remove(&id).unwrap_or_default(),
+                item_ids: item_ids.remove(&id).unwrap_or_default(),
+                restaurant_id: row.try_get("restaurant_id")?,
+                name: row.try_get("name")?,
+                display_order: row.try_get("display_order")?,
+                id,
+            })
+        })
+        .collect()
+}
+
+// Copies each line of a new order onto the station its item is routed to.
+// Runs inside the order's transaction.
+pub async fn route_order_lines(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+    order_id: &str,
+    order_items: &[OrderItem],
+    placed_at: NaiveDateTime,
+) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT mi.id, COALESCE(mi.station_id, ms.station_id) AS station_id
+         FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(restaurant_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut stations: HashMap<String, Option<String>> = HashMap::new();
+    for row in rows {
+        stations.insert(row.try_get("id")?, row.try_get("station_id")?);
+    }
+
+    for (line_index, item) in order_items.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO order_station_lines (id, order_id, line_index, station_id, menu_item_id, variant_name, notes, quantity, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(order_id)
+        .bind(line_index as i64)
+        .bind(stations.get(&item.menu_item_id).cloned().flatten())
+        .bind(&item.menu_item_id)
+        .bind(&item.variant_name)
+        .bind(&item.notes)
+        .bind(item.quantity)
+        .bind(placed_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn station_line_from_row(
+    row: &sqlx::sqlite::SqliteRow,
+    now: DateTime<Utc>,
+) -> Result<StationLine, sqlx::Error> {
+    let created_at: NaiveDateTime = row.try_get("created_at")?;
+    let bumped_at: Option<NaiveDateTime> = row.try_get("bumped_at")?;
+    let created_at = DateTime::from_naive_utc_and_offset(created_at, Utc);
+    let bumped_at = bumped_at.map(|at| DateTime::from_naive_utc_and_offset(at, Utc));
+    Ok(StationLine {
+        id: row.try_get("id")?,
+        order_id: row.try_get("order_id")?,
+        order_number: row.try_get("order_number")?,
+        order_status: row.try_get("order_status")?,
+        table_name: row.try_get("table_name")?,
+        station_id: row.try_get("station_id")?,
+        menu_item_id: row.try_get("menu_item_id")?,
+        item_name: row.try_get("item_name")?,
+        variant_name: row.try_get("variant_name")?,
+        notes: row.try_get("notes")?,
+        quantity: row.try_get("quantity")?,
+        status: row.try_get("status")?,
+        elapsed_seconds: (bumped_at.unwrap_or(now) - created_at).num_seconds().max(0),
+        created_at,
+        bumped_at,
+        recall_count: row.try_get("recall_count")?,
+    })
+}
+
+async fn find_station_line(
+    conn: &mut SqliteConnection,
+    line_id: &str,
+) -> Result<Option<sqlx::sqlite::SqliteRow>, sqlx::Error> {
+    sqlx::query(&format!("{STATION_LINE_SELECT} WHERE l.id = ?"))
+        .bind(line_id)
+        .fetch_optional(conn)
+        .await
+}
+
+// The first bump moves an order into preparation and bumping its last pending
+// line makes it ready. A recall takes a ready order back to preparing.
+async fn sync_order_status(conn: &mut SqliteConnection, order_id: &str) -> Result<(), sqlx::Error> {
+    let pending: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM order_station_lines WHERE order_id = ? AND status = 'pending'",
+    )
+    .bind(order_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let (status, from): (&str, &[&str]) = if pending == 0 {
+        ("ready", &["pending", "confirmed", "preparing"])
+    } else {
+        ("preparing", &["pending", "confirmed", "ready"])
+    };
+    let previous: String = sqlx::query_scalar("SELECT status FROM orders WHERE id = ?")
+        .bind(order_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !from.contains(&previous.as_str()) {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE orders SET status = ? WHERE id = ?")
+        .bind(status)
+        .bind(order_id)
+        .execute(&mut *conn)
+        .await?;
+    enqueue_order_status_changed(conn, order_id, &previous, status).await
+}
+
+pub async fn list_stations(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match load_stations(pool.get_ref(), &restaurant_id).await {
+        Ok(stations) => Ok(HttpResponse::Ok().json(stations)),
+        Err(e) => {
+            log::error!("Database error fetching kitchen stations: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn create_station(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreateKitchenStationRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Station name is required"
+        })));
+    }
+
+    let station = KitchenStation {
+        id: Uuid::new_v4().to_string(),
+        restaurant_id,
+        name,
+        display_order: req.display_order.unwrap_or(0),
+        section_ids: Vec::new(),
+        item_ids: Vec::new(),
+    };
+    let result = sqlx::query(
+        "INSERT INTO kitchen_stations (id, restaurant_id, name, display_order) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&station.id)
+    .bind(&station.restaurant_id)
+    .bind(&station.name)
+    .bind(station.display_order)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Created().json(station)),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": format!("Station {} already exists", station.name)
+            })))
+        }
+        Err(e) => {
+            log::error!("Database error creating kitchen station: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create kitchen station"
+            })))
+        }
+    }
+}
+
+pub async fn update_station(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateKitchenStationRequest>,
+) -> Result<HttpResponse> {
+    let station_id = path.into_inner();
+
+    let restaurant_id = match station_restaurant_id(pool.get_ref(), &station_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Kitchen station not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching kitchen station: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let name = req.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Station name is required"
+        })));
+    }
+
+    let result = sqlx::query(
+        "UPDATE kitchen_stations SET name = COALESCE(?, name), display_order = COALESCE(?, display_order)
+         WHERE id = ?",
+    )
+    .bind(name)
+    .bind(req.display_order)
+    .bind(&station_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "error": format!("Station {} already exists", name.unwrap_or_default())
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error updating kitchen station: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update kitchen station"
+            })));
+        }
+    }
+
+    match load_stations(pool.get_ref(), &restaurant_id).await {
+        Ok(stations) => match stations
+            .into_iter()
+            .find(|station| station.id == station_id)
+        {
+            Some(station) => Ok(HttpResponse::Ok().json(station)),
+            None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Kitchen station not found"
+            }))),
+        },
+        Err(e) => {
+            log::error!("Database error fetching kitchen stations: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn delete_station(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let station_id = path.into_inner();
+
+    let restaurant_id = match station_restaurant_id(pool.get_ref(), &station_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Kitchen station not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching kitchen station: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    // Mapped sections, items and open lines fall back to no station
+    match sqlx::query("DELETE FROM kitchen_stations WHERE id = ?")
+        .bind(&station_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting kitchen station: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete kitchen station"
+            })))
+        }
+    }
+}
+
+// Shared by the section and item mapping endpoints; `owner_sql` returns the
+// restaurant of the section or item being mapped
+async fn assign_station(
+    pool: &Pool<Sqlite>,
+    claims: &Claims,
+    owner_sql: &str,
+    update_sql: &str,
+    target_id: &str,
+    not_found: &str,
+    station_id: Option<&str>,
+) -> Result<HttpResponse> {
+    let restaurant_id: Option<String> = match sqlx::query_scalar(owner_sql)
+        .bind(target_id)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(restaurant_id) => restaurant_id,
+        Err(e) => {
+            log::error!("Database error checking station mapping target: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+    let Some(restaurant_id) = restaurant_id else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": not_found
+        })));
+    };
+
+    if !can_manage_menu(pool, &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if let Some(station_id) = station_id {
+        match station_restaurant_id(pool, station_id).await {
+            Ok(Some(owner)) if owner == restaurant_id => {}
+            Ok(_) => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Kitchen station not found"
+                })));
+            }
+            Err(e) => {
+                log::error!("Database error fetching kitchen station: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        }
+    }
+
+    match sqlx::query(update_sql)
+        .bind(station_id)
+        .bind(target_id)
+        .execute(pool)
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error assigning kitchen station: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to assign kitchen station"
+            })))
+        }
+    }
+}
+
+pub async fn assign_section_station(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<AssignStationRequest>,
+) -> Result<HttpResponse> {
+    assign_station(
+        pool.get_ref(),
+        &claims,
+        "SELECT restaurant_id FROM menu_sections WHERE id = ?",
+        "UPDATE menu_sections SET station_id = ? WHERE id = ?",
+        &path.into_inner(),
+        "Menu section not found",
+        req.station_id.as_deref(),
+    )
+    .await
+}
+
+pub async fn assign_item_station(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<AssignStationRequest>,
+) -> Result<HttpResponse> {
+    assign_station(
+        pool.get_ref(),
+        &claims,
+        "SELECT ms.restaurant_id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id WHERE mi.id = ?",
+        "UPDATE menu_items SET station_id = ? WHERE id = ?",
+        &path.into_inner(),
+        "Menu item not found",
+        req.station_id.as_deref(),
+    )
+    .await
+}
+
+// Lines of orders still awaiting payment stay off the displays until paid
+pub async fn list_station_lines(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<StationLineQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let status = query.status.as_deref().unwrap_or("pending");
+    let (status_filter, order_by) = match status {
+        "pending" => (
+            " AND l.status = 'pending' AND o.status NOT IN ('cancelled', 'delivered')",
+            "l.created_at, o.id, l.line_index",
+        ),
+        "bumped" => (" AND l.status = 'bumped'", "l.bumped_at DESC, l.id"),
+        "all" => ("", "l.created_at DESC, o.id, l.line_index"),
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Status must be 'pending', 'bumped' or 'all'"
+            })));
+        }
+    };
+
+    if let Some(station_id) = &query.station_id {
+        match station_restaurant_id(pool.get_ref(), station_id).await {
+            Ok(Some(owner)) if owner == restaurant_id => {}
+            Ok(_) => {
+                return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Kitchen station not found"
+                })));
+            }
+            Err(e) => {
+                log::error!("Database error fetching kitchen station: {e}");
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })));
+            }
+        }
+    }
+
+    let station_filter = if query.station_id.is_some() {
+        " AND l.station_id = ?"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "{STATION_LINE_SELECT}
+         WHERE t.restaurant_id = ? AND o.payment_status != 'awaiting_payment'{status_filter}{station_filter}
+         ORDER BY {order_by}
+         LIMIT ?"
+    );
+    let mut lines_query = sqlx::query(&sql).bind(&restaurant_id);
+    if let Some(station_id) = &query.station_id {
+        lines_query = lines_query.bind(station_id);
+    }
+
+    let now = Utc::now();
+    match lines_query
+        .bind(STATION_FEED_LIMIT)
+        .fetch_all(pool.get_ref())
+        .await
+        .and_then(|rows| {
+            rows.iter()
+                .map(|row| station_line_from_row(row, now))
+                .collect::<Result<Vec<_>, _>>()
+        }) {
+        Ok(lines) => Ok(HttpResponse::Ok().json(lines)),
+        Err(e) => {
+            log::error!("Database error fetching station lines: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+async fn change_line_status(
+    pool: &Pool<Sqlite>,
+    claims: &Claims,
+    line_id: &str,
+    bump: bool,
+) -> Result<HttpResponse> {
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Database error starting transaction: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let row = match find_station_line(&mut tx, line_id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => {
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Order line not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching station line: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+    let restaurant_id: String = row.try_get("restaurant_id").unwrap_or_default();
+    let order_id: String = row.try_get("order_id").unwrap_or_default();
+    let order_status: String = row.try_get("order_status").unwrap_or_default();
+    let payment_status: String = row.try_get("payment_status").unwrap_or_default();
+    let line_status: String = row.try_get("status").unwrap_or_default();
+
+    if !is_manager(pool, &restaurant_id, &claims.sub).await {
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let conflict = match order_status.as_str() {
+        "cancelled" => Some("Order was cancelled"),
+        "delivered" => Some("Order was already delivered"),
+        _ if payment_status == "awaiting_payment" => Some("Order is awaiting payment"),
+        _ => None,
+    };
+    if let Some(message) = conflict {
+        let _ = tx.rollback().await;
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "error": message
+        })));
+    }
+
+    // Bumping a bumped line or recalling a pending one changes nothing, so a
+    // double tap on the display is harmless
+    let already = if bump {
+        line_status == "bumped"
+    } else {
+        line_status == "pending"
+    };
+    if !already {
+        let result = if bump {
+            sqlx::query(
+                "UPDATE order_station_lines SET status = 'bumped', bumped_at = ?, bumped_by = ? WHERE id = ?",
+            )
+            .bind(Utc::now().naive_utc())
+            .bind(&claims.sub)
+            .bind(line_id)
+            .execute(&mut *tx)
+            .await
+        } else {
+            sqlx::query(
+                "UPDATE order_station_lines SET status = 'pending', bumped_at = NULL, bumped_by = NULL,
+                     recall_count = recall_count + 1
+                 WHERE id = ?",
+            )
+            .bind(line_id)
+            .execute(&mut *tx)
+            .await
+        };
+
+        if let Err(e) = result {
+            log::error!("Database error updating station line: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+        if let Err(e) = sync_order_status(&mut tx, &order_id).await {
+            log::error!("Database error updating order status: {e}");
+            let _ = tx.rollback().await;
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    }
+
+    let line = match find_station_line(&mut tx, line_id).await {
+        Ok(Some(row)) => station_line_from_row(&row, Utc::now()),
+        Ok(None) => Err(sqlx::Error::RowNotFound),
+        Err(e) => Err(e),
+    };
+    match line {
+        Ok(line) => match tx.commit().await {
+            Ok(_) => Ok(HttpResponse::Ok().json(line)),
+            Err(e) => {
+                log::error!("Database error committing station line: {e}");
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Internal server error"
+                })))
+            }
+        },
+        Err(e) => {
+            log::error!("Database error fetching station line: {e}");
+            let _ = tx.rollback().await;
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn bump_station_line(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    change_line_status(pool.get_ref(), &claims, &path.into_inner(), true).await
+}
+
+pub async fn recall_station_line(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    change_line_status(pool.get_ref(), &claims, &path.into_inner(), false).await
+}
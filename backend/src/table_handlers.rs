@@ -1,11 +1,27 @@
 use crate::models::{
-    BulkQrCodeRequest, BulkQrCodeResponse, Claims, CreateTableRequest, QrCodeResponse,
-    RefreshCodeResponse, UpdateTableRequest,
+    BulkQrCodeRequest, BulkQrCodeResponse, Claims, CreateTableRequest, Page, QrCodeResponse,
+    RefreshCodeResponse, Table, TableListQuery, TableRow, UpdateTableRequest,
 };
+use crate::pagination::{PageRequest, SortField};
 use actix_web::{web, HttpResponse, Result};
-use sqlx::{Pool, Sqlite};
+use sqlx::{FromRow, Pool, Sqlite};
 use uuid::Uuid;
 
+static TABLE_SORTS: [SortField; 2] = [
+    SortField {
+        name: "created_at",
+        expr: "CAST(created_at AS TEXT)",
+        numeric: false,
+        descending: true,
+    },
+    SortField {
+        name: "name",
+        expr: "name",
+        numeric: false,
+        descending: false,
+    },
+];
+
 // Helper function to generate secure unique codes
 fn generate_unique_code() -> String {
     use rand::Rng;
@@ -142,6 +158,7 @@ pub async fn list_tables(
     pool: web::Data<Pool<Sqlite>>,
     path: web::Path<String>,
     claims: web::ReqData<Claims>,
+    query: web::Query<TableListQuery>,
 ) -> Result<HttpResponse> {
     let restaurant_id = path.into_inner();
 
@@ -169,30 +186,63 @@ pub async fn list_tables(
         }
     }
 
-    // Fetch tables for this restaurant
-    let tables = sqlx::query!(
-        "SELECT id, restaurant_id, name, unique_code, created_at FROM tables WHERE restaurant_id = ? ORDER BY created_at DESC",
-        restaurant_id
-    )
-    .fetch_all(pool.get_ref())
-    .await;
+    let page = match PageRequest::parse(
+        &TABLE_SORTS,
+        query.cursor.as_deref(),
+        query.limit,
+        query.sort.as_deref(),
+        query.order.as_deref(),
+    ) {
+        Ok(page) => page,
+        Err(message) => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": message
+            })));
+        }
+    };
+
+    // Fetch one page of tables for this restaurant
+    let mut tables_query = sqlx::QueryBuilder::<Sqlite>::new(format!(
+        "SELECT id, restaurant_id, name, unique_code, created_at, {} AS sort_key
+         FROM tables WHERE restaurant_id = ",
+        page.sort_expr()
+    ));
+    tables_query.push_bind(&restaurant_id);
+    if let Some(q) = query.q.as_deref().map(str::trim) {
+        if !q.is_empty() {
+            tables_query
+                .push(" AND (name LIKE ")
+                .push_bind(format!("%{q}%"))
+                .push(" OR unique_code = ")
+                .push_bind(q.to_uppercase())
+                .push(")");
+        }
+    }
+    page.push_after_cursor(&mut tables_query, "id");
+    page.push_order_and_limit(&mut tables_query, "id");
+
+    let tables = match tables_query.build().fetch_all(pool.get_ref()).await {
+        Ok(rows) => page.split(rows, "id"),
+        Err(e) => Err(e),
+    };
 
     match tables {
-        Ok(table_rows) => {
-            let tables_json: Vec<serde_json::Value> = table_rows
-                .into_iter()
-                .map(|row| {
-                    serde_json::json!({
-                        "id": row.id,
-                        "restaurant_id": row.restaurant_id,
-                        "name": row.name,
-                        "unique_code": row.unique_code,
-                        "created_at": row.created_at
-                    })
-                })
-                .collect();
-
-            Ok(HttpResponse::Ok().json(tables_json))
+        Ok((rows, next_cursor)) => {
+            let items = match rows
+                .iter()
+                .map(|row| TableRow::from_row(row).map(Table::from))
+                .collect::<Result<Vec<Table>, sqlx::Error>>()
+            {
+                Ok(items) => items,
+                Err(e) => {
+                    log::error!("Error reading tables: {e}");
+                    return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Failed to fetch tables"
+                    })));
+                }
+            };
+
+            Ok(HttpResponse::Ok().json(Page { items, next_cursor }))
         }
         Err(e) => {
             log::error!("Database error fetching tables: {e}");
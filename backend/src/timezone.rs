@@ -0,0 +1,56 @@
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse::<Tz>().ok()
+}
+
+// Restaurants with a missing or unrecognised zone are treated as UTC
+pub async fn restaurant_timezone<'e, E>(executor: E, restaurant_id: &str) -> Result<Tz, sqlx::Error>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let name: Option<String> = sqlx::query_scalar("SELECT timezone FROM restaurants WHERE id = ?")
+        .bind(restaurant_id)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(name
+        .and_then(|name| parse_timezone(&name))
+        .unwrap_or(Tz::UTC))
+}
+
+pub fn business_date(tz: Tz, at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(&tz).date_naive()
+}
+
+// Start (inclusive) and end (exclusive) of a local calendar day, in UTC. These
+// compare correctly against the UTC timestamps stored in created_at columns.
+pub fn day_bounds_utc(tz: Tz, date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    let next_day = date.succ_opt().unwrap_or(date);
+    (
+        local_midnight_utc(tz, date),
+        local_midnight_utc(tz, next_day),
+    )
+}
+
+fn local_midnight_utc(tz: Tz, date: NaiveDate) -> NaiveDateTime {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    local_to_utc(tz, midnight).naive_utc()
+}
+
+// Resolves a wall-clock time in `tz`. Ambiguous times (DST fall back) take the
+// earlier instant; times inside a DST gap are pushed forward by the one-hour shift.
+pub fn local_to_utc(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+        LocalResult::None => tz
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&local)),
+    }
+}
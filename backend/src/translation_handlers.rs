@@ -0,0 +1,580 @@
+use crate::locale::{normalize_locale, DEFAULT_LANGUAGE};
+use crate::models::{
+    Claims, ItemTranslation, PublicMenu, RestaurantTranslation, RestaurantTranslations,
+    SectionTranslation, UpsertItemTranslationRequest, UpsertRestaurantTranslationRequest,
+    UpsertSectionTranslationRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager, is_super_admin};
+use actix_web::{web, HttpResponse, Result};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+
+// The default language first, then every locale with at least one translation
+pub async fn menu_languages(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+) -> Result<(String, Vec<String>), sqlx::Error> {
+    let default_language: Option<String> =
+        sqlx::query_scalar("SELECT default_language FROM restaurants WHERE id = ?")
+            .bind(restaurant_id)
+            .fetch_optional(pool)
+            .await?;
+    let default_language = default_language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+    let locales: Vec<String> = sqlx::query_scalar(
+        "SELECT locale FROM restaurant_translations WHERE restaurant_id = ?1
+         UNION
+         SELECT t.locale FROM menu_section_translations t
+         JOIN menu_sections ms ON ms.id = t.section_id WHERE ms.restaurant_id = ?1
+         UNION
+         SELECT t.locale FROM menu_item_translations t
+         JOIN menu_items mi ON mi.id = t.item_id
+         JOIN menu_sections ms ON ms.id = mi.section_id WHERE ms.restaurant_id = ?1
+         ORDER BY locale",
+    )
+    .bind(restaurant_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut languages = vec![default_language.clone()];
+    languages.extend(
+        locales
+            .into_iter()
+            .filter(|locale| *locale != default_language),
+    );
+
+    Ok((default_language, languages))
+}
+
+fn translated(translation: Option<&Option<String>>, base: String) -> String {
+    translation.cloned().flatten().unwrap_or(base)
+}
+
+// Swaps in translated text where it exists. Anything left untranslated keeps
+// the default-language value so guests never see blanks.
+pub async fn translate_public_menu(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    locale: &str,
+    menu: &mut PublicMenu,
+) -> Result<(), sqlx::Error> {
+    let restaurant = sqlx::query(
+        "SELECT name, address FROM restaurant_translations WHERE restaurant_id = ? AND locale = ?",
+    )
+    .bind(restaurant_id)
+    .bind(locale)
+    .fetch_optional(pool)
+    .await?;
+    if let Some(row) = restaurant {
+        let name: Option<String> = row.try_get("name")?;
+        let address: Option<String> = row.try_get("address")?;
+        if let Some(name) = name {
+            menu.restaurant.name = name;
+        }
+        if address.is_some() {
+            menu.restaurant.address = address;
+        }
+    }
+
+    let sections: HashMap<String, Option<String>> = sqlx::query_as(
+        "SELECT t.section_id, t.name FROM menu_section_translations t
+         JOIN menu_sections ms ON ms.id = t.section_id
+         WHERE ms.restaurant_id = ? AND t.locale = ?",
+    )
+    .bind(restaurant_id)
+    .bind(locale)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let items: HashMap<String, (Option<String>, Option<String>)> =
+        sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+            "SELECT t.item_id, t.name, t.description FROM menu_item_translations t
+             JOIN menu_items mi ON mi.id = t.item_id
+             JOIN menu_sections ms ON ms.id = mi.section_id
+             WHERE ms.restaurant_id = ? AND t.locale = ?",
+        )
+        .bind(restaurant_id)
+        .bind(locale)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(item_id, name, description)| (item_id, (name, description)))
+        .collect();
+
+    for section in &mut menu.sections {
+        section.name = translated(sections.get(&section.id), std::mem::take(&mut section.name));
+        for item in &mut section.items {
+            if let Some((name, description)) = items.get(&item.id) {
+                item.name = translated(Some(name), std::mem::take(&mut item.name));
+                if description.is_some() {
+                    item.description = description.clone();
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Normalises the locale from the path and rejects the default language,
+// whose text lives in the base columns
+async fn translation_locale(
+    pool: &Pool<Sqlite>,
+    restaurant_id: &str,
+    raw: &str,
+) -> Result<String, HttpResponse> {
+    let Some(locale) = normalize_locale(raw) else {
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid locale, expected a language tag like hi or en-IN"
+        })));
+    };
+
+    match menu_languages(pool, restaurant_id).await {
+        Ok((default_language, _)) if default_language == locale => Err(HttpResponse::BadRequest()
+            .json(serde_json::json!({
+                "error": format!("{locale} is the default language; edit the menu itself instead")
+            }))),
+        Ok(_) => Ok(locale),
+        Err(e) => {
+            log::error!("Database error fetching restaurant languages: {e}");
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn clean(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn nothing_to_translate() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Provide at least one translated field"
+    }))
+}
+
+fn deleted_or_not_found(
+    result: Result<sqlx::sqlite::SqliteQueryResult, sqlx::Error>,
+) -> HttpResponse {
+    match result {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Translation not found"
+        })),
+        Err(e) => {
+            log::error!("Database error deleting translation: {e}");
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete translation"
+            }))
+        }
+    }
+}
+
+async fn section_restaurant(
+    pool: &Pool<Sqlite>,
+    section_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT restaurant_id FROM menu_sections WHERE id = ?")
+        .bind(section_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn item_restaurant(
+    pool: &Pool<Sqlite>,
+    item_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT ms.restaurant_id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.id = ?",
+    )
+    .bind(item_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_translations(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    let (default_language, languages) = match menu_languages(pool.get_ref(), &restaurant_id).await {
+        Ok(languages) => languages,
+        Err(e) => {
+            log::error!("Database error fetching restaurant languages: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    let restaurant = sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+        "SELECT locale, name, address FROM restaurant_translations
+         WHERE restaurant_id = ? ORDER BY locale",
+    )
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let sections = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT t.section_id, t.locale, t.name FROM menu_section_translations t
+         JOIN menu_sections ms ON ms.id = t.section_id
+         WHERE ms.restaurant_id = ?
+         ORDER BY ms.display_order, t.locale",
+    )
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let items = sqlx::query_as::<_, (String, String, Option<String>, Option<String>)>(
+        "SELECT t.item_id, t.locale, t.name, t.description FROM menu_item_translations t
+         JOIN menu_items mi ON mi.id = t.item_id
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         WHERE ms.restaurant_id = ?
+         ORDER BY ms.display_order, mi.display_order, t.locale",
+    )
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match (restaurant, sections, items) {
+        (Ok(restaurant), Ok(sections), Ok(items)) => {
+            Ok(HttpResponse::Ok().json(RestaurantTranslations {
+                default_language,
+                languages,
+                restaurant: restaurant
+                    .into_iter()
+                    .map(|(locale, name, address)| RestaurantTranslation {
+                        locale,
+                        name,
+                        address,
+                    })
+                    .collect(),
+                sections: sections
+                    .into_iter()
+                    .map(|(section_id, locale, name)| SectionTranslation {
+                        section_id,
+                        locale,
+                        name,
+                    })
+                    .collect(),
+                items: items
+                    .into_iter()
+                    .map(|(item_id, locale, name, description)| ItemTranslation {
+                        item_id,
+                        locale,
+                        name,
+                        description,
+                    })
+                    .collect(),
+            }))
+        }
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            log::error!("Database error fetching translations: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn upsert_restaurant_translation(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpsertRestaurantTranslationRequest>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, locale) = path.into_inner();
+
+    // Same rule as editing the restaurant details themselves
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admin can update restaurant details"
+        })));
+    }
+
+    let locale = match translation_locale(pool.get_ref(), &restaurant_id, &locale).await {
+        Ok(locale) => locale,
+        Err(response) => return Ok(response),
+    };
+
+    let translation = RestaurantTranslation {
+        locale,
+        name: clean(&req.name),
+        address: clean(&req.address),
+    };
+    if translation.name.is_none() && translation.address.is_none() {
+        return Ok(nothing_to_translate());
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO restaurant_translations (restaurant_id, locale, name, address)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT (restaurant_id, locale) DO UPDATE
+         SET name = excluded.name, address = excluded.address, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(&restaurant_id)
+    .bind(&translation.locale)
+    .bind(&translation.name)
+    .bind(&translation.address)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Ok().json(translation)),
+        Err(e) => {
+            log::error!("Database error saving restaurant translation: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to save translation"
+            })))
+        }
+    }
+}
+
+pub async fn delete_restaurant_translation(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let (restaurant_id, locale) = path.into_inner();
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admin can update restaurant details"
+        })));
+    }
+
+    let result =
+        sqlx::query("DELETE FROM restaurant_translations WHERE restaurant_id = ? AND locale = ?")
+            .bind(&restaurant_id)
+            .bind(normalize_locale(&locale).unwrap_or(locale))
+            .execute(pool.get_ref())
+            .await;
+
+    Ok(deleted_or_not_found(result))
+}
+
+pub async fn upsert_section_translation(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpsertSectionTranslationRequest>,
+) -> Result<HttpResponse> {
+    let (section_id, locale) = path.into_inner();
+
+    let restaurant_id = match section_restaurant(pool.get_ref(), &section_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu section not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking section: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let locale = match translation_locale(pool.get_ref(), &restaurant_id, &locale).await {
+        Ok(locale) => locale,
+        Err(response) => return Ok(response),
+    };
+
+    let translation = SectionTranslation {
+        section_id,
+        locale,
+        name: clean(&req.name),
+    };
+    if translation.name.is_none() {
+        return Ok(nothing_to_translate());
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO menu_section_translations (section_id, locale, name)
+         VALUES (?, ?, ?)
+         ON CONFLICT (section_id, locale) DO UPDATE
+         SET name = excluded.name, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(&translation.section_id)
+    .bind(&translation.locale)
+    .bind(&translation.name)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Ok().json(translation)),
+        Err(e) => {
+            log::error!("Database error saving section translation: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to save translation"
+            })))
+        }
+    }
+}
+
+pub async fn delete_section_translation(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let (section_id, locale) = path.into_inner();
+
+    let restaurant_id = match section_restaurant(pool.get_ref(), &section_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu section not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking section: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let result =
+        sqlx::query("DELETE FROM menu_section_translations WHERE section_id = ? AND locale = ?")
+            .bind(&section_id)
+            .bind(normalize_locale(&locale).unwrap_or(locale))
+            .execute(pool.get_ref())
+            .await;
+
+    Ok(deleted_or_not_found(result))
+}
+
+pub async fn upsert_item_translation(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpsertItemTranslationRequest>,
+) -> Result<HttpResponse> {
+    let (item_id, locale) = path.into_inner();
+
+    let restaurant_id = match item_restaurant(pool.get_ref(), &item_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking item: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let locale = match translation_locale(pool.get_ref(), &restaurant_id, &locale).await {
+        Ok(locale) => locale,
+        Err(response) => return Ok(response),
+    };
+
+    let translation = ItemTranslation {
+        item_id,
+        locale,
+        name: clean(&req.name),
+        description: clean(&req.description),
+    };
+    if translation.name.is_none() && translation.description.is_none() {
+        return Ok(nothing_to_translate());
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO menu_item_translations (item_id, locale, name, description)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT (item_id, locale) DO UPDATE
+         SET name = excluded.name, description = excluded.description, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(&translation.item_id)
+    .bind(&translation.locale)
+    .bind(&translation.name)
+    .bind(&translation.description)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Ok().json(translation)),
+        Err(e) => {
+            log::error!("Database error saving item translation: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to save translation"
+            })))
+        }
+    }
+}
+
+pub async fn delete_item_translation(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<(String, String)>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let (item_id, locale) = path.into_inner();
+
+    let restaurant_id = match item_restaurant(pool.get_ref(), &item_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking item: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let result = sqlx::query("DELETE FROM menu_item_translations WHERE item_id = ? AND locale = ?")
+        .bind(&item_id)
+        .bind(normalize_locale(&locale).unwrap_or(locale))
+        .execute(pool.get_ref())
+        .await;
+
+    Ok(deleted_or_not_found(result))
+}
@@ -0,0 +1,334 @@
+use crate::models::{
+    Claims, CreateMenuItemVariantRequest, MenuItemVariant, UpdateMenuItemVariantRequest,
+};
+use crate::permissions::{can_manage_menu, is_manager};
+use actix_web::{web, HttpResponse, Result};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Variants of every item in a restaurant, keyed by item id and in menu order
+pub async fn load_variants<'e, E>(
+    executor: E,
+    restaurant_id: &str,
+) -> Result<HashMap<String, Vec<MenuItemVariant>>, sqlx::Error>
+where
+    E: sqlx::SqliteExecutor<'e>,
+{
+    let rows = sqlx::query(
+        "SELECT v.id, v.item_id, v.name, CAST(v.price AS REAL) AS price, v.available, v.display_order
+         FROM menu_item_variants v
+         JOIN menu_items mi ON v.item_id = mi.id
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE ms.restaurant_id = ?
+         ORDER BY v.display_order ASC, v.created_at ASC",
+    )
+    .bind(restaurant_id)
+    .fetch_all(executor)
+    .await?;
+
+    let mut variants: HashMap<String, Vec<MenuItemVariant>> = HashMap::new();
+    for row in rows {
+        let variant = variant_from_row(&row)?;
+        variants
+            .entry(variant.item_id.clone())
+            .or_default()
+            .push(variant);
+    }
+
+    Ok(variants)
+}
+
+fn variant_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<MenuItemVariant, sqlx::Error> {
+    let display_order: i64 = row.try_get("display_order")?;
+    Ok(MenuItemVariant {
+        id: row.try_get("id")?,
+        item_id: row.try_get("item_id")?,
+        name: row.try_get("name")?,
+        price: row.try_get("price")?,
+        available: row.try_get("available")?,
+        display_order: display_order as i32,
+    })
+}
+
+async fn fetch_variant(
+    pool: &Pool<Sqlite>,
+    variant_id: &str,
+) -> Result<Option<(String, MenuItemVariant)>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT ms.restaurant_id, v.id, v.item_id, v.name, CAST(v.price AS REAL) AS price, v.available, v.display_order
+         FROM menu_item_variants v
+         JOIN menu_items mi ON v.item_id = mi.id
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE v.id = ?",
+    )
+    .bind(variant_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(|row| Ok((row.try_get("restaurant_id")?, variant_from_row(&row)?)))
+        .transpose()
+}
+
+async fn item_restaurant(
+    pool: &Pool<Sqlite>,
+    item_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT ms.restaurant_id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.id = ?",
+    )
+    .bind(item_id)
+    .fetch_optional(pool)
+    .await
+}
+
+fn valid_price(price: f64) -> bool {
+    price.is_finite() && price >= 0.0
+}
+
+pub async fn list_variants(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let item_id = path.into_inner();
+
+    let restaurant_id = match item_restaurant(pool.get_ref(), &item_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking item: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !is_manager(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Access denied"
+        })));
+    }
+
+    match load_variants(pool.get_ref(), &restaurant_id).await {
+        Ok(mut variants) => {
+            Ok(HttpResponse::Ok().json(variants.remove(&item_id).unwrap_or_default()))
+        }
+        Err(e) => {
+            log::error!("Database error fetching variants: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn create_variant(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreateMenuItemVariantRequest>,
+) -> Result<HttpResponse> {
+    let item_id = path.into_inner();
+
+    let restaurant_id = match item_restaurant(pool.get_ref(), &item_id).await {
+        Ok(Some(restaurant_id)) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Menu item not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error checking item: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Variant name is required"
+        })));
+    }
+    if !valid_price(req.price) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Price cannot be negative"
+        })));
+    }
+
+    // New variants go after the existing ones unless an order is given
+    let display_order = match req.display_order {
+        Some(display_order) => display_order,
+        None => sqlx::query_scalar::<_, i32>(
+            "SELECT COALESCE(MAX(display_order), 0) + 1 FROM menu_item_variants WHERE item_id = ?",
+        )
+        .bind(&item_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(1),
+    };
+
+    let variant = MenuItemVariant {
+        id: Uuid::new_v4().to_string(),
+        item_id,
+        name: name.to_string(),
+        price: req.price,
+        available: req.available.unwrap_or(true),
+        display_order,
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO menu_item_variants (id, item_id, name, price, available, display_order)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&variant.id)
+    .bind(&variant.item_id)
+    .bind(&variant.name)
+    .bind(variant.price)
+    .bind(variant.available)
+    .bind(variant.display_order)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Created().json(variant)),
+        Err(e) => {
+            log::error!("Database error creating variant: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to create variant"
+            })))
+        }
+    }
+}
+
+pub async fn update_variant(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateMenuItemVariantRequest>,
+) -> Result<HttpResponse> {
+    let variant_id = path.into_inner();
+
+    let (restaurant_id, mut variant) = match fetch_variant(pool.get_ref(), &variant_id).await {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Variant not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching variant: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    if let Some(name) = &req.name {
+        let name = name.trim();
+        if name.is_empty() {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Variant name is required"
+            })));
+        }
+        variant.name = name.to_string();
+    }
+    if let Some(price) = req.price {
+        if !valid_price(price) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Price cannot be negative"
+            })));
+        }
+        variant.price = price;
+    }
+    if let Some(available) = req.available {
+        variant.available = available;
+    }
+    if let Some(display_order) = req.display_order {
+        variant.display_order = display_order;
+    }
+
+    let result = sqlx::query(
+        "UPDATE menu_item_variants SET name = ?, price = ?, available = ?, display_order = ? WHERE id = ?",
+    )
+    .bind(&variant.name)
+    .bind(variant.price)
+    .bind(variant.available)
+    .bind(variant.display_order)
+    .bind(&variant_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::Ok().json(variant)),
+        Err(e) => {
+            log::error!("Database error updating variant: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update variant"
+            })))
+        }
+    }
+}
+
+pub async fn delete_variant(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let variant_id = path.into_inner();
+
+    let restaurant_id = match fetch_variant(pool.get_ref(), &variant_id).await {
+        Ok(Some((restaurant_id, _))) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Variant not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching variant: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !can_manage_menu(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Menu management permission required"
+        })));
+    }
+
+    match sqlx::query("DELETE FROM menu_item_variants WHERE id = ?")
+        .bind(&variant_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting variant: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete variant"
+            })))
+        }
+    }
+}
@@ -0,0 +1,509 @@
+use crate::models::{
+    Claims, CreateWebhookRequest, UpdateWebhookRequest, WebhookDelivery, WebhookDeliveryAttempt,
+    WebhookDeliveryDetail, WebhookDeliveryQuery, WebhookSubscription,
+};
+use crate::permissions::is_super_admin;
+use crate::webhooks::{generate_secret, validate_target_url, WEBHOOK_EVENTS};
+use crate::Settings;
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+use uuid::Uuid;
+
+const DELIVERY_STATUSES: [&str; 4] = ["pending", "sending", "delivered", "failed"];
+
+// The delivery log is a recent window, not an archive
+const DELIVERY_LIST_LIMIT: i64 = 100;
+
+const DELIVERY_SELECT: &str = "SELECT id, subscription_id, restaurant_id, event_id, event_type,
+        status, attempts, next_attempt_at, last_status_code, last_error, delivered_at,
+        redelivery_of, created_at
+    FROM webhook_deliveries";
+
+fn validate_events(events: &[String]) -> Result<(), String> {
+    if events.is_empty() {
+        return Err("Subscribe to at least one event".to_string());
+    }
+    match events
+        .iter()
+        .find(|event| !WEBHOOK_EVENTS.contains(&event.as_str()))
+    {
+        Some(event) => Err(format!(
+            "Unknown event {event}; events must be among: {}",
+            WEBHOOK_EVENTS.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+fn to_utc(at: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::from_naive_utc_and_offset(at, Utc)
+}
+
+fn subscription_from_row(
+    row: &sqlx::sqlite::SqliteRow,
+) -> Result<WebhookSubscription, sqlx::Error> {
+    let events: String = row.try_get("events")?;
+    Ok(WebhookSubscription {
+        id: row.try_get("id")?,
+        restaurant_id: row.try_get("restaurant_id")?,
+        url: row.try_get("url")?,
+        secret: row.try_get("secret")?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        description: row.try_get("description")?,
+        active: row.try_get("active")?,
+        created_at: to_utc(row.try_get("created_at")?),
+    })
+}
+
+fn delivery_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<WebhookDelivery, sqlx::Error> {
+    let status: String = row.try_get("status")?;
+    let next_attempt_at: NaiveDateTime = row.try_get("next_attempt_at")?;
+    let delivered_at: Option<NaiveDateTime> = row.try_get("delivered_at")?;
+    Ok(WebhookDelivery {
+        id: row.try_get("id")?,
+        subscription_id: row.try_get("subscription_id")?,
+        event_id: row.try_get("event_id")?,
+        event_type: row.try_get("event_type")?,
+        // Only deliveries still in the outbox have a next attempt
+        next_attempt_at: matches!(status.as_str(), "pending" | "sending")
+            .then(|| to_utc(next_attempt_at)),
+        status,
+        attempts: row.try_get("attempts")?,
+        last_status_code: row.try_get("last_status_code")?,
+        last_error: row.try_get("last_error")?,
+        delivered_at: delivered_at.map(to_utc),
+        redelivery_of: row.try_get("redelivery_of")?,
+        created_at: to_utc(row.try_get("created_at")?),
+    })
+}
+
+async fn fetch_subscription(
+    pool: &Pool<Sqlite>,
+    subscription_id: &str,
+) -> Result<Option<WebhookSubscription>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT id, restaurant_id, url, secret, events, description, active, created_at
+         FROM webhook_subscriptions WHERE id = ?",
+    )
+    .bind(subscription_id)
+    .fetch_optional(pool)
+    .await?;
+    row.as_ref().map(subscription_from_row).transpose()
+}
+
+async fn fetch_delivery(
+    pool: &Pool<Sqlite>,
+    delivery_id: &str,
+) -> Result<Option<(String, WebhookDelivery)>, sqlx::Error> {
+    let row = sqlx::query(&format!("{DELIVERY_SELECT} WHERE id = ?"))
+        .bind(delivery_id)
+        .fetch_optional(pool)
+        .await?;
+    match row {
+        Some(row) => Ok(Some((
+            row.try_get("restaurant_id")?,
+            delivery_from_row(&row)?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+// Resolves a subscription the caller administers, or the response to send instead
+async fn owned_subscription(
+    pool: &Pool<Sqlite>,
+    subscription_id: &str,
+    user_id: &str,
+) -> Result<WebhookSubscription, HttpResponse> {
+    match fetch_subscription(pool, subscription_id).await {
+        Ok(Some(subscription)) => {
+            if is_super_admin(pool, &subscription.restaurant_id, user_id).await {
+                Ok(subscription)
+            } else {
+                Err(HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": "Only super admins can manage webhooks"
+                })))
+            }
+        }
+        Ok(None) => Err(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Webhook not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching webhook: {e}");
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn list_webhooks(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admins can manage webhooks"
+        })));
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, restaurant_id, url, secret, events, description, active, created_at
+         FROM webhook_subscriptions WHERE restaurant_id = ? ORDER BY created_at, id",
+    )
+    .bind(&restaurant_id)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rows.and_then(|rows| {
+        rows.iter()
+            .map(subscription_from_row)
+            .collect::<Result<Vec<_>, _>>()
+    }) {
+        Ok(subscriptions) => Ok(HttpResponse::Ok().json(subscriptions)),
+        Err(e) => {
+            log::error!("Database error listing webhooks: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn create_webhook(
+    pool: web::Data<Pool<Sqlite>>,
+    settings: web::Data<Settings>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<CreateWebhookRequest>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admins can manage webhooks"
+        })));
+    }
+
+    let url = req.url.trim();
+    let allow_private_targets = settings.allow_private_webhook_targets();
+    if let Err(e) =
+        validate_target_url(url, allow_private_targets).and(validate_events(&req.events))
+    {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": e })));
+    }
+
+    let subscription_id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO webhook_subscriptions (id, restaurant_id, url, secret, events, description, created_by)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&subscription_id)
+    .bind(&restaurant_id)
+    .bind(url)
+    .bind(generate_secret())
+    .bind(serde_json::json!(req.events).to_string())
+    .bind(&req.description)
+    .bind(&claims.sub)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Database error creating webhook: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create webhook"
+        })));
+    }
+
+    match fetch_subscription(pool.get_ref(), &subscription_id).await {
+        Ok(Some(subscription)) => Ok(HttpResponse::Created().json(subscription)),
+        Ok(None) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching webhook: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub async fn update_webhook(
+    pool: web::Data<Pool<Sqlite>>,
+    settings: web::Data<Settings>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<UpdateWebhookRequest>,
+) -> Result<HttpResponse> {
+    let subscription_id = path.into_inner();
+
+    if let Err(response) = owned_subscription(pool.get_ref(), &subscription_id, &claims.sub).await {
+        return Ok(response);
+    }
+
+    let url = req.url.as_deref().map(str::trim);
+    let allow_private_targets = settings.allow_private_webhook_targets();
+    let validation = url
+        .map(|url| validate_target_url(url, allow_private_targets))
+        .transpose()
+        .and(req.events.as_deref().map(validate_events).transpose());
+    if let Err(e) = validation {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": e })));
+    }
+
+    let result = sqlx::query(
+        "UPDATE webhook_subscriptions SET url = COALESCE(?, url), events = COALESCE(?, events),
+            description = COALESCE(?, description), active = COALESCE(?, active)
+         WHERE id = ?",
+    )
+    .bind(url)
+    .bind(
+        req.events
+            .as_ref()
+            .map(|events| serde_json::json!(events).to_string()),
+    )
+    .bind(&req.description)
+    .bind(req.active)
+    .bind(&subscription_id)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Database error updating webhook: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to update webhook"
+        })));
+    }
+
+    match fetch_subscription(pool.get_ref(), &subscription_id).await {
+        Ok(Some(subscription)) => Ok(HttpResponse::Ok().json(subscription)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Webhook not found"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching webhook: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Deleting a subscription also drops its outbox and delivery log
+pub async fn delete_webhook(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let subscription_id = path.into_inner();
+
+    if let Err(response) = owned_subscription(pool.get_ref(), &subscription_id, &claims.sub).await {
+        return Ok(response);
+    }
+
+    let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = ?")
+        .bind(&subscription_id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => Ok(HttpResponse::NoContent().finish()),
+        Err(e) => {
+            log::error!("Database error deleting webhook: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete webhook"
+            })))
+        }
+    }
+}
+
+pub async fn list_webhook_deliveries(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    query: web::Query<WebhookDeliveryQuery>,
+) -> Result<HttpResponse> {
+    let subscription_id = path.into_inner();
+
+    if let Err(response) = owned_subscription(pool.get_ref(), &subscription_id, &claims.sub).await {
+        return Ok(response);
+    }
+
+    if let Some(status) = &query.status {
+        if !DELIVERY_STATUSES.contains(&status.as_str()) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Status must be one of: {}", DELIVERY_STATUSES.join(", "))
+            })));
+        }
+    }
+
+    let rows = sqlx::query(&format!(
+        "{DELIVERY_SELECT} WHERE subscription_id = ? AND (? IS NULL OR status = ?)
+         ORDER BY created_at DESC, rowid DESC LIMIT ?"
+    ))
+    .bind(&subscription_id)
+    .bind(&query.status)
+    .bind(&query.status)
+    .bind(DELIVERY_LIST_LIMIT)
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match rows.and_then(|rows| {
+        rows.iter()
+            .map(delivery_from_row)
+            .collect::<Result<Vec<_>, _>>()
+    }) {
+        Ok(deliveries) => Ok(HttpResponse::Ok().json(deliveries)),
+        Err(e) => {
+            log::error!("Database error listing webhook deliveries: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+async fn delivery_detail(
+    pool: &Pool<Sqlite>,
+    delivery: WebhookDelivery,
+) -> Result<WebhookDeliveryDetail, sqlx::Error> {
+    let payload: String = sqlx::query_scalar("SELECT payload FROM webhook_deliveries WHERE id = ?")
+        .bind(&delivery.id)
+        .fetch_one(pool)
+        .await?;
+    let rows = sqlx::query(
+        "SELECT status_code, error, response_body, duration_ms, attempted_at
+         FROM webhook_delivery_attempts WHERE delivery_id = ? ORDER BY id",
+    )
+    .bind(&delivery.id)
+    .fetch_all(pool)
+    .await?;
+
+    let attempts = rows
+        .iter()
+        .map(|row| {
+            Ok(WebhookDeliveryAttempt {
+                status_code: row.try_get("status_code")?,
+                error: row.try_get("error")?,
+                response_body: row.try_get("response_body")?,
+                duration_ms: row.try_get("duration_ms")?,
+                attempted_at: to_utc(row.try_get("attempted_at")?),
+            })
+        })
+        .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+    Ok(WebhookDeliveryDetail {
+        delivery,
+        payload,
+        attempts,
+    })
+}
+
+pub async fn get_webhook_delivery(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let delivery_id = path.into_inner();
+
+    let delivery = match fetch_delivery(pool.get_ref(), &delivery_id).await {
+        Ok(Some((restaurant_id, delivery))) => {
+            if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+                return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": "Only super admins can manage webhooks"
+                })));
+            }
+            delivery
+        }
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Webhook delivery not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching webhook delivery: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    match delivery_detail(pool.get_ref(), delivery).await {
+        Ok(detail) => Ok(HttpResponse::Ok().json(detail)),
+        Err(e) => {
+            log::error!("Database error fetching webhook delivery attempts: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+// Sends an event again as a new delivery with the same event id, so receivers
+// can tell it apart from a new event. The original keeps its history.
+pub async fn redeliver_webhook(
+    pool: web::Data<Pool<Sqlite>>,
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse> {
+    let delivery_id = path.into_inner();
+
+    let restaurant_id = match fetch_delivery(pool.get_ref(), &delivery_id).await {
+        Ok(Some((restaurant_id, _))) => restaurant_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Webhook delivery not found"
+            })));
+        }
+        Err(e) => {
+            log::error!("Database error fetching webhook delivery: {e}");
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })));
+        }
+    };
+
+    if !is_super_admin(pool.get_ref(), &restaurant_id, &claims.sub).await {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only super admins can manage webhooks"
+        })));
+    }
+
+    let redelivery_id = Uuid::new_v4().to_string();
+    let now = Utc::now().naive_utc();
+    let result = sqlx::query(
+        "INSERT INTO webhook_deliveries (id, subscription_id, restaurant_id, event_id, event_type, payload, next_attempt_at, redelivery_of, created_at)
+         SELECT ?, subscription_id, restaurant_id, event_id, event_type, payload, ?, id, ?
+         FROM webhook_deliveries WHERE id = ?",
+    )
+    .bind(&redelivery_id)
+    .bind(now)
+    .bind(now)
+    .bind(&delivery_id)
+    .execute(pool.get_ref())
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Database error queueing webhook redelivery: {e}");
+        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to queue redelivery"
+        })));
+    }
+
+    match fetch_delivery(pool.get_ref(), &redelivery_id).await {
+        Ok(Some((_, delivery))) => Ok(HttpResponse::Created().json(delivery)),
+        Ok(None) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        }))),
+        Err(e) => {
+            log::error!("Database error fetching webhook delivery: {e}");
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
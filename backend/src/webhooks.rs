@@ -0,0 +1,489 @@
+use chrono::{Duration, Utc};
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const WEBHOOK_EVENTS: [&str; 5] = [
+    "order.created",
+    "order.status_changed",
+    "order.payment_status_changed",
+    "menu.published",
+    "day.closed",
+];
+
+pub const EVENT_HEADER: &str = "X-LetsOrder-Event";
+pub const DELIVERY_HEADER: &str = "X-LetsOrder-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-LetsOrder-Signature";
+
+// Attempts before a delivery is given up on. With the backoff below the last
+// retry happens a little over four hours after the event.
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 10;
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 6 * 60 * 60;
+
+// How long a worker may take to send a claimed delivery before another
+// worker picks it up again
+const DELIVERY_LEASE_SECONDS: i64 = 60;
+const DELIVERY_BATCH_SIZE: i64 = 20;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+const WORKER_INTERVAL_SECONDS: u64 = 5;
+
+// Only the start of a response is kept in the delivery log
+const RESPONSE_BODY_LIMIT: usize = 1024;
+
+pub fn generate_secret() -> String {
+    let bytes: [u8; 24] = rand::thread_rng().gen();
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("whsec_{hex}")
+}
+
+// 30s, 1m, 2m, 4m... capped at six hours
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    Duration::seconds((RETRY_BASE_SECONDS << exponent).min(RETRY_MAX_SECONDS))
+}
+
+// Receivers recompute the HMAC-SHA256 of "{timestamp}.{body}" with their
+// secret and should reject old timestamps to stop replays
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("t={timestamp},v1={:x}", mac.finalize().into_bytes())
+}
+
+// Loopback, private, link-local (which includes cloud metadata endpoints such
+// as 169.254.169.254) and other addresses that are not on the public internet
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (18..20).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+// Checked when a subscription is saved and again before every send. Host
+// names are resolved when sending, see PublicAddressResolver.
+pub fn validate_target_url(url: &str, allow_private_targets: bool) -> Result<(), String> {
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => parsed,
+        _ => return Err("URL must be an absolute http or https URL".to_string()),
+    };
+    if allow_private_targets {
+        return Ok(());
+    }
+
+    let host = parsed.host_str().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let blocked = match host.parse::<IpAddr>() {
+        Ok(ip) => !is_public_address(ip),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost")
+        }
+    };
+    if blocked {
+        return Err("URL must point to a public address".to_string());
+    }
+    Ok(())
+}
+
+// Refuses names that resolve to any non-public address. The client connects
+// to the addresses returned here, so a name cannot be re-pointed at an
+// internal service between the check and the request.
+struct PublicAddressResolver;
+
+impl reqwest::dns::Resolve for PublicAddressResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.is_empty() || addrs.iter().any(|addr| !is_public_address(addr.ip())) {
+                return Err(
+                    format!("{} does not resolve to a public address", name.as_str()).into(),
+                );
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+pub struct WebhookClient {
+    http: reqwest::Client,
+    allow_private_targets: bool,
+}
+
+// Redirects are not followed, since they could lead to an internal address,
+// and proxies are not used, since they would resolve names on our behalf
+pub fn webhook_client(allow_private_targets: bool) -> WebhookClient {
+    let mut builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy();
+    if !allow_private_targets {
+        builder = builder.dns_resolver(Arc::new(PublicAddressResolver));
+    }
+
+    WebhookClient {
+        http: builder.build().expect("webhook HTTP client builds"),
+        allow_private_targets,
+    }
+}
+
+// Queues an event for every active subscription of the restaurant that wants
+// it. Call inside the transaction making the change, so events are only sent
+// for changes that were committed.
+pub async fn enqueue_event(
+    conn: &mut SqliteConnection,
+    restaurant_id: &str,
+    event_type: &str,
+    data: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, events FROM webhook_subscriptions WHERE restaurant_id = ? AND active = TRUE",
+    )
+    .bind(restaurant_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut subscription_ids = Vec::new();
+    for row in rows {
+        let events: String = row.try_get("events")?;
+        let events: Vec<String> = serde_json::from_str(&events).unwrap_or_default();
+        if events.iter().any(|event| event == event_type) {
+            subscription_ids.push(row.try_get::<String, _>("id")?);
+        }
+    }
+    if subscription_ids.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let event_id = Uuid::new_v4().to_string();
+    let payload = serde_json::json!({
+        "id": event_id,
+        "type": event_type,
+        "restaurant_id": restaurant_id,
+        "created_at": now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "data": data,
+    })
+    .to_string();
+
+    for subscription_id in subscription_ids {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (id, subscription_id, restaurant_id, event_id, event_type, payload, next_attempt_at, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&subscription_id)
+        .bind(restaurant_id)
+        .bind(&event_id)
+        .bind(event_type)
+        .bind(&payload)
+        .bind(now.naive_utc())
+        .bind(now.naive_utc())
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Order events carry the order's id and daily number alongside `fields`
+async fn enqueue_order_event(
+    conn: &mut SqliteConnection,
+    order_id: &str,
+    event_type: &str,
+    fields: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    let Some(row) = sqlx::query(
+        "SELECT t.restaurant_id, o.order_number FROM orders o
+         JOIN tables t ON o.table_id = t.id WHERE o.id = ?",
+    )
+    .bind(order_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(());
+    };
+    let restaurant_id: String = row.try_get("restaurant_id")?;
+    let order_number: Option<i64> = row.try_get("order_number")?;
+
+    let mut data = serde_json::json!({
+        "order_id": order_id,
+        "order_number": order_number,
+    });
+    if let (Some(data), serde_json::Value::Object(fields)) = (data.as_object_mut(), fields) {
+        data.extend(fields);
+    }
+    enqueue_event(conn, &restaurant_id, event_type, data).await
+}
+
+pub async fn enqueue_order_status_changed(
+    conn: &mut SqliteConnection,
+    order_id: &str,
+    previous_status: &str,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    enqueue_order_event(
+        conn,
+        order_id,
+        "order.status_changed",
+        serde_json::json!({ "previous_status": previous_status, "status": status }),
+    )
+    .await
+}
+
+pub async fn enqueue_payment_status_changed(
+    conn: &mut SqliteConnection,
+    order_id: &str,
+    payment_status: &str,
+) -> Result<(), sqlx::Error> {
+    enqueue_order_event(
+        conn,
+        order_id,
+        "order.payment_status_changed",
+        serde_json::json!({ "payment_status": payment_status }),
+    )
+    .await
+}
+
+struct DueDelivery {
+    id: String,
+    event_type: String,
+    payload: String,
+    attempts: i32,
+    url: String,
+    secret: String,
+    active: bool,
+}
+
+struct AttemptOutcome {
+    status_code: Option<i32>,
+    error: Option<String>,
+    response_body: Option<String>,
+    duration_ms: i64,
+}
+
+impl AttemptOutcome {
+    fn succeeded(&self) -> bool {
+        self.status_code
+            .is_some_and(|code| (200..300).contains(&code))
+    }
+}
+
+async fn claim_due_deliveries(pool: &Pool<Sqlite>) -> Result<Vec<DueDelivery>, sqlx::Error> {
+    let now = Utc::now().naive_utc();
+    let rows = sqlx::query(
+        "UPDATE webhook_deliveries SET status = 'sending', next_attempt_at = ?
+         WHERE id IN (
+            SELECT id FROM webhook_deliveries
+            WHERE status IN ('pending', 'sending') AND next_attempt_at <= ?
+            ORDER BY next_attempt_at LIMIT ?
+         )
+         RETURNING id",
+    )
+    .bind(now + Duration::seconds(DELIVERY_LEASE_SECONDS))
+    .bind(now)
+    .bind(DELIVERY_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let mut due = Vec::new();
+    for row in rows {
+        let id: String = row.try_get("id")?;
+        let row = sqlx::query(
+            "SELECT d.id, d.event_type, d.payload, d.attempts, s.url, s.secret, s.active
+             FROM webhook_deliveries d
+             JOIN webhook_subscriptions s ON d.subscription_id = s.id
+             WHERE d.id = ?",
+        )
+        .bind(&id)
+        .fetch_one(pool)
+        .await?;
+        due.push(DueDelivery {
+            id: row.try_get("id")?,
+            event_type: row.try_get("event_type")?,
+            payload: row.try_get("payload")?,
+            attempts: row.try_get("attempts")?,
+            url: row.try_get("url")?,
+            secret: row.try_get("secret")?,
+            active: row.try_get("active")?,
+        });
+    }
+    Ok(due)
+}
+
+async fn send(client: &WebhookClient, delivery: &DueDelivery) -> AttemptOutcome {
+    let started = Instant::now();
+    if let Err(e) = validate_target_url(&delivery.url, client.allow_private_targets) {
+        return AttemptOutcome {
+            status_code: None,
+            error: Some(e),
+            response_body: None,
+            duration_ms: 0,
+        };
+    }
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(&delivery.secret, timestamp, delivery.payload.as_bytes());
+
+    let result = client
+        .http
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(DELIVERY_HEADER, &delivery.id)
+        .header(SIGNATURE_HEADER, signature)
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    let (status_code, error, response_body) = match result {
+        Ok(response) => {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let body: String = body.chars().take(RESPONSE_BODY_LIMIT).collect();
+            let error = (!status.is_success()).then(|| format!("Endpoint responded {status}"));
+            (Some(status.as_u16() as i32), error, Some(body))
+        }
+        // The resolver's refusal is the source of the request error
+        Err(e) => {
+            let error = match std::error::Error::source(&e) {
+                Some(source) => format!("{e}: {source}"),
+                None => e.to_string(),
+            };
+            (None, Some(error), None)
+        }
+    };
+
+    AttemptOutcome {
+        status_code,
+        error,
+        response_body,
+        duration_ms: started.elapsed().as_millis() as i64,
+    }
+}
+
+async fn record_attempt(
+    pool: &Pool<Sqlite>,
+    delivery: &DueDelivery,
+    outcome: &AttemptOutcome,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let attempts = delivery.attempts + 1;
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO webhook_delivery_attempts (delivery_id, status_code, error, response_body, duration_ms, attempted_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&delivery.id)
+    .bind(outcome.status_code)
+    .bind(&outcome.error)
+    .bind(&outcome.response_body)
+    .bind(outcome.duration_ms)
+    .bind(now.naive_utc())
+    .execute(&mut *tx)
+    .await?;
+
+    let (status, next_attempt_at, delivered_at) = if outcome.succeeded() {
+        ("delivered", now, Some(now))
+    } else if attempts >= MAX_DELIVERY_ATTEMPTS {
+        ("failed", now, None)
+    } else {
+        ("pending", now + retry_delay(attempts), None)
+    };
+    sqlx::query(
+        "UPDATE webhook_deliveries SET status = ?, attempts = ?, next_attempt_at = ?,
+            last_status_code = ?, last_error = ?, delivered_at = ?
+         WHERE id = ?",
+    )
+    .bind(status)
+    .bind(attempts)
+    .bind(next_attempt_at.naive_utc())
+    .bind(outcome.status_code)
+    .bind(&outcome.error)
+    .bind(delivered_at.map(|at| at.naive_utc()))
+    .bind(&delivery.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+// Sends every delivery that is due and returns how many were claimed.
+// Deliveries for disabled subscriptions are failed without a request.
+pub async fn deliver_due_webhooks(
+    pool: &Pool<Sqlite>,
+    client: &WebhookClient,
+) -> Result<usize, sqlx::Error> {
+    let due = claim_due_deliveries(pool).await?;
+    let count = due.len();
+
+    let (active, disabled): (Vec<DueDelivery>, Vec<DueDelivery>) =
+        due.into_iter().partition(|delivery| delivery.active);
+    for delivery in disabled {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = 'failed', last_error = 'Subscription is disabled'
+             WHERE id = ?",
+        )
+        .bind(&delivery.id)
+        .execute(pool)
+        .await?;
+    }
+
+    let outcomes = join_all(active.iter().map(|delivery| send(client, delivery))).await;
+    for (delivery, outcome) in active.iter().zip(outcomes) {
+        record_attempt(pool, delivery, &outcome).await?;
+    }
+
+    Ok(count)
+}
+
+// Polls the outbox for the lifetime of the server
+pub fn spawn_webhook_worker(pool: Pool<Sqlite>, allow_private_targets: bool) {
+    tokio::spawn(async move {
+        let client = webhook_client(allow_private_targets);
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(WORKER_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = deliver_due_webhooks(&pool, &client).await {
+                log::error!("Database error delivering webhooks: {e}");
+            }
+        }
+    });
+}
@@ -0,0 +1,283 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_scoped_api_keys() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salmon): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.name = 'Grilled Salmon'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+    let keys_uri = format!("/api/restaurants/{restaurant_id}/api-keys");
+    let bearer = |credential: &str| ("Authorization", format!("Bearer {credential}"));
+
+    for payload in [
+        json!({"name": "POS", "scopes": ["orders:delete"]}),
+        json!({"name": "POS", "scopes": []}),
+        json!({"name": " ", "scopes": ["orders:read"]}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&keys_uri)
+            .insert_header(bearer(&token))
+            .set_json(payload)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    let create_key = |name: &str, scopes: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&keys_uri)
+            .insert_header(bearer(&token))
+            .set_json(json!({"name": name, "scopes": scopes}))
+            .to_request()
+    };
+    let resp = test::call_service(
+        &app,
+        create_key("POS", json!(["orders:read", "orders:status"])),
+    )
+    .await;
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = test::read_body_json(resp).await;
+    let pos_key = created["key"].as_str().unwrap().to_string();
+    let pos_key_id = created["api_key"]["id"].as_str().unwrap().to_string();
+    assert!(pos_key.starts_with("lok_"));
+    assert_eq!(created["api_key"]["key_prefix"], pos_key[..12]);
+    assert!(created["api_key"]["last_used_at"].is_null());
+
+    // Only the hash is stored
+    let stored: String = sqlx::query_scalar("SELECT key_hash FROM api_keys WHERE id = ?")
+        .bind(&pos_key_id)
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    assert_ne!(stored, pos_key);
+    assert!(!stored.contains(&pos_key[4..]));
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": salmon, "quantity": 1, "special_requests": null}]
+        }))
+        .to_request();
+    let order: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = order["order_id"].as_str().unwrap().to_string();
+
+    // The key reads orders and moves them along
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/orders"))
+        .insert_header(bearer(&pos_key))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let page: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(page["items"][0]["id"], order_id);
+
+    let status_uri = format!("/api/orders/{order_id}/status");
+    let set_status = |credential: &str, status: &str| {
+        test::TestRequest::put()
+            .uri(&status_uri)
+            .insert_header(bearer(credential))
+            .set_json(json!({"status": status}))
+            .to_request()
+    };
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, set_status(&pos_key, "delivered")).await;
+    assert_eq!(body["previous_status"], "pending");
+    assert_eq!(body["status"], "delivered");
+    let resp = test::call_service(&app, set_status(&pos_key, "delivered")).await;
+    assert_eq!(resp.status(), 200);
+    let resp = test::call_service(&app, set_status(&pos_key, "preparing")).await;
+    assert_eq!(resp.status(), 409);
+    let resp = test::call_service(&app, set_status(&token, "served")).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/print-jobs/claim"
+        ))
+        .insert_header(bearer(&pos_key))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    // Outside its scopes and its restaurant the key is refused
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/sections"))
+        .insert_header(bearer(&pos_key))
+        .set_json(json!({"name": "Specials", "display_order": null}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::get()
+        .uri("/api/restaurants/some-other-restaurant/orders")
+        .insert_header(bearer(&pos_key))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::get()
+        .uri(&keys_uri)
+        .insert_header(bearer(&pos_key))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/orders"))
+        .insert_header(bearer("lok_not-a-real-key"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // A menu key manages sections and items
+    let created: serde_json::Value =
+        test::call_and_read_body_json(&app, create_key("Menu sync", json!(["menu:manage"]))).await;
+    let menu_key = created["key"].as_str().unwrap().to_string();
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/sections"))
+        .insert_header(bearer(&menu_key))
+        .set_json(json!({"name": "Specials", "display_order": null}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{salmon}/availability"))
+        .insert_header(bearer(&menu_key))
+        .set_json(json!({"available": false}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let resp = test::call_service(&app, set_status(&menu_key, "cancelled")).await;
+    assert_eq!(resp.status(), 403);
+
+    // ...and can publish the draft and roll it back
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(bearer(&menu_key))
+        .set_json(json!({"note": "Synced from POS"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let version: serde_json::Value = test::read_body_json(resp).await;
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/versions/{}/rollback",
+            version["id"].as_str().unwrap()
+        ))
+        .insert_header(bearer(&menu_key))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&keys_uri)
+        .insert_header(bearer(&token))
+        .to_request();
+    let keys: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(keys.as_array().unwrap().len(), 2);
+    let pos = keys
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|key| key["id"] == pos_key_id)
+        .unwrap();
+    assert!(pos["last_used_at"].is_string());
+    assert!(pos.get("key").is_none() && pos.get("key_hash").is_none());
+
+    // Revoked keys stop working at once and stay listed
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/api-keys/{pos_key_id}"))
+        .insert_header(bearer(&token))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/orders"))
+        .insert_header(bearer(&pos_key))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::get()
+        .uri(&keys_uri)
+        .insert_header(bearer(&token))
+        .to_request();
+    let keys: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let pos = keys
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|key| key["id"] == pos_key_id)
+        .unwrap();
+    assert!(pos["revoked_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_api_keys_are_refused_outside_their_routes() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    let bearer = |credential: &str| ("Authorization", format!("Bearer {credential}"));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/api-keys"))
+        .insert_header(bearer(&token))
+        .set_json(json!({"name": "Dashboard", "scopes": ["orders:read"]}))
+        .to_request();
+    let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let key = created["key"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/orders"))
+        .insert_header(bearer(&key))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // Routes that only check for a signed-in caller still refuse keys
+    for uri in [
+        "/api/contact/submissions",
+        "/api/user/restaurants",
+        "/api/test",
+    ] {
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header(bearer(&key))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403, "{uri}");
+    }
+
+    // A listed route still needs the scope it is listed with
+    let req = test::TestRequest::put()
+        .uri("/api/orders/some-order/status")
+        .insert_header(bearer(&key))
+        .set_json(json!({"status": "confirmed"}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+}
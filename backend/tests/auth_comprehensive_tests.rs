@@ -114,7 +114,7 @@ async fn test_jwt_token_generation() {
     let user = create_test_user("test_user_id", "test@example.com");
 
     let token = jwt_manager
-        .generate_token(&user)
+        .generate_token(&user, "test_session_id")
         .expect("Failed to generate token");
 
     assert!(!token.is_empty());
@@ -130,7 +130,7 @@ async fn test_jwt_token_validation() {
     let user = create_test_user("test_user_id", "test@example.com");
 
     let token = jwt_manager
-        .generate_token(&user)
+        .generate_token(&user, "test_session_id")
         .expect("Failed to generate token");
 
     let claims = jwt_manager
@@ -148,7 +148,7 @@ async fn test_jwt_token_invalid_signature() {
     let user = create_test_user("user_id", "test@example.com");
 
     let token = jwt_manager1
-        .generate_token(&user)
+        .generate_token(&user, "test_session_id")
         .expect("Failed to generate token");
 
     // Token signed with different key should be invalid
@@ -212,11 +212,11 @@ async fn test_jwt_token_expired() {
 
 #[tokio::test]
 async fn test_jwt_token_claims_content() {
-    let jwt_manager = JwtManager::new("test_secret_key".to_string(), 24);
+    let jwt_manager = JwtManager::new("test_secret_key".to_string(), 15);
     let user = create_test_user("user_123", "user@example.com");
 
     let token = jwt_manager
-        .generate_token(&user)
+        .generate_token(&user, "test_session_id")
         .expect("Failed to generate token");
 
     let claims = jwt_manager
@@ -226,11 +226,12 @@ async fn test_jwt_token_claims_content() {
     // Verify all claim fields
     assert_eq!(claims.sub, user.id);
     assert_eq!(claims.email, user.email);
+    assert_eq!(claims.sid.as_deref(), Some("test_session_id"));
     assert!(claims.exp > claims.iat);
 
-    // Verify expiration is approximately 24 hours from now
+    // Verify expiration is approximately 15 minutes from now
     let now = Utc::now().timestamp() as usize;
-    let expected_exp = now + (24 * 60 * 60);
+    let expected_exp = now + (15 * 60);
     assert!((claims.exp as i64 - expected_exp as i64).abs() < 60); // Within 1 minute
 }
 
@@ -434,7 +435,7 @@ async fn test_user_login_valid_credentials() {
 
     // Simulate login process
     let stored_user = sqlx::query!(
-        "SELECT id, email, phone, password_hash FROM users WHERE email = ?",
+        "SELECT id, email, phone, password_hash, email_verified FROM users WHERE email = ?",
         email
     )
     .fetch_optional(&pool)
@@ -455,13 +456,13 @@ async fn test_user_login_valid_credentials() {
         email: user_row.email,
         phone: user_row.phone,
         password_hash: user_row.password_hash,
-        email_verified: user_row.email_verified.unwrap_or(false),
+        email_verified: user_row.email_verified,
         created_at: Utc::now(),
     };
 
     // Generate token
     let token = jwt_manager
-        .generate_token(&user)
+        .generate_token(&user, "test_session_id")
         .expect("Failed to generate token");
     assert!(!token.is_empty());
 }
@@ -576,12 +577,11 @@ async fn test_token_replay_attack_prevention() {
     let user = create_test_user("user_id", "test@example.com");
 
     let token = jwt_manager
-        .generate_token(&user)
+        .generate_token(&user, "test_session_id")
         .expect("Failed to generate token");
 
-    // Token should be valid multiple times (JWT is stateless)
-    // In a real implementation, you might implement token blacklisting
-    // or short-lived tokens with refresh tokens
+    // The signature check alone is stateless; revocation happens in the
+    // middleware, which also requires the token's session to be live
 
     let claims1 = jwt_manager
         .validate_token(&token)
@@ -0,0 +1,274 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_bundle_is_listed_and_expanded_into_components() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String =
+        sqlx::query_scalar("SELECT restaurant_id FROM menu_sections LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    let item_id = |name: &'static str| {
+        let pool = test_app.pool.clone();
+        async move {
+            sqlx::query_scalar::<_, String>("SELECT id FROM menu_items WHERE name = ?")
+                .bind(name)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        }
+    };
+    let salad = item_id("Caesar Salad").await;
+    let bread = item_id("Garlic Bread").await;
+    let salmon = item_id("Grilled Salmon").await;
+    let chicken = item_id("Chicken Parmesan").await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/bundles"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "name": "Lunch Deal",
+            "description": "Starter and main",
+            "price": 30.0,
+            "slots": [
+                {"name": "Starter", "item_ids": [salad, bread]},
+                {"name": "Main", "item_ids": [salmon, chicken]}
+            ]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let bundle: serde_json::Value = test::read_body_json(resp).await;
+    let bundle_id = bundle["id"].as_str().unwrap().to_string();
+    let starter_slot = bundle["slots"][0]["id"].as_str().unwrap().to_string();
+    let main_slot = bundle["slots"][1]["id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(menu["bundles"][0]["name"], "Lunch Deal");
+    assert_eq!(menu["bundles"][0]["price"], 30.0);
+    assert_eq!(
+        menu["bundles"][0]["slots"][0]["choices"],
+        json!([
+            {"item_id": salad, "name": "Caesar Salad"},
+            {"item_id": bread, "name": "Garlic Bread"}
+        ])
+    );
+
+    // Every slot needs exactly one of its listed choices
+    for choices in [
+        json!([{"slot_id": starter_slot, "menu_item_id": salad}]),
+        json!([
+            {"slot_id": starter_slot, "menu_item_id": salad},
+            {"slot_id": main_slot, "menu_item_id": bread}
+        ]),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [],
+                "bundles": [{"bundle_id": bundle_id, "quantity": 1, "choices": choices}]
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "invalid_bundle_choice");
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": bread, "quantity": 1, "special_requests": null}],
+            "bundles": [{
+                "bundle_id": bundle_id,
+                "quantity": 2,
+                "choices": [
+                    {"slot_id": starter_slot, "menu_item_id": salad},
+                    {"slot_id": main_slot, "menu_item_id": salmon}
+                ],
+                "special_requests": "No croutons"
+            }]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(created["total_amount"], 68.99);
+
+    let order_id = created["order_id"].as_str().unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let order: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let lines = order["items"].as_array().unwrap();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["menu_item_name"], "Garlic Bread");
+    assert_eq!(lines[0]["bundle_id"], serde_json::Value::Null);
+
+    // The bundle price is split 12.99 : 24.99 over its components
+    assert_eq!(lines[1]["menu_item_name"], "Caesar Salad");
+    assert_eq!(lines[1]["bundle_name"], "Lunch Deal");
+    assert_eq!(lines[1]["quantity"], 2);
+    assert_eq!(lines[1]["price"], 10.26);
+    assert_eq!(lines[1]["special_requests"], "No croutons");
+    assert_eq!(lines[2]["menu_item_name"], "Grilled Salmon");
+    assert_eq!(lines[2]["bundle_id"], bundle_id.as_str());
+    assert_eq!(lines[2]["price"], 19.74);
+
+    // A slot left without anything orderable hides the bundle
+    for item in [&salmon, &chicken] {
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/items/{item}/availability"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"available": false}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(menu["bundles"], json!([]));
+}
+
+#[tokio::test]
+async fn test_manage_bundles() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, cake, tiramisu): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, c.id, t.id FROM menu_items c, menu_items t
+         JOIN menu_sections ms ON c.section_id = ms.id
+         WHERE c.name = 'Chocolate Cake' AND t.name = 'Tiramisu'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    for payload in [
+        json!({"name": " ", "price": 10.0, "slots": [{"name": "Dessert", "item_ids": [cake]}]}),
+        json!({"name": "Sweet", "price": -1.0, "slots": [{"name": "Dessert", "item_ids": [cake]}]}),
+        json!({"name": "Sweet", "price": 10.0, "slots": []}),
+        json!({"name": "Sweet", "price": 10.0, "slots": [{"name": "Dessert", "item_ids": []}]}),
+        json!({"name": "Sweet", "price": 10.0, "slots": [{"name": "Dessert", "item_ids": ["missing"]}]}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/bundles"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/bundles"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "name": "Sweet Tooth",
+            "price": 15.0,
+            "slots": [{"name": "Dessert", "item_ids": [cake]}]
+        }))
+        .to_request();
+    let bundle: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let bundle_id = bundle["id"].as_str().unwrap().to_string();
+    assert_eq!(bundle["available"], true);
+    assert_eq!(bundle["display_order"], 1);
+
+    // New slots replace the old ones
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/bundles/{bundle_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "price": 16.5,
+            "slots": [
+                {"name": "First", "item_ids": [cake, tiramisu]},
+                {"name": "Second", "item_ids": [tiramisu]}
+            ]
+        }))
+        .to_request();
+    let updated: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated["name"], "Sweet Tooth");
+    assert_eq!(updated["price"], 16.5);
+    assert_eq!(updated["slots"][0]["item_ids"], json!([cake, tiramisu]));
+    assert_eq!(updated["slots"][1]["name"], "Second");
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/bundles/{bundle_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"available": false}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(menu["bundles"], json!([]));
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [],
+            "bundles": [{"bundle_id": bundle_id, "quantity": 1, "choices": []}]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    for expected in [204, 404] {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/bundles/{bundle_id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), expected);
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/bundles"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let bundles: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(bundles, json!([]));
+}
@@ -65,6 +65,8 @@ impl TestRestaurant {
             address: self.address.clone(),
             establishment_year: self.establishment_year,
             google_maps_link: self.google_maps_link.clone(),
+            timezone: None,
+            default_language: None,
         }
     }
 }
@@ -1,10 +1,11 @@
-use actix_web::{test, web, App};
-use backend::{auth::JwtManager, init_database, seed_database_if_empty, Settings};
+use backend::{auth::JwtManager, init_database, seed_database_if_empty, PaymentSettings, Settings};
 use sqlx::{Pool, Sqlite};
 use std::sync::Once;
 
 static INIT: Once = Once::new();
 
+pub const TEST_PAYMENT_WEBHOOK_SECRET: &str = "test-payment-webhook-secret";
+
 pub struct TestApp {
     pub pool: Pool<Sqlite>,
     pub jwt_manager: JwtManager,
@@ -29,10 +30,19 @@ impl TestApp {
 
         let jwt_manager = JwtManager::new(
             "test-secret-key-for-testing-only".to_string(),
-            24, // 24 hours expiration
+            15, // 15 minute access tokens
         );
 
-        let settings = Settings::default();
+        // Payments are off by default; tests run against the fake gateway
+        let settings = Settings {
+            payments: Some(PaymentSettings {
+                provider: "fake".to_string(),
+                currency: "INR".to_string(),
+                webhook_secret: Some(TEST_PAYMENT_WEBHOOK_SECRET.to_string()),
+                enabled: true,
+            }),
+            ..Settings::default()
+        };
 
         Self {
             pool,
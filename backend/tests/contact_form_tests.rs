@@ -250,7 +250,7 @@ async fn test_rate_limiting_basic() {
     for i in 0..5 {
         let req = test::TestRequest::post()
             .uri("/contact")
-            .set_json(&json!({
+            .set_json(json!({
                 "name": "John Doe",
                 "email": format!("john{}@example.com", i),
                 "message": "Test message"
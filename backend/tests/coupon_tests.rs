@@ -0,0 +1,299 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_coupon_discounts_order_and_is_recorded() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad, bread): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, s.id, b.id FROM menu_items s, menu_items b
+         JOIN menu_sections ms ON s.section_id = ms.id
+         WHERE s.name = 'Caesar Salad' AND b.name = 'Garlic Bread'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/coupons"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "code": "welcome10",
+            "discount_type": "percentage",
+            "discount_value": 10.0,
+            "min_order_amount": 20.0,
+            "max_uses": 1
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let coupon: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(coupon["code"], "WELCOME10");
+
+    let order = |code: &str, items: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": items,
+                "coupon_code": code
+            }))
+            .to_request()
+    };
+
+    // 12.99 is below the minimum order amount
+    let resp = test::call_service(
+        &app,
+        order(
+            "WELCOME10",
+            json!([{"menu_item_id": salad, "quantity": 1, "special_requests": null}]),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "coupon_minimum_not_met");
+
+    let resp = test::call_service(
+        &app,
+        order(
+            "NOPE",
+            json!([{"menu_item_id": salad, "quantity": 2, "special_requests": null}]),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "coupon_not_found");
+
+    // Codes are matched case-insensitively
+    let resp = test::call_service(
+        &app,
+        order(
+            "welcome10",
+            json!([{"menu_item_id": salad, "quantity": 2, "special_requests": null}]),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(created["total_amount"], 23.38);
+    assert_eq!(created["discount"]["coupon_code"], "WELCOME10");
+    assert_eq!(created["discount"]["amount"], 2.6);
+
+    let order_id = created["order_id"].as_str().unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let stored: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stored["total_amount"], 23.38);
+    assert_eq!(stored["discount"]["amount"], 2.6);
+
+    // The single use has been taken
+    let resp = test::call_service(
+        &app,
+        order(
+            "WELCOME10",
+            json!([{"menu_item_id": salad, "quantity": 2, "special_requests": null}]),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "coupon_exhausted");
+
+    // Cancelling the order gives the use back
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/orders/{order_id}/status"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"status": "cancelled"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = test::call_service(
+        &app,
+        order(
+            "WELCOME10",
+            json!([{"menu_item_id": salad, "quantity": 2, "special_requests": null}]),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 201);
+
+    // An item-scoped fixed coupon only discounts that item, up to its value
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/coupons"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "code": "BREAD",
+            "discount_type": "fixed",
+            "discount_value": 50.0,
+            "item_id": bread
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::call_service(
+        &app,
+        order(
+            "BREAD",
+            json!([{"menu_item_id": salad, "quantity": 1, "special_requests": null}]),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "coupon_not_applicable");
+
+    let created: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        order(
+            "BREAD",
+            json!([
+                {"menu_item_id": salad, "quantity": 1, "special_requests": null},
+                {"menu_item_id": bread, "quantity": 1, "special_requests": null}
+            ]),
+        ),
+    )
+    .await;
+    assert_eq!(created["total_amount"], 12.99);
+    assert_eq!(created["discount"]["amount"], 8.99);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/coupons"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let coupons: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let welcome = coupons
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|coupon| coupon["code"] == "WELCOME10")
+        .unwrap();
+    assert_eq!(welcome["times_used"], 1);
+}
+
+#[tokio::test]
+async fn test_coupon_validation_and_validity_window() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.name = 'Caesar Salad'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    for payload in [
+        json!({"code": "X", "discount_type": "fixed", "discount_value": 5.0}),
+        json!({"code": "HAS SPACE", "discount_type": "fixed", "discount_value": 5.0}),
+        json!({"code": "BOGO", "discount_type": "bogo", "discount_value": 5.0}),
+        json!({"code": "TOOMUCH", "discount_type": "percentage", "discount_value": 150.0}),
+        json!({"code": "ZERO", "discount_type": "fixed", "discount_value": 0.0}),
+        json!({"code": "NOUSES", "discount_type": "fixed", "discount_value": 5.0, "max_uses": 0}),
+        json!({"code": "MISSING", "discount_type": "fixed", "discount_value": 5.0, "item_id": "missing"}),
+        json!({"code": "BACKWARDS", "discount_type": "fixed", "discount_value": 5.0,
+               "valid_from": "2025-02-01T00:00:00Z", "valid_until": "2025-01-01T00:00:00Z"}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/coupons"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400, "{payload}");
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/coupons"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "code": "EXPIRED",
+            "discount_type": "fixed",
+            "discount_value": 5.0,
+            "valid_from": "2025-01-01T00:00:00Z",
+            "valid_until": "2025-02-01T00:00:00Z"
+        }))
+        .to_request();
+    let coupon: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let coupon_id = coupon["id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/coupons"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"code": "expired", "discount_type": "fixed", "discount_value": 1.0}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+
+    let order = || {
+        test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [{"menu_item_id": salad, "quantity": 1, "special_requests": null}],
+                "coupon_code": "EXPIRED"
+            }))
+            .to_request()
+    };
+    let resp = test::call_service(&app, order()).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "coupon_not_valid");
+
+    // Extending the window makes the coupon usable again
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/coupons/{coupon_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"valid_until": "2100-01-01T00:00:00Z"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let created: serde_json::Value = test::call_and_read_body_json(&app, order()).await;
+    assert_eq!(created["total_amount"], 7.99);
+    assert_eq!(created["discount"]["amount"], 5.0);
+
+    for expected in [204, 404] {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/coupons/{coupon_id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), expected);
+    }
+}
@@ -0,0 +1,288 @@
+use actix_web::test;
+use backend::create_app;
+use backend::payments::{FakePaymentGateway, WebhookEvent, FAKE_CARD_SUCCESS};
+use serde_json::json;
+
+mod common;
+use common::test_app::{create_test_app, TEST_PAYMENT_WEBHOOK_SECRET};
+
+#[tokio::test]
+async fn test_close_day_freezes_summary_until_reopened() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad, bread): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, s.id, b.id FROM menu_items s, menu_items b
+         JOIN menu_sections ms ON s.section_id = ms.id
+         WHERE s.name = 'Caesar Salad' AND b.name = 'Garlic Bread'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+    let today = chrono::Utc::now()
+        .date_naive()
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let order = |menu_item_id: &str, quantity: i32, prepaid: bool| {
+        test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [{"menu_item_id": menu_item_id, "quantity": quantity, "special_requests": null}],
+                "pay_before_kitchen": prepaid
+            }))
+            .to_request()
+    };
+    let set_status = |order_id: String, status: &'static str| {
+        let pool = test_app.pool.clone();
+        async move {
+            sqlx::query("UPDATE orders SET status = ? WHERE id = ?")
+                .bind(status)
+                .bind(order_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    };
+
+    // Paid at the table and served. Days are taken from the order's business
+    // date, not its UTC creation time.
+    let served: serde_json::Value =
+        test::call_and_read_body_json(&app, order(&salad, 1, false)).await;
+    let served_id = served["order_id"].as_str().unwrap().to_string();
+    set_status(served_id.clone(), "delivered").await;
+    sqlx::query("UPDATE orders SET created_at = datetime(created_at, '-2 days') WHERE id = ?")
+        .bind(&served_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    // Prepaid online, partly refunded and still in the kitchen
+    let prepaid: serde_json::Value =
+        test::call_and_read_body_json(&app, order(&salad, 2, true)).await;
+    let prepaid_id = prepaid["order_id"].as_str().unwrap().to_string();
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{prepaid_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let refund = || {
+        test::TestRequest::post()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/orders/{prepaid_id}/refund"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"amount": 5.0}))
+            .to_request()
+    };
+    let resp = test::call_service(&app, refund()).await;
+    assert_eq!(resp.status(), 200);
+
+    let cancelled: serde_json::Value =
+        test::call_and_read_body_json(&app, order(&bread, 1, false)).await;
+    set_status(
+        cancelled["order_id"].as_str().unwrap().to_string(),
+        "cancelled",
+    )
+    .await;
+
+    // Never paid, so not a sale yet
+    let resp = test::call_service(&app, order(&bread, 1, true)).await;
+    assert_eq!(resp.status(), 201);
+    let unpaid: serde_json::Value = test::read_body_json(resp).await;
+    let unpaid_id = unpaid["order_id"].as_str().unwrap().to_string();
+    let unpaid_intent = unpaid["payment"]["intent_id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/closings/preview"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let preview: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(preview["business_date"], today.as_str());
+    // Orders still in the kitchen or awaiting payment are left out of the totals
+    assert_eq!(preview["order_count"], 1);
+    assert_eq!(preview["cancelled_count"], 1);
+    assert_eq!(preview["gross_sales"], 12.99);
+    assert_eq!(preview["refunds"], 0.0);
+    assert_eq!(preview["taxes"], 0.0);
+    assert_eq!(preview["net_sales"], 12.99);
+    assert_eq!(
+        preview["payment_methods"],
+        json!([{"method": "pay_at_table", "order_count": 1, "amount": 12.99}])
+    );
+    assert_eq!(preview["in_progress_orders"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        preview["in_progress_orders"][0]["order_id"],
+        prepaid_id.as_str()
+    );
+    assert_eq!(
+        preview["in_progress_orders"][1]["order_id"],
+        unpaid_id.as_str()
+    );
+
+    let close = || {
+        test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/closings"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"business_date": null}))
+            .to_request()
+    };
+    let resp = test::call_service(&app, close()).await;
+    assert_eq!(resp.status(), 201);
+    let closing: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(closing["summary"], preview);
+
+    let resp = test::call_service(&app, close()).await;
+    assert_eq!(resp.status(), 409);
+
+    // The closed day takes no new orders, payments or refunds
+    let resp = test::call_service(&app, order(&salad, 1, false)).await;
+    assert_eq!(resp.status(), 422);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "day_closed");
+    let resp = test::call_service(&app, refund()).await;
+    assert_eq!(resp.status(), 409);
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{unpaid_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let payload = FakePaymentGateway::webhook_payload(&WebhookEvent::PaymentSucceeded {
+        intent_id: unpaid_intent,
+    });
+    let req = test::TestRequest::post()
+        .uri("/payments/webhook")
+        .insert_header((
+            "X-Payment-Signature",
+            FakePaymentGateway::new(TEST_PAYMENT_WEBHOOK_SECRET).sign_webhook(&payload),
+        ))
+        .set_payload(payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let payment_status: String =
+        sqlx::query_scalar("SELECT payment_status FROM orders WHERE id = ?")
+            .bind(&unpaid_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(payment_status, "awaiting_payment");
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/orders/{unpaid_id}/status"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"status": "cancelled"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "day_closed");
+
+    let frozen = sqlx::query("UPDATE day_closings SET summary = '{}'")
+        .execute(&test_app.pool)
+        .await;
+    assert!(frozen.is_err());
+
+    let closing_uri = format!("/api/restaurants/{restaurant_id}/closings/{today}");
+    let req = test::TestRequest::get()
+        .uri(&closing_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let stored: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stored["summary"]["net_sales"], 12.99);
+
+    let reopen = |reason: &str| {
+        test::TestRequest::post()
+            .uri(&format!("{closing_uri}/reopen"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"reason": reason}))
+            .to_request()
+    };
+
+    // Only super admins may reopen
+    sqlx::query("UPDATE restaurant_managers SET role = 'manager' WHERE restaurant_id = ?")
+        .bind(&restaurant_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    let resp = test::call_service(&app, reopen("Missed a table")).await;
+    assert_eq!(resp.status(), 403);
+    sqlx::query("UPDATE restaurant_managers SET role = 'super_admin' WHERE restaurant_id = ?")
+        .bind(&restaurant_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let resp = test::call_service(&app, reopen("  ")).await;
+    assert_eq!(resp.status(), 400);
+    let resp = test::call_service(&app, reopen("Missed a table")).await;
+    assert_eq!(resp.status(), 204);
+    let resp = test::call_service(&app, reopen("Missed a table")).await;
+    assert_eq!(resp.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{closing_uri}/audit"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let audit: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(audit[0]["action"], "closed");
+    assert_eq!(audit[1]["action"], "reopened");
+    assert_eq!(audit[1]["reason"], "Missed a table");
+
+    // Reopened days take orders and payments again and can be closed afresh
+    let resp = test::call_service(&app, order(&salad, 1, false)).await;
+    assert_eq!(resp.status(), 201);
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{unpaid_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    set_status(prepaid_id.clone(), "delivered").await;
+    let resp = test::call_service(&app, close()).await;
+    assert_eq!(resp.status(), 201);
+    let closing: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(closing["summary"]["order_count"], 2);
+    assert_eq!(closing["summary"]["gross_sales"], 38.97);
+    assert_eq!(closing["summary"]["refunds"], 5.0);
+    assert_eq!(closing["summary"]["net_sales"], 33.97);
+    assert_eq!(
+        closing["summary"]["in_progress_orders"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/closings"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let closings: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(closings.as_array().unwrap().len(), 2);
+    assert_eq!(closings[1]["reopen_reason"], "Missed a table");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/closings"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"business_date": "2999-01-01"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
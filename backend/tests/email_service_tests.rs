@@ -1,6 +1,5 @@
 use backend::email_service::{EmailRequest, EmailService, EmailType};
 use std::collections::HashMap;
-use tokio_test;
 
 #[tokio::test]
 async fn test_email_service_initialization() {
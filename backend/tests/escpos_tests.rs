@@ -0,0 +1,121 @@
+use backend::escpos::{render_kitchen_ticket, render_receipt, PaperWidth, PrintLine, PrintOrder};
+use std::path::PathBuf;
+
+fn sample_order() -> PrintOrder {
+    PrintOrder {
+        order_id: "0f8e4c2a9b7d4e1f8a6c3b5d7e9f1a2b".to_string(),
+        order_number: Some(42),
+        restaurant_name: "Café Aurora".to_string(),
+        restaurant_address: Some("12 Harbour Street, Kochi".to_string()),
+        table_name: "Table 7".to_string(),
+        customer_name: Some("Asha".to_string()),
+        placed_at: "2025-03-10 19:45".to_string(),
+        lines: vec![
+            PrintLine {
+                quantity: 2,
+                name: "Grilled Salmon".to_string(),
+                variant_name: Some("Large".to_string()),
+                bundle_name: None,
+                notes: Some("No lemon, sauce on the side please".to_string()),
+                line_total: 51.98,
+            },
+            PrintLine {
+                quantity: 1,
+                name: "Slow-Roasted Heirloom Tomato and Burrata Salad".to_string(),
+                variant_name: None,
+                bundle_name: Some("Lunch Deal".to_string()),
+                notes: None,
+                line_total: 9.5,
+            },
+            PrintLine {
+                quantity: 3,
+                name: "Crème Brûlée".to_string(),
+                variant_name: None,
+                bundle_name: None,
+                notes: Some(" ".to_string()),
+                line_total: 18.0,
+            },
+        ],
+        coupon_code: Some("SPRING10".to_string()),
+        discount_amount: 7.95,
+        total_amount: 71.53,
+        payment_status: "paid".to_string(),
+        order_url: "https://yourdomain.com/order/0f8e4c2a9b7d4e1f8a6c3b5d7e9f1a2b".to_string(),
+    }
+}
+
+// Rewrite the fixtures with UPDATE_GOLDEN=1 after an intended layout change
+fn assert_golden(name: &str, bytes: &[u8]) {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "fixtures",
+        "escpos",
+        name,
+    ]
+    .iter()
+    .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, bytes).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("missing golden file {}: {e}", path.display()));
+    assert!(
+        bytes == expected.as_slice(),
+        "{name} differs from its golden file\nexpected:\n{}\nactual:\n{}",
+        expected.escape_ascii(),
+        bytes.escape_ascii()
+    );
+}
+
+#[test]
+fn test_kitchen_tickets_match_golden_files() {
+    let order = sample_order();
+    assert_golden(
+        "kitchen_ticket_58mm.bin",
+        &render_kitchen_ticket(&order, None, PaperWidth::Mm58),
+    );
+    assert_golden(
+        "kitchen_ticket_80mm.bin",
+        &render_kitchen_ticket(&order, Some("Grill"), PaperWidth::Mm80),
+    );
+}
+
+#[test]
+fn test_receipts_match_golden_files() {
+    let order = sample_order();
+    assert_golden(
+        "receipt_58mm.bin",
+        &render_receipt(&order, PaperWidth::Mm58),
+    );
+    assert_golden(
+        "receipt_80mm.bin",
+        &render_receipt(&order, PaperWidth::Mm80),
+    );
+
+    // Orders without a number or discount print a short id and no subtotal
+    let order = PrintOrder {
+        order_number: None,
+        coupon_code: None,
+        discount_amount: 0.0,
+        total_amount: 79.48,
+        payment_status: "not_required".to_string(),
+        restaurant_address: None,
+        ..sample_order()
+    };
+    assert_golden(
+        "receipt_unnumbered_80mm.bin",
+        &render_receipt(&order, PaperWidth::Mm80),
+    );
+}
+
+#[test]
+fn test_paper_width_parsing() {
+    assert_eq!(PaperWidth::parse(None), Ok(PaperWidth::Mm80));
+    assert_eq!(PaperWidth::parse(Some(58)).map(PaperWidth::columns), Ok(32));
+    assert_eq!(PaperWidth::parse(Some(80)).map(PaperWidth::columns), Ok(48));
+    assert!(PaperWidth::parse(Some(72)).is_err());
+}
@@ -0,0 +1,329 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+fn order_body(menu_item_id: &str, quantity: i32) -> serde_json::Value {
+    json!({
+        "table_code": "TBL001",
+        "items": [{"menu_item_id": menu_item_id, "quantity": quantity, "special_requests": null}],
+        "customer_name": "Guest"
+    })
+}
+
+#[tokio::test]
+async fn test_orders_decrement_stock_and_sell_out() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, menu_item_id): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         ORDER BY mi.display_order LIMIT 1",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": 3}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["stock_quantity"], 3);
+
+    // More than what is left is rejected without touching the stock
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 4))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "insufficient_stock");
+    assert_eq!(body["remaining"], 3);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 2))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let levels = body.as_array().unwrap();
+    assert_eq!(levels.len(), 1);
+    assert_eq!(levels[0]["item_id"], menu_item_id);
+    assert_eq!(levels[0]["stock_quantity"], 0);
+    assert_eq!(levels[0]["available"], false);
+
+    // Sold out items disappear from the guest menu and cannot be ordered
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|section| section["items"].as_array().unwrap())
+        .all(|item| item["id"] != menu_item_id));
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/items/{menu_item_id}/restock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"quantity": 5}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["stock_quantity"], 5);
+    assert_eq!(body["available"], true);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+}
+
+#[tokio::test]
+async fn test_stock_tracking_is_optional() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let menu_item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    // Untracked items can be ordered in any quantity
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 50))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": -1}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": 1}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": null}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["stock_quantity"].is_null());
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 10))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+}
+
+#[tokio::test]
+async fn test_restock_keeps_manually_disabled_items_off() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let menu_item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": 2}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // Taken off the menu by hand, e.g. because the kitchen cannot make it today
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/availability"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"available": false}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/items/{menu_item_id}/restock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"quantity": 5}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["stock_quantity"], 7);
+    assert_eq!(body["available"], false);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": 10}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["stock_quantity"], 10);
+    assert_eq!(body["available"], false);
+}
+
+#[tokio::test]
+async fn test_cancelled_orders_return_their_stock() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let menu_item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{menu_item_id}/stock"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"stock_quantity": 2}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id, 2))
+        .to_request();
+    let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = created["order_id"].as_str().unwrap();
+
+    let (stock, available): (Option<i32>, bool) =
+        sqlx::query_as("SELECT stock_quantity, available FROM menu_items WHERE id = ?")
+            .bind(&menu_item_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(stock, Some(0));
+    assert!(!available);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/orders/{order_id}/status"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"status": "cancelled"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // The item sold out because of this order, so it is back on sale
+    let (stock, available): (Option<i32>, bool) =
+        sqlx::query_as("SELECT stock_quantity, available FROM menu_items WHERE id = ?")
+            .bind(&menu_item_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(stock, Some(2));
+    assert!(available);
+
+    // Repeating the cancel does not return the stock twice
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/orders/{order_id}/status"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"status": "cancelled"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let stock: Option<i32> =
+        sqlx::query_scalar("SELECT stock_quantity FROM menu_items WHERE id = ?")
+            .bind(&menu_item_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(stock, Some(2));
+}
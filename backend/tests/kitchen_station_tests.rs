@@ -0,0 +1,222 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_order_lines_route_to_stations_and_bumps_make_orders_ready() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let item = |name: &'static str| {
+        let pool = test_app.pool.clone();
+        async move {
+            let ids: (String, String, String) = sqlx::query_as(
+                "SELECT ms.restaurant_id, ms.id, mi.id FROM menu_items mi
+                 JOIN menu_sections ms ON mi.section_id = ms.id WHERE mi.name = ?",
+            )
+            .bind(name)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            ids
+        }
+    };
+    let (restaurant_id, mains, salmon) = item("Grilled Salmon").await;
+    let (_, desserts, cake) = item("Chocolate Cake").await;
+    let (_, _, bread) = item("Garlic Bread").await;
+    let (_, _, salad) = item("Caesar Salad").await;
+
+    let create_station = |name: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/stations"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"name": name, "display_order": null}))
+            .to_request()
+    };
+    let resp = test::call_service(&app, create_station("Kitchen")).await;
+    assert_eq!(resp.status(), 201);
+    let kitchen: serde_json::Value = test::read_body_json(resp).await;
+    let kitchen_id = kitchen["id"].as_str().unwrap().to_string();
+    let pastry: serde_json::Value =
+        test::call_and_read_body_json(&app, create_station("Pastry")).await;
+    let pastry_id = pastry["id"].as_str().unwrap().to_string();
+    let resp = test::call_service(&app, create_station("Kitchen")).await;
+    assert_eq!(resp.status(), 409);
+    let resp = test::call_service(&app, create_station("  ")).await;
+    assert_eq!(resp.status(), 400);
+
+    let assign = |uri: String, station_id: Option<&str>| {
+        test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"station_id": station_id}))
+            .to_request()
+    };
+    // Appetizers stay unrouted, but garlic bread is sent to the kitchen itself
+    for (uri, station_id) in [
+        (format!("/api/sections/{mains}/station"), &kitchen_id),
+        (format!("/api/sections/{desserts}/station"), &pastry_id),
+        (format!("/api/items/{bread}/station"), &kitchen_id),
+    ] {
+        let resp = test::call_service(&app, assign(uri, Some(station_id))).await;
+        assert_eq!(resp.status(), 204);
+    }
+    let resp = test::call_service(
+        &app,
+        assign(format!("/api/items/{salad}/station"), Some("missing")),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+
+    let place_order = |prepaid: bool| {
+        test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [
+                    {"menu_item_id": salmon, "quantity": 1, "special_requests": "No lemon"},
+                    {"menu_item_id": cake, "quantity": 2, "special_requests": null},
+                    {"menu_item_id": bread, "quantity": 1, "special_requests": null},
+                    {"menu_item_id": salad, "quantity": 1, "special_requests": null}
+                ],
+                "pay_before_kitchen": prepaid
+            }))
+            .to_request()
+    };
+    let order: serde_json::Value = test::call_and_read_body_json(&app, place_order(false)).await;
+    let order_id = order["order_id"].as_str().unwrap().to_string();
+    // Orders waiting for online payment stay off the displays
+    let unpaid: serde_json::Value = test::call_and_read_body_json(&app, place_order(true)).await;
+
+    let lines = |query: String| {
+        test::TestRequest::get()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/kitchen/lines?{query}"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let kitchen_lines: serde_json::Value =
+        test::call_and_read_body_json(&app, lines(format!("station_id={kitchen_id}"))).await;
+    assert_eq!(kitchen_lines.as_array().unwrap().len(), 2);
+    assert_eq!(kitchen_lines[0]["item_name"], "Grilled Salmon");
+    assert_eq!(kitchen_lines[0]["notes"], "No lemon");
+    assert_eq!(kitchen_lines[0]["table_name"], "Table 1");
+    assert_eq!(kitchen_lines[1]["item_name"], "Garlic Bread");
+    let pastry_lines: serde_json::Value =
+        test::call_and_read_body_json(&app, lines(format!("station_id={pastry_id}"))).await;
+    assert_eq!(pastry_lines.as_array().unwrap().len(), 1);
+    assert_eq!(pastry_lines[0]["quantity"], 2);
+    let all_lines: serde_json::Value =
+        test::call_and_read_body_json(&app, lines(String::new())).await;
+    assert_eq!(all_lines.as_array().unwrap().len(), 4);
+    assert!(all_lines[3]["station_id"].is_null());
+
+    let line_action = |line: &serde_json::Value, action: &str| {
+        test::TestRequest::post()
+            .uri(&format!(
+                "/api/kitchen/lines/{}/{action}",
+                line["id"].as_str().unwrap()
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+
+    let bumped: serde_json::Value =
+        test::call_and_read_body_json(&app, line_action(&kitchen_lines[0], "bump")).await;
+    assert_eq!(bumped["status"], "bumped");
+    assert_eq!(bumped["order_status"], "preparing");
+    assert!(bumped["bumped_at"].is_string());
+    let resp = test::call_service(&app, line_action(&kitchen_lines[0], "bump")).await;
+    assert_eq!(resp.status(), 200);
+
+    test::call_service(&app, line_action(&kitchen_lines[1], "bump")).await;
+    let bumped: serde_json::Value =
+        test::call_and_read_body_json(&app, line_action(&pastry_lines[0], "bump")).await;
+    assert_eq!(bumped["order_status"], "preparing");
+    let bumped: serde_json::Value =
+        test::call_and_read_body_json(&app, line_action(&all_lines[3], "bump")).await;
+    assert_eq!(bumped["order_status"], "ready");
+
+    let pending: serde_json::Value =
+        test::call_and_read_body_json(&app, lines(format!("station_id={kitchen_id}"))).await;
+    assert_eq!(pending.as_array().unwrap().len(), 0);
+    let done: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        lines(format!("station_id={kitchen_id}&status=bumped")),
+    )
+    .await;
+    assert_eq!(done.as_array().unwrap().len(), 2);
+    assert!(done[0]["elapsed_seconds"].as_i64().unwrap() >= 0);
+
+    // A recalled line reopens the order until it is bumped again
+    let recalled: serde_json::Value =
+        test::call_and_read_body_json(&app, line_action(&kitchen_lines[1], "recall")).await;
+    assert_eq!(recalled["status"], "pending");
+    assert_eq!(recalled["recall_count"], 1);
+    assert!(recalled["bumped_at"].is_null());
+    assert_eq!(recalled["order_status"], "preparing");
+    let bumped: serde_json::Value =
+        test::call_and_read_body_json(&app, line_action(&kitchen_lines[1], "bump")).await;
+    assert_eq!(bumped["order_status"], "ready");
+    let status: String = sqlx::query_scalar("SELECT status FROM orders WHERE id = ?")
+        .bind(&order_id)
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "ready");
+
+    let unpaid_line: String = sqlx::query_scalar(
+        "SELECT id FROM order_station_lines WHERE order_id = ? AND line_index = 0",
+    )
+    .bind(unpaid["order_id"].as_str().unwrap())
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+    let resp = test::call_service(&app, line_action(&json!({"id": unpaid_line}), "bump")).await;
+    assert_eq!(resp.status(), 409);
+
+    // Deleting a station leaves its lines and mappings unrouted
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/stations/{pastry_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/stations"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let stations: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        stations,
+        json!([{
+            "id": kitchen_id,
+            "restaurant_id": restaurant_id,
+            "name": "Kitchen",
+            "display_order": 0,
+            "section_ids": [mains],
+            "item_ids": [bread]
+        }])
+    );
+
+    let resp = test::call_service(&app, lines(format!("station_id={pastry_id}"))).await;
+    assert_eq!(resp.status(), 404);
+    let resp = test::call_service(&app, lines("status=cooking".to_string())).await;
+    assert_eq!(resp.status(), 400);
+}
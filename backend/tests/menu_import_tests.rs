@@ -0,0 +1,406 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_export_round_trips_through_import() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String =
+        sqlx::query_scalar("SELECT restaurant_id FROM menu_sections LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/export?format=csv"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("section,section_order,name,description,price,available,display_order")
+    );
+    assert_eq!(lines.count(), 7);
+    assert!(csv.contains("Appetizers,1,Caesar Salad,"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/export"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let exported: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(exported["sections"].as_array().unwrap().len(), 3);
+
+    // Re-importing an unchanged export is a no-op in either format
+    for (format, content) in [("json", exported.to_string()), ("csv", csv)] {
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/menu/import?format={format}&mode=replace"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_payload(content)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let result: serde_json::Value = test::read_body_json(resp).await;
+        for count in [
+            "sections_created",
+            "sections_updated",
+            "sections_removed",
+            "items_created",
+            "items_updated",
+            "items_removed",
+        ] {
+            assert_eq!(result[count], 0, "{format} {count}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_import_reports_row_errors_and_applies_nothing() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String =
+        sqlx::query_scalar("SELECT restaurant_id FROM menu_sections LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let csv = "section,name,price,available\n\
+               Drinks,Lemonade,3.50,yes\n\
+               Drinks,Iced Tea,cheap,yes\n\
+               ,Water,1.00,\n\
+               Drinks,lemonade,4.00,\n\
+               Drinks,Cola,2.50,maybe\n";
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/import?format=csv"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_payload(csv)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let result: serde_json::Value = test::read_body_json(resp).await;
+    let errors: Vec<(u64, &str)> = result["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| {
+            (
+                error["row"].as_u64().unwrap(),
+                error["field"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![(3, "price"), (4, "section"), (5, "name"), (6, "available")]
+    );
+
+    let section_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM menu_sections WHERE name = 'Drinks'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(section_count, 0);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/import?format=csv"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_payload("section,description\nDrinks,Cold\n")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let result: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(result["errors"].as_array().unwrap().len(), 2);
+    assert_eq!(result["errors"][0]["row"], 1);
+}
+
+#[tokio::test]
+async fn test_merge_and_replace_modes() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String =
+        sqlx::query_scalar("SELECT restaurant_id FROM menu_sections LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let import = json!({
+        "sections": [
+            {"name": "Desserts", "items": [
+                {"name": "tiramisu", "description": "Classic Italian dessert", "price": 10.5},
+                {"name": "Panna Cotta", "price": 7.0, "available": false}
+            ]},
+            {"name": "Drinks", "items": [
+                {"name": "Lemonade", "description": "Fresh, with \"real\" lemons", "price": 3.5}
+            ]}
+        ]
+    });
+
+    // Merge only touches what the import mentions
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/import?mode=merge"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(&import)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let result: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(result["sections_created"], 1);
+    assert_eq!(result["items_created"], 2);
+    assert_eq!(result["items_updated"], 1);
+    assert_eq!(result["items_removed"], 0);
+
+    let (name, price): (String, f64) = sqlx::query_as(
+        "SELECT name, CAST(price AS REAL) FROM menu_items WHERE lower(name) = 'tiramisu'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+    assert_eq!(name, "tiramisu");
+    assert_eq!(price, 10.5);
+
+    let item_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM menu_items mi JOIN menu_sections ms ON ms.id = mi.section_id
+         WHERE ms.restaurant_id = ?",
+    )
+    .bind(&restaurant_id)
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+    assert_eq!(item_count, 9);
+
+    // A dry run reports what replace would do without changing anything
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/import?mode=replace&dry_run=true"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(&import)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let result: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(result["dry_run"], true);
+    assert_eq!(result["sections_removed"], 2);
+    assert_eq!(result["items_removed"], 6);
+    assert_eq!(result["items_updated"], 0);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(menu["sections"].as_array().unwrap().len(), 4);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/import?mode=replace"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(&import)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let sections = menu["sections"].as_array().unwrap();
+    let names: Vec<&str> = sections
+        .iter()
+        .map(|section| section["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Desserts", "Drinks"]);
+    assert_eq!(sections[0]["items"].as_array().unwrap().len(), 2);
+    assert_eq!(sections[0]["items"][1]["available"], false);
+
+    // Quoting survives a CSV round trip
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/export?format=csv"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let csv = String::from_utf8(
+        test::read_body(test::call_service(&app, req).await)
+            .await
+            .to_vec(),
+    )
+    .unwrap();
+    assert!(csv.contains("Drinks,4,Lemonade,\"Fresh, with \"\"real\"\" lemons\",3.50,true,1"));
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/import?format=csv&mode=replace&dry_run=true"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_payload(csv)
+        .to_request();
+    let result: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(result["items_updated"], 0);
+    assert_eq!(result["items_created"], 0);
+}
+
+#[tokio::test]
+async fn test_clone_menu_between_managed_restaurants() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let source_id: String = sqlx::query_scalar("SELECT restaurant_id FROM menu_sections LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE menu_items SET available = FALSE WHERE name = 'Garlic Bread'")
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/restaurants")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Demo Restaurant Uptown"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let target_id = body["id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{target_id}/menu/clone"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "source_restaurant_id": source_id,
+            "price_multiplier": 1.1,
+            "skip_unavailable": true
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let result: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(result["sections_created"], 3);
+    assert_eq!(result["items_created"], 6);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{target_id}/menu"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let sections = menu["sections"].as_array().unwrap();
+    let names: Vec<&str> = sections
+        .iter()
+        .map(|section| section["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Appetizers", "Main Courses", "Desserts"]);
+    let appetizers = sections[0]["items"].as_array().unwrap();
+    assert_eq!(appetizers.len(), 1);
+    assert_eq!(appetizers[0]["name"], "Caesar Salad");
+    assert_eq!(appetizers[0]["price"], 14.29);
+    let mains: Vec<&str> = sections[1]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        mains,
+        vec!["Grilled Salmon", "Chicken Parmesan", "Vegetarian Pasta"]
+    );
+
+    // Both restaurants have to be managed by the caller
+    let other_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO restaurants (id, name) VALUES (?, 'Someone Else')")
+        .bind(&other_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{target_id}/menu/clone"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"source_restaurant_id": other_id}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{other_id}/menu/clone"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"source_restaurant_id": source_id}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{target_id}/menu/clone"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"source_restaurant_id": source_id, "price_multiplier": 0}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
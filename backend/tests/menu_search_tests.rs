@@ -0,0 +1,301 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+fn result_names(body: &serde_json::Value) -> Vec<String> {
+    body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["item"]["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_search_ranks_matches_and_respects_availability() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String =
+        sqlx::query_scalar("SELECT restaurant_id FROM menu_sections LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    // A name match outranks a description match
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001/search?q=parmesan"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(result_names(&body), ["Chicken Parmesan", "Caesar Salad"]);
+    assert_eq!(body["results"][0]["section_name"], "Main Courses");
+    assert_eq!(body["results"][0]["item"]["price"], 19.99);
+
+    // Words are prefix-matched and all of them must appear
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/menu/{restaurant_id}/TBL001/search?q=fresh%20sal"
+        ))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let mut names = result_names(&body);
+    names.sort();
+    assert_eq!(names, ["Caesar Salad", "Grilled Salmon"]);
+
+    let item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items WHERE name = 'Chicken Parmesan'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}/availability"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"available": false}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001/search?q=parmesan"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(result_names(&body), ["Caesar Salad"]);
+
+    // Search syntax typed by guests is treated as plain text
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/menu/{restaurant_id}/TBL001/search?q=%22cake%22%20OR%20NEAR("
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001/search?q=%20%20"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/NOPE/search?q=cake"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_search_index_follows_item_and_translation_changes() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, section_id): (String, String) =
+        sqlx::query_as("SELECT restaurant_id, id FROM menu_sections WHERE name = 'Desserts'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let search = |q: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/menu/{restaurant_id}/TBL001/search?q={q}"))
+            .to_request()
+    };
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/sections/{section_id}/items"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Mango Lassi", "description": "Yogurt drink", "price": 4.5}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let item: serde_json::Value = test::read_body_json(resp).await;
+    let item_id = item["item_id"].as_str().unwrap().to_string();
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("lassi")).await;
+    assert_eq!(result_names(&body), ["Mango Lassi"]);
+    assert_eq!(body["results"][0]["section_id"], section_id);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Mango Shake"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("lassi")).await;
+    assert!(result_names(&body).is_empty());
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("shake")).await;
+    assert_eq!(result_names(&body), ["Mango Shake"]);
+
+    // Translated text is searchable and results come back in the guest's language
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}/translations/hi"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "आम शेक"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/menu/{restaurant_id}/TBL001/search?q=%E0%A4%86%E0%A4%AE&lang=hi"
+        ))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["language"], "hi");
+    assert_eq!(result_names(&body), ["आम शेक"]);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/items/{item_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("shake")).await;
+    assert!(result_names(&body).is_empty());
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM menu_item_search WHERE item_id = ?")
+            .bind(&item_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test]
+async fn test_search_only_finds_published_text() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, item_id): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         WHERE mi.name = 'Chicken Parmesan'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let search = |q: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/menu/{restaurant_id}/TBL001/search?q={q}"))
+            .to_request()
+    };
+    let publish = || {
+        test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"note": null}))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, publish()).await;
+    assert_eq!(resp.status(), 201);
+    let first: serde_json::Value = test::read_body_json(resp).await;
+
+    // Unpublished renames and deletes do not change what guests find
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Chicken Milanese"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("milanese")).await;
+    assert!(result_names(&body).is_empty());
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("chicken")).await;
+    assert_eq!(result_names(&body), ["Chicken Parmesan"]);
+
+    let salmon_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items WHERE name = 'Grilled Salmon'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/items/{salmon_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("salmon")).await;
+    assert_eq!(result_names(&body), ["Grilled Salmon"]);
+
+    let resp = test::call_service(&app, publish()).await;
+    assert_eq!(resp.status(), 201);
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("milanese")).await;
+    assert_eq!(result_names(&body), ["Chicken Milanese"]);
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("salmon")).await;
+    assert!(result_names(&body).is_empty());
+
+    // Rolling back brings back the old version's text
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/versions/{}/rollback",
+            first["id"].as_str().unwrap()
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("milanese")).await;
+    assert!(result_names(&body).is_empty());
+    let body: serde_json::Value = test::call_and_read_body_json(&app, search("parmesan")).await;
+    assert_eq!(result_names(&body)[0], "Chicken Parmesan");
+
+    // Translations are not versioned, so new translated text is found at once
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}/translations/hi"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Murgh Parmesan"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, search("murgh&lang=hi")).await;
+    assert_eq!(result_names(&body), ["Murgh Parmesan"]);
+}
@@ -0,0 +1,300 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+fn guest_price(menu: &serde_json::Value, item_id: &str) -> Option<f64> {
+    menu["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|section| section["items"].as_array().unwrap())
+        .find(|item| item["id"] == item_id)
+        .map(|item| item["price"].as_f64().unwrap())
+}
+
+#[tokio::test]
+async fn test_draft_changes_stay_hidden_until_published() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, item_id, price): (String, String, f64) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id, CAST(mi.price AS REAL) FROM menu_items mi
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         ORDER BY mi.display_order LIMIT 1",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"note": "Opening menu"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let first: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(first["version_number"], 1);
+    assert_eq!(first["is_live"], true);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"price": price + 5.0}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // Guests and orders keep using the published price
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(guest_price(&menu, &item_id), Some(price));
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": item_id, "quantity": 1, "special_requests": null}],
+            "customer_name": "Guest"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let order: serde_json::Value = test::read_body_json(resp).await;
+    assert!((order["total_amount"].as_f64().unwrap() - price).abs() < 0.001);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/diff"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let diff: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let changed = diff["changed_items"].as_array().unwrap();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0]["id"], item_id);
+    assert_eq!(changed[0]["changes"][0]["field"], "price");
+    assert_eq!(
+        changed[0]["changes"][0]["after"],
+        format!("{:.2}", price + 5.0)
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"note": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(guest_price(&menu, &item_id), Some(price + 5.0));
+
+    // Nothing left to publish
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"note": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/versions"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let versions: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let versions = versions.as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version_number"], 2);
+    assert_eq!(versions[0]["is_live"], true);
+    assert_eq!(versions[1]["is_live"], false);
+    assert_eq!(versions[1]["note"], "Opening menu");
+}
+
+#[tokio::test]
+async fn test_rollback_restores_previous_version() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, item_id, price): (String, String, f64) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id, CAST(mi.price AS REAL) FROM menu_items mi
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         ORDER BY mi.display_order LIMIT 1",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"note": null}))
+        .to_request();
+    let first: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let first_id = first["id"].as_str().unwrap().to_string();
+
+    sqlx::query("UPDATE menu_items SET price = ? WHERE id = ?")
+        .bind(price * 2.0)
+        .bind(&item_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"note": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/versions/{first_id}/rollback"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["is_live"], true);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(guest_price(&menu, &item_id), Some(price));
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/versions/{first_id}"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let detail: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(detail["version"]["version_number"], 1);
+    assert_eq!(detail["menu"]["sections"].as_array().unwrap().len(), 3);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/versions/missing/rollback"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    // Published versions cannot be edited in place
+    let result = sqlx::query("UPDATE menu_versions SET note = 'changed' WHERE id = ?")
+        .bind(&first_id)
+        .execute(&test_app.pool)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_draft_deletes_stay_on_sale_until_published() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, item_id, price): (String, String, f64) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id, CAST(mi.price AS REAL) FROM menu_items mi
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         ORDER BY mi.display_order LIMIT 1",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let publish = || {
+        test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"note": null}))
+            .to_request()
+    };
+    let order = || {
+        test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [{"menu_item_id": item_id, "quantity": 1, "special_requests": null}],
+                "customer_name": "Guest"
+            }))
+            .to_request()
+    };
+    let resp = test::call_service(&app, publish()).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/items/{item_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // Guests keep seeing and ordering the published item
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(guest_price(&menu, &item_id), Some(price));
+
+    let resp = test::call_service(&app, order()).await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::call_service(&app, publish()).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(guest_price(&menu, &item_id), None);
+
+    let resp = test::call_service(&app, order()).await;
+    assert_eq!(resp.status(), 400);
+}
@@ -0,0 +1,293 @@
+use actix_web::test;
+use backend::create_app;
+use backend::opening_hours_handlers::{
+    availability_at, CLOSED_OUTSIDE_HOURS, CLOSED_SPECIAL_DAY, CLOSED_TEMPORARILY,
+};
+use backend::schedule::{Schedule, TimeRange, WeeklyWindow};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::json;
+
+mod common;
+use common::test_app::{create_test_app, TestApp};
+
+fn late_shift_schedule() -> Schedule {
+    // Friday 18:00 until 02:00 on Saturday
+    Schedule::new(vec![WeeklyWindow {
+        day_of_week: 4,
+        range: TimeRange::parse("18:00", "02:00").unwrap(),
+    }])
+}
+
+async fn restaurant_and_item(test_app: &TestApp) -> (String, String) {
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    let menu_item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order LIMIT 1")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    (restaurant_id, menu_item_id)
+}
+
+fn order_body(menu_item_id: &str) -> serde_json::Value {
+    json!({
+        "table_code": "TBL001",
+        "items": [{"menu_item_id": menu_item_id, "quantity": 1, "special_requests": null}],
+        "customer_name": "Guest"
+    })
+}
+
+#[tokio::test]
+async fn test_schedule_late_shift_spans_midnight() {
+    let schedule = late_shift_schedule();
+    let friday = NaiveDate::from_ymd_opt(2025, 10, 17).unwrap();
+    let saturday = friday.succ_opt().unwrap();
+
+    assert!(!schedule.is_active_at(friday.and_hms_opt(17, 59, 0).unwrap()));
+    assert!(schedule.is_active_at(friday.and_hms_opt(23, 30, 0).unwrap()));
+    assert!(schedule.is_active_at(saturday.and_hms_opt(1, 59, 0).unwrap()));
+    assert!(!schedule.is_active_at(saturday.and_hms_opt(2, 0, 0).unwrap()));
+
+    assert_eq!(
+        schedule.next_start_after(saturday.and_hms_opt(12, 0, 0).unwrap()),
+        Some(
+            NaiveDate::from_ymd_opt(2025, 10, 24)
+                .unwrap()
+                .and_hms_opt(18, 0, 0)
+                .unwrap()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_schedule_special_day_overrides_weekly_hours() {
+    let mut schedule = late_shift_schedule();
+    let friday = NaiveDate::from_ymd_opt(2025, 10, 17).unwrap();
+    schedule.overrides.insert(friday, Vec::new());
+
+    assert!(schedule.is_closed_all_day(friday));
+    assert!(!schedule.is_active_at(friday.and_hms_opt(19, 0, 0).unwrap()));
+
+    // An empty weekly schedule places no restriction on ordering
+    let open = Schedule::default();
+    assert!(open.is_active_at(friday.and_hms_opt(3, 0, 0).unwrap()));
+}
+
+#[tokio::test]
+async fn test_availability_uses_restaurant_timezone() {
+    let schedule = late_shift_schedule();
+    let tz: Tz = "Asia/Kolkata".parse().unwrap();
+
+    // 13:00 UTC is 18:30 in Kolkata on a Friday
+    let now = Utc.with_ymd_and_hms(2025, 10, 17, 13, 0, 0).unwrap();
+    assert!(availability_at(&schedule, tz, None, None, now).is_open);
+
+    let earlier = Utc.with_ymd_and_hms(2025, 10, 17, 6, 0, 0).unwrap();
+    let availability = availability_at(&schedule, tz, None, None, earlier);
+    assert!(!availability.is_open);
+    assert_eq!(availability.reason.as_deref(), Some(CLOSED_OUTSIDE_HOURS));
+    assert_eq!(
+        availability.next_open_at,
+        Some(Utc.with_ymd_and_hms(2025, 10, 17, 12, 30, 0).unwrap())
+    );
+
+    let mut holiday = late_shift_schedule();
+    holiday
+        .overrides
+        .insert(NaiveDate::from_ymd_opt(2025, 10, 17).unwrap(), Vec::new());
+    let availability = availability_at(&holiday, tz, None, None, now);
+    assert_eq!(availability.reason.as_deref(), Some(CLOSED_SPECIAL_DAY));
+}
+
+#[tokio::test]
+async fn test_temporary_closure_reopens_at_closed_until() {
+    let now = Utc.with_ymd_and_hms(2025, 10, 17, 10, 0, 0).unwrap();
+    let until = now + Duration::hours(2);
+
+    let availability = availability_at(
+        &Schedule::default(),
+        Tz::UTC,
+        Some(until),
+        Some("Kitchen closed until 12".to_string()),
+        now,
+    );
+    assert!(!availability.is_open);
+    assert_eq!(availability.reason.as_deref(), Some(CLOSED_TEMPORARILY));
+    assert_eq!(
+        availability.closure_message.as_deref(),
+        Some("Kitchen closed until 12")
+    );
+    assert_eq!(availability.next_open_at, Some(until));
+
+    // Expired closures are ignored
+    let later = availability_at(&Schedule::default(), Tz::UTC, Some(until), None, until);
+    assert!(later.is_open);
+}
+
+#[tokio::test]
+async fn test_orders_rejected_outside_opening_hours() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let (restaurant_id, menu_item_id) = restaurant_and_item(&test_app).await;
+
+    // Open every day for one hour, starting two hours from now
+    let now = Utc::now();
+    let open_time = (now + Duration::hours(2)).format("%H:%M").to_string();
+    let close_time = (now + Duration::hours(3)).format("%H:%M").to_string();
+    let weekly: Vec<_> = (0..7)
+        .map(|day| json!({"day_of_week": day, "open_time": open_time, "close_time": close_time}))
+        .collect();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/restaurants/{restaurant_id}/opening-hours"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"weekly": weekly}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["weekly"].as_array().unwrap().len(), 7);
+    assert_eq!(body["availability"]["is_open"], false);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], CLOSED_OUTSIDE_HOURS);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["availability"]["is_open"], false);
+    let next_open_at: DateTime<Utc> = body["availability"]["next_open_at"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(next_open_at > now);
+    assert!(next_open_at <= now + Duration::hours(2));
+
+    // Clearing the weekly hours makes the restaurant open around the clock again
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/restaurants/{restaurant_id}/opening-hours"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"weekly": []}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+}
+
+#[tokio::test]
+async fn test_special_days_and_temporary_closure() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let (restaurant_id, menu_item_id) = restaurant_and_item(&test_app).await;
+    let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+    let special_day_uri = format!("/api/restaurants/{restaurant_id}/special-days/{today}");
+
+    let req = test::TestRequest::put()
+        .uri(&special_day_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"closed": false, "windows": [], "note": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::put()
+        .uri(&special_day_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"closed": true, "note": "Diwali"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["special_days"][0]["date"], today);
+    assert_eq!(body["special_days"][0]["note"], "Diwali");
+    assert_eq!(body["availability"]["reason"], CLOSED_SPECIAL_DAY);
+
+    let req = test::TestRequest::delete()
+        .uri(&special_day_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+
+    let closure_uri = format!("/api/restaurants/{restaurant_id}/closure");
+    let closed_until = Utc::now() + Duration::hours(1);
+    let req = test::TestRequest::put()
+        .uri(&closure_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"closed_until": closed_until, "reason": "Kitchen closed until 7pm"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], CLOSED_TEMPORARILY);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        body["availability"]["closure_message"],
+        "Kitchen closed until 7pm"
+    );
+
+    let req = test::TestRequest::put()
+        .uri(&closure_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"closed_until": null, "reason": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&menu_item_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+}
@@ -0,0 +1,169 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_order_export_streams_one_row_per_line() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad, bread): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, s.id, b.id FROM menu_items s, menu_items b
+         JOIN menu_sections ms ON s.section_id = ms.id
+         WHERE s.name = 'Caesar Salad' AND b.name = 'Garlic Bread'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    // More orders than one export batch, all on the same day
+    let mut first_order = String::new();
+    for i in 0..205 {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [
+                    {"menu_item_id": salad, "quantity": 2, "special_requests": null},
+                    {"menu_item_id": bread, "quantity": 1, "special_requests": null}
+                ]
+            }))
+            .to_request();
+        let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let order_id = created["order_id"].as_str().unwrap().to_string();
+        sqlx::query("UPDATE orders SET created_at = '2025-03-10 12:00:00' WHERE id = ?")
+            .bind(&order_id)
+            .execute(&test_app.pool)
+            .await
+            .unwrap();
+        if i == 0 {
+            first_order = order_id;
+        }
+    }
+    sqlx::query("UPDATE orders SET status = 'cancelled' WHERE id = ?")
+        .bind(&first_order)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    let discounted: String = sqlx::query_scalar(
+        "UPDATE orders SET discount_amount = 5.0, total_amount = total_amount - 5.0, coupon_code = 'SAVE5'
+         WHERE id = (SELECT id FROM orders WHERE id != ? ORDER BY id LIMIT 1)
+         RETURNING id",
+    )
+    .bind(&first_order)
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let export = |query: &str| {
+        test::TestRequest::get()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/orders/export?{query}"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, export("from=2025-03-10&to=2025-03-10")).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "order_id,order_number,created_at,business_date,table_name,menu_item_id,item_name,variant,quantity,unit_price,line_total,tax,status,payment_status"
+    );
+    assert_eq!(lines.len(), 1 + 205 * 2 + 1);
+    let cancelled: Vec<&&str> = lines
+        .iter()
+        .filter(|line| line.starts_with(&first_order))
+        .collect();
+    assert_eq!(cancelled.len(), 2);
+    assert!(cancelled[0].ends_with(",cancelled,not_required"));
+    assert!(lines.iter().any(|line| line.contains(&format!(
+        ",2025-03-10T12:00:00Z,2025-03-10,Table 1,{salad},Caesar Salad,,2,12.99,25.98,0.00,pending,"
+    ))));
+
+    let resp = test::call_service(&app, export("from=2025-03-10&to=2025-03-10&format=jsonl")).await;
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/x-ndjson"
+    );
+    let jsonl = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let rows: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 411);
+    let ids: std::collections::HashSet<&str> = rows
+        .iter()
+        .map(|row| row["order_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 205);
+    let bread_row = rows
+        .iter()
+        .find(|row| row["menu_item_id"] == bread.as_str())
+        .unwrap();
+    assert_eq!(bread_row["item_name"], "Garlic Bread");
+    assert_eq!(bread_row["quantity"], 1);
+    assert_eq!(bread_row["tax"], 0.0);
+    assert_eq!(bread_row["table_name"], "Table 1");
+
+    // The coupon shows up as its own line, so the lines add up to the total
+    let discount_row = rows
+        .iter()
+        .find(|row| row["order_id"] == discounted.as_str() && row["menu_item_id"] == "")
+        .unwrap();
+    assert_eq!(discount_row["item_name"], "Discount (SAVE5)");
+    assert_eq!(discount_row["line_total"], -5.0);
+    let total_amount: f64 =
+        sqlx::query_scalar("SELECT CAST(total_amount AS REAL) FROM orders WHERE id = ?")
+            .bind(&discounted)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    let line_sum: f64 = rows
+        .iter()
+        .filter(|row| row["order_id"] == discounted.as_str())
+        .map(|row| row["line_total"].as_f64().unwrap())
+        .sum();
+    assert!((line_sum - total_amount).abs() < 0.005);
+
+    // Days without orders export just the header
+    let resp = test::call_service(&app, export("from=2025-03-11&to=2025-03-11")).await;
+    let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(csv.lines().count(), 1);
+
+    for query in [
+        "format=xml",
+        "from=10-03-2025",
+        "from=2025-03-12&to=2025-03-10",
+    ] {
+        let resp = test::call_service(&app, export(query)).await;
+        assert_eq!(resp.status(), 400, "{query}");
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/orders/export"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
@@ -0,0 +1,154 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::{create_test_app, TestApp};
+
+async fn menu_item_ids(test_app: &TestApp) -> Vec<String> {
+    sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order, name")
+        .fetch_all(&test_app.pool)
+        .await
+        .expect("Failed to fetch menu items")
+}
+
+async fn order_count(test_app: &TestApp) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM orders")
+        .fetch_one(&test_app.pool)
+        .await
+        .expect("Failed to count orders")
+}
+
+#[tokio::test]
+async fn test_retry_with_same_key_returns_original_order() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let items = menu_item_ids(&test_app).await;
+    let order = json!({
+        "table_code": "TBL001",
+        "items": [{"menu_item_id": items[0], "quantity": 1, "special_requests": null}],
+        "customer_name": "Guest"
+    });
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .insert_header(("Idempotency-Key", "tap-1"))
+        .set_json(&order)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let first: serde_json::Value = test::read_body_json(resp).await;
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .insert_header(("Idempotency-Key", "tap-1"))
+        .set_json(&order)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.headers().get("Idempotent-Replayed").unwrap(), "true");
+    let second: serde_json::Value = test::read_body_json(resp).await;
+
+    assert_eq!(first, second);
+    assert_eq!(order_count(&test_app).await, 1);
+}
+
+#[tokio::test]
+async fn test_body_key_is_accepted() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let items = menu_item_ids(&test_app).await;
+    let order = json!({
+        "table_code": "TBL001",
+        "items": [{"menu_item_id": items[0], "quantity": 2, "special_requests": null}],
+        "customer_name": null,
+        "idempotency_key": "body-key"
+    });
+
+    for _ in 0..2 {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(&order)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    assert_eq!(order_count(&test_app).await, 1);
+}
+
+#[tokio::test]
+async fn test_same_key_with_different_payload_is_rejected() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let items = menu_item_ids(&test_app).await;
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .insert_header(("Idempotency-Key", "tap-2"))
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": items[0], "quantity": 1, "special_requests": null}],
+            "customer_name": "Guest"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .insert_header(("Idempotency-Key", "tap-2"))
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": items[1], "quantity": 1, "special_requests": null}],
+            "customer_name": "Guest"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+
+    assert_eq!(order_count(&test_app).await, 1);
+}
+
+#[tokio::test]
+async fn test_orders_without_key_are_not_deduplicated() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let items = menu_item_ids(&test_app).await;
+    let order = json!({
+        "table_code": "TBL001",
+        "items": [{"menu_item_id": items[0], "quantity": 1, "special_requests": null}],
+        "customer_name": "Guest"
+    });
+
+    for _ in 0..2 {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(&order)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    assert_eq!(order_count(&test_app).await, 2);
+}
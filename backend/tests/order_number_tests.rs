@@ -0,0 +1,129 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::{create_test_app, TestApp};
+
+async fn first_menu_item_id(test_app: &TestApp) -> String {
+    sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .expect("Failed to fetch menu item")
+}
+
+fn order_for(table_code: &str, menu_item_id: &str) -> serde_json::Value {
+    json!({
+        "table_code": table_code,
+        "items": [{"menu_item_id": menu_item_id, "quantity": 1, "special_requests": null}],
+        "customer_name": null
+    })
+}
+
+#[tokio::test]
+async fn test_order_numbers_are_sequential_per_restaurant() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    let mut numbers = Vec::new();
+    for table_code in ["TBL001", "TBL002", "TBL001"] {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(order_for(table_code, &menu_item_id))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        numbers.push(body["order_number"].as_i64().unwrap());
+    }
+    assert_eq!(numbers, vec![1, 2, 3]);
+
+    // The number is also visible when fetching the order
+    let order_id: String = sqlx::query_scalar("SELECT id FROM orders WHERE order_number = 2")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["order_number"], 2);
+}
+
+#[tokio::test]
+async fn test_order_numbers_are_independent_between_restaurants() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    // A second restaurant with its own table and menu
+    sqlx::query("INSERT INTO restaurants (id, name) VALUES ('r2', 'Second Restaurant')")
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO tables (id, restaurant_id, name, unique_code) VALUES ('t2', 'r2', 'Table 1', 'R2TBL1')")
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO menu_sections (id, restaurant_id, name) VALUES ('s2', 'r2', 'Mains')")
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO menu_items (id, section_id, name, price) VALUES ('i2', 's2', 'Dal', 5.5)",
+    )
+    .execute(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_for("TBL001", &menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["order_number"], 1);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_for("R2TBL1", "i2"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["order_number"], 1);
+}
+
+#[tokio::test]
+async fn test_order_numbers_restart_each_business_day() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    // Yesterday ended on a high ticket number
+    sqlx::query(
+        "INSERT INTO order_number_counters (restaurant_id, business_date, last_number)
+         SELECT id, date('now', '-1 day'), 57 FROM restaurants",
+    )
+    .execute(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_for("TBL001", &menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["order_number"], 1);
+}
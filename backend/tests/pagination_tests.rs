@@ -0,0 +1,216 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_list_endpoints_page_with_cursors() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.name = 'Caesar Salad'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    // Five orders, two of them sharing a timestamp so ties are broken by id
+    let placed = [
+        ("TBL001", 1, "2025-03-10 09:00:00", "delivered", "Asha"),
+        ("TBL002", 2, "2025-03-10 10:00:00", "pending", "Ben"),
+        ("TBL001", 3, "2025-03-10 10:00:00", "pending", "Asha Rao"),
+        ("TBL002", 4, "2025-03-11 12:00:00", "cancelled", "Chen"),
+        ("TBL001", 5, "2025-03-12 08:00:00", "pending", "Dana"),
+    ];
+    for (table_code, quantity, created_at, status, customer) in placed {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": table_code,
+                "items": [{"menu_item_id": salad, "quantity": quantity, "special_requests": null}]
+            }))
+            .to_request();
+        let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        sqlx::query("UPDATE orders SET created_at = ?, status = ?, customer_name = ? WHERE id = ?")
+            .bind(created_at)
+            .bind(status)
+            .bind(customer)
+            .bind(created["order_id"].as_str().unwrap())
+            .execute(&test_app.pool)
+            .await
+            .unwrap();
+    }
+
+    let get = |uri: String| {
+        test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let orders_uri = format!("/api/restaurants/{restaurant_id}/orders");
+
+    // Walk every page of the default newest-first listing
+    let mut seen = Vec::new();
+    let mut uri = format!("{orders_uri}?limit=2");
+    loop {
+        let page: serde_json::Value = test::call_and_read_body_json(&app, get(uri.clone())).await;
+        let items = page["items"].as_array().unwrap();
+        assert!(items.len() <= 2);
+        seen.extend(items.iter().map(|order| order["created_at"].clone()));
+        match page["next_cursor"].as_str() {
+            Some(cursor) => uri = format!("{orders_uri}?limit=2&cursor={cursor}"),
+            None => break,
+        }
+    }
+    assert_eq!(seen.len(), 5);
+    let mut sorted = seen.clone();
+    sorted.sort_by(|a, b| b.as_str().cmp(&a.as_str()));
+    assert_eq!(seen, sorted);
+
+    let page: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        get(format!("{orders_uri}?sort=total_amount&order=asc&limit=3")),
+    )
+    .await;
+    let totals: Vec<f64> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|order| order["total_amount"].as_f64().unwrap())
+        .collect();
+    assert_eq!(totals, vec![12.99, 25.98, 38.97]);
+    let cursor = page["next_cursor"].as_str().unwrap().to_string();
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{orders_uri}?cursor={cursor}"))).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["items"][0]["total_amount"], 51.96);
+    assert!(page["next_cursor"].is_null());
+
+    let resp = test::call_service(
+        &app,
+        get(format!("{orders_uri}?cursor={cursor}&sort=created_at")),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+
+    let count = |query: &'static str| {
+        let uri = format!("{orders_uri}?{query}");
+        let req = get(uri);
+        let app = &app;
+        async move {
+            let page: serde_json::Value = test::call_and_read_body_json(app, req).await;
+            page["items"].as_array().unwrap().len()
+        }
+    };
+    assert_eq!(count("status=pending").await, 3);
+    assert_eq!(count("customer=asha").await, 2);
+    assert_eq!(count("from=2025-03-10&to=2025-03-11").await, 4);
+    assert_eq!(count("from=2025-03-11").await, 2);
+    assert_eq!(
+        count("status=pending&from=2025-03-10&to=2025-03-10").await,
+        2
+    );
+
+    let table_id: String = sqlx::query_scalar("SELECT id FROM tables WHERE unique_code = 'TBL002'")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{orders_uri}?table_id={table_id}"))).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["items"][0]["table_name"], "Table 2");
+
+    for query in [
+        "limit=0",
+        "limit=500",
+        "sort=customer",
+        "order=sideways",
+        "status=lost",
+        "from=March",
+        "cursor=not-a-cursor",
+    ] {
+        let resp = test::call_service(&app, get(format!("{orders_uri}?{query}"))).await;
+        assert_eq!(resp.status(), 400, "{query}");
+    }
+
+    // Tables, managers and contact submissions share the envelope
+    let tables_uri = format!("/api/restaurants/{restaurant_id}/tables");
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{tables_uri}?sort=name&limit=1"))).await;
+    assert_eq!(page["items"][0]["name"], "Table 1");
+    let cursor = page["next_cursor"].as_str().unwrap();
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{tables_uri}?cursor={cursor}"))).await;
+    assert_eq!(page["items"][0]["name"], "Table 2");
+    assert!(page["next_cursor"].is_null());
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{tables_uri}?q=tbl002"))).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+    let managers_uri = format!("/api/restaurants/{restaurant_id}/managers");
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{managers_uri}?sort=email"))).await;
+    assert_eq!(page["items"][0]["email"], "manager@example.com");
+    assert!(page["next_cursor"].is_null());
+    let page: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{managers_uri}?role=manager"))).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 0);
+
+    for (name, status, created_at) in [
+        ("Ira", "new", "2025-03-01 10:00:00"),
+        ("Jo", "read", "2025-03-02 10:00:00"),
+        ("Kai", "new", "2025-03-03 10:00:00"),
+    ] {
+        sqlx::query(
+            "INSERT INTO contact_submissions (name, email, message, status, created_at)
+             VALUES (?, ?, 'Hello', ?, ?)",
+        )
+        .bind(name)
+        .bind(format!("{}@example.com", name.to_lowercase()))
+        .bind(status)
+        .bind(created_at)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    }
+    let page: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        get("/api/contact/submissions?status=new&limit=1".to_string()),
+    )
+    .await;
+    assert_eq!(page["items"][0]["name"], "Kai");
+    let cursor = page["next_cursor"].as_str().unwrap();
+    let page: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        get(format!(
+            "/api/contact/submissions?status=new&limit=1&cursor={cursor}"
+        )),
+    )
+    .await;
+    assert_eq!(page["items"][0]["name"], "Ira");
+    assert!(page["next_cursor"].is_null());
+    let page: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        get("/api/contact/submissions?from=2025-03-02&to=2025-03-02".to_string()),
+    )
+    .await;
+    assert_eq!(page["items"][0]["name"], "Jo");
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,567 @@
+use actix_web::test;
+use backend::payments::{
+    FakePaymentGateway, PaymentError, PaymentGateway, PaymentIntentStatus, PaymentProvider,
+    WebhookEvent, FAKE_CARD_DECLINED, FAKE_CARD_SUCCESS,
+};
+use backend::{create_app, Settings};
+use serde_json::json;
+
+mod common;
+use common::test_app::{create_test_app, TestApp, TEST_PAYMENT_WEBHOOK_SECRET as WEBHOOK_SECRET};
+
+async fn first_menu_item_id(test_app: &TestApp) -> String {
+    sqlx::query_scalar("SELECT id FROM menu_items ORDER BY display_order LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .expect("Failed to fetch menu item")
+}
+
+fn prepaid_order(menu_item_id: &str) -> serde_json::Value {
+    json!({
+        "table_code": "TBL001",
+        "items": [{"menu_item_id": menu_item_id, "quantity": 2, "special_requests": null}],
+        "customer_name": "Guest",
+        "pay_before_kitchen": true
+    })
+}
+
+#[tokio::test]
+async fn test_fake_gateway_intent_lifecycle() {
+    let gateway = FakePaymentGateway::new(WEBHOOK_SECRET);
+
+    let intent = gateway.create_intent(25.5, "INR", "order-1").unwrap();
+    assert!(intent.id.starts_with("fpi_"));
+    assert_eq!(intent.status, PaymentIntentStatus::RequiresConfirmation);
+
+    assert_eq!(
+        gateway.confirm_intent(&intent.id, FAKE_CARD_SUCCESS),
+        Ok(PaymentIntentStatus::Succeeded)
+    );
+    assert_eq!(
+        gateway.confirm_intent(&intent.id, FAKE_CARD_DECLINED),
+        Err(PaymentError::Declined("card_declined".to_string()))
+    );
+    assert!(gateway.create_intent(0.0, "INR", "order-1").is_err());
+    assert_eq!(gateway.cancel_intent(&intent.id), Ok(()));
+
+    let refund = gateway.refund(&intent.id, 10.0).unwrap();
+    assert_eq!(refund.intent_id, intent.id);
+    assert_eq!(refund.amount, 10.0);
+}
+
+#[tokio::test]
+async fn test_fake_gateway_webhook_signature() {
+    let gateway = FakePaymentGateway::new(WEBHOOK_SECRET);
+    let event = WebhookEvent::PaymentSucceeded {
+        intent_id: "fpi_123".to_string(),
+    };
+    let payload = FakePaymentGateway::webhook_payload(&event);
+    let signature = gateway.sign_webhook(&payload);
+
+    assert_eq!(gateway.verify_webhook(&payload, &signature), Ok(event));
+
+    let other = FakePaymentGateway::new("another-secret");
+    assert_eq!(
+        other.verify_webhook(&payload, &signature),
+        Err(PaymentError::InvalidSignature)
+    );
+}
+
+#[tokio::test]
+async fn test_payments_disabled_without_opt_in_and_secret() {
+    let mut payments = Settings::default().payments.unwrap();
+    assert!(!payments.enabled);
+    assert!(PaymentGateway::from_settings(Some(&payments))
+        .provider()
+        .is_none());
+
+    // Enabling is not enough while the webhook secret is unset
+    payments.enabled = true;
+    assert!(PaymentGateway::from_settings(Some(&payments))
+        .provider()
+        .is_none());
+
+    payments.webhook_secret = Some(WEBHOOK_SECRET.to_string());
+    assert!(PaymentGateway::from_settings(Some(&payments))
+        .provider()
+        .is_some());
+}
+
+#[tokio::test]
+async fn test_regular_order_does_not_require_payment() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    let mut order = prepaid_order(&menu_item_id);
+    order["pay_before_kitchen"] = json!(null);
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["payment_status"], "not_required");
+    assert!(body["payment"].is_null());
+}
+
+#[tokio::test]
+async fn test_prepaid_order_confirmed_after_payment() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+    assert_eq!(body["status"], "pending");
+    assert_eq!(body["payment_status"], "awaiting_payment");
+    assert_eq!(body["payment"]["provider"], "fake");
+    assert_eq!(body["payment"]["amount"], body["total_amount"]);
+
+    // A declined card leaves the order waiting for payment
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{order_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_DECLINED}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 402);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "card_declined");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["status"], "pending");
+    assert_eq!(body["payment_status"], "awaiting_payment");
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{order_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "confirmed");
+    assert_eq!(body["payment_status"], "paid");
+}
+
+#[tokio::test]
+async fn test_payment_webhook_marks_order_paid() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+    let intent_id = body["payment"]["intent_id"].as_str().unwrap().to_string();
+
+    let gateway = FakePaymentGateway::new(WEBHOOK_SECRET);
+    let payload =
+        FakePaymentGateway::webhook_payload(&WebhookEvent::PaymentSucceeded { intent_id });
+
+    // Tampered signatures are rejected
+    let req = test::TestRequest::post()
+        .uri("/payments/webhook")
+        .insert_header(("X-Payment-Signature", "deadbeef"))
+        .set_payload(payload.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::post()
+        .uri("/payments/webhook")
+        .insert_header(("X-Payment-Signature", gateway.sign_webhook(&payload)))
+        .set_payload(payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let (status, payment_status): (String, String) =
+        sqlx::query_as("SELECT status, payment_status FROM orders WHERE id = ?")
+            .bind(&order_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "confirmed");
+    assert_eq!(payment_status, "paid");
+}
+
+#[tokio::test]
+async fn test_canceled_intent_cancels_order_and_returns_stock() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    sqlx::query("UPDATE menu_items SET stock_quantity = 2 WHERE id = ?")
+        .bind(&menu_item_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+    let intent_id = body["payment"]["intent_id"].as_str().unwrap().to_string();
+
+    let gateway = FakePaymentGateway::new(WEBHOOK_SECRET);
+    let payload = FakePaymentGateway::webhook_payload(&WebhookEvent::PaymentCanceled { intent_id });
+    let req = test::TestRequest::post()
+        .uri("/payments/webhook")
+        .insert_header(("X-Payment-Signature", gateway.sign_webhook(&payload)))
+        .set_payload(payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let status: String = sqlx::query_scalar("SELECT status FROM orders WHERE id = ?")
+        .bind(&order_id)
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "cancelled");
+
+    let payment_status: String =
+        sqlx::query_scalar("SELECT status FROM payments WHERE order_id = ?")
+            .bind(&order_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(payment_status, "canceled");
+
+    let (stock, available): (Option<i32>, bool) =
+        sqlx::query_as("SELECT stock_quantity, available FROM menu_items WHERE id = ?")
+            .bind(&menu_item_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(stock, Some(2));
+    assert!(available);
+}
+
+#[tokio::test]
+async fn test_cancelled_order_can_no_longer_be_paid() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+    let gateway = FakePaymentGateway::new(WEBHOOK_SECRET);
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+    let intent_id = body["payment"]["intent_id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/orders/{order_id}/status"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"status": "cancelled"}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let payment_status: String =
+        sqlx::query_scalar("SELECT status FROM payments WHERE order_id = ?")
+            .bind(&order_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert_eq!(payment_status, "canceled");
+
+    // Neither the guest's confirm call nor a late webhook can pay for it
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{order_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["status"], "cancelled");
+    assert_eq!(body["payment_status"], "awaiting_payment");
+
+    let payload =
+        FakePaymentGateway::webhook_payload(&WebhookEvent::PaymentSucceeded { intent_id });
+    let req = test::TestRequest::post()
+        .uri("/payments/webhook")
+        .insert_header(("X-Payment-Signature", gateway.sign_webhook(&payload)))
+        .set_payload(payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // An order cancelled with its intent still open is not marked paid either
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let other_order_id = body["order_id"].as_str().unwrap().to_string();
+    let other_intent_id = body["payment"]["intent_id"].as_str().unwrap().to_string();
+    sqlx::query("UPDATE orders SET status = 'cancelled' WHERE id = ?")
+        .bind(&other_order_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let payload = FakePaymentGateway::webhook_payload(&WebhookEvent::PaymentSucceeded {
+        intent_id: other_intent_id,
+    });
+    let req = test::TestRequest::post()
+        .uri("/payments/webhook")
+        .insert_header(("X-Payment-Signature", gateway.sign_webhook(&payload)))
+        .set_payload(payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    for id in [&order_id, &other_order_id] {
+        let (status, payment_status): (String, String) =
+            sqlx::query_as("SELECT status, payment_status FROM orders WHERE id = ?")
+                .bind(id)
+                .fetch_one(&test_app.pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "cancelled");
+        assert_eq!(payment_status, "awaiting_payment");
+    }
+}
+
+#[tokio::test]
+async fn test_refund_paid_order() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+    let total = body["total_amount"].as_f64().unwrap();
+
+    let refund_uri = format!("/api/restaurants/{restaurant_id}/orders/{order_id}/refund");
+
+    // Nothing to refund before the payment goes through
+    let req = test::TestRequest::post()
+        .uri(&refund_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"amount": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    // Staff lists leave out the unpaid checkout unless asked for it
+    let listed = |uri: String| {
+        let token = token.clone();
+        let order_id = order_id.clone();
+        let app = &app;
+        async move {
+            let req = test::TestRequest::get()
+                .uri(&uri)
+                .insert_header(("Authorization", format!("Bearer {token}")))
+                .to_request();
+            let body: serde_json::Value = test::call_and_read_body_json(app, req).await;
+            let orders = body
+                .get("items")
+                .unwrap_or(&body)
+                .as_array()
+                .unwrap()
+                .clone();
+            orders.iter().any(|order| order["id"] == order_id.as_str())
+        }
+    };
+    let table_id: String = sqlx::query_scalar("SELECT table_id FROM orders WHERE id = ?")
+        .bind(&order_id)
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    let orders_uri = format!("/api/restaurants/{restaurant_id}/orders");
+    let today_uri = format!("/api/restaurants/{restaurant_id}/orders/today");
+    let table_uri = format!("/api/restaurants/{restaurant_id}/tables/{table_id}/orders");
+    assert!(!listed(orders_uri.clone()).await);
+    assert!(!listed(today_uri.clone()).await);
+    assert!(!listed(table_uri.clone()).await);
+    assert!(listed(format!("{orders_uri}?include_unpaid=true")).await);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{order_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(listed(orders_uri).await);
+    assert!(listed(today_uri).await);
+    assert!(listed(table_uri).await);
+
+    let req = test::TestRequest::post()
+        .uri(&refund_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"amount": 5.0}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["payment_status"], "partially_refunded");
+
+    // A paid order is only cancelled once the money has gone back
+    let cancel = || {
+        test::TestRequest::put()
+            .uri(&format!("/api/orders/{order_id}/status"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"status": "cancelled"}))
+            .to_request()
+    };
+    let resp = test::call_service(&app, cancel()).await;
+    assert_eq!(resp.status(), 409);
+
+    // Cannot refund more than what is left
+    let req = test::TestRequest::post()
+        .uri(&refund_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"amount": total}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri(&refund_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"amount": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["payment_status"], "refunded");
+    assert!((body["refunded_total"].as_f64().unwrap() - total).abs() < 0.001);
+
+    let resp = test::call_service(&app, cancel()).await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_concurrent_refunds_cannot_exceed_payment() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let menu_item_id = first_menu_item_id(&test_app).await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(prepaid_order(&menu_item_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = body["order_id"].as_str().unwrap().to_string();
+    let total = body["total_amount"].as_f64().unwrap();
+    let req = test::TestRequest::post()
+        .uri(&format!("/orders/{order_id}/payment/confirm"))
+        .set_json(json!({"payment_method": FAKE_CARD_SUCCESS}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // Each refund fits on its own; together they would exceed the payment
+    let refund = || {
+        test::TestRequest::post()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/orders/{order_id}/refund"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"amount": total * 0.75}))
+            .to_request()
+    };
+    let (first, second) = tokio::join!(
+        test::call_service(&app, refund()),
+        test::call_service(&app, refund())
+    );
+    // The loser is refused by the amount check or, if both passed it, by the
+    // conditional update
+    let mut statuses = [first.status().as_u16(), second.status().as_u16()];
+    statuses.sort();
+    assert_eq!(statuses[0], 200);
+    assert!(statuses[1] == 400 || statuses[1] == 409);
+
+    let refunded: f64 =
+        sqlx::query_scalar("SELECT refunded_amount FROM payments WHERE order_id = ?")
+            .bind(&order_id)
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    assert!((refunded - total * 0.75).abs() < 0.001);
+}
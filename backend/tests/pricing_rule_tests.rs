@@ -0,0 +1,232 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_pricing_rules_adjust_menu_and_order_prices() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad, bread): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, s.id, b.id FROM menu_items s, menu_items b
+         JOIN menu_sections ms ON s.section_id = ms.id
+         WHERE s.name = 'Caesar Salad' AND b.name = 'Garlic Bread'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let rules = [
+        json!({
+            "name": "Everything 10% off",
+            "scope": "restaurant",
+            "target_id": null,
+            "adjustment_type": "percentage",
+            "adjustment_value": -10.0,
+            "windows": []
+        }),
+        // An item rule beats a restaurant rule even when it is less generous
+        json!({
+            "name": "Bread surcharge",
+            "scope": "item",
+            "target_id": bread,
+            "adjustment_type": "fixed",
+            "adjustment_value": 1.0,
+            "windows": []
+        }),
+    ];
+    let mut rule_ids = Vec::new();
+    for rule in rules {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/pricing-rules"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(rule)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let rule: serde_json::Value = test::read_body_json(resp).await;
+        rule_ids.push(rule["id"].as_str().unwrap().to_string());
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let appetizers = &menu["sections"][0]["items"];
+    assert_eq!(appetizers[0]["price"], 11.69);
+    assert_eq!(appetizers[0]["original_price"], 12.99);
+    assert_eq!(appetizers[0]["pricing_rule"], "Everything 10% off");
+    assert_eq!(appetizers[1]["price"], 9.99);
+    assert_eq!(appetizers[1]["pricing_rule"], "Bread surcharge");
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [
+                {"menu_item_id": salad, "quantity": 1, "special_requests": null},
+                {"menu_item_id": bread, "quantity": 2, "special_requests": null}
+            ]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(created["total_amount"], 31.67);
+
+    let order_id = created["order_id"].as_str().unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let order: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(order["items"][0]["price"], 11.69);
+    assert_eq!(order["items"][0]["pricing_rule_id"], rule_ids[0].as_str());
+    assert_eq!(order["items"][1]["price"], 9.99);
+    assert_eq!(order["items"][1]["pricing_rule_name"], "Bread surcharge");
+
+    // Switching rules off restores the menu price
+    for rule_id in &rule_ids {
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/pricing-rules/{rule_id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"active": false}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": salad, "quantity": 1, "special_requests": null}]
+        }))
+        .to_request();
+    let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(created["total_amount"], 12.99);
+
+    let order_id = created["order_id"].as_str().unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let order: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        order["items"][0]["pricing_rule_id"],
+        serde_json::Value::Null
+    );
+}
+
+#[tokio::test]
+async fn test_pricing_rule_schedule_and_validation() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, section_id): (String, String) =
+        sqlx::query_as("SELECT restaurant_id, id FROM menu_sections WHERE name = 'Desserts'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    for payload in [
+        json!({"name": "x", "scope": "item", "target_id": null, "adjustment_type": "fixed", "adjustment_value": -1.0, "windows": []}),
+        json!({"name": "x", "scope": "section", "target_id": "missing", "adjustment_type": "fixed", "adjustment_value": -1.0, "windows": []}),
+        json!({"name": "x", "scope": "restaurant", "target_id": section_id, "adjustment_type": "fixed", "adjustment_value": -1.0, "windows": []}),
+        json!({"name": "x", "scope": "menu", "target_id": null, "adjustment_type": "fixed", "adjustment_value": -1.0, "windows": []}),
+        json!({"name": "x", "scope": "restaurant", "target_id": null, "adjustment_type": "percentage", "adjustment_value": -150.0, "windows": []}),
+        json!({"name": "x", "scope": "restaurant", "target_id": null, "adjustment_type": "bogo", "adjustment_value": 0.0, "windows": []}),
+        json!({"name": "x", "scope": "restaurant", "target_id": null, "adjustment_type": "fixed", "adjustment_value": -1.0,
+               "windows": [{"day_of_week": 7, "start_time": "17:00", "end_time": "19:00"}]}),
+        json!({"name": "x", "scope": "restaurant", "target_id": null, "adjustment_type": "fixed", "adjustment_value": -1.0,
+               "windows": [{"day_of_week": 0, "start_time": "5pm", "end_time": "19:00"}]}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/restaurants/{restaurant_id}/pricing-rules"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400, "{payload}");
+    }
+
+    // Monday happy hour on desserts; times are in the restaurant's zone (UTC)
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/pricing-rules"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({
+            "name": "Happy hour",
+            "scope": "section",
+            "target_id": section_id,
+            "adjustment_type": "percentage",
+            "adjustment_value": -50.0,
+            "windows": [{"day_of_week": 0, "start_time": "17:00", "end_time": "19:00"}]
+        }))
+        .to_request();
+    let rule: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let rule_id = rule["id"].as_str().unwrap().to_string();
+    assert_eq!(rule["windows"][0]["start_time"], "17:00");
+
+    let dessert_prices = |at: &str| {
+        test::TestRequest::get()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/menu/preview?at={at}"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let menu: serde_json::Value =
+        test::call_and_read_body_json(&app, dessert_prices("2025-10-20T18:00:00Z")).await;
+    assert_eq!(menu["sections"][2]["items"][0]["price"], 4.5);
+    assert_eq!(menu["sections"][2]["items"][0]["original_price"], 8.99);
+    assert_eq!(menu["sections"][0]["items"][0]["price"], 12.99);
+
+    let menu: serde_json::Value =
+        test::call_and_read_body_json(&app, dessert_prices("2025-10-20T19:30:00Z")).await;
+    assert_eq!(menu["sections"][2]["items"][0]["price"], 8.99);
+    assert_eq!(
+        menu["sections"][2]["items"][0]["pricing_rule"],
+        serde_json::Value::Null
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/pricing-rules"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let rules: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(rules.as_array().unwrap().len(), 1);
+
+    for expected in [204, 404] {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/pricing-rules/{rule_id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), expected);
+    }
+}
@@ -0,0 +1,270 @@
+use actix_web::test;
+use backend::create_app;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_print_downloads_and_job_queue() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salmon, salad): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, s.id, c.id FROM menu_items s, menu_items c
+         JOIN menu_sections ms ON s.section_id = ms.id
+         WHERE s.name = 'Grilled Salmon' AND c.name = 'Caesar Salad'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [
+                {"menu_item_id": salmon, "quantity": 2, "special_requests": "No lemon"},
+                {"menu_item_id": salad, "quantity": 1, "special_requests": null}
+            ]
+        }))
+        .to_request();
+    let order: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let order_id = order["order_id"].as_str().unwrap().to_string();
+
+    let get = |uri: String| {
+        test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let order_uri = format!("/api/restaurants/{restaurant_id}/orders/{order_id}");
+
+    let resp = test::call_service(&app, get(format!("{order_uri}/ticket?width=58"))).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/octet-stream"
+    );
+    let ticket = test::read_body(resp).await;
+    assert!(ticket.starts_with(&[0x1b, b'@']));
+    assert!(ticket.ends_with(&[0x1d, b'V', 66, 3]));
+    let text = String::from_utf8_lossy(&ticket);
+    assert!(text.contains("2 x Grilled Salmon\n"));
+    assert!(text.contains("    NOTE: No lemon\n"));
+    assert!(text.contains(&"-".repeat(32)));
+    assert!(!text.contains("25.98"));
+
+    let receipt =
+        test::read_body(test::call_service(&app, get(format!("{order_uri}/receipt"))).await).await;
+    let text = String::from_utf8_lossy(&receipt);
+    assert!(text.contains(&format!("/order/{order_id}")));
+    assert!(text.contains(&"-".repeat(48)));
+
+    // A station ticket only carries the lines routed to that station
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/stations"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Grill", "display_order": null}))
+        .to_request();
+    let station: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let station_id = station["id"].as_str().unwrap().to_string();
+    let resp = test::call_service(
+        &app,
+        get(format!("{order_uri}/ticket?station_id={station_id}")),
+    )
+    .await;
+    assert_eq!(resp.status(), 404);
+    sqlx::query(
+        "UPDATE order_station_lines SET station_id = ? WHERE order_id = ? AND line_index = 0",
+    )
+    .bind(&station_id)
+    .bind(&order_id)
+    .execute(&test_app.pool)
+    .await
+    .unwrap();
+    let ticket = test::read_body(
+        test::call_service(
+            &app,
+            get(format!("{order_uri}/ticket?station_id={station_id}")),
+        )
+        .await,
+    )
+    .await;
+    let text = String::from_utf8_lossy(&ticket);
+    assert!(text.contains("GRILL"));
+    assert!(text.contains("Grilled Salmon"));
+    assert!(!text.contains("Caesar Salad"));
+
+    for query in [
+        "ticket?width=72",
+        "receipt?station_id=anything",
+        "ticket?station_id=missing",
+    ] {
+        let resp = test::call_service(&app, get(format!("{order_uri}/{query}"))).await;
+        assert_eq!(resp.status(), 400, "{query}");
+    }
+    let resp = test::call_service(
+        &app,
+        get(format!(
+            "/api/restaurants/{restaurant_id}/orders/missing/ticket"
+        )),
+    )
+    .await;
+    assert_eq!(resp.status(), 404);
+
+    // The queue hands out jobs oldest first and requeues failures
+    let post = |uri: String, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(body)
+            .to_request()
+    };
+    let jobs_uri = format!("/api/restaurants/{restaurant_id}/print-jobs");
+    let resp = test::call_service(
+        &app,
+        post(
+            jobs_uri.clone(),
+            json!({"order_id": order_id, "kind": "ticket", "width": 58, "station_id": null}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 201);
+    let ticket_job: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(ticket_job["status"], "queued");
+    assert_eq!(ticket_job["width"], 58);
+    let receipt_job: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        post(
+            jobs_uri.clone(),
+            json!({"order_id": order_id, "kind": "receipt", "width": null, "station_id": null}),
+        ),
+    )
+    .await;
+    assert_eq!(receipt_job["width"], 80);
+    let resp = test::call_service(
+        &app,
+        post(
+            jobs_uri.clone(),
+            json!({"order_id": order_id, "kind": "invoice", "width": null, "station_id": null}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 400);
+
+    let claim = || post(format!("{jobs_uri}/claim"), json!({}));
+    let ack = |job: &serde_json::Value, body: serde_json::Value| {
+        post(
+            format!("/api/print-jobs/{}/ack", job["id"].as_str().unwrap()),
+            body,
+        )
+    };
+
+    let resp = test::call_service(
+        &app,
+        ack(&ticket_job, json!({"success": true, "error": null})),
+    )
+    .await;
+    assert_eq!(resp.status(), 409);
+
+    let claimed: serde_json::Value = test::call_and_read_body_json(&app, claim()).await;
+    assert_eq!(claimed["job"]["id"], ticket_job["id"]);
+    assert_eq!(claimed["job"]["status"], "printing");
+    assert_eq!(claimed["job"]["attempts"], 1);
+    let payload = general_purpose::STANDARD
+        .decode(claimed["payload_base64"].as_str().unwrap())
+        .unwrap();
+    let downloaded = test::read_body(
+        test::call_service(&app, get(format!("{order_uri}/ticket?width=58"))).await,
+    )
+    .await;
+    assert_eq!(payload, downloaded.to_vec());
+
+    let failed: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        ack(&ticket_job, json!({"success": false, "error": "Paper out"})),
+    )
+    .await;
+    assert_eq!(failed["status"], "queued");
+    assert_eq!(failed["last_error"], "Paper out");
+
+    // The requeued ticket is still older than the receipt
+    let claimed: serde_json::Value = test::call_and_read_body_json(&app, claim()).await;
+    assert_eq!(claimed["job"]["id"], ticket_job["id"]);
+    assert_eq!(claimed["job"]["attempts"], 2);
+    let printed: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        ack(&ticket_job, json!({"success": true, "error": null})),
+    )
+    .await;
+    assert_eq!(printed["status"], "printed");
+    assert!(printed["printed_at"].is_string());
+    assert!(printed["last_error"].is_null());
+    let resp = test::call_service(
+        &app,
+        ack(&ticket_job, json!({"success": true, "error": null})),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+
+    let claimed: serde_json::Value = test::call_and_read_body_json(&app, claim()).await;
+    assert_eq!(claimed["job"]["id"], receipt_job["id"]);
+    let resp = test::call_service(&app, claim()).await;
+    assert_eq!(resp.status(), 204);
+
+    // An agent that never acknowledges loses its claim after the timeout
+    sqlx::query("UPDATE print_jobs SET claimed_at = datetime('now', '-10 minutes') WHERE id = ?")
+        .bind(receipt_job["id"].as_str().unwrap())
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    let claimed: serde_json::Value = test::call_and_read_body_json(&app, claim()).await;
+    assert_eq!(claimed["job"]["id"], receipt_job["id"]);
+    assert_eq!(claimed["job"]["attempts"], 2);
+    let retried: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        ack(&receipt_job, json!({"success": false, "error": null})),
+    )
+    .await;
+    assert_eq!(retried["status"], "queued");
+    test::call_service(&app, claim()).await;
+    let failed: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        ack(
+            &receipt_job,
+            json!({"success": false, "error": "Cover open"}),
+        ),
+    )
+    .await;
+    assert_eq!(failed["status"], "failed");
+    assert_eq!(failed["attempts"], 3);
+
+    let jobs: serde_json::Value =
+        test::call_and_read_body_json(&app, get(format!("{jobs_uri}?status=failed"))).await;
+    assert_eq!(jobs.as_array().unwrap().len(), 1);
+    assert_eq!(jobs[0]["id"], receipt_job["id"]);
+    let jobs: serde_json::Value = test::call_and_read_body_json(&app, get(jobs_uri.clone())).await;
+    assert_eq!(jobs.as_array().unwrap().len(), 2);
+    let resp = test::call_service(&app, get(format!("{jobs_uri}?status=lost"))).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{jobs_uri}/claim"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
@@ -0,0 +1,157 @@
+use actix_web::test;
+use backend::create_app;
+use backend::timezone::{business_date, day_bounds_utc, parse_timezone};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+fn datetime(s: &str) -> chrono::NaiveDateTime {
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+#[tokio::test]
+async fn test_day_bounds_follow_restaurant_timezone() {
+    let kolkata = parse_timezone("Asia/Kolkata").unwrap();
+    let date = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+
+    let (start, end) = day_bounds_utc(kolkata, date);
+    assert_eq!(start, datetime("2025-03-09 18:30:00"));
+    assert_eq!(end, datetime("2025-03-10 18:30:00"));
+
+    // 1am local on the 10th is still the evening of the 9th in UTC
+    let early_morning = Utc.with_ymd_and_hms(2025, 3, 9, 19, 30, 0).unwrap();
+    assert_eq!(business_date(kolkata, early_morning), date);
+
+    assert!(parse_timezone("Mars/Olympus_Mons").is_none());
+}
+
+#[tokio::test]
+async fn test_day_bounds_when_midnight_is_skipped() {
+    // Brazil started DST at midnight on 2018-11-04, so that day began at 01:00 local
+    let sao_paulo = parse_timezone("America/Sao_Paulo").unwrap();
+    let date = NaiveDate::from_ymd_opt(2018, 11, 4).unwrap();
+
+    let (start, end) = day_bounds_utc(sao_paulo, date);
+    assert_eq!(start, datetime("2018-11-04 03:00:00"));
+    assert_eq!(end, datetime("2018-11-05 02:00:00"));
+}
+
+#[tokio::test]
+async fn test_restaurant_timezone_is_validated() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post()
+        .uri("/api/restaurants")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Nowhere Cafe", "timezone": "Asia/Bengaluru"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::post()
+        .uri("/api/restaurants")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Chennai Cafe", "timezone": "Asia/Kolkata"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["timezone"], "Asia/Kolkata");
+    let restaurant_id = body["id"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/restaurants/{restaurant_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"timezone": "Not/AZone"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    // Restaurants created without a zone default to UTC
+    let req = test::TestRequest::post()
+        .uri("/api/restaurants")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Default Cafe"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["timezone"], "UTC");
+}
+
+#[tokio::test]
+async fn test_today_orders_use_local_day() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE restaurants SET timezone = 'Asia/Kolkata' WHERE id = ?")
+        .bind(&restaurant_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    let table_id: String = sqlx::query_scalar("SELECT id FROM tables WHERE unique_code = 'TBL001'")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+
+    let kolkata = parse_timezone("Asia/Kolkata").unwrap();
+    let (day_start, _) = day_bounds_utc(kolkata, business_date(kolkata, Utc::now()));
+
+    // One order just after local midnight, one just before it
+    for (id, created_at) in [
+        ("after-midnight", day_start + Duration::minutes(1)),
+        ("before-midnight", day_start - Duration::minutes(1)),
+    ] {
+        sqlx::query(
+            "INSERT INTO orders (id, table_id, items, total_amount, created_at) VALUES (?, ?, '[]', 10.0, ?)",
+        )
+        .bind(id)
+        .bind(&table_id)
+        .bind(created_at)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/orders/today"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let ids: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|order| order["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["after-midnight"]);
+}
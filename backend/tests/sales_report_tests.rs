@@ -0,0 +1,176 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_sales_report_uses_restaurant_days_and_skips_cancelled() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, salad, bread, salmon): (String, String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, s.id, b.id, f.id FROM menu_items s, menu_items b, menu_items f
+         JOIN menu_sections ms ON s.section_id = ms.id
+         WHERE s.name = 'Caesar Salad' AND b.name = 'Garlic Bread' AND f.name = 'Grilled Salmon'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    // Kolkata is UTC+05:30, so 19:00 UTC on the 10th is already the 11th there
+    let orders = [
+        ("TBL001", &salad, 2, "2025-03-10 04:00:00", "delivered"),
+        ("TBL002", &salmon, 2, "2025-03-10 19:00:00", "pending"),
+        ("TBL001", &bread, 1, "2025-03-10 05:00:00", "cancelled"),
+    ];
+    for (table_code, menu_item_id, quantity, created_at, status) in orders {
+        let req = test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": table_code,
+                "items": [{"menu_item_id": menu_item_id, "quantity": quantity, "special_requests": null}]
+            }))
+            .to_request();
+        let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        sqlx::query("UPDATE orders SET created_at = ?, status = ? WHERE id = ?")
+            .bind(created_at)
+            .bind(status)
+            .bind(created["order_id"].as_str().unwrap())
+            .execute(&test_app.pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("UPDATE restaurants SET timezone = 'Asia/Kolkata' WHERE id = ?")
+        .bind(&restaurant_id)
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+
+    let report = |query: &str| {
+        test::TestRequest::get()
+            .uri(&format!(
+                "/api/restaurants/{restaurant_id}/reports/sales?{query}"
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+
+    let day: serde_json::Value =
+        test::call_and_read_body_json(&app, report("from=2025-03-10&to=2025-03-10")).await;
+    assert_eq!(day["timezone"], "Asia/Kolkata");
+    assert_eq!(day["order_count"], 1);
+    assert_eq!(day["cancelled_count"], 1);
+    assert_eq!(day["cancellation_rate"], 0.5);
+    assert_eq!(day["net_sales"], 25.98);
+    assert_eq!(day["items"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        day["hours"],
+        json!([{"day_of_week": 0, "hour": 9, "order_count": 1, "revenue": 25.98}])
+    );
+
+    let range: serde_json::Value =
+        test::call_and_read_body_json(&app, report("from=2025-03-10&to=2025-03-11")).await;
+    assert_eq!(range["order_count"], 2);
+    assert_eq!(range["gross_sales"], 75.96);
+    assert_eq!(range["net_sales"], 75.96);
+    assert_eq!(range["average_order_value"], 37.98);
+    assert_eq!(range["items"][0]["name"], "Grilled Salmon");
+    assert_eq!(range["items"][0]["quantity"], 2);
+    assert_eq!(range["sections"][0]["name"], "Main Courses");
+    assert_eq!(range["sections"][1]["name"], "Appetizers");
+    assert_eq!(range["sections"][1]["revenue"], 25.98);
+    assert_eq!(range["tables"][0]["name"], "Table 2");
+    assert_eq!(range["tables"][1]["order_count"], 1);
+    assert_eq!(range["hours"][1]["day_of_week"], 1);
+    assert_eq!(range["hours"][1]["hour"], 0);
+
+    let resp = test::call_service(
+        &app,
+        report("from=2025-03-10&to=2025-03-11&format=csv&breakdown=items"),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "menu_item_id,name,section,quantity,revenue");
+    assert_eq!(
+        lines[1],
+        format!("{salmon},Grilled Salmon,Main Courses,2,49.98")
+    );
+    assert_eq!(lines.len(), 3);
+
+    let resp = test::call_service(&app, report("from=2025-03-10&to=2025-03-10&format=csv")).await;
+    let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(
+        csv.lines().nth(1).unwrap(),
+        "2025-03-10,2025-03-10,Asia/Kolkata,1,1,0.5000,25.98,0.00,0.00,25.98,25.98"
+    );
+
+    // Refunds come off net sales; orders still awaiting payment are not sales
+    let salad_order: String =
+        sqlx::query_scalar("SELECT id FROM orders WHERE created_at = '2025-03-10 04:00:00'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    sqlx::query(
+        "INSERT INTO payments (order_id, provider, provider_intent_id, amount, currency, status, refunded_amount)
+         VALUES (?, 'fake', 'pi_report', 25.98, 'INR', 'succeeded', 5.0)",
+    )
+    .bind(&salad_order)
+    .execute(&test_app.pool)
+    .await
+    .unwrap();
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": salmon, "quantity": 1, "special_requests": null}]
+        }))
+        .to_request();
+    let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    sqlx::query(
+        "UPDATE orders SET created_at = '2025-03-10 06:00:00', payment_status = 'awaiting_payment' WHERE id = ?",
+    )
+    .bind(created["order_id"].as_str().unwrap())
+    .execute(&test_app.pool)
+    .await
+    .unwrap();
+
+    let day: serde_json::Value =
+        test::call_and_read_body_json(&app, report("from=2025-03-10&to=2025-03-10")).await;
+    assert_eq!(day["order_count"], 1);
+    assert_eq!(day["gross_sales"], 25.98);
+    assert_eq!(day["refunds"], 5.0);
+    assert_eq!(day["net_sales"], 20.98);
+    assert_eq!(day["tables"][0]["revenue"], 20.98);
+    assert_eq!(day["items"][0]["revenue"], 25.98);
+
+    for query in [
+        "format=xml",
+        "format=csv&breakdown=customers",
+        "from=10-03-2025",
+        "from=2025-03-12&to=2025-03-10",
+        "from=2024-01-01&to=2025-03-10",
+    ] {
+        let resp = test::call_service(&app, report(query)).await;
+        assert_eq!(resp.status(), 400, "{query}");
+    }
+}
@@ -0,0 +1,168 @@
+use actix_web::test;
+use backend::create_app;
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_scheduled_section_hidden_outside_its_window() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, section_id, menu_item_id): (String, String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, ms.id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON ms.id = mi.section_id
+         ORDER BY ms.display_order, mi.display_order LIMIT 1",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    // Served every day for one hour, starting two hours from now
+    let now = Utc::now();
+    let start_time = (now + Duration::hours(2)).format("%H:%M").to_string();
+    let end_time = (now + Duration::hours(3)).format("%H:%M").to_string();
+    let windows: Vec<_> = (0..7)
+        .map(|day| json!({"day_of_week": day, "start_time": start_time, "end_time": end_time}))
+        .collect();
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/sections/{section_id}/schedule"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"windows": windows}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["windows"].as_array().unwrap().len(), 7);
+    assert_eq!(body["active_now"], false);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let sections = body["sections"].as_array().unwrap();
+    assert!(!sections.is_empty());
+    assert!(sections.iter().all(|section| section["id"] != section_id));
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(json!({
+            "table_code": "TBL001",
+            "items": [{"menu_item_id": menu_item_id, "quantity": 1, "special_requests": null}],
+            "customer_name": "Guest"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "section_not_available");
+
+    // Previewing inside the window shows the section again
+    let at = (now + Duration::minutes(150)).format("%Y-%m-%dT%H:%M:%SZ");
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/restaurants/{restaurant_id}/menu/preview?at={at}"
+        ))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|section| section["id"] == section_id));
+}
+
+#[tokio::test]
+async fn test_section_schedule_validation_and_reset() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let section_id: String = sqlx::query_scalar("SELECT id FROM menu_sections LIMIT 1")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+    let schedule_uri = format!("/api/sections/{section_id}/schedule");
+
+    let req = test::TestRequest::put()
+        .uri(&schedule_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(
+            json!({"windows": [{"day_of_week": 7, "start_time": "07:00", "end_time": "11:00"}]}),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::put()
+        .uri(&schedule_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(
+            json!({"windows": [{"day_of_week": 0, "start_time": "7am", "end_time": "11:00"}]}),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::put()
+        .uri(&schedule_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(
+            json!({"windows": [{"day_of_week": 0, "start_time": "17:00", "end_time": "19:00"}]}),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&schedule_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["windows"][0]["start_time"], "17:00");
+    assert_eq!(body["windows"][0]["end_time"], "19:00");
+
+    // An empty schedule makes the section available all the time again
+    let req = test::TestRequest::put()
+        .uri(&schedule_uri)
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"windows": []}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["windows"].as_array().unwrap().is_empty());
+    assert_eq!(body["active_now"], true);
+
+    // Requires a logged-in manager
+    let req = test::TestRequest::get().uri(&schedule_uri).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
@@ -0,0 +1,211 @@
+use actix_web::test;
+use backend::create_app;
+use backend::email_handlers::hash_token;
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_refresh_logout_and_revocation() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let login = || {
+        test::TestRequest::post()
+            .uri("/auth/login")
+            .insert_header(("User-Agent", "test-browser"))
+            .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+            .to_request()
+    };
+    let refresh = |refresh_token: &str| {
+        test::TestRequest::post()
+            .uri("/auth/refresh")
+            .set_json(json!({"refresh_token": refresh_token}))
+            .to_request()
+    };
+    let get = |uri: &str, token: &str| {
+        test::TestRequest::get()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let post = |uri: &str, token: &str| {
+        test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, login()).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let refresh_token = body["refresh_token"].as_str().unwrap().to_string();
+    assert_eq!(body["expires_in"], 15 * 60);
+    assert_eq!(
+        test::call_service(&app, get("/api/test", &token))
+            .await
+            .status(),
+        200
+    );
+
+    // Refreshing rotates the refresh token and keeps the session
+    let resp = test::call_service(&app, refresh(&refresh_token)).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let rotated = body["refresh_token"].as_str().unwrap().to_string();
+    let refreshed_token = body["token"].as_str().unwrap().to_string();
+    assert_ne!(rotated, refresh_token);
+    assert_eq!(body["user"]["email"], "manager@example.com");
+    let claims = test_app
+        .jwt_manager
+        .validate_token(&refreshed_token)
+        .unwrap();
+    let first_session = test_app.jwt_manager.validate_token(&token).unwrap().sid;
+    assert_eq!(claims.sid, first_session);
+
+    // A second device shows up in the session list
+    let body: serde_json::Value = test::call_and_read_body_json(&app, login()).await;
+    let other_token = body["token"].as_str().unwrap().to_string();
+    let other_refresh = body["refresh_token"].as_str().unwrap().to_string();
+    let sessions: serde_json::Value =
+        test::call_and_read_body_json(&app, get("/api/auth/sessions", &refreshed_token)).await;
+    let sessions = sessions.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    let current: Vec<_> = sessions.iter().filter(|s| s["current"] == true).collect();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["id"], first_session.clone().unwrap());
+    assert_eq!(current[0]["user_agent"], "test-browser");
+
+    // Replaying a rotated-out refresh token revokes its session
+    let resp = test::call_service(&app, refresh(&refresh_token)).await;
+    assert_eq!(resp.status(), 401);
+    assert_eq!(
+        test::call_service(&app, refresh(&rotated)).await.status(),
+        401
+    );
+    assert_eq!(
+        test::call_service(&app, get("/api/test", &refreshed_token))
+            .await
+            .status(),
+        401
+    );
+    assert_eq!(
+        test::call_service(&app, refresh("not-a-refresh-token"))
+            .await
+            .status(),
+        401
+    );
+
+    // Logout ends only the current session
+    let body: serde_json::Value = test::call_and_read_body_json(&app, login()).await;
+    let third_token = body["token"].as_str().unwrap().to_string();
+    let third_refresh = body["refresh_token"].as_str().unwrap().to_string();
+    assert_eq!(
+        test::call_service(&app, post("/api/auth/logout", &third_token))
+            .await
+            .status(),
+        204
+    );
+    assert_eq!(
+        test::call_service(&app, get("/api/test", &third_token))
+            .await
+            .status(),
+        401
+    );
+    assert_eq!(
+        test::call_service(&app, refresh(&third_refresh))
+            .await
+            .status(),
+        401
+    );
+    assert_eq!(
+        test::call_service(&app, get("/api/test", &other_token))
+            .await
+            .status(),
+        200
+    );
+
+    // Another user's session cannot be revoked
+    let req = test::TestRequest::delete()
+        .uri("/api/auth/sessions/some-other-session")
+        .insert_header(("Authorization", format!("Bearer {other_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // Logging out everywhere ends every session
+    let body: serde_json::Value = test::call_and_read_body_json(&app, login()).await;
+    let fourth_token = body["token"].as_str().unwrap().to_string();
+    assert_eq!(
+        test::call_service(&app, post("/api/auth/logout-all", &fourth_token))
+            .await
+            .status(),
+        204
+    );
+    for token in [&other_token, &fourth_token] {
+        assert_eq!(
+            test::call_service(&app, get("/api/test", token))
+                .await
+                .status(),
+            401
+        );
+    }
+    assert_eq!(
+        test::call_service(&app, refresh(&other_refresh))
+            .await
+            .status(),
+        401
+    );
+
+    // So does a password reset
+    let body: serde_json::Value = test::call_and_read_body_json(&app, login()).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let user_id: String =
+        sqlx::query_scalar("SELECT id FROM users WHERE email = 'manager@example.com'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    sqlx::query("INSERT INTO password_reset_tokens (user_id, token, expires_at) VALUES (?, ?, ?)")
+        .bind(&user_id)
+        .bind(hash_token("reset-token"))
+        .bind(Utc::now().naive_utc() + Duration::hours(1))
+        .execute(&test_app.pool)
+        .await
+        .unwrap();
+    let req = test::TestRequest::post()
+        .uri("/auth/confirm-password-reset")
+        .set_json(json!({"token": "reset-token", "new_password": "new-password-456"}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    assert_eq!(
+        test::call_service(&app, get("/api/test", &token))
+            .await
+            .status(),
+        401
+    );
+
+    // Tokens without a session are refused
+    let user = backend::models::User {
+        id: user_id,
+        email: "manager@example.com".to_string(),
+        phone: None,
+        password_hash: String::new(),
+        email_verified: true,
+        created_at: Utc::now(),
+    };
+    let forged = test_app
+        .jwt_manager
+        .generate_token(&user, "no-such-session")
+        .unwrap();
+    assert_eq!(
+        test::call_service(&app, get("/api/test", &forged))
+            .await
+            .status(),
+        401
+    );
+}
@@ -0,0 +1,31 @@
+use backend::JwtSettings;
+use config::{Config, File, FileFormat};
+
+fn jwt_settings(ini: &str) -> Result<JwtSettings, config::ConfigError> {
+    Config::builder()
+        .add_source(File::from_str(ini, FileFormat::Ini))
+        .build()?
+        .get("jwt")
+}
+
+#[test]
+fn test_access_token_lifetime_defaults_to_fifteen_minutes() {
+    let jwt = jwt_settings("[jwt]\nsecret = s\n").unwrap();
+    assert_eq!(jwt.access_token_minutes, 15);
+
+    let jwt = jwt_settings("[jwt]\nsecret = s\naccess_token_minutes = 30\n").unwrap();
+    assert_eq!(jwt.access_token_minutes, 30);
+}
+
+#[test]
+fn test_legacy_expiration_hours_is_converted_to_minutes() {
+    let jwt = jwt_settings("[jwt]\nsecret = s\nexpiration_hours = 24\n").unwrap();
+    assert_eq!(jwt.access_token_minutes, 24 * 60);
+}
+
+#[test]
+fn test_both_token_lifetime_keys_are_rejected() {
+    let err = jwt_settings("[jwt]\nsecret = s\nexpiration_hours = 24\naccess_token_minutes = 15\n")
+        .unwrap_err();
+    assert!(err.to_string().contains("expiration_hours"));
+}
@@ -0,0 +1,177 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+#[tokio::test]
+async fn test_public_menu_is_served_in_negotiated_language() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, section_id): (String, String) =
+        sqlx::query_as("SELECT restaurant_id, id FROM menu_sections WHERE name = 'Appetizers'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    let item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items WHERE name = 'Caesar Salad'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+
+    let translations = [
+        (
+            format!("/api/restaurants/{restaurant_id}/translations/hi"),
+            json!({"name": "डेमो रेस्टोरेंट"}),
+        ),
+        (
+            format!("/api/sections/{section_id}/translations/hi"),
+            json!({"name": "ऐपेटाइज़र"}),
+        ),
+        (
+            format!("/api/items/{item_id}/translations/HI"),
+            json!({"name": "सीज़र सलाद"}),
+        ),
+    ];
+    for (uri, payload) in translations {
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200, "{uri}");
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/translations"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let listed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(listed["default_language"], "en");
+    assert_eq!(listed["languages"], json!(["en", "hi"]));
+    assert_eq!(listed["items"][0]["locale"], "hi");
+
+    let requests = [
+        test::TestRequest::get().uri(&format!("/menu/{restaurant_id}/TBL001?lang=hi")),
+        test::TestRequest::get()
+            .uri(&format!("/menu/{restaurant_id}/TBL001"))
+            .insert_header(("Accept-Language", "hi-IN,en;q=0.5")),
+    ];
+    for req in requests {
+        let resp = test::call_service(&app, req.to_request()).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("Content-Language").unwrap(), "hi");
+        let menu: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(menu["language"], "hi");
+        assert_eq!(menu["restaurant"]["name"], "डेमो रेस्टोरेंट");
+
+        let appetizers = &menu["sections"][0];
+        assert_eq!(appetizers["name"], "ऐपेटाइज़र");
+        assert_eq!(appetizers["items"][0]["name"], "सीज़र सलाद");
+        // Fields without a translation fall back to the default language
+        assert_eq!(appetizers["items"][1]["name"], "Garlic Bread");
+        assert_eq!(menu["sections"][1]["name"], "Main Courses");
+    }
+
+    // Unknown languages get the default menu
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .insert_header(("Accept-Language", "fr-FR,fr;q=0.9"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(menu["language"], "en");
+    assert_eq!(menu["available_languages"], json!(["en", "hi"]));
+    assert_eq!(menu["sections"][0]["items"][0]["name"], "Caesar Salad");
+}
+
+#[tokio::test]
+async fn test_translation_validation_and_delete() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let item_id: String = sqlx::query_scalar("SELECT id FROM menu_items WHERE name = 'Tiramisu'")
+        .fetch_one(&test_app.pool)
+        .await
+        .unwrap();
+
+    for (locale, payload) in [
+        ("en", json!({"name": "Tiramisu"})),
+        ("not a locale", json!({"name": "Tiramisu"})),
+        ("it", json!({})),
+    ] {
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/api/items/{item_id}/translations/{}",
+                locale.replace(' ', "%20")
+            ))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400, "{locale}");
+    }
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{item_id}/translations/it"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"description": "Dolce al caffè"}))
+        .to_request();
+    let translation: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(translation["locale"], "it");
+    assert_eq!(translation["name"], serde_json::Value::Null);
+
+    for expected in [204, 404] {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/items/{item_id}/translations/it"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), expected);
+    }
+
+    // A restaurant whose menu is written in Hindi treats "hi" as the base text
+    let req = test::TestRequest::post()
+        .uri("/api/restaurants")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Hindi Kitchen", "default_language": "hi_IN"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let restaurant: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(restaurant["default_language"], "hi-in");
+
+    let req = test::TestRequest::post()
+        .uri("/api/restaurants")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Nowhere", "default_language": "??"}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
@@ -0,0 +1,262 @@
+use actix_web::test;
+use backend::create_app;
+use serde_json::json;
+
+mod common;
+use common::test_app::create_test_app;
+
+fn order_body(menu_item_id: &str, variant_id: Option<&str>, quantity: i32) -> serde_json::Value {
+    json!({
+        "table_code": "TBL001",
+        "items": [{
+            "menu_item_id": menu_item_id,
+            "variant_id": variant_id,
+            "quantity": quantity,
+            "special_requests": null
+        }],
+        "customer_name": "Guest"
+    })
+}
+
+#[tokio::test]
+async fn test_variants_are_grouped_on_public_menu_and_ordered_by_id() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, item_id): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.name = 'Garlic Bread'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    let mut variant_ids = Vec::new();
+    for (name, price) in [("Half", 4.99), ("Full", 8.99)] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/items/{item_id}/variants"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"name": name, "price": price}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let variant: serde_json::Value = test::read_body_json(resp).await;
+        variant_ids.push(variant["id"].as_str().unwrap().to_string());
+    }
+    let (half_id, full_id) = (&variant_ids[0], &variant_ids[1]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let garlic_bread = &menu["sections"][0]["items"][1];
+    assert_eq!(garlic_bread["name"], "Garlic Bread");
+    assert_eq!(
+        garlic_bread["variants"],
+        json!([
+            {"id": half_id, "name": "Half", "price": 4.99, "original_price": 4.99},
+            {"id": full_id, "name": "Full", "price": 8.99, "original_price": 8.99}
+        ])
+    );
+    assert_eq!(menu["sections"][0]["items"][0]["variants"], json!([]));
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&item_id, None, 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "variant_required");
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&item_id, Some(half_id), 2))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(created["total_amount"], 9.98);
+
+    let order_id = created["order_id"].as_str().unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/orders/{order_id}"))
+        .to_request();
+    let order: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let line = &order["items"][0];
+    assert_eq!(line["menu_item_id"], item_id.as_str());
+    assert_eq!(line["menu_item_name"], "Garlic Bread");
+    assert_eq!(line["variant_id"], half_id.as_str());
+    assert_eq!(line["variant_name"], "Half");
+    assert_eq!(line["price"], 4.99);
+
+    // Unavailable variants drop off the menu and cannot be ordered
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/variants/{half_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"available": false}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&item_id, Some(half_id), 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    // A variant of another item is rejected too
+    let other_item_id: String =
+        sqlx::query_scalar("SELECT id FROM menu_items WHERE name = 'Caesar Salad'")
+            .fetch_one(&test_app.pool)
+            .await
+            .unwrap();
+    let req = test::TestRequest::post()
+        .uri("/orders")
+        .set_json(order_body(&other_item_id, Some(full_id), 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/variants/{full_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"available": false}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/menu/{restaurant_id}/TBL001"))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let appetizers: Vec<&str> = menu["sections"][0]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(appetizers, ["Caesar Salad"]);
+}
+
+#[tokio::test]
+async fn test_manage_variants() {
+    let test_app = create_test_app().await;
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let (restaurant_id, item_id): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.name = 'Tiramisu'",
+    )
+    .fetch_one(&test_app.pool)
+    .await
+    .unwrap();
+
+    for payload in [
+        json!({"name": "  ", "price": 5.0}),
+        json!({"name": "Slice", "price": -1.0}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/items/{item_id}/variants"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/publish"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"note": null}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/items/{item_id}/variants"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Slice", "price": 6.5}))
+        .to_request();
+    let variant: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let variant_id = variant["id"].as_str().unwrap().to_string();
+    assert_eq!(variant["display_order"], 1);
+    assert_eq!(variant["available"], true);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/variants/{variant_id}"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .set_json(json!({"name": "Large slice", "price": 7.25}))
+        .to_request();
+    let updated: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated["name"], "Large slice");
+    assert_eq!(updated["price"], 7.25);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let menu: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let tiramisu = menu["sections"][2]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["id"] == item_id.as_str())
+        .unwrap();
+    assert_eq!(tiramisu["variants"][0]["name"], "Large slice");
+
+    // Variants are menu content, so they show up in the draft diff
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/restaurants/{restaurant_id}/menu/diff"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let diff: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(diff["changed_items"][0]["name"], "Tiramisu");
+    assert_eq!(
+        diff["changed_items"][0]["changes"],
+        json!([{"field": "variants", "before": "", "after": "Large slice 7.25"}])
+    );
+
+    for expected in [204, 404] {
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/variants/{variant_id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), expected);
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/items/{item_id}/variants"))
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let variants: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(variants, json!([]));
+}
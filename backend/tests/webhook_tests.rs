@@ -0,0 +1,495 @@
+use actix_web::{test, web, App, HttpRequest, HttpResponse, HttpServer};
+use backend::webhooks::{
+    deliver_due_webhooks, sign_payload, webhook_client, DELIVERY_HEADER, EVENT_HEADER,
+    MAX_DELIVERY_ATTEMPTS, SIGNATURE_HEADER,
+};
+use backend::{create_app, Settings, WebhookSettings};
+use serde_json::json;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+
+mod common;
+use common::test_app::{create_test_app, TestApp};
+
+// The receivers below listen on loopback, which production settings refuse
+fn local_settings(test_app: &TestApp) -> Settings {
+    Settings {
+        webhooks: Some(WebhookSettings {
+            allow_private_targets: true,
+        }),
+        ..test_app.settings.clone()
+    }
+}
+
+#[derive(Clone)]
+struct Received {
+    event: String,
+    delivery_id: String,
+    signature: String,
+    body: String,
+}
+
+// A local endpoint that records every request and answers with `status`
+#[derive(Clone)]
+struct Receiver {
+    requests: Arc<Mutex<Vec<Received>>>,
+    status: Arc<AtomicU16>,
+}
+
+async fn receive(req: HttpRequest, body: String, receiver: web::Data<Receiver>) -> HttpResponse {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    receiver.requests.lock().unwrap().push(Received {
+        event: header(EVENT_HEADER),
+        delivery_id: header(DELIVERY_HEADER),
+        signature: header(SIGNATURE_HEADER),
+        body,
+    });
+    let status = receiver.status.load(Ordering::SeqCst);
+    HttpResponse::build(actix_web::http::StatusCode::from_u16(status).unwrap()).body("ok")
+}
+
+async fn start_receiver() -> (Receiver, String) {
+    let receiver = Receiver {
+        requests: Arc::new(Mutex::new(Vec::new())),
+        status: Arc::new(AtomicU16::new(200)),
+    };
+    let data = web::Data::new(receiver.clone());
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .route("/hook", web::post().to(receive))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let port = server.addrs()[0].port();
+    tokio::spawn(server.run());
+    (receiver, format!("http://127.0.0.1:{port}/hook"))
+}
+
+fn verify_signature(secret: &str, received: &Received) {
+    let timestamp: i64 = received
+        .signature
+        .strip_prefix("t=")
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|ts| ts.parse().ok())
+        .expect("signature carries a timestamp");
+    assert_eq!(
+        received.signature,
+        sign_payload(secret, timestamp, received.body.as_bytes())
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_subscriptions_and_delivery() {
+    let test_app = create_test_app().await;
+    let pool = test_app.pool.clone();
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        local_settings(&test_app),
+    ))
+    .await;
+    let (receiver, hook_url) = start_receiver().await;
+    let client = webhook_client(true);
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let auth = ("Authorization", format!("Bearer {token}"));
+
+    let (restaurant_id, salmon): (String, String) = sqlx::query_as(
+        "SELECT ms.restaurant_id, mi.id FROM menu_items mi
+         JOIN menu_sections ms ON mi.section_id = ms.id
+         WHERE mi.name = 'Grilled Salmon'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let hooks_uri = format!("/api/restaurants/{restaurant_id}/webhooks");
+
+    // Only http(s) URLs and known events are accepted
+    for payload in [
+        json!({"url": "ftp://example.com/hook", "events": ["order.created"]}),
+        json!({"url": hook_url, "events": ["order.eaten"]}),
+        json!({"url": hook_url, "events": []}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&hooks_uri)
+            .insert_header(auth.clone())
+            .set_json(payload)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&hooks_uri)
+        .insert_header(auth.clone())
+        .set_json(json!({
+            "url": hook_url,
+            "events": ["order.created", "order.status_changed"],
+            "description": "POS bridge"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let subscription: serde_json::Value = test::read_body_json(resp).await;
+    let subscription_id = subscription["id"].as_str().unwrap().to_string();
+    let secret = subscription["secret"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("whsec_"));
+    assert_eq!(subscription["active"], true);
+
+    let req = test::TestRequest::get()
+        .uri(&hooks_uri)
+        .insert_header(auth.clone())
+        .to_request();
+    let list: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(list.as_array().unwrap().len(), 1);
+
+    // Placing an order queues an order.created delivery
+    let place_order = || {
+        test::TestRequest::post()
+            .uri("/orders")
+            .set_json(json!({
+                "table_code": "TBL001",
+                "items": [{"menu_item_id": salmon, "quantity": 1, "special_requests": null}]
+            }))
+            .to_request()
+    };
+    let order: serde_json::Value = test::call_and_read_body_json(&app, place_order()).await;
+    let order_id = order["order_id"].as_str().unwrap().to_string();
+
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 1);
+    let first = receiver.requests.lock().unwrap()[0].clone();
+    assert_eq!(first.event, "order.created");
+    verify_signature(&secret, &first);
+    let envelope: serde_json::Value = serde_json::from_str(&first.body).unwrap();
+    assert_eq!(envelope["type"], "order.created");
+    assert_eq!(envelope["restaurant_id"], restaurant_id);
+    assert_eq!(envelope["data"]["order_id"], order_id);
+    assert_eq!(envelope["data"]["items"][0]["quantity"], 1);
+
+    // Nothing else is due once the delivery went through
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 0);
+
+    let deliveries_uri = format!("/api/webhooks/{subscription_id}/deliveries");
+    let req = test::TestRequest::get()
+        .uri(&deliveries_uri)
+        .insert_header(auth.clone())
+        .to_request();
+    let deliveries: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(deliveries[0]["id"], first.delivery_id);
+    assert_eq!(deliveries[0]["status"], "delivered");
+    assert_eq!(deliveries[0]["attempts"], 1);
+    assert_eq!(deliveries[0]["last_status_code"], 200);
+
+    // A failing endpoint leaves the delivery pending with a backoff
+    receiver.status.store(500, Ordering::SeqCst);
+    test::call_service(&app, place_order()).await;
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 1);
+    let failing_id = receiver.requests.lock().unwrap()[1].delivery_id.clone();
+
+    let detail_uri = format!("/api/webhook-deliveries/{failing_id}");
+    let req = test::TestRequest::get()
+        .uri(&detail_uri)
+        .insert_header(auth.clone())
+        .to_request();
+    let detail: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(detail["delivery"]["status"], "pending");
+    assert_eq!(detail["delivery"]["last_status_code"], 500);
+    assert!(detail["delivery"]["next_attempt_at"].is_string());
+    assert_eq!(detail["attempts"].as_array().unwrap().len(), 1);
+    assert_eq!(detail["attempts"][0]["response_body"], "ok");
+    assert!(detail["payload"]
+        .as_str()
+        .unwrap()
+        .contains("\"order.created\""));
+
+    // The retry is not due yet; once it is, a recovered endpoint gets it
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 0);
+    sqlx::query(
+        "UPDATE webhook_deliveries SET next_attempt_at = datetime('now', '-1 minute') WHERE id = ?",
+    )
+    .bind(&failing_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    receiver.status.store(204, Ordering::SeqCst);
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 1);
+
+    let req = test::TestRequest::get()
+        .uri(&detail_uri)
+        .insert_header(auth.clone())
+        .to_request();
+    let detail: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(detail["delivery"]["status"], "delivered");
+    assert_eq!(detail["delivery"]["attempts"], 2);
+    assert!(detail["delivery"]["next_attempt_at"].is_null());
+    assert_eq!(detail["attempts"].as_array().unwrap().len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{deliveries_uri}?status=pending"))
+        .insert_header(auth.clone())
+        .to_request();
+    let pending: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(pending.as_array().unwrap().is_empty());
+
+    // Redelivery sends the same event again under a new delivery id
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/webhook-deliveries/{}/redeliver",
+            first.delivery_id
+        ))
+        .insert_header(auth.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let redelivery: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(redelivery["redelivery_of"], first.delivery_id);
+    assert_eq!(redelivery["event_id"], envelope["id"]);
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 1);
+    let resent = receiver.requests.lock().unwrap().last().unwrap().clone();
+    assert_eq!(resent.delivery_id, redelivery["id"].as_str().unwrap());
+    assert_eq!(resent.body, first.body);
+    verify_signature(&secret, &resent);
+
+    // Deliveries give up after the last attempt
+    receiver.status.store(503, Ordering::SeqCst);
+    test::call_service(&app, place_order()).await;
+    sqlx::query("UPDATE webhook_deliveries SET attempts = ? WHERE status = 'pending'")
+        .bind(MAX_DELIVERY_ATTEMPTS - 1)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 1);
+    let req = test::TestRequest::get()
+        .uri(&format!("{deliveries_uri}?status=failed"))
+        .insert_header(auth.clone())
+        .to_request();
+    let failed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(failed.as_array().unwrap().len(), 1);
+    assert_eq!(failed[0]["attempts"], MAX_DELIVERY_ATTEMPTS);
+    assert!(failed[0]["next_attempt_at"].is_null());
+
+    // Disabled subscriptions stop receiving events
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/webhooks/{subscription_id}"))
+        .insert_header(auth.clone())
+        .set_json(json!({"active": false}))
+        .to_request();
+    let updated: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated["active"], false);
+    assert_eq!(
+        updated["events"],
+        json!(["order.created", "order.status_changed"])
+    );
+    let sent = receiver.requests.lock().unwrap().len();
+    test::call_service(&app, place_order()).await;
+    assert_eq!(deliver_due_webhooks(&pool, &client).await.unwrap(), 0);
+    assert_eq!(receiver.requests.lock().unwrap().len(), sent);
+
+    // Webhooks are managed by super admins only
+    sqlx::query("UPDATE restaurant_managers SET role = 'manager' WHERE restaurant_id = ?")
+        .bind(&restaurant_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let req = test::TestRequest::get()
+        .uri(&hooks_uri)
+        .insert_header(auth.clone())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/webhooks/{subscription_id}"))
+        .insert_header(auth.clone())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+    sqlx::query("UPDATE restaurant_managers SET role = 'super_admin' WHERE restaurant_id = ?")
+        .bind(&restaurant_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/webhooks/{subscription_id}"))
+        .insert_header(auth.clone())
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+}
+
+#[tokio::test]
+async fn test_status_and_payment_events() {
+    let test_app = create_test_app().await;
+    let pool = test_app.pool.clone();
+    let (receiver, hook_url) = start_receiver().await;
+
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO webhook_subscriptions (id, restaurant_id, url, secret, events, active)
+         VALUES ('sub-1', ?, ?, 'whsec_test', ?, 1)",
+    )
+    .bind(&restaurant_id)
+    .bind(&hook_url)
+    .bind(r#"["order.status_changed","order.payment_status_changed"]"#)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO orders (id, table_id, items, status, total_amount, payment_status)
+         SELECT 'order-1', id, '[]', 'pending', 10, 'awaiting_payment' FROM tables
+         WHERE unique_code = 'TBL001'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    backend::webhooks::enqueue_order_status_changed(&mut conn, "order-1", "pending", "confirmed")
+        .await
+        .unwrap();
+    backend::webhooks::enqueue_payment_status_changed(&mut conn, "order-1", "paid")
+        .await
+        .unwrap();
+    // Events the subscription does not list are not queued
+    backend::webhooks::enqueue_event(&mut conn, &restaurant_id, "day.closed", json!({}))
+        .await
+        .unwrap();
+    drop(conn);
+
+    assert_eq!(
+        deliver_due_webhooks(&pool, &webhook_client(true))
+            .await
+            .unwrap(),
+        2
+    );
+    let requests = receiver.requests.lock().unwrap().clone();
+    let mut events: Vec<serde_json::Value> = requests
+        .iter()
+        .map(|received| serde_json::from_str(&received.body).unwrap())
+        .collect();
+    events.sort_by_key(|event| event["type"].as_str().unwrap().to_string());
+    assert_eq!(events[0]["type"], "order.payment_status_changed");
+    assert_eq!(events[0]["data"]["payment_status"], "paid");
+    assert_eq!(events[1]["type"], "order.status_changed");
+    assert_eq!(events[1]["data"]["previous_status"], "pending");
+    assert_eq!(events[1]["data"]["status"], "confirmed");
+    assert_eq!(events[1]["data"]["order_id"], "order-1");
+}
+
+#[tokio::test]
+async fn test_webhooks_only_reach_public_addresses() {
+    let test_app = create_test_app().await;
+    let pool = test_app.pool.clone();
+    let app = test::init_service(create_app(
+        test_app.pool.clone(),
+        test_app.jwt_manager.clone(),
+        test_app.settings.clone(),
+    ))
+    .await;
+    let (receiver, hook_url) = start_receiver().await;
+
+    let req = test::TestRequest::post()
+        .uri("/auth/login")
+        .set_json(json!({"email": "manager@example.com", "password": "password123"}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token = body["token"].as_str().unwrap().to_string();
+    let auth = ("Authorization", format!("Bearer {token}"));
+    let restaurant_id: String = sqlx::query_scalar("SELECT id FROM restaurants LIMIT 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let hooks_uri = format!("/api/restaurants/{restaurant_id}/webhooks");
+
+    for url in [
+        hook_url.as_str(),
+        "http://localhost/hook",
+        "http://10.0.0.5/hook",
+        "http://192.168.1.20/hook",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://[::1]/hook",
+        "http://[fd00:ec2::254]/hook",
+        "http://[::ffff:127.0.0.1]/hook",
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&hooks_uri)
+            .insert_header(auth.clone())
+            .set_json(json!({"url": url, "events": ["day.closed"]}))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400, "{url}");
+    }
+
+    let req = test::TestRequest::post()
+        .uri(&hooks_uri)
+        .insert_header(auth.clone())
+        .set_json(json!({"url": "https://93.184.215.14/hook", "events": ["menu.published"]}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let subscription: serde_json::Value = test::read_body_json(resp).await;
+    let req = test::TestRequest::put()
+        .uri(&format!(
+            "/api/webhooks/{}",
+            subscription["id"].as_str().unwrap()
+        ))
+        .insert_header(auth.clone())
+        .set_json(json!({"url": "http://127.0.0.1:9/hook"}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // Targets saved before the check, or names resolving to private
+    // addresses, are refused at send time
+    let port = hook_url
+        .trim_start_matches("http://127.0.0.1:")
+        .trim_end_matches("/hook");
+    for (id, url) in [
+        ("sub-ip", hook_url.clone()),
+        ("sub-name", format!("http://localhost.:{port}/hook")),
+    ] {
+        sqlx::query(
+            "INSERT INTO webhook_subscriptions (id, restaurant_id, url, secret, events, active)
+             VALUES (?, ?, ?, 'whsec_test', '[\"day.closed\"]', 1)",
+        )
+        .bind(id)
+        .bind(&restaurant_id)
+        .bind(url)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let mut conn = pool.acquire().await.unwrap();
+    backend::webhooks::enqueue_event(&mut conn, &restaurant_id, "day.closed", json!({}))
+        .await
+        .unwrap();
+    drop(conn);
+
+    assert_eq!(
+        deliver_due_webhooks(&pool, &webhook_client(false))
+            .await
+            .unwrap(),
+        2
+    );
+    assert!(receiver.requests.lock().unwrap().is_empty());
+    let errors: Vec<String> = sqlx::query_scalar(
+        "SELECT last_error FROM webhook_deliveries WHERE status = 'pending' ORDER BY subscription_id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(errors.len(), 2);
+    for error in errors {
+        assert!(error.contains("public address"), "{error}");
+    }
+}
@@ -457,7 +457,7 @@ max_connections = 10
 
 [jwt]
 secret = "${JWT_SECRET:-change-this-secret-in-production}"
-expiration_hours = 24
+access_token_minutes = 15
 EOF
 
 # Also create without extension as config crate expects
@@ -1,6 +1,7 @@
 // API utility module for menu app
 import type { 
   MenuData, 
+  MenuSearchData,
   OrderData, 
   CreateOrderResult,
   OrderDetails
@@ -123,10 +124,13 @@ function shouldRetryError(error: Error): boolean {
 export async function fetchMenu(
   restaurantCode: string,
   tableCode: string,
+  lang?: string,
 ): Promise<MenuData> {
   return retryWithBackoff(
     async () => {
-      const apiUrl = `${API_URL}/menu/${restaurantCode}/${tableCode}`;
+      // Without an explicit language the server negotiates from Accept-Language
+      const query = lang ? `?lang=${encodeURIComponent(lang)}` : '';
+      const apiUrl = `${API_URL}/menu/${restaurantCode}/${tableCode}${query}`;
       
       const response = await fetch(apiUrl, {
         headers: {
@@ -157,11 +161,47 @@ export async function fetchMenu(
 }
 
 /**
- * Create a new order
+ * Search the menu for a table, ranked by relevance
+ */
+export async function searchMenu(
+  restaurantCode: string,
+  tableCode: string,
+  q: string,
+  lang?: string,
+): Promise<MenuSearchData> {
+  const params = new URLSearchParams({ q });
+  if (lang) {
+    params.set('lang', lang);
+  }
+  const apiUrl = `${API_URL}/menu/${restaurantCode}/${tableCode}/search?${params}`;
+
+  try {
+    const response = await fetch(apiUrl, {
+      headers: {
+        'Accept': 'application/json'
+      },
+      signal: AbortSignal.timeout(10000) // 10 seconds
+    });
+
+    if (!response.ok) {
+      throw new Error(`Failed to search menu: ${response.status}`);
+    }
+
+    return await response.json();
+  } catch (error) {
+    logError(`searchMenu(${restaurantCode}, ${tableCode})`, error, { q });
+    throw error;
+  }
+}
+
+/**
+ * Create a new order. Callers keep one idempotency key per checkout attempt.
  */
 export async function createOrder(
   orderData: OrderData,
+  idempotencyKey: string,
 ): Promise<CreateOrderResult> {
+  // The same key is sent on every retry so the backend never creates duplicates
   return retryWithBackoff(
     async () => {
       const response = await fetch(`${API_URL}/orders`, {
@@ -169,6 +209,7 @@ export async function createOrder(
         headers: {
           "Content-Type": "application/json",
           "Accept": "application/json",
+          "Idempotency-Key": idempotencyKey,
         },
         body: JSON.stringify(orderData),
         // Add timeout
@@ -14,13 +14,15 @@ export const mockMenuData: MenuData = {
           id: "item-1",
           name: "Caesar Salad",
           description: "Fresh romaine lettuce with parmesan and croutons",
-          price: 12.99
+          price: 12.99,
+          variants: []
         },
         {
           id: "item-2",
           name: "Garlic Bread",
           description: "Toasted bread with garlic butter",
-          price: 8.99
+          price: 8.99,
+          variants: []
         }
       ]
     },
@@ -32,17 +34,28 @@ export const mockMenuData: MenuData = {
           id: "item-3",
           name: "Grilled Salmon",
           description: "Fresh Atlantic salmon with seasonal vegetables",
-          price: 24.99
+          price: 24.99,
+          variants: []
         },
         {
           id: "item-4",
           name: "Beef Steak",
           description: "Prime ribeye with mashed potatoes",
-          price: 32.99
+          price: 32.99,
+          variants: []
         }
       ]
     }
-  ]
+  ],
+  availability: {
+    is_open: true,
+    reason: null,
+    closure_message: null,
+    next_open_at: null
+  },
+  language: "en",
+  available_languages: ["en"],
+  bundles: []
 }
 
 export const emptyMenuData: MenuData = {
@@ -50,7 +63,16 @@ export const emptyMenuData: MenuData = {
     name: "Empty Restaurant",
     address: "456 Empty Street, Empty City"
   },
-  sections: []
+  sections: [],
+  availability: {
+    is_open: true,
+    reason: null,
+    closure_message: null,
+    next_open_at: null
+  },
+  language: "en",
+  available_languages: ["en"],
+  bundles: []
 }
 
 export const mockFetchSuccess = (data: any) => {
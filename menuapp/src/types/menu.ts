@@ -2,6 +2,8 @@ import {
   PublicMenu, 
   PublicMenuSection, 
   PublicMenuItem, 
+  PublicMenuVariant,
+  MenuSearchResponse,
   OrderItem as GeneratedOrderItem,
   CreateOrderResponse,
   OrderResponse,
@@ -14,7 +16,15 @@ type NullToUndefined<T> = {
 };
 
 // Use generated types with null converted to undefined for optional fields
-export type MenuItem = NullToUndefined<PublicMenuItem>;
+// Variants and pricing details are optional so items rebuilt from the
+// rendered page still type-check
+export type MenuItem = NullToUndefined<
+  Omit<PublicMenuItem, 'variants' | 'original_price' | 'pricing_rule'>
+> & {
+  variants?: PublicMenuVariant[];
+  original_price?: number;
+  pricing_rule?: string;
+};
 export type MenuSection = {
   id: string;
   name: string;
@@ -24,6 +34,15 @@ export type MenuSection = {
 // Use the actual backend API response structure
 export type MenuData = NullToUndefined<PublicMenu>;
 
+export type MenuSearchHit = {
+  section_id: string;
+  section_name: string;
+  item: MenuItem;
+};
+export type MenuSearchData = Omit<MenuSearchResponse, 'results'> & {
+  results: MenuSearchHit[];
+};
+
 // Order types - use generated types
 export type OrderItem = GeneratedOrderItem;
 
@@ -32,6 +51,16 @@ export interface OrderData {
   table_code: string;
   items: OrderItem[];
   customer_name?: string;
+  bundles?: OrderBundle[];
+  coupon_code?: string;
+}
+
+// A bundle with one chosen item per slot
+export interface OrderBundle {
+  bundle_id: string;
+  quantity: number;
+  choices: { slot_id: string; menu_item_id: string; variant_id?: string }[];
+  special_requests?: string;
 }
 
 // Cart-specific types (client-side extensions)